@@ -781,6 +781,7 @@ impl Context {
             .get_account_transactions(
                 address,
                 start_seq_number,
+                Order::Ascending,
                 limit as u64,
                 true,
                 ledger_version,
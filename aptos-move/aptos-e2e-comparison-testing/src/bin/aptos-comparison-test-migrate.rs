@@ -0,0 +1,33 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_comparison_testing::{migrate_data, DataFormatVersion};
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(
+    name = "aptos-comparison-test-migrate",
+    about = "Convert data dumped by aptos-comparison-testing to the current data format."
+)]
+pub struct Args {
+    /// Path to the dumped data
+    #[clap(long)]
+    input: PathBuf,
+
+    /// Path the converted data is written to
+    #[clap(long)]
+    output: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let num_migrated = migrate_data(&args.input, &args.output)?;
+    println!(
+        "Converted {} txn index entries and files to data format v{}",
+        num_migrated,
+        DataFormatVersion::CURRENT.0
+    );
+    Ok(())
+}
@@ -0,0 +1,221 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ComparisonStatus;
+use anyhow::Result;
+use aptos_metrics_core::{register_int_counter_vec, Encoder, IntCounterVec, TextEncoder};
+use aptos_types::transaction::Version;
+use clap::ValueEnum;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+const COMPARISON_DIVERGENCES_NAME: &str = "aptos_comparison_divergences_total";
+
+static COMPARISON_DIVERGENCES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        COMPARISON_DIVERGENCES_NAME,
+        "Number of txns whose V1 and V2 execution results diverge",
+        &["package_name"]
+    )
+    .unwrap()
+});
+
+/// A txn whose V1 and V2 execution results diverge
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct DivergenceReport {
+    pub version: Version,
+    pub package_name: String,
+    pub status: ComparisonStatus,
+}
+
+/// Where the divergences found by `Execution` are written to
+pub trait ComparisonOutput: Send {
+    fn write_divergence(&mut self, report: &DivergenceReport) -> Result<()>;
+
+    /// Called once after all txns are executed
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// Print a line per divergence
+    #[default]
+    Stdout,
+    /// Write a JSON object per line and divergence to a file
+    Json,
+    /// Count the divergences per package in the `aptos_comparison_divergences_total` counter,
+    /// whose values are written to a file, or stdout, at the end of the run
+    Prometheus,
+}
+
+impl OutputFormat {
+    /// Creates the output, `path` is the file written to by the `Json` and `Prometheus` formats
+    pub fn create_output(&self, path: Option<&Path>) -> Result<Box<dyn ComparisonOutput>> {
+        Ok(match self {
+            Self::Stdout => Box::new(StdoutOutput),
+            Self::Json => {
+                let path = path.ok_or_else(|| anyhow::anyhow!("json output requires a path"))?;
+                Box::new(JsonFileOutput::create(path)?)
+            },
+            Self::Prometheus => Box::new(PrometheusOutput::new(path.map(Path::to_path_buf))),
+        })
+    }
+}
+
+pub struct StdoutOutput;
+
+impl StdoutOutput {
+    fn format(report: &DivergenceReport) -> String {
+        format!(
+            "divergence at version:{}, package:{}, status:{:?}",
+            report.version, report.package_name, report.status
+        )
+    }
+}
+
+impl ComparisonOutput for StdoutOutput {
+    fn write_divergence(&mut self, report: &DivergenceReport) -> Result<()> {
+        println!("{}", Self::format(report));
+        Ok(())
+    }
+}
+
+pub struct JsonFileOutput(BufWriter<File>);
+
+impl JsonFileOutput {
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(Self(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl ComparisonOutput for JsonFileOutput {
+    fn write_divergence(&mut self, report: &DivergenceReport) -> Result<()> {
+        serde_json::to_writer(&mut self.0, report)?;
+        writeln!(self.0)?;
+        // flush so that the divergences found so far survive an interrupted run
+        self.0.flush()?;
+        Ok(())
+    }
+}
+
+/// The counter values are written in the Prometheus text format to `path`, or to stdout if none
+pub struct PrometheusOutput {
+    path: Option<PathBuf>,
+}
+
+impl PrometheusOutput {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    fn encode() -> Result<Vec<u8>> {
+        let metric_families: Vec<_> = aptos_metrics_core::gather()
+            .into_iter()
+            .filter(|metric_family| metric_family.get_name() == COMPARISON_DIVERGENCES_NAME)
+            .collect();
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&metric_families, &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+impl ComparisonOutput for PrometheusOutput {
+    fn write_divergence(&mut self, report: &DivergenceReport) -> Result<()> {
+        COMPARISON_DIVERGENCES
+            .with_label_values(&[&report.package_name])
+            .inc();
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let buffer = Self::encode()?;
+        match &self.path {
+            Some(path) => fs::write(path, buffer)?,
+            None => io::stdout().write_all(&buffer)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+
+    fn report(version: Version, package_name: &str) -> DivergenceReport {
+        DivergenceReport {
+            version,
+            package_name: package_name.to_string(),
+            status: ComparisonStatus::Mismatch {
+                field: "events".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_stdout_output() {
+        assert_eq!(
+            StdoutOutput::format(&report(10, "pkg")),
+            "divergence at version:10, package:pkg, status:Mismatch { field: \"events\" }"
+        );
+        StdoutOutput.write_divergence(&report(10, "pkg")).unwrap();
+    }
+
+    #[test]
+    fn test_json_file_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("divergences.json");
+        let mut output = OutputFormat::Json.create_output(Some(&path)).unwrap();
+        output.write_divergence(&report(10, "pkg_a")).unwrap();
+        output.write_divergence(&report(11, "pkg_b")).unwrap();
+
+        let lines: Vec<String> = BufReader::new(fs::File::open(&path).unwrap())
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+        assert_eq!(lines, vec![
+            r#"{"version":10,"package_name":"pkg_a","status":{"Mismatch":{"field":"events"}}}"#,
+            r#"{"version":11,"package_name":"pkg_b","status":{"Mismatch":{"field":"events"}}}"#,
+        ]);
+        assert!(OutputFormat::Json.create_output(None).is_err());
+    }
+
+    #[test]
+    fn test_prometheus_output() {
+        let count = |package_name| {
+            COMPARISON_DIVERGENCES
+                .with_label_values(&[package_name])
+                .get()
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("divergences.prom");
+        let mut output = OutputFormat::Prometheus.create_output(Some(&path)).unwrap();
+        output
+            .write_divergence(&report(10, "test_prometheus_a"))
+            .unwrap();
+        output
+            .write_divergence(&report(11, "test_prometheus_a"))
+            .unwrap();
+        output
+            .write_divergence(&report(12, "test_prometheus_b"))
+            .unwrap();
+        assert_eq!(count("test_prometheus_a"), 2);
+        assert_eq!(count("test_prometheus_b"), 1);
+
+        output.finish().unwrap();
+        let dumped = fs::read_to_string(&path).unwrap();
+        assert!(dumped.contains(
+            "aptos_comparison_divergences_total{package_name=\"test_prometheus_a\"} 2"
+        ));
+        assert!(dumped.contains(
+            "aptos_comparison_divergences_total{package_name=\"test_prometheus_b\"} 1"
+        ));
+    }
+}
@@ -3,30 +3,85 @@
 
 use crate::{
     dump_and_compile_from_package_metadata, is_aptos_package, CompilationCache, DataManager,
-    IndexWriter, PackageInfo, TxnIndex,
+    IndexReader, IndexWriter, PackageInfo, TxnIndex,
 };
 use anyhow::{format_err, Result};
 use aptos_framework::natives::code::PackageMetadata;
 use aptos_rest_client::Client;
+use aptos_storage_interface::DbReader;
 use aptos_types::{
-    state_store::{state_key::StateKey, state_value::StateValue, TStateView},
+    state_store::{state_key::StateKey, state_value::StateValue, StateView, TStateView},
     transaction::{
         signature_verified_transaction::SignatureVerifiedTransaction, Transaction,
         TransactionOutput, Version,
     },
-    write_set::TOTAL_SUPPLY_STATE_KEY,
+    write_set::{WriteSet, TOTAL_SUPPLY_STATE_KEY},
 };
 use aptos_validator_interface::{
-    AptosValidatorInterface, DebuggerStateView, FilterCondition, RestDebuggerInterface,
+    AptosValidatorInterface, DebuggerStateView, FilterCondition, LazyStateView,
+    RestDebuggerInterface,
 };
 use aptos_vm::{AptosVM, VMExecutor};
 use move_core_types::account_address::AccountAddress;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::Deref,
     path::PathBuf,
     sync::{Arc, Mutex},
 };
+use tokio::{sync::Semaphore, task::JoinHandle};
+
+/// State view used to execute a txn while collecting the state it reads.
+enum DataCollectionStateView {
+    Debugger(DebuggerStateView),
+    Lazy(LazyStateView<dyn DbReader>),
+}
+
+impl DataCollectionStateView {
+    fn execute(&self, txns: Vec<Transaction>) -> Result<Vec<TransactionOutput>> {
+        match self {
+            Self::Debugger(state_view) => {
+                DataCollection::execute_transactions_at_version_with_state_view(txns, state_view)
+            },
+            Self::Lazy(state_view) => {
+                DataCollection::execute_transactions_at_version_with_state_view(txns, state_view)
+            },
+        }
+    }
+
+    fn into_data_reads(self) -> HashMap<StateKey, StateValue> {
+        match self {
+            Self::Debugger(state_view) => state_view.get_state_keys().lock().unwrap().clone(),
+            Self::Lazy(state_view) => state_view.data_reads(),
+        }
+    }
+}
+
+/// Outcome of comparing the write set computed by executing a txn with the one committed on chain.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct VerificationReport {
+    /// Number of state keys written identically by both write sets
+    pub matching_keys: usize,
+    /// State keys written differently, or written by only one of the write sets, in order
+    pub diverging_keys: Vec<StateKey>,
+}
+
+/// Why a version in the dumped range would not be dumped, as reported by a dry run
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SkipReason {
+    /// Rejected by the filter condition, e.g. a failed txn, a publish txn or a txn whose source
+    /// code is not available
+    Filtered,
+    /// Fetching the batch of txns containing the version failed
+    FetchFailed,
+}
+
+/// Versions a dump would process, without executing or dumping them
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct DryRunReport {
+    pub kept_versions: Vec<Version>,
+    pub skipped_versions: BTreeMap<Version, SkipReason>,
+}
 
 pub struct DataCollection {
     debugger: Arc<dyn AptosValidatorInterface + Send>,
@@ -34,6 +89,11 @@ pub struct DataCollection {
     batch_size: u64,
     dump_write_set: bool,
     filter_condition: FilterCondition,
+    /// If set, state is fetched on demand from this DB instead of through the debugger.
+    lazy_state_db: Option<Arc<dyn DbReader>>,
+    /// Maximum number of txns executed at the same time
+    max_concurrency: usize,
+    verify_outputs: bool,
 }
 
 impl DataCollection {
@@ -45,6 +105,8 @@ impl DataCollection {
         skip_publish_txns: bool,
         dump_write_set: bool,
         skip_source_code: bool,
+        lazy_state_db: Option<Arc<dyn DbReader>>,
+        max_concurrency: usize,
     ) -> Self {
         Self {
             debugger,
@@ -56,9 +118,18 @@ impl DataCollection {
                 skip_publish_txns,
                 check_source_code: !skip_source_code,
             },
+            lazy_state_db,
+            max_concurrency: max_concurrency.max(1),
+            verify_outputs: false,
         }
     }
 
+    /// Check the write set of each executed txn against the one committed on chain
+    pub fn with_verify_outputs(mut self, verify_outputs: bool) -> Self {
+        self.verify_outputs = verify_outputs;
+        self
+    }
+
     pub fn new_with_rest_client(
         rest_client: Client,
         current_dir: PathBuf,
@@ -67,6 +138,8 @@ impl DataCollection {
         skip_publish_txns: bool,
         dump_write_set: bool,
         skip_source_code: bool,
+        lazy_state_db: Option<Arc<dyn DbReader>>,
+        max_concurrency: usize,
     ) -> Result<Self> {
         Ok(Self::new(
             Arc::new(RestDebuggerInterface::new(rest_client)),
@@ -76,24 +149,59 @@ impl DataCollection {
             skip_publish_txns,
             dump_write_set,
             skip_source_code,
+            lazy_state_db,
+            max_concurrency,
         ))
     }
 
     fn execute_transactions_at_version_with_state_view(
         txns: Vec<Transaction>,
-        debugger_stateview: &DebuggerStateView,
+        debugger_stateview: &(impl StateView + Sync),
     ) -> Result<Vec<TransactionOutput>> {
         let sig_verified_txns: Vec<SignatureVerifiedTransaction> =
             txns.into_iter().map(|x| x.into()).collect::<Vec<_>>();
         // check whether total supply can be retrieved
         // used for debugging the aggregator panic issue, will be removed later
-        // FIXME(#10412): remove the assert
-        let val = debugger_stateview.get_state_value(TOTAL_SUPPLY_STATE_KEY.deref());
-        assert!(val.is_ok() && val.unwrap().is_some());
+        // FIXME(#10412): remove the check
+        // total supply can legitimately be missing (e.g., close to genesis), so only warn
+        match debugger_stateview.get_state_value(TOTAL_SUPPLY_STATE_KEY.deref()) {
+            Ok(Some(_)) => {},
+            Ok(None) => println!("warning: total supply is not available in the state view"),
+            Err(err) => println!("warning: failed to read total supply: {:?}", err),
+        }
         AptosVM::execute_block_no_limit(&sig_verified_txns, debugger_stateview)
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
 
+    /// Compares the write set of `computed_output`, produced by executing the txn at `version`,
+    /// with the write set committed on chain for that txn
+    pub async fn verify_execution_against_ground_truth(
+        &self,
+        version: Version,
+        computed_output: &TransactionOutput,
+    ) -> Result<VerificationReport> {
+        let committed_write_set = self.debugger.get_committed_write_set(version).await?;
+        Ok(Self::compare_write_sets(
+            &committed_write_set,
+            computed_output.write_set(),
+        ))
+    }
+
+    fn compare_write_sets(expected: &WriteSet, computed: &WriteSet) -> VerificationReport {
+        let expected: BTreeMap<_, _> = expected.iter().collect();
+        let computed: BTreeMap<_, _> = computed.iter().collect();
+        let keys: BTreeSet<&StateKey> = expected.keys().chain(computed.keys()).copied().collect();
+        let mut report = VerificationReport::default();
+        for key in keys {
+            if expected.get(key) == computed.get(key) {
+                report.matching_keys += 1;
+            } else {
+                report.diverging_keys.push(key.clone());
+            }
+        }
+        report
+    }
+
     fn dump_and_check_src(
         version: Version,
         address: AccountAddress,
@@ -158,22 +266,124 @@ impl DataCollection {
         }
     }
 
-    pub async fn dump_data(&self, begin: Version, limit: u64) -> Result<()> {
+    /// Runs `f` on the blocking thread pool once a permit of `semaphore` is available, so that
+    /// no more tasks than there are permits run at the same time.
+    async fn spawn_blocking_with_permit<F>(semaphore: &Arc<Semaphore>, f: F) -> JoinHandle<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed");
+        tokio::task::spawn_blocking(move || {
+            f();
+            drop(permit);
+        })
+    }
+
+    /// Returns the version to start dumping `[begin, begin + limit)` from, skipping the versions
+    /// up to the last one already in the index.
+    fn resume_version(&self, begin: Version, limit: u64) -> Version {
+        IndexReader::get_last_version_in_range(&self.current_dir, begin, begin + limit)
+            .map_or(begin, |last_version| last_version + 1)
+    }
+
+    /// Returns the `(start, limit)` batches of txns fetched to dump `[begin, begin + limit)`
+    fn batches(&self, begin: Version, limit: u64, resume: bool) -> Vec<(Version, u64)> {
+        let end = begin + limit;
+        let begin = if resume {
+            let resume_version = self.resume_version(begin, limit);
+            if resume_version > begin {
+                println!("resume dumping data from version:{}", resume_version);
+            }
+            resume_version
+        } else {
+            begin
+        };
+        (begin..end)
+            .step_by(self.batch_size as usize)
+            .map(|cur_version| (cur_version, self.batch_size.min(end - cur_version)))
+            .collect()
+    }
+
+    /// Returns the `(start, limit)` batches of txns fetched to dump exactly `versions`, which are
+    /// sorted and without duplicates. Consecutive versions are fetched together.
+    fn version_list_batches(&self, versions: &[Version]) -> Vec<(Version, u64)> {
+        let mut batches: Vec<(Version, u64)> = vec![];
+        for version in versions {
+            match batches.last_mut() {
+                Some((start, limit)) if *start + *limit == *version && *limit < self.batch_size => {
+                    *limit += 1
+                },
+                _ => batches.push((*version, 1)),
+            }
+        }
+        batches
+    }
+
+    /// Lists the versions `dump_data` would execute and dump, and why the others are skipped,
+    /// without executing any txn
+    pub async fn dry_run(&self, begin: Version, limit: u64, resume: bool) -> DryRunReport {
+        self.dry_run_batches(self.batches(begin, limit, resume))
+            .await
+    }
+
+    /// Same as `dry_run`, for `dump_versions`
+    pub async fn dry_run_versions(&self, versions: &[Version]) -> DryRunReport {
+        self.dry_run_batches(self.version_list_batches(versions))
+            .await
+    }
+
+    async fn dry_run_batches(&self, batches: Vec<(Version, u64)>) -> DryRunReport {
+        let mut report = DryRunReport::default();
+        for (cur_version, batch) in batches {
+            let res_txns = self
+                .debugger
+                .get_and_filter_committed_transactions(cur_version, batch, self.filter_condition)
+                .await;
+            let kept_versions: BTreeSet<Version> = match res_txns {
+                Ok(txns) => txns.into_iter().map(|(version, _, _)| version).collect(),
+                Err(_) => {
+                    report.skipped_versions.extend(
+                        (cur_version..cur_version + batch).map(|v| (v, SkipReason::FetchFailed)),
+                    );
+                    continue;
+                },
+            };
+            for version in cur_version..cur_version + batch {
+                if kept_versions.contains(&version) {
+                    report.kept_versions.push(version);
+                } else {
+                    report
+                        .skipped_versions
+                        .insert(version, SkipReason::Filtered);
+                }
+            }
+        }
+        report
+    }
+
+    pub async fn dump_data(&self, begin: Version, limit: u64, resume: bool) -> Result<()> {
+        self.dump_batches(self.batches(begin, limit, resume)).await
+    }
+
+    /// Dumps exactly `versions`, which are sorted and without duplicates
+    pub async fn dump_versions(&self, versions: &[Version]) -> Result<()> {
+        self.dump_batches(self.version_list_batches(versions)).await
+    }
+
+    async fn dump_batches(&self, batches: Vec<(Version, u64)>) -> Result<()> {
         println!("begin dumping data");
         let compilation_cache = Arc::new(Mutex::new(CompilationCache::default()));
         let data_manager = Arc::new(Mutex::new(DataManager::new_with_dir_creation(
             &self.current_dir,
         )));
         let index_writer = Arc::new(Mutex::new(IndexWriter::new(&self.current_dir)));
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
 
-        let mut cur_version = begin;
-
-        while cur_version < begin + limit {
-            let batch = if cur_version + self.batch_size <= begin + limit {
-                self.batch_size
-            } else {
-                begin + limit - cur_version
-            };
+        for (cur_version, batch) in batches {
             let res_txns = self
                 .debugger
                 .get_and_filter_committed_transactions(cur_version, batch, self.filter_condition)
@@ -197,21 +407,56 @@ impl DataCollection {
                     let data_manager = data_manager.clone();
                     let index = index_writer.clone();
 
-                    let state_view =
-                        DebuggerStateView::new_with_data_reads(self.debugger.clone(), version);
+                    let state_view = match &self.lazy_state_db {
+                        // the debugger reads the state before the txn, i.e. at version - 1
+                        Some(db) => DataCollectionStateView::Lazy(LazyStateView::new(
+                            db.clone(),
+                            version - 1,
+                        )),
+                        None => DataCollectionStateView::Debugger(
+                            DebuggerStateView::new_with_data_reads(self.debugger.clone(), version),
+                        ),
+                    };
 
-                    let txn_execution_thread = tokio::task::spawn_blocking(move || {
-                        let epoch_result_res =
-                            Self::execute_transactions_at_version_with_state_view(
-                                vec![txn.clone()],
-                                &state_view,
-                            );
-                        if let Err(err) = epoch_result_res {
-                            println!(
-                                "execution error during transaction at version:{} :{}",
-                                version, err
+                    let committed_write_set = if self.verify_outputs {
+                        match self.debugger.get_committed_write_set(version).await {
+                            Ok(write_set) => Some(write_set),
+                            Err(err) => {
+                                println!(
+                                    "failed to get the committed write set at version:{} :{}",
+                                    version, err
+                                );
+                                None
+                            },
+                        }
+                    } else {
+                        None
+                    };
+
+                    let txn_execution = move || {
+                        let epoch_result_res = state_view.execute(vec![txn.clone()]);
+                        let output = match &epoch_result_res {
+                            Ok(output) => output,
+                            Err(err) => {
+                                println!(
+                                    "execution error during transaction at version:{} :{}",
+                                    version, err
+                                );
+                                return;
+                            },
+                        };
+
+                        if let Some(committed_write_set) = &committed_write_set {
+                            let report = Self::compare_write_sets(
+                                committed_write_set,
+                                output[0].write_set(),
                             );
-                            return;
+                            if !report.diverging_keys.is_empty() {
+                                println!(
+                                    "write set differs from the committed one at version:{}, diverging state keys:{:?}",
+                                    version, report.diverging_keys
+                                );
+                            }
                         }
 
                         let mut version_idx = TxnIndex {
@@ -240,23 +485,332 @@ impl DataCollection {
                         Self::dump_txn_index(
                             &mut data_manager.lock().unwrap(),
                             version_idx,
-                            &state_view.get_state_keys().lock().unwrap(),
+                            &state_view.into_data_reads(),
                             epoch_result_res,
                             dump_write_set,
                         );
 
                         // Log version
                         index.lock().unwrap().add_version(version);
-                    });
+                    };
+                    let txn_execution_thread =
+                        Self::spawn_blocking_with_permit(&semaphore, txn_execution).await;
                     txn_execution_ths.push(txn_execution_thread);
                 }
                 futures::future::join_all(txn_execution_ths).await;
-                // Dump version
-                index_writer.lock().unwrap().dump_version();
+                // Dump version, and flush so that an interrupted dump can be resumed
+                let mut index_writer = index_writer.lock().unwrap();
+                index_writer.dump_version();
+                index_writer.flush_writer();
             }
-            cur_version += batch;
         }
         index_writer.lock().unwrap().flush_writer();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_language_e2e_tests::data_store::FakeDataStore;
+    use aptos_types::{
+        account_state::AccountState,
+        transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionStatus},
+        write_set::{WriteOp, WriteSetMut},
+    };
+    use aptos_validator_interface::FilterCondition;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+    use tempfile::TempDir;
+
+    /// Records the version ranges requested, without returning any txn unless it is kept.
+    #[derive(Default)]
+    struct RecordingDebugger {
+        requested_ranges: Mutex<Vec<(Version, u64)>>,
+        /// Returned as the committed write set of any version
+        committed_write_set: WriteSet,
+        /// Versions that pass the filter
+        kept_versions: BTreeSet<Version>,
+        /// Fetching a range containing this version fails
+        failing_version: Option<Version>,
+    }
+
+    #[async_trait::async_trait]
+    impl AptosValidatorInterface for RecordingDebugger {
+        async fn get_account_state_by_version(
+            &self,
+            _account: AccountAddress,
+            _version: Version,
+        ) -> Result<Option<AccountState>> {
+            unimplemented!()
+        }
+
+        async fn get_state_value_by_version(
+            &self,
+            _state_key: &StateKey,
+            _version: Version,
+        ) -> Result<Option<StateValue>> {
+            unimplemented!()
+        }
+
+        async fn get_committed_transactions(
+            &self,
+            _start: Version,
+            _limit: u64,
+        ) -> Result<(Vec<Transaction>, Vec<TransactionInfo>)> {
+            unimplemented!()
+        }
+
+        async fn get_and_filter_committed_transactions(
+            &self,
+            start: Version,
+            limit: u64,
+            _filter_condition: FilterCondition,
+        ) -> Result<
+            Vec<(
+                u64,
+                Transaction,
+                Option<(
+                    AccountAddress,
+                    String,
+                    HashMap<(AccountAddress, String), PackageMetadata>,
+                )>,
+            )>,
+        > {
+            self.requested_ranges.lock().unwrap().push((start, limit));
+            let range = start..start + limit;
+            if self.failing_version.is_some_and(|v| range.contains(&v)) {
+                return Err(format_err!("failed to fetch txns"));
+            }
+            Ok(self
+                .kept_versions
+                .range(range)
+                .map(|v| (*v, Transaction::StateCheckpoint(HashValue::zero()), None))
+                .collect())
+        }
+
+        async fn get_latest_version(&self) -> Result<Version> {
+            unimplemented!()
+        }
+
+        async fn get_committed_write_set(&self, _version: Version) -> Result<WriteSet> {
+            Ok(self.committed_write_set.clone())
+        }
+
+        async fn get_version_by_account_sequence(
+            &self,
+            _account: AccountAddress,
+            _seq: u64,
+        ) -> Result<Option<Version>> {
+            unimplemented!()
+        }
+    }
+
+    fn data_collection(dir: &TempDir, debugger: Arc<RecordingDebugger>) -> DataCollection {
+        DataCollection::new(
+            debugger,
+            dir.path().to_path_buf(),
+            10,
+            false,
+            false,
+            false,
+            true,
+            None,
+            1,
+        )
+    }
+
+    async fn dump(dir: &TempDir, begin: Version, limit: u64, resume: bool) -> Vec<(Version, u64)> {
+        let debugger = Arc::new(RecordingDebugger::default());
+        let data_collection = data_collection(dir, debugger.clone());
+        data_collection
+            .dump_data(begin, limit, resume)
+            .await
+            .unwrap();
+        let requested_ranges = debugger.requested_ranges.lock().unwrap();
+        requested_ranges.clone()
+    }
+
+    #[tokio::test]
+    async fn test_resume_dump() {
+        let dir = TempDir::new().unwrap();
+
+        // Resuming with an empty index dumps the whole range.
+        assert_eq!(dump(&dir, 100, 20, true).await, vec![(100, 10), (110, 10)]);
+
+        // Simulate a dump interrupted after versions up to 107 were dumped.
+        let mut index_writer = IndexWriter::new(dir.path());
+        for version in [103, 100, 107] {
+            index_writer.add_version(version);
+        }
+        index_writer.dump_version();
+        index_writer.flush_writer();
+        // Versions outside of the range don't affect resuming.
+        index_writer.add_version(500);
+        index_writer.dump_version();
+        index_writer.flush_writer();
+        drop(index_writer);
+
+        assert_eq!(
+            IndexReader::get_last_version_in_range(dir.path(), 100, 120),
+            Some(107)
+        );
+        assert_eq!(dump(&dir, 100, 20, true).await, vec![(108, 10), (118, 2)]);
+        // Without resuming, the whole range is dumped again.
+        assert_eq!(dump(&dir, 100, 20, false).await, vec![(100, 10), (110, 10)]);
+        // Nothing is left to dump if the whole range is in the index.
+        assert_eq!(dump(&dir, 100, 8, true).await, vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_dump_versions() {
+        let dir = TempDir::new().unwrap();
+        let debugger = Arc::new(RecordingDebugger::default());
+        data_collection(&dir, debugger.clone())
+            .dump_versions(&[5, 6, 7, 12, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31])
+            .await
+            .unwrap();
+        // Only the listed versions are fetched, with consecutive ones in batches of at most 10
+        assert_eq!(debugger.requested_ranges.lock().unwrap().clone(), vec![
+            (5, 3),
+            (12, 1),
+            (20, 10),
+            (30, 2)
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let dir = TempDir::new().unwrap();
+        let debugger = Arc::new(RecordingDebugger {
+            kept_versions: [100, 104, 109, 110, 121].into_iter().collect(),
+            failing_version: Some(115),
+            ..Default::default()
+        });
+        let report = data_collection(&dir, debugger.clone())
+            .dry_run(100, 25, false)
+            .await;
+        // Nothing is dumped
+        assert!(!dir.path().join(crate::INDEX_FILE).exists());
+
+        // The dry run fetches the same batches as a real dump
+        assert_eq!(
+            debugger.requested_ranges.lock().unwrap().clone(),
+            dump(&dir, 100, 25, false).await
+        );
+
+        assert_eq!(report.kept_versions, vec![100, 104, 109, 121]);
+        assert_eq!(report.skipped_versions.len(), 21);
+        assert_eq!(report.skipped_versions[&101], SkipReason::Filtered);
+        assert_eq!(report.skipped_versions[&124], SkipReason::Filtered);
+        // 110 passes the filter, but the batch containing it could not be fetched
+        for version in 110..120 {
+            assert_eq!(report.skipped_versions[&version], SkipReason::FetchFailed);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_spawn_blocking_with_permit_limits_concurrency() {
+        let max_concurrency = 3;
+        let semaphore = Arc::new(Semaphore::new(max_concurrency));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let high_water_mark = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = vec![];
+        for _ in 0..10 {
+            let in_flight = in_flight.clone();
+            let high_water_mark = high_water_mark.clone();
+            handles.push(
+                DataCollection::spawn_blocking_with_permit(&semaphore, move || {
+                    let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    high_water_mark.fetch_max(running, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                })
+                .await,
+            );
+        }
+        for res in futures::future::join_all(handles).await {
+            res.unwrap();
+        }
+
+        let high_water_mark = high_water_mark.load(Ordering::SeqCst);
+        assert!(high_water_mark >= 1);
+        assert!(high_water_mark <= max_concurrency);
+    }
+
+    #[tokio::test]
+    async fn test_verify_execution_against_ground_truth() {
+        let write_set = |values: &[(&str, u8)]| {
+            WriteSetMut::new(values.iter().map(|(key, value)| {
+                (
+                    StateKey::raw(key.as_bytes().to_vec()),
+                    WriteOp::legacy_modification(vec![*value].into()),
+                )
+            }))
+            .freeze()
+            .unwrap()
+        };
+        let output = |write_set| {
+            TransactionOutput::new(
+                write_set,
+                vec![],
+                0,
+                TransactionStatus::Keep(ExecutionStatus::Success),
+            )
+        };
+        let debugger = Arc::new(RecordingDebugger {
+            committed_write_set: write_set(&[("a", 1), ("b", 2)]),
+            ..Default::default()
+        });
+        let data_collection = DataCollection::new(
+            debugger,
+            PathBuf::from("."),
+            10,
+            false,
+            false,
+            false,
+            true,
+            None,
+            1,
+        );
+
+        let report = data_collection
+            .verify_execution_against_ground_truth(10, &output(write_set(&[("a", 1), ("b", 2)])))
+            .await
+            .unwrap();
+        assert_eq!(report, VerificationReport {
+            matching_keys: 2,
+            diverging_keys: vec![],
+        });
+
+        // `b` is perturbed and `c` is only written by the execution
+        let report = data_collection
+            .verify_execution_against_ground_truth(
+                10,
+                &output(write_set(&[("a", 1), ("b", 20), ("c", 3)])),
+            )
+            .await
+            .unwrap();
+        assert_eq!(report, VerificationReport {
+            matching_keys: 1,
+            diverging_keys: vec![StateKey::raw(b"b".to_vec()), StateKey::raw(b"c".to_vec())],
+        });
+    }
+
+    #[test]
+    fn test_execute_without_total_supply() {
+        let state_view = FakeDataStore::default();
+        assert!(state_view
+            .get_state_value(TOTAL_SUPPLY_STATE_KEY.deref())
+            .unwrap()
+            .is_none());
+        let outputs =
+            DataCollection::execute_transactions_at_version_with_state_view(vec![], &state_view)
+                .unwrap();
+        assert!(outputs.is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{DataManager, TxnIndex, ERR_LOG, INDEX_FILE};
+use anyhow::{bail, Result};
+use aptos_language_e2e_tests::data_store::FakeDataStore;
+use aptos_types::write_set::WriteSet;
+use rocksdb::IteratorMode;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fs, path::Path};
+
+/// Precedes the format version in the header of every dumped file and txn index entry.
+/// Data dumped before the header was introduced is plain BCS, which does not start with these
+/// bytes: a `TxnIndex` would need a txn version close to `u64::MAX`, and the state data and
+/// write sets would need an enum variant that does not exist.
+const DATA_FORMAT_MAGIC: &[u8; 8] = b"ACTD\xff\xff\xff\xff";
+
+/// Version of the format of the dumped data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct DataFormatVersion(pub u32);
+
+impl DataFormatVersion {
+    pub const CURRENT: DataFormatVersion = Self::V2;
+    /// Plain BCS payload, without a header.
+    pub const V1: DataFormatVersion = DataFormatVersion(1);
+    /// BCS payload, preceded by a header with the format version.
+    pub const V2: DataFormatVersion = DataFormatVersion(2);
+
+    /// Splits dumped `bytes` into their format version and payload.
+    pub fn split(bytes: &[u8]) -> (DataFormatVersion, &[u8]) {
+        match bytes.strip_prefix(DATA_FORMAT_MAGIC) {
+            Some(rest) if rest.len() >= 4 => {
+                let (version, payload) = rest.split_at(4);
+                (
+                    DataFormatVersion(u32::from_le_bytes(version.try_into().unwrap())),
+                    payload,
+                )
+            },
+            _ => (Self::V1, bytes),
+        }
+    }
+}
+
+/// Serializes `value` in the current format.
+pub(crate) fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = DATA_FORMAT_MAGIC.to_vec();
+    bytes.extend(DataFormatVersion::CURRENT.0.to_le_bytes());
+    bytes.extend(bcs::to_bytes(value).unwrap());
+    bytes
+}
+
+/// Deserializes `bytes` dumped in any supported format.
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    match DataFormatVersion::split(bytes) {
+        // The payload has not changed between v1 and v2, only the header was added.
+        (DataFormatVersion::V1 | DataFormatVersion::V2, payload) => Ok(bcs::from_bytes(payload)?),
+        (version, _) => bail!("unsupported data format version {}", version.0),
+    }
+}
+
+/// Converts the data dumped to `input` in any supported format to the current format, and
+/// writes it to `output`. Returns the number of txn index entries and files converted.
+pub fn migrate_data(input: &Path, output: &Path) -> Result<usize> {
+    let source = DataManager::new(input);
+    if !source.check_dir_availability() {
+        bail!("{} does not contain dumped data", input.display());
+    }
+    let target = DataManager::new_with_dir_creation(output);
+
+    let mut num_migrated = 0;
+    for entry in source.db.iterator(IteratorMode::Start) {
+        let (key, value) = entry?;
+        target.db.put(key, encode(&decode::<TxnIndex>(&value)?))?;
+        num_migrated += 1;
+    }
+    num_migrated +=
+        migrate_files::<FakeDataStore>(&source.state_data_dir_path, &target.state_data_dir_path)?;
+    num_migrated +=
+        migrate_files::<WriteSet>(&source.write_set_dir_path, &target.write_set_dir_path)?;
+
+    for file_name in [INDEX_FILE, ERR_LOG] {
+        if input.join(file_name).exists() {
+            fs::copy(input.join(file_name), output.join(file_name))?;
+        }
+    }
+    Ok(num_migrated)
+}
+
+fn migrate_files<T: Serialize + DeserializeOwned>(input: &Path, output: &Path) -> Result<usize> {
+    let mut num_migrated = 0;
+    for entry in fs::read_dir(input)? {
+        let path = entry?.path();
+        let value = decode::<T>(&fs::read(&path)?)?;
+        fs::write(output.join(path.file_name().unwrap()), encode(&value))?;
+        num_migrated += 1;
+    }
+    Ok(num_migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PackageInfo;
+    use aptos_crypto::HashValue;
+    use aptos_types::transaction::Transaction;
+    use std::{collections::HashMap, path::PathBuf};
+    use tempfile::TempDir;
+
+    const V1_TEST_DATA: &str = "test-data-mainnet-10m-15";
+
+    /// Copies the checked-in v1 dump, as opening its txn index DB writes to the directory.
+    fn copy_v1_test_data() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let source = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(V1_TEST_DATA);
+        for sub_dir in ["", crate::ROCKS_INDEX_DB, crate::STATE_DATA] {
+            fs::create_dir_all(dir.path().join(sub_dir)).unwrap();
+            for entry in fs::read_dir(source.join(sub_dir)).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_file() {
+                    fs::copy(
+                        &path,
+                        dir.path().join(sub_dir).join(path.file_name().unwrap()),
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        fs::create_dir_all(dir.path().join(crate::WRITE_SET_DATA)).unwrap();
+        dir
+    }
+
+    fn read_versions(root: &Path) -> Vec<u64> {
+        fs::read_to_string(root.join(INDEX_FILE))
+            .unwrap()
+            .lines()
+            .map(|line| line.trim().parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_read_v1_data() {
+        let dir = copy_v1_test_data();
+        let data_manager = DataManager::new(dir.path());
+        let versions = read_versions(dir.path());
+        assert!(!versions.is_empty());
+        for version in versions {
+            let value = data_manager
+                .db
+                .get(bcs::to_bytes(&version).unwrap())
+                .unwrap()
+                .unwrap();
+            assert_eq!(DataFormatVersion::split(&value).0, DataFormatVersion::V1);
+            let txn_idx = data_manager.read_txn_index_versioned(version).unwrap();
+            assert_eq!(txn_idx.version, version);
+            data_manager.get_state(version);
+        }
+        assert!(data_manager.read_txn_index_versioned(0).is_err());
+    }
+
+    #[test]
+    fn test_new_dumps_use_current_format() {
+        let dir = TempDir::new().unwrap();
+        let data_manager = DataManager::new_with_dir_creation(dir.path());
+        let txn_idx = TxnIndex {
+            version: 7,
+            package_info: PackageInfo::non_compilable_info(),
+            txn: Transaction::StateCheckpoint(HashValue::zero()),
+        };
+        data_manager.dump_txn_index(7, &txn_idx);
+        data_manager.dump_state_data(7, &HashMap::new());
+        data_manager.dump_write_set(7, &WriteSet::default());
+
+        let value = data_manager
+            .db
+            .get(bcs::to_bytes(&7u64).unwrap())
+            .unwrap()
+            .unwrap();
+        let files = [
+            data_manager.state_data_dir_path.join("7_state"),
+            data_manager.write_set_dir_path.join("7_write_set"),
+        ];
+        for bytes in files
+            .iter()
+            .map(|path| fs::read(path).unwrap())
+            .chain([value])
+        {
+            assert_eq!(
+                DataFormatVersion::split(&bytes).0,
+                DataFormatVersion::CURRENT
+            );
+        }
+        assert_eq!(DataFormatVersion::CURRENT, DataFormatVersion(2));
+
+        let read = data_manager.read_txn_index_versioned(7).unwrap();
+        assert_eq!(
+            bcs::to_bytes(&read).unwrap(),
+            bcs::to_bytes(&txn_idx).unwrap()
+        );
+        assert_eq!(data_manager.get_write_set(7), Some(WriteSet::default()));
+        data_manager.get_state(7);
+    }
+
+    #[test]
+    fn test_unsupported_format_version() {
+        let mut bytes = DATA_FORMAT_MAGIC.to_vec();
+        bytes.extend(3u32.to_le_bytes());
+        bytes.extend(bcs::to_bytes(&WriteSet::default()).unwrap());
+        assert!(decode::<WriteSet>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_migrate_v1_data() {
+        let input = copy_v1_test_data();
+        let output = TempDir::new().unwrap();
+        let versions = read_versions(input.path());
+
+        let num_migrated = migrate_data(input.path(), output.path()).unwrap();
+        assert_eq!(read_versions(output.path()), versions);
+
+        let source = DataManager::new(input.path());
+        let target = DataManager::new(output.path());
+        let num_index_entries = source.db.iterator(IteratorMode::Start).count();
+        assert_eq!(
+            target.db.iterator(IteratorMode::Start).count(),
+            num_index_entries
+        );
+        // The test data has a state file, but no write set, per version.
+        assert_eq!(num_migrated, num_index_entries + versions.len());
+        for version in versions {
+            let value = target
+                .db
+                .get(bcs::to_bytes(&version).unwrap())
+                .unwrap()
+                .unwrap();
+            assert_eq!(DataFormatVersion::split(&value).0, DataFormatVersion::V2);
+            let state = fs::read(
+                target
+                    .state_data_dir_path
+                    .join(format!("{}_state", version)),
+            )
+            .unwrap();
+            assert_eq!(DataFormatVersion::split(&state).0, DataFormatVersion::V2);
+
+            assert_eq!(
+                bcs::to_bytes(&target.read_txn_index_versioned(version).unwrap()).unwrap(),
+                bcs::to_bytes(&source.read_txn_index_versioned(version).unwrap()).unwrap(),
+            );
+            assert_eq!(
+                bcs::to_bytes(&target.get_state(version)).unwrap(),
+                bcs::to_bytes(&source.get_state(version)).unwrap(),
+            );
+        }
+    }
+}
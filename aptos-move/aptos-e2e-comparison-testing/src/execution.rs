@@ -3,8 +3,8 @@
 
 use crate::{
     check_aptos_packages_availability, compile_aptos_packages, compile_package,
-    generate_compiled_blob, is_aptos_package, DataManager, IndexReader, PackageInfo, TxnIndex,
-    APTOS_COMMONS,
+    generate_compiled_blob, is_aptos_package, ComparisonOutput, DataManager, DivergenceReport,
+    HtmlDiffReporter, IndexReader, PackageInfo, StdoutOutput, TxnIndex, APTOS_COMMONS,
 };
 use anyhow::Result;
 use aptos_framework::APTOS_PACKAGES;
@@ -12,6 +12,7 @@ use aptos_language_e2e_tests::{data_store::FakeDataStore, executor::FakeExecutor
 use aptos_types::{
     contract_event::ContractEvent,
     on_chain_config::{FeatureFlag, Features, OnChainConfig},
+    state_store::state_key::StateKey,
     transaction::{Transaction, TransactionPayload, Version},
     vm_status::VMStatus,
     write_set::WriteSet,
@@ -21,7 +22,13 @@ use clap::ValueEnum;
 use itertools::Itertools;
 use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
 use move_package::CompilerVersion;
-use std::{collections::HashMap, path::PathBuf};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
 fn load_packages_to_executor(
     executor: &mut FakeExecutor,
@@ -83,22 +90,134 @@ impl Default for ExecutionMode {
     }
 }
 
+/// Outcome of comparing the V1 and V2 execution results of a single txn
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum ComparisonStatus {
+    Match,
+    /// `field` is the first part of the output that differs, e.g. `events` or `write_set_value`
+    Mismatch {
+        field: String,
+    },
+    /// Only the V1 execution failed
+    V1Error,
+    /// Only the V2 execution failed
+    V2Error,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ComparisonReport {
+    pub version: Version,
+    pub status: ComparisonStatus,
+}
+
 pub struct Execution {
     input_path: PathBuf,
     execution_mode: ExecutionMode,
     bytecode_version: u32,
+    report_path: Option<PathBuf>,
+    reports: Mutex<Vec<ComparisonReport>>,
+    compare_write_set: bool,
+    output: Mutex<Box<dyn ComparisonOutput>>,
+    html_report_dir: Option<PathBuf>,
 }
 
 impl Execution {
     pub fn new(input_path: PathBuf, execution_mode: ExecutionMode) -> Self {
+        Self::new_with_output(input_path, execution_mode, Box::new(StdoutOutput))
+    }
+
+    /// Divergences between the V1 and V2 execution results are written to `output`
+    pub fn new_with_output(
+        input_path: PathBuf,
+        execution_mode: ExecutionMode,
+        output: Box<dyn ComparisonOutput>,
+    ) -> Self {
         Self {
             input_path,
             execution_mode,
             bytecode_version: 6,
+            report_path: None,
+            reports: Mutex::new(vec![]),
+            compare_write_set: false,
+            output: Mutex::new(output),
+            html_report_dir: None,
         }
     }
 
-    pub async fn execute_txns(&self, begin: Version, num_txns_to_execute: u64) -> Result<()> {
+    /// Write the comparison results of the run to `report_path` as JSON
+    pub fn with_report_path(mut self, report_path: Option<PathBuf>) -> Self {
+        self.report_path = report_path;
+        self
+    }
+
+    /// Check the write set of each execution against the one stored by `Dump --dump-write-set`
+    pub fn with_compare_write_set(mut self, compare_write_set: bool) -> Self {
+        self.compare_write_set = compare_write_set;
+        self
+    }
+
+    /// Write an HTML report of the V1 and V2 results of each diverging txn to `html_report_dir`
+    pub fn with_html_report_dir(mut self, html_report_dir: Option<PathBuf>) -> Self {
+        self.html_report_dir = html_report_dir;
+        self
+    }
+
+    fn write_html_report(
+        &self,
+        version: Version,
+        v1_output: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
+        v2_output: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
+    ) {
+        if let Some(dir) = &self.html_report_dir {
+            let res = fs::create_dir_all(dir)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| {
+                    HtmlDiffReporter::write_report(
+                        version,
+                        v1_output,
+                        v2_output,
+                        &dir.join(format!("{}.html", version)),
+                    )
+                });
+            if let Err(err) = res {
+                println!(
+                    "failed to write the html report at version:{} :{}",
+                    version, err
+                );
+            }
+        }
+    }
+
+    fn record_comparison(&self, version: Version, status: ComparisonStatus) {
+        self.reports
+            .lock()
+            .unwrap()
+            .push(ComparisonReport { version, status });
+    }
+
+    fn write_divergence(&self, report: &DivergenceReport) {
+        if let Err(err) = self.output.lock().unwrap().write_divergence(report) {
+            println!(
+                "failed to write the divergence at version:{} :{}",
+                report.version, err
+            );
+        }
+    }
+
+    fn write_report(&self, report_path: &Path) -> Result<()> {
+        let reports = self.reports.lock().unwrap();
+        serde_json::to_writer_pretty(File::create(report_path)?, &*reports)?;
+        Ok(())
+    }
+
+    /// Compiles the aptos packages and checks that the dumped data is available
+    fn prepare_execution(
+        &self,
+    ) -> Result<(
+        DataManager,
+        HashMap<PackageInfo, HashMap<ModuleId, Vec<u8>>>,
+        HashMap<PackageInfo, HashMap<ModuleId, Vec<u8>>>,
+    )> {
         let aptos_commons_path = self.input_path.join(APTOS_COMMONS);
         if !check_aptos_packages_availability(aptos_commons_path.clone()) {
             return Err(anyhow::Error::msg("aptos packages are missing"));
@@ -123,6 +242,16 @@ impl Execution {
         if !IndexReader::check_availability(&self.input_path) {
             return Err(anyhow::Error::msg("index file is missing"));
         }
+        Ok((
+            data_manager,
+            compiled_package_cache,
+            compiled_package_cache_v2,
+        ))
+    }
+
+    pub async fn execute_txns(&self, begin: Version, num_txns_to_execute: u64) -> Result<()> {
+        let (data_manager, mut compiled_package_cache, mut compiled_package_cache_v2) =
+            self.prepare_execution()?;
         let mut index_reader = IndexReader::new(&self.input_path);
 
         // get the first idx from the version_index file
@@ -154,7 +283,38 @@ impl Execution {
             }
             i += 1;
         }
-        Ok(())
+        if let Some(report_path) = &self.report_path {
+            self.write_report(report_path)?;
+        }
+        self.output.lock().unwrap().finish()
+    }
+
+    /// Executes exactly `versions`, skipping the ones that were not dumped
+    pub async fn execute_versions(&self, versions: &[Version]) -> Result<()> {
+        let (data_manager, mut compiled_package_cache, mut compiled_package_cache_v2) =
+            self.prepare_execution()?;
+        for version in versions {
+            if data_manager.get_txn_index(*version).is_none() {
+                println!("txn at version:{} is not dumped, skip it", version);
+                continue;
+            }
+            let res = self.execute_one_txn(
+                *version,
+                &data_manager,
+                &mut compiled_package_cache,
+                &mut compiled_package_cache_v2,
+            );
+            if res.is_err() {
+                println!(
+                    "execution at version:{} failed, skip to the next txn",
+                    version
+                );
+            }
+        }
+        if let Some(report_path) = &self.report_path {
+            self.write_report(report_path)?;
+        }
+        self.output.lock().unwrap().finish()
     }
 
     fn compile_code(
@@ -216,6 +376,15 @@ impl Execution {
             if self.bytecode_version == 6 {
                 features.enable(FeatureFlag::VM_BINARY_FORMAT_V6);
             }
+            let dumped_write_set = if self.compare_write_set {
+                let write_set = data_manager.get_write_set(cur_version);
+                if write_set.is_none() {
+                    println!("write set is not dumped at version:{}", cur_version);
+                }
+                write_set
+            } else {
+                None
+            };
             // execute and compare
             self.execute_and_compare(
                 cur_version,
@@ -224,6 +393,7 @@ impl Execution {
                 &txn_idx,
                 compiled_package_cache,
                 compiled_package_cache_v2,
+                dumped_write_set.as_ref(),
             );
         }
         Ok(())
@@ -237,6 +407,7 @@ impl Execution {
         txn_idx: &TxnIndex,
         compiled_package_cache: &HashMap<PackageInfo, HashMap<ModuleId, Vec<u8>>>,
         compiled_package_cache_v2: &HashMap<PackageInfo, HashMap<ModuleId, Vec<u8>>>,
+        dumped_write_set: Option<&WriteSet>,
     ) {
         let mut package_cache_main = compiled_package_cache;
         let package_cache_other = compiled_package_cache_v2;
@@ -250,6 +421,12 @@ impl Execution {
             &txn_idx.txn,
             package_cache_main,
         );
+        let main_label = if self.execution_mode.is_v2() {
+            "V2"
+        } else {
+            "V1"
+        };
+        Self::check_dumped_write_set(cur_version, dumped_write_set, main_label, &res_main_opt);
         if self.execution_mode.is_compare() {
             let res_other_opt = self.execute_code(
                 state,
@@ -258,7 +435,19 @@ impl Execution {
                 &txn_idx.txn,
                 package_cache_other,
             );
-            Self::print_mismatches(cur_version, &res_main_opt.unwrap(), &res_other_opt.unwrap());
+            Self::check_dumped_write_set(cur_version, dumped_write_set, "V2", &res_other_opt);
+            let res_main = res_main_opt.unwrap();
+            let res_other = res_other_opt.unwrap();
+            let status = Self::print_mismatches(cur_version, &res_main, &res_other);
+            if status != ComparisonStatus::Match {
+                self.write_html_report(cur_version, &res_main, &res_other);
+                self.write_divergence(&DivergenceReport {
+                    version: cur_version,
+                    package_name: txn_idx.package_info.package_name.clone(),
+                    status: status.clone(),
+                });
+            }
+            self.record_comparison(cur_version, status);
         } else {
             let res = res_main_opt.unwrap();
             if let Ok(res_ok) = res {
@@ -312,18 +501,50 @@ impl Execution {
         None
     }
 
+    fn check_dumped_write_set(
+        cur_version: Version,
+        dumped_write_set: Option<&WriteSet>,
+        label: &str,
+        res_opt: &Option<Result<(WriteSet, Vec<ContractEvent>), VMStatus>>,
+    ) {
+        if let (Some(dumped_write_set), Some(Ok((write_set, _)))) = (dumped_write_set, res_opt) {
+            if let Some(state_key) = Self::first_write_set_mismatch(dumped_write_set, write_set) {
+                println!(
+                    "write set from {} differs from the dumped one at version:{}, first differing state key:{:?}",
+                    label, cur_version, state_key
+                );
+            }
+        }
+    }
+
+    /// Returns the smallest state key whose write op differs between the two write sets,
+    /// including keys written by only one of them
+    fn first_write_set_mismatch(expected: &WriteSet, actual: &WriteSet) -> Option<StateKey> {
+        let expected: BTreeMap<_, _> = expected.iter().collect();
+        let actual: BTreeMap<_, _> = actual.iter().collect();
+        let keys: BTreeSet<&StateKey> = expected.keys().chain(actual.keys()).copied().collect();
+        keys.into_iter()
+            .find(|key| expected.get(key) != actual.get(key))
+            .cloned()
+    }
+
     fn print_mismatches(
         cur_version: u64,
         res_1: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
         res_2: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
-    ) {
+    ) -> ComparisonStatus {
+        let mismatch = |field: &str| ComparisonStatus::Mismatch {
+            field: field.to_string(),
+        };
         match (res_1, res_2) {
             (Err(e1), Err(e2)) => {
                 if e1 != e2 {
                     println!("error is different at {}", cur_version);
                     println!("error {} is raised from V1", e1);
                     println!("error {} is raised from V2", e2);
+                    return mismatch("error");
                 }
+                ComparisonStatus::Match
             },
             (Err(e), Ok(res)) => {
                 println!("error {} is raised from V1 at {}", e, cur_version);
@@ -331,6 +552,7 @@ impl Execution {
                     "output from V2 at version:{}\nwrite set:{:?}\n events:{:?}\n",
                     cur_version, res.0, res.1
                 );
+                ComparisonStatus::V1Error
             },
             (Ok(res), Err(e)) => {
                 println!("error {} is raised from V2 at {}", e, cur_version);
@@ -338,36 +560,144 @@ impl Execution {
                     "output from V1 at version:{}\nwrite set:{:?}\n events:{:?}\n",
                     cur_version, res.0, res.1
                 );
+                ComparisonStatus::V2Error
             },
             (Ok(res_1), Ok(res_2)) => {
+                let mut status = ComparisonStatus::Match;
                 // compare events
-                for idx in 0..res_1.1.len() {
-                    let event_1 = &res_1.1[idx];
-                    let event_2 = &res_2.1[idx];
+                if res_1.1.len() != res_2.1.len() {
+                    println!("number of events is different at version {}", cur_version);
+                    status = mismatch("events");
+                }
+                for (idx, (event_1, event_2)) in res_1.1.iter().zip(res_2.1.iter()).enumerate() {
                     if event_1 != event_2 {
                         println!("event is different at version {}", cur_version);
                         println!("event raised from V1: {} at index:{}", event_1, idx);
                         println!("event raised from V2: {} at index:{}", event_2, idx);
+                        if status == ComparisonStatus::Match {
+                            status = mismatch("events");
+                        }
                     }
                 }
                 // compare write set
                 let res_1_write_set_vec = res_1.0.iter().collect_vec();
                 let res_2_write_set_vec = res_2.0.iter().collect_vec();
-                for idx in 0..res_1_write_set_vec.len() {
-                    let write_set_1 = res_1_write_set_vec[0];
-                    let write_set_2 = res_2_write_set_vec[0];
+                if res_1_write_set_vec.len() != res_2_write_set_vec.len() {
+                    println!("write set size is different at version {}", cur_version);
+                    if status == ComparisonStatus::Match {
+                        status = mismatch("write_set");
+                    }
+                }
+                for (idx, (write_set_1, write_set_2)) in res_1_write_set_vec
+                    .iter()
+                    .zip(res_2_write_set_vec.iter())
+                    .enumerate()
+                {
                     if write_set_1.0 != write_set_2.0 {
                         println!("write set key is different at version {}", cur_version);
                         println!("state key at V1: {:?} at index:{}", write_set_1.0, idx);
                         println!("state key at V2: {:?} at index:{}", write_set_2.0, idx);
+                        if status == ComparisonStatus::Match {
+                            status = mismatch("write_set_key");
+                        }
                     }
                     if write_set_1.1 != write_set_2.1 {
                         println!("write set value is different at version {}", cur_version);
                         println!("state value at V1: {:?} at index {}", write_set_1.1, idx);
                         println!("state value at V2: {:?} at index {}", write_set_2.1, idx);
+                        if status == ComparisonStatus::Match {
+                            status = mismatch("write_set_value");
+                        }
                     }
                 }
+                status
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataManager;
+    use aptos_types::write_set::{WriteOp, WriteSetMut};
+    use move_core_types::{language_storage::TypeTag, vm_status::StatusCode};
+
+    #[test]
+    fn test_report_records_mismatch() {
+        let execution = Execution::new(PathBuf::from("."), ExecutionMode::Compare);
+
+        let output = |data: Vec<u8>| {
+            Ok((WriteSet::default(), vec![ContractEvent::new_v2(
+                TypeTag::U64,
+                data,
+            )]))
+        };
+        let status = Execution::print_mismatches(10, &output(vec![1]), &output(vec![1]));
+        execution.record_comparison(10, status);
+        let status = Execution::print_mismatches(11, &output(vec![1]), &output(vec![2]));
+        execution.record_comparison(11, status);
+        let status = Execution::print_mismatches(
+            12,
+            &Err(VMStatus::error(StatusCode::ABORTED, None)),
+            &output(vec![1]),
+        );
+        execution.record_comparison(12, status);
+
+        let dir = tempfile::tempdir().unwrap();
+        let report_path = dir.path().join("report.json");
+        execution.write_report(&report_path).unwrap();
+        let reports: Vec<ComparisonReport> =
+            serde_json::from_reader(File::open(&report_path).unwrap()).unwrap();
+        assert_eq!(reports, vec![
+            ComparisonReport {
+                version: 10,
+                status: ComparisonStatus::Match,
+            },
+            ComparisonReport {
+                version: 11,
+                status: ComparisonStatus::Mismatch {
+                    field: "events".to_string(),
+                },
+            },
+            ComparisonReport {
+                version: 12,
+                status: ComparisonStatus::V1Error,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_write_set_mismatch_with_dump() {
+        let write_set = |values: &[(&str, u8)]| {
+            WriteSetMut::new(values.iter().map(|(key, value)| {
+                (
+                    StateKey::raw(key.as_bytes().to_vec()),
+                    WriteOp::legacy_modification(vec![*value].into()),
+                )
+            }))
+            .freeze()
+            .unwrap()
+        };
+        let executed = write_set(&[("a", 1), ("b", 2), ("c", 3)]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let data_manager = DataManager::new_with_dir_creation(dir.path());
+        assert!(data_manager.get_write_set(10).is_none());
+        data_manager.dump_write_set(10, &executed);
+        // Perturb the value written to `b`
+        data_manager.dump_write_set(11, &write_set(&[("a", 1), ("b", 20), ("c", 3)]));
+        // `c` is missing
+        data_manager.dump_write_set(12, &write_set(&[("a", 1), ("b", 2)]));
+
+        let first_mismatch = |version| {
+            Execution::first_write_set_mismatch(
+                &data_manager.get_write_set(version).unwrap(),
+                &executed,
+            )
+        };
+        assert_eq!(first_mismatch(10), None);
+        assert_eq!(first_mismatch(11), Some(StateKey::raw(b"b".to_vec())));
+        assert_eq!(first_mismatch(12), Some(StateKey::raw(b"c".to_vec())));
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_types::{
+    contract_event::ContractEvent, state_store::state_key::StateKey, transaction::Version,
+    vm_status::VMStatus, write_set::WriteSet,
+};
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+/// Inline style so that the report renders without fetching anything
+const STYLE: &str = "body{font-family:sans-serif}\
+table{border-collapse:collapse;width:100%;table-layout:fixed}\
+td,th{border:1px solid #ccc;padding:4px;vertical-align:top;font-family:monospace;\
+white-space:pre-wrap;word-break:break-all}\
+tr.diff td{background:#fdd}";
+
+/// Writes a self-contained HTML page showing the V1 and V2 execution results of a txn side by side
+pub struct HtmlDiffReporter;
+
+impl HtmlDiffReporter {
+    pub fn write_report(
+        version: Version,
+        v1_output: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
+        v2_output: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
+        path: &Path,
+    ) -> Result<()> {
+        fs::write(path, Self::render(version, v1_output, v2_output))?;
+        Ok(())
+    }
+
+    fn render(
+        version: Version,
+        v1_output: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
+        v2_output: &Result<(WriteSet, Vec<ContractEvent>), VMStatus>,
+    ) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>V1/V2 divergence at version {version}</title>\n<style>{STYLE}</style>\n\
+             </head>\n<body>\n<h1>V1/V2 divergence at version {version}</h1>\n"
+        );
+        match (v1_output, v2_output) {
+            (Ok((write_set_1, events_1)), Ok((write_set_2, events_2))) => {
+                Self::write_set_table(&mut html, write_set_1, write_set_2);
+                Self::events_table(&mut html, events_1, events_2);
+            },
+            _ => {
+                let status = |output: &Result<_, VMStatus>| match output {
+                    Ok(_) => "executed successfully".to_string(),
+                    Err(e) => format!("{:?}", e),
+                };
+                html.push_str("<h2>Status</h2>\n");
+                Self::table(&mut html, &["", "V1", "V2"], [(
+                    "status".to_string(),
+                    Some(status(v1_output)),
+                    Some(status(v2_output)),
+                )]);
+            },
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Rows are keyed by state key, so keys written by only one side show up as missing on the other
+    fn write_set_table(html: &mut String, write_set_1: &WriteSet, write_set_2: &WriteSet) {
+        let mut rows: BTreeMap<&StateKey, (Option<String>, Option<String>)> = BTreeMap::new();
+        for (key, op) in write_set_1.iter() {
+            rows.entry(key).or_default().0 = Some(format!("{:?}", op));
+        }
+        for (key, op) in write_set_2.iter() {
+            rows.entry(key).or_default().1 = Some(format!("{:?}", op));
+        }
+        html.push_str("<h2>Write set</h2>\n");
+        Self::table(
+            html,
+            &["State key", "V1", "V2"],
+            rows.into_iter()
+                .map(|(key, (op_1, op_2))| (format!("{:?}", key), op_1, op_2)),
+        );
+    }
+
+    fn events_table(html: &mut String, events_1: &[ContractEvent], events_2: &[ContractEvent]) {
+        let num_events = events_1.len().max(events_2.len());
+        html.push_str("<h2>Events</h2>\n");
+        Self::table(
+            html,
+            &["Index", "V1", "V2"],
+            (0..num_events).map(|idx| {
+                let event = |events: &[ContractEvent]| events.get(idx).map(|e| e.to_string());
+                (idx.to_string(), event(events_1), event(events_2))
+            }),
+        );
+    }
+
+    fn table(
+        html: &mut String,
+        headers: &[&str],
+        rows: impl IntoIterator<Item = (String, Option<String>, Option<String>)>,
+    ) {
+        html.push_str("<table>\n<tr>");
+        for header in headers {
+            write!(html, "<th>{}</th>", escape(header)).unwrap();
+        }
+        html.push_str("</tr>\n");
+        for (label, cell_1, cell_2) in rows {
+            let class = if cell_1 != cell_2 {
+                " class=\"diff\""
+            } else {
+                ""
+            };
+            let cell = |cell: Option<String>| escape(cell.as_deref().unwrap_or("(missing)"));
+            writeln!(
+                html,
+                "<tr{}><td>{}</td><td>{}</td><td>{}</td></tr>",
+                class,
+                escape(&label),
+                cell(cell_1),
+                cell(cell_2)
+            )
+            .unwrap();
+        }
+        html.push_str("</table>\n");
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::write_set::{WriteOp, WriteSetMut};
+    use move_core_types::{language_storage::TypeTag, vm_status::StatusCode};
+
+    fn output(
+        values: &[(&str, u8)],
+        event_data: Vec<u8>,
+    ) -> Result<(WriteSet, Vec<ContractEvent>), VMStatus> {
+        let write_set = WriteSetMut::new(values.iter().map(|(key, value)| {
+            (
+                StateKey::raw(key.as_bytes().to_vec()),
+                WriteOp::legacy_modification(vec![*value].into()),
+            )
+        }))
+        .freeze()
+        .unwrap();
+        Ok((write_set, vec![ContractEvent::new_v2(
+            TypeTag::U64,
+            event_data,
+        )]))
+    }
+
+    #[test]
+    fn test_html_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("10.html");
+        HtmlDiffReporter::write_report(
+            10,
+            &output(&[("a", 1), ("b", 2)], vec![1]),
+            &output(&[("a", 1), ("c", 3)], vec![2]),
+            &path,
+        )
+        .unwrap();
+
+        let html = fs::read_to_string(&path).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert!(html.contains("<h1>V1/V2 divergence at version 10</h1>"));
+        assert!(html.contains("<h2>Write set</h2>"));
+        assert!(html.contains("<h2>Events</h2>"));
+        // no external resources
+        assert!(!html.contains("http"));
+        // `a` matches, `b` is only written by V1 and `c` only by V2, the event differs
+        let rows: Vec<&str> = html
+            .lines()
+            .filter(|line| line.starts_with("<tr"))
+            .collect();
+        assert_eq!(rows.iter().filter(|row| row.starts_with("<tr>")).count(), 3);
+        assert_eq!(
+            rows.iter()
+                .filter(|row| row.starts_with("<tr class=\"diff\">"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            rows.iter().filter(|row| row.contains("(missing)")).count(),
+            2
+        );
+        assert_eq!(
+            html.matches("<table>").count(),
+            html.matches("</table>").count()
+        );
+    }
+
+    #[test]
+    fn test_html_report_error() {
+        let html = HtmlDiffReporter::render(
+            11,
+            &Err(VMStatus::error(StatusCode::ABORTED, None)),
+            &output(&[], vec![]),
+        );
+        assert!(html.contains("<h2>Status</h2>"));
+        assert!(html.contains("executed successfully"));
+        assert!(!html.contains("<h2>Write set</h2>"));
+    }
+}
@@ -2,15 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_framework::{
-    natives::code::PackageMetadata, unzip_metadata_str, BuildOptions, BuiltPackage, APTOS_PACKAGES,
+    natives::code::{MoveOption, PackageDep, PackageMetadata, UpgradePolicy},
+    unzip_metadata_str, BuildOptions, BuiltPackage, APTOS_PACKAGES,
 };
 use aptos_language_e2e_tests::data_store::FakeDataStore;
 use aptos_types::{
     account_address::AccountAddress,
     state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::Transaction,
+    transaction::{Transaction, Version},
     write_set::WriteSet,
 };
+use futures::{stream, StreamExt};
+use rayon::prelude::*;
 use rocksdb::{DBWithThreadMode, SingleThreaded, DB};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -19,15 +22,22 @@ use std::{
     fs::{File, OpenOptions},
     io::{BufRead, BufReader, BufWriter, Read, Write},
     path::{Path, PathBuf},
-    process::Command,
+    sync::Arc,
 };
 use tempfile::TempDir;
+use tokio::process::Command;
 
+mod comparison_output;
 mod data_collection;
+mod data_format;
 mod execution;
+mod html_report;
 
+pub use comparison_output::*;
 pub use data_collection::*;
+pub use data_format::*;
 pub use execution::*;
+pub use html_report::*;
 use move_compiler::compiled_unit::CompiledUnitEnum;
 use move_core_types::language_storage::ModuleId;
 use move_package::{
@@ -54,6 +64,72 @@ const ERR_LOG: &str = "err_log.txt";
 const ROCKS_INDEX_DB: &str = "rocks_txn_idx_db";
 pub const APTOS_COMMONS: &str = "aptos-commons";
 const MAX_TO_FLUSH: usize = 50000;
+/// Number of aptos packages copied at the same time
+const N_PARALLEL: usize = 4;
+
+/// Progress of `prepare_aptos_packages_with_progress`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PrepareProgress {
+    /// The aptos-core repository started being cloned
+    CloneStarted,
+    /// The aptos-core repository is cloned
+    CloneFinished,
+    /// A package is copied out of the cloned repository, reported once per package
+    PackageCopied {
+        current: usize,
+        total: usize,
+        package_name: String,
+    },
+}
+
+pub type PrepareProgressCallback = Arc<dyn Fn(PrepareProgress) + Send + Sync>;
+
+/// Txns to dump or execute
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VersionSelection {
+    /// `limit` txns from `begin`
+    Range { begin: u64, limit: u64 },
+    /// Exactly these versions, sorted and without duplicates
+    List(Vec<u64>),
+}
+
+impl VersionSelection {
+    /// Selects `versions` if given, otherwise the range. If both are given, all the versions must
+    /// be in the range.
+    pub fn new(
+        begin: Option<u64>,
+        limit: Option<u64>,
+        versions: Option<Vec<u64>>,
+    ) -> anyhow::Result<Self> {
+        let range = match (begin, limit) {
+            (Some(begin), Some(limit)) => Some((begin, limit)),
+            (None, None) => None,
+            _ => anyhow::bail!("--begin-version and --limit must be given together"),
+        };
+        match (range, versions) {
+            (Some((begin, limit)), None) => Ok(Self::Range { begin, limit }),
+            (None, None) => anyhow::bail!("either a range or --versions must be given"),
+            (range, Some(mut versions)) => {
+                versions.sort_unstable();
+                versions.dedup();
+                if let Some((begin, limit)) = range {
+                    if let Some(version) = versions
+                        .iter()
+                        .find(|version| !(begin..begin + limit).contains(*version))
+                    {
+                        anyhow::bail!(
+                            "version {} is outside of the range [{}, {})",
+                            version,
+                            begin,
+                            begin + limit
+                        );
+                    }
+                }
+                Ok(Self::List(versions))
+            },
+        }
+    }
+}
 
 struct IndexWriter {
     index_writer: BufWriter<File>,
@@ -110,7 +186,7 @@ impl IndexWriter {
     }
 
     pub fn write_err(&mut self, err_msg: &str) {
-        self.index_writer
+        self.err_logger
             .write_fmt(format_args!("{}\n", err_msg))
             .unwrap();
         self.err_logger.flush().unwrap();
@@ -162,6 +238,21 @@ impl IndexReader {
         Some(cur_idx.trim().parse().unwrap())
     }
 
+    /// Returns the last version in `[begin, end)` that was dumped to the index under `root`.
+    pub fn get_last_version_in_range(root: &Path, begin: u64, end: u64) -> Option<u64> {
+        if !Self::check_availability(root) {
+            return None;
+        }
+        let mut index_reader = Self::new(root);
+        let mut last_version = None;
+        while let Some(version) = index_reader.get_next_version() {
+            if (begin..end).contains(&version) {
+                last_version = last_version.max(Some(version));
+            }
+        }
+        last_version
+    }
+
     pub fn get_next_version_ge(&mut self, version: u64) -> Option<u64> {
         loop {
             let next_val = self.get_next_version();
@@ -219,7 +310,7 @@ impl DataManager {
             let mut data_state_file = File::create(state_path).unwrap();
             let state_store = FakeDataStore::new_with_state_value(state.to_owned());
             data_state_file
-                .write_all(&bcs::to_bytes(&state_store).unwrap())
+                .write_all(&data_format::encode(&state_store))
                 .unwrap();
         }
     }
@@ -231,16 +322,26 @@ impl DataManager {
         if !write_set_path.exists() {
             let mut write_set_file = File::create(write_set_path).unwrap();
             write_set_file
-                .write_all(&bcs::to_bytes(&write_set).unwrap())
+                .write_all(&data_format::encode(write_set))
                 .unwrap();
         }
     }
 
+    pub fn get_write_set(&self, version: u64) -> Option<WriteSet> {
+        let write_set_path = self
+            .write_set_dir_path
+            .join(format!("{}_write_set", version));
+        let mut write_set_file = File::open(write_set_path).ok()?;
+        let mut buffer = Vec::<u8>::new();
+        write_set_file.read_to_end(&mut buffer).unwrap();
+        Some(data_format::decode::<WriteSet>(&buffer).unwrap())
+    }
+
     pub fn dump_txn_index(&self, version: u64, version_idx: &TxnIndex) {
         self.db
             .put(
                 bcs::to_bytes(&version).unwrap(),
-                bcs::to_bytes(&version_idx).unwrap(),
+                data_format::encode(version_idx),
             )
             .unwrap();
     }
@@ -248,19 +349,28 @@ impl DataManager {
     pub fn get_txn_index(&self, version: u64) -> Option<TxnIndex> {
         let db_val = self.db.get(bcs::to_bytes(&version).unwrap());
         if let Ok(Some(val)) = db_val {
-            let txn_idx = bcs::from_bytes::<TxnIndex>(&val).unwrap();
+            let txn_idx = data_format::decode::<TxnIndex>(&val).unwrap();
             Some(txn_idx)
         } else {
             None
         }
     }
 
+    /// Reads the txn index of `version`, dumped in any supported data format.
+    pub fn read_txn_index_versioned(&self, version: Version) -> anyhow::Result<TxnIndex> {
+        let val = self
+            .db
+            .get(bcs::to_bytes(&version)?)?
+            .ok_or_else(|| anyhow::anyhow!("no txn index for version {}", version))?;
+        data_format::decode(&val)
+    }
+
     pub fn get_state(&self, version: u64) -> FakeDataStore {
         let state_path = self.state_data_dir_path.join(format!("{}_state", version));
         let mut data_state_file = File::open(state_path).unwrap();
         let mut buffer = Vec::<u8>::new();
         data_state_file.read_to_end(&mut buffer).unwrap();
-        bcs::from_bytes::<FakeDataStore>(&buffer).unwrap()
+        data_format::decode::<FakeDataStore>(&buffer).unwrap()
     }
 }
 
@@ -279,24 +389,61 @@ fn get_aptos_dir(package_name: &str) -> Option<&str> {
     None
 }
 
-async fn download_aptos_packages(path: &Path) -> anyhow::Result<()> {
+async fn download_aptos_packages(
+    path: &Path,
+    progress: PrepareProgressCallback,
+) -> anyhow::Result<()> {
     let git_url = "https://github.com/aptos-labs/aptos-core";
     let tmp_dir = TempDir::new()?;
-    Command::new("git")
+    progress(PrepareProgress::CloneStarted);
+    let status = Command::new("git")
         .args(["clone", git_url, tmp_dir.path().to_str().unwrap()])
-        .output()
+        .status()
+        .await
         .map_err(|_| anyhow::anyhow!("Failed to clone Git repository"))?;
+    anyhow::ensure!(
+        status.success(),
+        "Failed to clone Git repository: {}",
+        status
+    );
+    progress(PrepareProgress::CloneFinished);
     let source_framework_path = PathBuf::from(tmp_dir.path()).join("aptos-move/framework");
-    for package_name in APTOS_PACKAGES {
-        let source_framework_path =
-            source_framework_path.join(get_aptos_dir(package_name).unwrap());
-        let target_framework_path = PathBuf::from(path).join(get_aptos_dir(package_name).unwrap());
-        Command::new("cp")
-            .arg("-r")
-            .arg(source_framework_path)
-            .arg(target_framework_path)
-            .output()
-            .map_err(|_| anyhow::anyhow!("Failed to copy"))?;
+    copy_aptos_packages(&source_framework_path, path, progress).await
+}
+
+async fn copy_aptos_packages(
+    source_framework_path: &Path,
+    path: &Path,
+    progress: PrepareProgressCallback,
+) -> anyhow::Result<()> {
+    let total = APTOS_PACKAGES.len();
+    let mut copies = stream::iter(APTOS_PACKAGES)
+        .map(|package_name| async move {
+            let dir_name = get_aptos_dir(package_name).unwrap();
+            let status = Command::new("cp")
+                .arg("-r")
+                .arg(source_framework_path.join(dir_name))
+                .arg(path.join(dir_name))
+                .status()
+                .await
+                .map_err(|_| anyhow::anyhow!("Failed to copy"))?;
+            anyhow::ensure!(
+                status.success(),
+                "Failed to copy {}: {}",
+                package_name,
+                status
+            );
+            Ok::<_, anyhow::Error>(package_name)
+        })
+        .buffer_unordered(N_PARALLEL);
+    let mut current = 0;
+    while let Some(package_name) = copies.next().await {
+        current += 1;
+        progress(PrepareProgress::PackageCopied {
+            current,
+            total,
+            package_name: package_name?.to_string(),
+        });
     }
 
     Ok(())
@@ -315,9 +462,17 @@ fn check_aptos_packages_availability(path: PathBuf) -> bool {
 }
 
 pub async fn prepare_aptos_packages(path: PathBuf) {
+    prepare_aptos_packages_with_progress(path, Arc::new(|_| {})).await
+}
+
+/// Same as `prepare_aptos_packages`, calling `progress` after each package is prepared
+pub async fn prepare_aptos_packages_with_progress(
+    path: PathBuf,
+    progress: PrepareProgressCallback,
+) {
     if !path.exists() {
         std::fs::create_dir_all(path.clone()).unwrap();
-        download_aptos_packages(&path).await.unwrap();
+        download_aptos_packages(&path, progress).await.unwrap();
     }
 }
 
@@ -385,32 +540,152 @@ fn generate_compiled_blob(
     compiled_blobs.insert(package_info.clone(), blob_map);
 }
 
+/// Sorts the packages topologically with Kahn's algorithm. Each layer only depends on the
+/// packages of the previous layers, so the packages of a layer can be compiled in parallel.
+/// Dependencies on packages outside of `deps` are ignored.
+fn topological_layers(
+    deps: HashMap<PackageInfo, Vec<PackageInfo>>,
+) -> anyhow::Result<Vec<Vec<PackageInfo>>> {
+    let mut num_pending_deps: HashMap<&PackageInfo, usize> = HashMap::new();
+    let mut dependents: HashMap<&PackageInfo, Vec<&PackageInfo>> = HashMap::new();
+    for (package, package_deps) in &deps {
+        let package_deps: HashSet<&PackageInfo> = package_deps
+            .iter()
+            .filter(|dep| deps.contains_key(*dep))
+            .collect();
+        num_pending_deps.insert(package, package_deps.len());
+        for dep in package_deps {
+            dependents.entry(dep).or_default().push(package);
+        }
+    }
+
+    let mut layers = vec![];
+    let mut layer: Vec<&PackageInfo> = num_pending_deps
+        .iter()
+        .filter(|(_, num)| **num == 0)
+        .map(|(package, _)| *package)
+        .collect();
+    let mut num_sorted = 0;
+    while !layer.is_empty() {
+        let mut next_layer = vec![];
+        for package in &layer {
+            for dependent in dependents.get(package).into_iter().flatten() {
+                let num = num_pending_deps.get_mut(dependent).unwrap();
+                *num -= 1;
+                if *num == 0 {
+                    next_layer.push(*dependent);
+                }
+            }
+        }
+        num_sorted += layer.len();
+        let mut sorted_layer: Vec<PackageInfo> = layer.into_iter().cloned().collect();
+        sorted_layer.sort_by_key(|package| package.to_string());
+        layers.push(sorted_layer);
+        layer = next_layer;
+    }
+    if num_sorted < deps.len() {
+        return Err(anyhow::Error::msg("cyclic dependencies between packages"));
+    }
+    Ok(layers)
+}
+
+/// Returns the packages of `packages` in the order they can be compiled, see
+/// `topological_layers`.
+fn build_compilation_order(
+    packages: &HashMap<(AccountAddress, String), PackageMetadata>,
+) -> anyhow::Result<Vec<Vec<PackageInfo>>> {
+    let package_info = |address: AccountAddress, package_name: &str| PackageInfo {
+        address,
+        package_name: package_name.to_string(),
+        upgrade_number: if is_aptos_package(package_name) {
+            None
+        } else {
+            packages
+                .get(&(address, package_name.to_string()))
+                .map(|metadata| metadata.upgrade_number)
+        },
+    };
+    topological_layers(
+        packages
+            .iter()
+            .map(|((address, package_name), metadata)| {
+                let deps = metadata
+                    .deps
+                    .iter()
+                    .map(|dep| package_info(dep.account, &dep.package_name))
+                    .collect();
+                (package_info(*address, package_name), deps)
+            })
+            .collect(),
+    )
+}
+
+/// Returns the aptos packages in the order they can be compiled, based on the dependencies
+/// declared in their manifests. Only the name and the dependencies of the package metadata are
+/// filled in.
+fn aptos_packages_compilation_order(
+    aptos_commons_path: &Path,
+) -> anyhow::Result<Vec<Vec<PackageInfo>>> {
+    let mut packages = HashMap::new();
+    for package in APTOS_PACKAGES {
+        let manifest_path = aptos_commons_path
+            .join(get_aptos_dir(package).unwrap())
+            .join("Move.toml");
+        let manifest = parse_source_manifest(parse_move_manifest_string(
+            std::fs::read_to_string(manifest_path)?,
+        )?)?;
+        // For simplicity, all packages including aptos token are stored under 0x1 in the map
+        let deps = manifest
+            .dependencies
+            .keys()
+            .map(|dep| PackageDep {
+                account: AccountAddress::ONE,
+                package_name: dep.to_string(),
+            })
+            .collect();
+        packages.insert(
+            (AccountAddress::ONE, package.to_string()),
+            PackageMetadata {
+                name: package.to_string(),
+                upgrade_policy: UpgradePolicy::compat(),
+                upgrade_number: 0,
+                source_digest: String::new(),
+                manifest: vec![],
+                modules: vec![],
+                deps,
+                extension: MoveOption::none(),
+            },
+        );
+    }
+    build_compilation_order(&packages)
+}
+
 fn compile_aptos_packages(
     aptos_commons_path: &Path,
     compiled_package_map: &mut HashMap<PackageInfo, HashMap<ModuleId, Vec<u8>>>,
     v2_flag: bool,
 ) -> anyhow::Result<()> {
-    for package in APTOS_PACKAGES {
-        let root_package_dir = aptos_commons_path.join(get_aptos_dir(package).unwrap());
-        let compiler_verion = if v2_flag {
-            Some(CompilerVersion::V2)
-        } else {
-            None
-        };
-        // For simplicity, all packages including aptos token are stored under 0x1 in the map
-        let package_info = PackageInfo {
-            address: AccountAddress::ONE,
-            package_name: package.to_string(),
-            upgrade_number: None,
-        };
-        let compiled_package = compile_package(root_package_dir, &package_info, compiler_verion);
-        if let Ok(built_package) = compiled_package {
-            generate_compiled_blob(&package_info, &built_package, compiled_package_map);
-        } else {
-            return Err(anyhow::Error::msg(format!(
-                "package {} cannot be compiled",
-                package
-            )));
+    let compiler_verion = if v2_flag {
+        Some(CompilerVersion::V2)
+    } else {
+        None
+    };
+    for layer in aptos_packages_compilation_order(aptos_commons_path)? {
+        let compiled_packages = layer
+            .par_iter()
+            .map(|package_info| {
+                let root_package_dir =
+                    aptos_commons_path.join(get_aptos_dir(&package_info.package_name).unwrap());
+                compile_package(root_package_dir, package_info, compiler_verion).map_err(|_| {
+                    anyhow::Error::msg(format!(
+                        "package {} cannot be compiled",
+                        package_info.package_name
+                    ))
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        for (package_info, built_package) in layer.iter().zip(compiled_packages) {
+            generate_compiled_blob(package_info, &built_package, compiled_package_map);
         }
     }
     Ok(())
@@ -549,3 +824,157 @@ fn dump_and_compile_from_package_metadata(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn metadata(name: &str, deps: &[&str]) -> PackageMetadata {
+        PackageMetadata {
+            name: name.to_string(),
+            upgrade_policy: UpgradePolicy::compat(),
+            upgrade_number: 0,
+            source_digest: String::new(),
+            manifest: vec![],
+            modules: vec![],
+            deps: deps
+                .iter()
+                .map(|dep| PackageDep {
+                    account: AccountAddress::TWO,
+                    package_name: dep.to_string(),
+                })
+                .collect(),
+            extension: MoveOption::none(),
+        }
+    }
+
+    fn packages(graph: &[(&str, &[&str])]) -> HashMap<(AccountAddress, String), PackageMetadata> {
+        graph
+            .iter()
+            .map(|(name, deps)| {
+                (
+                    (AccountAddress::TWO, name.to_string()),
+                    metadata(name, deps),
+                )
+            })
+            .collect()
+    }
+
+    fn names(layers: Vec<Vec<PackageInfo>>) -> Vec<Vec<String>> {
+        layers
+            .into_iter()
+            .map(|layer| layer.into_iter().map(|p| p.package_name).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_build_compilation_order_diamond() {
+        // D depends on B and C, which both depend on A. E only depends on a package outside of the
+        // graph, which is assumed to be compiled already.
+        let layers = build_compilation_order(&packages(&[
+            ("D", &["B", "C"]),
+            ("B", &["A"]),
+            ("C", &["A"]),
+            ("A", &[]),
+            ("E", &["Other"]),
+        ]))
+        .unwrap();
+        assert_eq!(layers[0][0].upgrade_number, Some(0));
+        assert_eq!(names(layers), vec![vec!["A", "E"], vec!["B", "C"], vec![
+            "D"
+        ]]);
+
+        assert!(build_compilation_order(&packages(
+            &[("A", &["C"]), ("B", &["A"]), ("C", &["B"]),]
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_aptos_packages_compilation_order() {
+        let framework_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../framework");
+        assert_eq!(
+            names(aptos_packages_compilation_order(&framework_path).unwrap()),
+            vec![
+                vec!["MoveStdlib"],
+                vec!["AptosStdlib"],
+                vec!["AptosFramework"],
+                vec!["AptosToken", "AptosTokenObjects"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_version_selection() {
+        assert_eq!(
+            VersionSelection::new(Some(10), Some(5), None).unwrap(),
+            VersionSelection::Range {
+                begin: 10,
+                limit: 5
+            }
+        );
+        assert_eq!(
+            VersionSelection::new(None, None, Some(vec![789, 123, 456, 123])).unwrap(),
+            VersionSelection::List(vec![123, 456, 789])
+        );
+        assert_eq!(
+            VersionSelection::new(Some(100), Some(1000), Some(vec![789, 123])).unwrap(),
+            VersionSelection::List(vec![123, 789])
+        );
+        // 1100 conflicts with the range
+        assert!(VersionSelection::new(Some(100), Some(1000), Some(vec![123, 1100])).is_err());
+        assert!(VersionSelection::new(Some(100), None, Some(vec![123])).is_err());
+        assert!(VersionSelection::new(None, None, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_copy_aptos_packages_progress() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        for dir_name in APTOS_PACKAGES_DIR_NAMES {
+            std::fs::create_dir(source.path().join(dir_name)).unwrap();
+            std::fs::write(source.path().join(dir_name).join("Move.toml"), dir_name).unwrap();
+        }
+
+        let reported = Arc::new(Mutex::new(vec![]));
+        let progress = {
+            let reported = reported.clone();
+            Arc::new(move |p: PrepareProgress| reported.lock().unwrap().push(p))
+        };
+        copy_aptos_packages(source.path(), target.path(), progress)
+            .await
+            .unwrap();
+
+        assert!(check_aptos_packages_availability(
+            target.path().to_path_buf()
+        ));
+        for dir_name in APTOS_PACKAGES_DIR_NAMES {
+            assert_eq!(
+                std::fs::read_to_string(target.path().join(dir_name).join("Move.toml")).unwrap(),
+                dir_name
+            );
+        }
+        // Called exactly once per package, in completion order
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), APTOS_PACKAGES.len());
+        let mut package_names = vec![];
+        for (i, p) in reported.iter().enumerate() {
+            let PrepareProgress::PackageCopied {
+                current,
+                total,
+                package_name,
+            } = p
+            else {
+                panic!("unexpected progress: {:?}", p);
+            };
+            assert_eq!(*current, i + 1);
+            assert_eq!(*total, APTOS_PACKAGES.len());
+            package_names.push(package_name.as_str());
+        }
+        package_names.sort();
+        let mut expected = APTOS_PACKAGES.to_vec();
+        expected.sort();
+        assert_eq!(package_names, expected);
+    }
+}
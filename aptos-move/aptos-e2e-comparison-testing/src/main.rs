@@ -3,11 +3,13 @@
 
 use anyhow::Result;
 use aptos_comparison_testing::{
-    prepare_aptos_packages, DataCollection, Execution, ExecutionMode, APTOS_COMMONS,
+    prepare_aptos_packages_with_progress, DataCollection, Execution, ExecutionMode, OutputFormat,
+    PrepareProgress, PrepareProgressCallback, VersionSelection, APTOS_COMMONS,
 };
 use aptos_rest_client::Client;
+use aptos_validator_interface::DBDebuggerInterface;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 use url::Url;
 
 const BATCH_SIZE: u64 = 100;
@@ -35,6 +37,27 @@ pub enum Cmd {
         /// Dump the write set of txns
         #[clap(long, default_value_t = false)]
         dump_write_set: bool,
+        /// Fetch the state read by txns on demand from the local DB at `--db-path`
+        #[clap(long, default_value_t = false, requires = "db_path")]
+        lazy_state: bool,
+        /// Path to the local DB used by `--lazy-state`
+        #[clap(long)]
+        db_path: Option<PathBuf>,
+        /// Continue an interrupted dump to `output_path`, skipping the versions already dumped
+        #[clap(long, default_value_t = false)]
+        resume: bool,
+        /// Maximum number of txns executed at the same time, defaults to the number of logical cores
+        #[clap(long)]
+        max_concurrency: Option<usize>,
+        /// Check the write set of each executed txn against the one committed on chain
+        #[clap(long, default_value_t = false)]
+        verify_outputs: bool,
+        /// Only list the versions that would be dumped and why the others are skipped
+        #[clap(long, default_value_t = false)]
+        dry_run: bool,
+        /// Dump exactly these versions, e.g. `--versions 123,456,789`, instead of a range
+        #[clap(long, value_delimiter = ',', conflicts_with = "resume")]
+        versions: Option<Vec<u64>>,
     },
     /// Execution of txns
     Execute {
@@ -43,6 +66,25 @@ pub enum Cmd {
         /// Whether to execute against V1, V2 alone or both compilers for comparison
         #[clap(long)]
         execution_mode: Option<ExecutionMode>,
+        /// Write a JSON summary of the V1/V2 comparison results to this path
+        #[clap(long)]
+        report_path: Option<PathBuf>,
+        /// Check the write set of each txn against the one dumped with `--dump-write-set`
+        #[clap(long, default_value_t = false)]
+        compare_write_set: bool,
+        /// Where to write the divergences between V1 and V2 execution results
+        #[clap(long, value_enum, default_value_t = OutputFormat::Stdout)]
+        output_format: OutputFormat,
+        /// File the divergences are written to with `--output-format json`, or the divergence
+        /// counter values with `--output-format prometheus` instead of stdout
+        #[clap(long, required_if_eq("output_format", "json"))]
+        divergence_path: Option<PathBuf>,
+        /// Write an HTML report comparing the V1 and V2 results of each diverging txn to this directory
+        #[clap(long)]
+        html_report_dir: Option<PathBuf>,
+        /// Execute exactly these versions, e.g. `--versions 123,456,789`, instead of a range
+        #[clap(long, value_delimiter = ',')]
+        versions: Option<Vec<u64>>,
     },
 }
 
@@ -53,16 +95,36 @@ pub struct Argument {
 
     /// Scan/execute from the txn of this version
     #[clap(long)]
-    begin_version: u64,
+    begin_version: Option<u64>,
 
     /// Number of txns to scan/execute
     #[clap(long)]
-    limit: u64,
+    limit: Option<u64>,
+
+    /// Do not report the progress of downloading the aptos packages
+    #[clap(long, default_value_t = false)]
+    no_progress: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Argument::parse();
+    let progress: PrepareProgressCallback = if args.no_progress {
+        Arc::new(|_| {})
+    } else {
+        Arc::new(|p: PrepareProgress| match p {
+            PrepareProgress::CloneStarted => println!("cloning aptos-core"),
+            PrepareProgress::CloneFinished => println!("cloned aptos-core"),
+            PrepareProgress::PackageCopied {
+                current,
+                total,
+                package_name,
+            } => println!(
+                "prepared aptos package {} ({}/{})",
+                package_name, current, total
+            ),
+        })
+    };
 
     match args.cmd {
         Cmd::Dump {
@@ -72,19 +134,32 @@ async fn main() -> Result<()> {
             skip_publish_txns,
             skip_source_code_check: skip_source_code,
             dump_write_set,
+            lazy_state,
+            db_path,
+            resume,
+            max_concurrency,
+            verify_outputs,
+            dry_run,
+            versions,
         } => {
+            let selection = VersionSelection::new(args.begin_version, args.limit, versions)?;
             let batch_size = BATCH_SIZE;
             let output = if let Some(path) = output_path {
                 path
             } else {
                 PathBuf::from(".")
             };
-            if !output.exists() {
+            if !output.exists() && !dry_run {
                 std::fs::create_dir_all(output.as_path()).unwrap();
             }
-            if !skip_source_code {
-                prepare_aptos_packages(output.join(APTOS_COMMONS)).await;
+            if !skip_source_code && !dry_run {
+                prepare_aptos_packages_with_progress(output.join(APTOS_COMMONS), progress).await;
             }
+            let lazy_state_db = if lazy_state {
+                Some(DBDebuggerInterface::open(db_path.unwrap())?.db_reader())
+            } else {
+                None
+            };
             let data_collector = DataCollection::new_with_rest_client(
                 Client::new(Url::parse(&endpoint)?),
                 output.clone(),
@@ -93,25 +168,61 @@ async fn main() -> Result<()> {
                 skip_publish_txns,
                 dump_write_set,
                 skip_source_code,
-            )?;
-            data_collector
-                .dump_data(args.begin_version, args.limit)
-                .await?;
+                lazy_state_db,
+                max_concurrency.unwrap_or_else(num_cpus::get),
+            )?
+            .with_verify_outputs(verify_outputs);
+            if dry_run {
+                let report = match &selection {
+                    VersionSelection::Range { begin, limit } => {
+                        data_collector.dry_run(*begin, *limit, resume).await
+                    },
+                    VersionSelection::List(versions) => {
+                        data_collector.dry_run_versions(versions).await
+                    },
+                };
+                println!("versions to dump: {:?}", report.kept_versions);
+                for (version, reason) in report.skipped_versions {
+                    println!("skip version:{} reason:{:?}", version, reason);
+                }
+                return Ok(());
+            }
+            match &selection {
+                VersionSelection::Range { begin, limit } => {
+                    data_collector.dump_data(*begin, *limit, resume).await?
+                },
+                VersionSelection::List(versions) => data_collector.dump_versions(versions).await?,
+            }
         },
         Cmd::Execute {
             input_path,
             execution_mode,
+            report_path,
+            compare_write_set,
+            output_format,
+            divergence_path,
+            html_report_dir,
+            versions,
         } => {
+            let selection = VersionSelection::new(args.begin_version, args.limit, versions)?;
             let input = if let Some(path) = input_path {
                 path
             } else {
                 PathBuf::from(".")
             };
-            prepare_aptos_packages(input.join(APTOS_COMMONS)).await;
-            let executor = Execution::new(input, execution_mode.unwrap_or_default());
-            executor
-                .execute_txns(args.begin_version, args.limit)
-                .await?;
+            prepare_aptos_packages_with_progress(input.join(APTOS_COMMONS), progress).await;
+            let output = output_format.create_output(divergence_path.as_deref())?;
+            let executor =
+                Execution::new_with_output(input, execution_mode.unwrap_or_default(), output)
+                    .with_report_path(report_path)
+                    .with_compare_write_set(compare_write_set)
+                    .with_html_report_dir(html_report_dir);
+            match &selection {
+                VersionSelection::Range { begin, limit } => {
+                    executor.execute_txns(*begin, *limit).await?
+                },
+                VersionSelection::List(versions) => executor.execute_versions(versions).await?,
+            }
         },
     };
     Ok(())
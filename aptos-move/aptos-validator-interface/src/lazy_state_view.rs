@@ -0,0 +1,154 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        Result as StateViewResult, TStateView,
+    },
+    transaction::Version,
+};
+use dashmap::DashMap;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
+
+/// A state view that fetches state values from the underlying `DbReader` on demand, instead of
+/// loading all the relevant state upfront. Fetched values are cached, and every accessed key is
+/// recorded so that the read set can be dumped or checked against the write set afterwards.
+pub struct LazyStateView<V: DbReader + ?Sized> {
+    db: Arc<V>,
+    /// State is read as of this version.
+    version: Version,
+    cache: DashMap<StateKey, Option<StateValue>>,
+    visited_keys: Arc<Mutex<BTreeSet<StateKey>>>,
+}
+
+impl<V: DbReader + ?Sized> LazyStateView<V> {
+    pub fn new(db: Arc<V>, version: Version) -> Self {
+        Self {
+            db,
+            version,
+            cache: DashMap::new(),
+            visited_keys: Arc::new(Mutex::new(BTreeSet::new())),
+        }
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Number of keys fetched from the underlying DB so far.
+    pub fn num_fetched_keys(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns the existing state values read so far.
+    pub fn data_reads(&self) -> HashMap<StateKey, StateValue> {
+        self.cache
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .value()
+                    .as_ref()
+                    .map(|val| (entry.key().clone(), val.clone()))
+            })
+            .collect()
+    }
+
+    pub fn into_visited_keys(self) -> BTreeSet<StateKey> {
+        std::mem::take(&mut *self.visited_keys.lock().unwrap())
+    }
+}
+
+impl<V: DbReader + ?Sized> TStateView for LazyStateView<V> {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        self.visited_keys.lock().unwrap().insert(state_key.clone());
+        if let Some(val) = self.cache.get(state_key) {
+            return Ok(val.clone());
+        }
+        let val = self
+            .db
+            .get_state_value_by_version(state_key, self.version)?;
+        self.cache.insert(state_key.clone(), val.clone());
+        Ok(val)
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        Ok(self.db.get_state_storage_usage(Some(self.version))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LazyStateView;
+    use aptos_cached_packages::aptos_stdlib;
+    use aptos_db::AptosDB;
+    use aptos_language_e2e_tests::data_store::FakeDataStore;
+    use aptos_storage_interface::{DbReader, DbReaderWriter};
+    use aptos_types::{
+        account_config,
+        test_helpers::transaction_test_helpers,
+        transaction::{
+            signature_verified_transaction::SignatureVerifiedTransaction, Transaction, Version,
+        },
+    };
+    use aptos_vm::{AptosVM, VMExecutor};
+    use std::collections::HashMap;
+
+    const CHUNK_SIZE: usize = 1000;
+
+    /// Loads the entire state at the given version, the way an eager view would.
+    fn load_all_state(db: &dyn DbReader, version: Version) -> FakeDataStore {
+        let num_keys = db.get_state_leaf_count(version).unwrap();
+        let mut data = HashMap::new();
+        while data.len() < num_keys {
+            let chunk = db
+                .get_state_value_chunk_with_proof(version, data.len(), CHUNK_SIZE)
+                .unwrap();
+            data.extend(chunk.raw_values);
+        }
+        FakeDataStore::new_with_state_value(data)
+    }
+
+    #[test]
+    fn test_lazy_state_view_matches_eager_view() {
+        let tmp_dir = aptos_temppath::TempPath::new();
+        tmp_dir.create_as_dir().unwrap();
+        let (db, db_rw) = DbReaderWriter::wrap(AptosDB::new_for_test(tmp_dir.path()));
+        aptos_executor_test_helpers::bootstrap_genesis::<AptosVM>(
+            &db_rw,
+            &aptos_vm_genesis::test_genesis_transaction(),
+        )
+        .unwrap();
+        let version = db.get_latest_version().unwrap();
+
+        let address = account_config::aptos_test_root_address();
+        let txn = Transaction::UserTransaction(transaction_test_helpers::get_test_signed_txn(
+            address,
+            1,
+            &aptos_vm_genesis::GENESIS_KEYPAIR.0,
+            aptos_vm_genesis::GENESIS_KEYPAIR.1.clone(),
+            Some(aptos_stdlib::aptos_coin_mint(address, 100)),
+        ));
+        let txns: Vec<SignatureVerifiedTransaction> = vec![txn.into()];
+
+        let eager_view = load_all_state(db.as_ref(), version);
+        let eager_outputs = AptosVM::execute_block_no_limit(&txns, &eager_view).unwrap();
+
+        let lazy_view = LazyStateView::new(db.clone(), version);
+        let lazy_outputs = AptosVM::execute_block_no_limit(&txns, &lazy_view).unwrap();
+        assert_eq!(eager_outputs, lazy_outputs);
+
+        let num_keys = db.get_state_leaf_count(version).unwrap();
+        let num_fetched_keys = lazy_view.num_fetched_keys();
+        let visited_keys = lazy_view.into_visited_keys();
+        assert!(!visited_keys.is_empty());
+        assert_eq!(visited_keys.len(), num_fetched_keys);
+        assert!(num_fetched_keys < num_keys);
+    }
+}
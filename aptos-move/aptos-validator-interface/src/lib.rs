@@ -2,10 +2,14 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod lazy_state_view;
 mod rest_interface;
 mod storage_interface;
 
-pub use crate::{rest_interface::RestDebuggerInterface, storage_interface::DBDebuggerInterface};
+pub use crate::{
+    lazy_state_view::LazyStateView, rest_interface::RestDebuggerInterface,
+    storage_interface::DBDebuggerInterface,
+};
 use anyhow::{anyhow, Result};
 use aptos_framework::natives::code::PackageMetadata;
 use aptos_types::{
@@ -19,6 +23,7 @@ use aptos_types::{
         Result as StateViewResult, TStateView,
     },
     transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
 };
 use lru::LruCache;
 use move_binary_format::file_format::CompiledModule;
@@ -77,6 +82,9 @@ pub trait AptosValidatorInterface: Sync {
 
     async fn get_latest_version(&self) -> Result<Version>;
 
+    /// Returns the write set committed on chain by the txn at `version`.
+    async fn get_committed_write_set(&self, version: Version) -> Result<WriteSet>;
+
     async fn get_version_by_account_sequence(
         &self,
         account: AccountAddress,
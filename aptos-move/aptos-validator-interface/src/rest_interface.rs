@@ -20,6 +20,7 @@ use aptos_types::{
         EntryFunction, ExecutionStatus::MiscellaneousError, Transaction, TransactionInfo,
         TransactionPayload, Version,
     },
+    write_set::WriteSet,
 };
 use async_recursion::async_recursion;
 use move_core_types::language_storage::ModuleId;
@@ -319,6 +320,17 @@ impl AptosValidatorInterface for RestDebuggerInterface {
         Ok(self.0.get_ledger_information().await?.into_inner().version)
     }
 
+    async fn get_committed_write_set(&self, version: Version) -> Result<WriteSet> {
+        self.0
+            .get_transactions_bcs(Some(version), Some(1))
+            .await?
+            .into_inner()
+            .into_iter()
+            .next()
+            .map(|txn| txn.changes)
+            .ok_or_else(|| anyhow!("Txn at version {} not found", version))
+    }
+
     async fn get_version_by_account_sequence(
         &self,
         account: AccountAddress,
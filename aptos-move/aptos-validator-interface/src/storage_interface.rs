@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{AptosValidatorInterface, FilterCondition};
-use anyhow::{bail, ensure, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use aptos_config::config::{
     RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
@@ -16,6 +16,7 @@ use aptos_types::{
     account_state::AccountState,
     state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
     transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
 };
 use std::{collections::HashMap, path::Path, sync::Arc};
 
@@ -36,6 +37,10 @@ impl DBDebuggerInterface {
             .map_err(anyhow::Error::from)?,
         )))
     }
+
+    pub fn db_reader(&self) -> Arc<dyn DbReader> {
+        self.0.clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -113,6 +118,14 @@ impl AptosValidatorInterface for DBDebuggerInterface {
         self.0.get_latest_version().map_err(Into::into)
     }
 
+    async fn get_committed_write_set(&self, version: Version) -> Result<WriteSet> {
+        self.0
+            .get_write_set_iterator(version, 1)?
+            .next()
+            .ok_or_else(|| anyhow!("Txn at version {} not found", version))?
+            .map_err(Into::into)
+    }
+
     async fn get_version_by_account_sequence(
         &self,
         account: AccountAddress,
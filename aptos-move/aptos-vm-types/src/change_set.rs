@@ -449,6 +449,22 @@ impl VMChangeSet {
         Ok(())
     }
 
+    /// Removes the writes to the given keys from the resource write set.
+    pub(crate) fn remove_resource_writes<'a>(
+        &mut self,
+        keys: impl Iterator<Item = &'a StateKey>,
+    ) -> Result<(), PanicError> {
+        for key in keys {
+            self.resource_write_set.remove(key).ok_or_else(|| {
+                code_invariant_error(format!(
+                    "Cannot remove a resource write which does not exist, for: {:?}.",
+                    key
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
     /// The events are set to the input events.
     pub(crate) fn set_events(&mut self, materialized_events: impl Iterator<Item = ContractEvent>) {
         self.events = materialized_events
@@ -135,12 +135,14 @@ impl VMOutput {
     }
 
     /// Updates the VMChangeSet based on the input aggregator v1 deltas, patched resource write set,
-    /// patched events, and generates TransactionOutput
+    /// patched events, and generates TransactionOutput. The writes to `unchanged_resources`, which
+    /// leave the stored values as they are, are dropped after being patched.
     pub fn into_transaction_output_with_materialized_write_set(
         mut self,
         materialized_aggregator_v1_deltas: Vec<(StateKey, WriteOp)>,
         patched_resource_write_set: Vec<(StateKey, WriteOp)>,
         patched_events: Vec<ContractEvent>,
+        unchanged_resources: Vec<StateKey>,
     ) -> Result<TransactionOutput, PanicError> {
         // materialize aggregator V1 deltas into writes
         if materialized_aggregator_v1_deltas.len()
@@ -166,6 +168,8 @@ impl VMOutput {
         // materialize delayed fields into resource writes
         self.change_set
             .extend_resource_write_set(patched_resource_write_set.into_iter())?;
+        self.change_set
+            .remove_resource_writes(unchanged_resources.iter())?;
         let _ = self.change_set.drain_delayed_field_change_set();
 
         // materialize delayed fields into events
@@ -3,7 +3,10 @@
 
 use crate::{
     output::VMOutput,
-    tests::utils::{as_state_key, build_vm_output, mock_add, mock_create_with_layout, mock_modify},
+    tests::utils::{
+        as_state_key, build_vm_output, mock_add, mock_create_with_layout, mock_modify,
+        mock_modify_with_layout,
+    },
 };
 use aptos_aggregator::delta_change_set::serialize;
 use aptos_language_e2e_tests::data_store::FakeDataStore;
@@ -11,8 +14,11 @@ use aptos_types::{
     state_store::state_key::StateKey, transaction::TransactionOutput, write_set::WriteOp,
 };
 use claims::{assert_err, assert_matches, assert_ok};
-use move_core_types::vm_status::{AbortLocation, VMStatus};
-use std::collections::BTreeMap;
+use move_core_types::{
+    value::MoveTypeLayout,
+    vm_status::{AbortLocation, VMStatus},
+};
+use std::{collections::BTreeMap, sync::Arc};
 
 fn assert_eq_outputs(vm_output: &VMOutput, txn_output: TransactionOutput) {
     let vm_output_writes = &vm_output
@@ -58,7 +64,12 @@ fn test_ok_output_equality_no_deltas() {
         .try_materialize_into_transaction_output(&state_view));
     let txn_output_2 = assert_ok!(vm_output
         .clone()
-        .into_transaction_output_with_materialized_write_set(vec![], vec![], vec![]));
+        .into_transaction_output_with_materialized_write_set(
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ));
 
     // Because there are no deltas, we should not see any difference in write sets and
     // also all calls must succeed.
@@ -92,6 +103,7 @@ fn test_ok_output_equality_with_deltas() {
             vec![mock_modify("3", 400)],
             vec![],
             vec![],
+            vec![],
         );
 
     let expected_aggregator_write_set =
@@ -123,6 +135,43 @@ fn test_ok_output_equality_with_deltas() {
     assert_eq_outputs(&materialized_vm_output, txn_output_2.unwrap());
 }
 
+#[test]
+fn test_unchanged_resource_writes_are_dropped() {
+    let layout = Arc::new(MoveTypeLayout::U128);
+    let vm_output = build_vm_output(
+        vec![
+            mock_modify_with_layout("0", 0, Some(layout.clone())),
+            mock_modify_with_layout("1", 1, Some(layout)),
+        ],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    );
+
+    let txn_output = assert_ok!(vm_output
+        .clone()
+        .into_transaction_output_with_materialized_write_set(
+            vec![],
+            vec![mock_modify("0", 0), mock_modify("1", 10)],
+            vec![],
+            vec![as_state_key!("0")],
+        ));
+    let mut write_set_mut = txn_output.write_set().clone().into_mut();
+    assert_eq!(
+        write_set_mut.as_inner_mut(),
+        &BTreeMap::from([mock_modify("1", 10)])
+    );
+
+    // Dropping a write which is not in the output is an invariant violation.
+    assert_err!(vm_output.into_transaction_output_with_materialized_write_set(
+        vec![],
+        vec![mock_modify("1", 10)],
+        vec![],
+        vec![as_state_key!("2")],
+    ));
+}
+
 #[test]
 fn test_err_output_equality_with_deltas() {
     let delta_key = "3";
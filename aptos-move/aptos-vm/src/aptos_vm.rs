@@ -2031,6 +2031,8 @@ impl VMExecutor for AptosVM {
                     concurrency_level: Self::get_concurrency_level(),
                     allow_fallback: true,
                     discard_failed_blocks: Self::get_discard_failed_blocks(),
+                    id_start_value_seed: None,
+                    skip_unchanged_group_writes: false,
                 },
                 onchain: onchain_config,
             },
@@ -52,6 +52,7 @@ pub struct AptosTransactionOutput {
     // Note: should these mutexes be changed to ExplicitSyncSwapper?
     vm_output: Mutex<Option<VMOutput>>,
     committed_output: OnceCell<TransactionOutput>,
+    group_reads_needing_exchange: OnceCell<Vec<(StateKey, StateValueMetadata)>>,
 }
 
 impl AptosTransactionOutput {
@@ -59,6 +60,7 @@ impl AptosTransactionOutput {
         Self {
             vm_output: Mutex::new(Some(output)),
             committed_output: OnceCell::new(),
+            group_reads_needing_exchange: OnceCell::new(),
         }
     }
 
@@ -243,24 +245,26 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
             .collect()
     }
 
-    fn group_reads_needing_delayed_field_exchange(&self) -> Vec<(StateKey, StateValueMetadata)> {
-        self.vm_output
-            .lock()
-            .as_ref()
-            .expect("Output to be set to get reads")
-            .change_set()
-            .resource_write_set()
-            .iter()
-            .flat_map(|(key, write)| {
-                if let AbstractResourceWriteOp::ResourceGroupInPlaceDelayedFieldChange(change) =
-                    write
-                {
-                    Some((key.clone(), change.metadata.clone()))
-                } else {
-                    None
-                }
-            })
-            .collect()
+    fn cached_group_reads_needing_exchange(&self) -> &[(StateKey, StateValueMetadata)] {
+        self.group_reads_needing_exchange.get_or_init(|| {
+            self.vm_output
+                .lock()
+                .as_ref()
+                .expect("Output to be set to get reads")
+                .change_set()
+                .resource_write_set()
+                .iter()
+                .flat_map(|(key, write)| {
+                    if let AbstractResourceWriteOp::ResourceGroupInPlaceDelayedFieldChange(change) =
+                        write
+                    {
+                        Some((key.clone(), change.metadata.clone()))
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        })
     }
 
     /// Should never be called after incorporating materialized output, as that consumes vm_output.
@@ -288,6 +292,7 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
         aggregator_v1_writes: Vec<(StateKey, WriteOp)>,
         materialized_resource_write_set: Vec<(StateKey, WriteOp)>,
         materialized_events: Vec<ContractEvent>,
+        unchanged_resource_groups: Vec<StateKey>,
     ) -> Result<(), PanicError> {
         assert!(
             self.committed_output
@@ -300,6 +305,7 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
                             aggregator_v1_writes,
                             materialized_resource_write_set,
                             materialized_events,
+                            unchanged_resource_groups,
                         )?,
                 )
                 .is_ok(),
@@ -64,6 +64,8 @@ impl<S: StateView + Sync + Send + 'static> GlobalExecutor<S> {
                     concurrency_level: self.concurrency_level,
                     allow_fallback: true,
                     discard_failed_blocks: false,
+                    id_start_value_seed: None,
+                    skip_unchanged_group_writes: false,
                 },
                 onchain: onchain_config,
             },
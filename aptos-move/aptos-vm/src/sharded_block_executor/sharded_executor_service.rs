@@ -233,6 +233,8 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                                 concurrency_level: concurrency_level_per_shard,
                                 allow_fallback: true,
                                 discard_failed_blocks: false,
+                                id_start_value_seed: None,
+                                skip_unchanged_group_writes: false,
                             },
                             onchain: onchain_config,
                         },
@@ -313,6 +313,9 @@ pub(crate) struct CapturedReads<T: Transaction> {
     /// Set if the invarint on CapturedReads intended use is violated. Leads to an alert
     /// and sequential execution fallback.
     incorrect_use: bool,
+    /// Set if the transaction must be re-executed even if all reads are still valid,
+    /// e.g. after an error that may have been caused by speculative reads.
+    reexecution_requested: bool,
 }
 
 #[derive(Debug)]
@@ -714,6 +717,14 @@ impl<T: Transaction> CapturedReads<T> {
     pub(crate) fn mark_incorrect_use(&mut self) {
         self.incorrect_use = true;
     }
+
+    pub(crate) fn request_reexecution(&mut self) {
+        self.reexecution_requested = true;
+    }
+
+    pub(crate) fn is_reexecution_requested(&self) -> bool {
+        self.reexecution_requested
+    }
 }
 
 #[derive(Derivative)]
@@ -26,6 +26,14 @@ impl Mode {
     pub const SEQUENTIAL: &'static str = "sequential";
 }
 
+/// What delayed field identifiers are being replaced with values in.
+pub struct DelayedFieldExchangeKind;
+
+impl DelayedFieldExchangeKind {
+    pub const GROUP_WRITES: &'static str = "group_writes";
+    pub const RESOURCE_WRITES: &'static str = "resource_writes";
+}
+
 fn time_buckets() -> std::vec::Vec<f64> {
     exponential_buckets(
         /*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30,
@@ -56,6 +64,15 @@ pub static MODULE_PUBLISHING_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of resource group writes skipped because they left the group unchanged.
+pub static SKIPPED_UNCHANGED_GROUP_WRITES_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_skipped_unchanged_group_writes_count",
+        "Count of resource group writes skipped as unchanged (sequential execution)"
+    )
+    .unwrap()
+});
+
 /// Count of speculative transaction re-executions due to a failed validation.
 pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -85,6 +102,26 @@ pub static EXCEED_PER_BLOCK_OUTPUT_LIMIT_COUNT: Lazy<IntCounterVec> = Lazy::new(
     .unwrap()
 });
 
+/// Count of retriable errors returned by speculative executions, by error kind.
+pub static BLOCK_EXECUTOR_RETRIABLE_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_block_executor_retriable_errors_total",
+        "Count of retriable errors returned by speculative executions",
+        &["kind"]
+    )
+    .unwrap()
+});
+
+/// Count of resource group errors that made the executor fall back, by reason.
+pub static RESOURCE_GROUP_FALLBACK_REASONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_block_executor_resource_group_fallback_reasons_total",
+        "Count of resource group errors that made the executor fall back, by reason",
+        &["reason"]
+    )
+    .unwrap()
+});
+
 pub static PARALLEL_EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
@@ -160,6 +197,28 @@ pub static DEPENDENCY_WAIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static DELAYED_FIELD_EXCHANGE_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_execution_delayed_field_exchange_seconds",
+        // metric description
+        "The time spent in seconds replacing delayed field identifiers with values in a txn output",
+        &["kind"],
+        time_buckets(),
+    )
+    .unwrap()
+});
+
+/// Count of delayed field identifiers replaced with values.
+pub static DELAYED_FIELD_IDS_REPLACED_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_delayed_field_ids_replaced_count",
+        "Count of delayed field identifiers replaced with values",
+        &["kind"]
+    )
+    .unwrap()
+});
+
 pub static BLOCK_GAS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "aptos_execution_block_gas",
@@ -2,7 +2,73 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_aggregator::types::code_invariant_error;
 use aptos_types::delayed_fields::PanicError;
+use move_core_types::vm_status::{StatusCode, StatusType, VMStatus};
+use std::fmt;
+
+/// Classification of the errors returned by `ExecutorTask`, used to decide whether the parallel
+/// execution can recover from an error by re-executing the transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Failure that may be caused by speculative reads of data written by other transactions.
+    ResourceConflict,
+    /// Failure caused by a bug, that re-executing the transaction will not fix.
+    InvariantViolation,
+    /// Execution ran out of gas or hit an execution limit, which depends on the data read.
+    GasExhausted,
+    /// Failure of a dependency outside of the block executor, e.g. the storage.
+    ExternalDependency,
+    /// Any other failure, which is not known to depend on speculative reads.
+    Other,
+}
+
+impl ErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::ResourceConflict => "resource_conflict",
+            ErrorKind::InvariantViolation => "invariant_violation",
+            ErrorKind::GasExhausted => "gas_exhausted",
+            ErrorKind::ExternalDependency => "external_dependency",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+/// Bound on the errors of `ExecutorTask`.
+pub trait ExecutorError {
+    fn error_kind(&self) -> ErrorKind;
+
+    /// Whether re-executing the transaction in the parallel execution may resolve the error.
+    /// Otherwise, the parallel execution falls back to the sequential one right away.
+    fn is_retriable(&self) -> bool {
+        matches!(
+            self.error_kind(),
+            ErrorKind::ResourceConflict | ErrorKind::GasExhausted
+        )
+    }
+}
+
+impl ExecutorError for VMStatus {
+    fn error_kind(&self) -> ErrorKind {
+        match self.status_code() {
+            StatusCode::SPECULATIVE_EXECUTION_ABORT_ERROR => ErrorKind::ResourceConflict,
+            StatusCode::STORAGE_ERROR => ErrorKind::ExternalDependency,
+            StatusCode::OUT_OF_GAS
+            | StatusCode::EXECUTION_LIMIT_REACHED
+            | StatusCode::IO_LIMIT_REACHED
+            | StatusCode::STORAGE_LIMIT_REACHED => ErrorKind::GasExhausted,
+            _ => match self.status_type() {
+                StatusType::InvariantViolation => ErrorKind::InvariantViolation,
+                StatusType::Validation
+                | StatusType::Verification
+                | StatusType::Deserialization
+                | StatusType::Execution
+                | StatusType::Unknown => ErrorKind::Other,
+            },
+        }
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum ParallelBlockExecutionError {
@@ -15,17 +81,87 @@ pub(crate) enum ParallelBlockExecutionError {
     FatalVMError,
 }
 
-// This is separate error because we need to match the error variant to provide a specialized
-// fallback logic if a resource group serialization error occurs.
+/// Errors that can occur when finalizing and serializing resource groups at commit time.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) struct ResourceGroupSerializationError;
+pub(crate) enum ResourceGroupError {
+    /// Finalized group emptiness does not agree with whether the metadata op is a deletion.
+    EmptyMismatch { is_empty: bool, is_deletion: bool },
+    /// Group was only read and exchanged, but its metadata op is a deletion.
+    DeletionWithExchange,
+    /// BCS serialization of the finalized group failed.
+    SerializationFailed,
+    /// Finalizing the group in the multi-versioned data structure failed.
+    CommitFailed(String),
+}
+
+impl ResourceGroupError {
+    /// Why the error made the executor fall back, `None` for commit failures.
+    pub(crate) fn fallback_reason(&self) -> Option<FallbackReason> {
+        match self {
+            ResourceGroupError::EmptyMismatch { .. } => Some(FallbackReason::EmptyMismatch),
+            ResourceGroupError::DeletionWithExchange => Some(FallbackReason::DeletionWithExchange),
+            ResourceGroupError::SerializationFailed => Some(FallbackReason::SerializationFailed),
+            ResourceGroupError::CommitFailed(_) => None,
+        }
+    }
+}
+
+/// Reasons for falling back due to resource group errors, aggregated in
+/// `RESOURCE_GROUP_FALLBACK_REASONS`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FallbackReason {
+    SerializationFailed,
+    EmptyMismatch,
+    DeletionWithExchange,
+}
+
+impl FallbackReason {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            FallbackReason::SerializationFailed => "serialization_failed",
+            FallbackReason::EmptyMismatch => "empty_mismatch",
+            FallbackReason::DeletionWithExchange => "deletion_with_exchange",
+        }
+    }
+}
+
+impl fmt::Display for ResourceGroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceGroupError::EmptyMismatch {
+                is_empty,
+                is_deletion,
+            } => write!(
+                f,
+                "Group is empty = {} but op is deletion = {} in parallel execution",
+                is_empty, is_deletion
+            ),
+            ResourceGroupError::DeletionWithExchange => write!(
+                f,
+                "Value only read and exchanged, but metadata op is Deletion"
+            ),
+            ResourceGroupError::SerializationFailed => {
+                write!(f, "Resource group serialization failed")
+            },
+            ResourceGroupError::CommitFailed(e) => {
+                write!(f, "Error committing resource group {}", e)
+            },
+        }
+    }
+}
+
+impl From<ResourceGroupError> for PanicError {
+    fn from(err: ResourceGroupError) -> Self {
+        code_invariant_error(err.to_string())
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 /// Logging is bottlenecked in constructors.
 pub(crate) enum SequentialBlockExecutionError<E> {
     // This is separate error because we need to match the error variant to provide a specialized
     // fallback logic if a resource group serialization error occurs.
-    ResourceGroupSerializationError,
+    ResourceGroupError(ResourceGroupError),
     ErrorToReturn(BlockExecutionError<E>),
 }
 
@@ -54,3 +190,18 @@ impl<E> From<PanicError> for SequentialBlockExecutionError<E> {
         ))
     }
 }
+
+impl<E> From<ResourceGroupError> for SequentialBlockExecutionError<E> {
+    fn from(err: ResourceGroupError) -> Self {
+        match err {
+            // Serialization failures are handled by falling back to sequential execution
+            // without dynamic change set optimizations, everything else is a code invariant error.
+            ResourceGroupError::SerializationFailed => {
+                SequentialBlockExecutionError::ResourceGroupError(err)
+            },
+            _ => SequentialBlockExecutionError::ErrorToReturn(
+                BlockExecutionError::FatalBlockExecutorError(err.into()),
+            ),
+        }
+    }
+}
@@ -52,7 +52,7 @@ use num_cpus;
 use rayon::ThreadPool;
 use std::{
     cell::RefCell,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
     marker::{PhantomData, Sync},
     sync::{
         atomic::{AtomicBool, AtomicU32, Ordering},
@@ -60,6 +60,10 @@ use std::{
     },
 };
 
+/// Incarnations below which a transaction that failed with a retriable error is re-executed
+/// regardless of whether its reads were invalidated.
+const MAX_RETRIABLE_ERROR_INCARNATION: Incarnation = 3;
+
 pub struct BlockExecutor<T, E, S, L, X> {
     // Number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
@@ -97,6 +101,13 @@ where
         }
     }
 
+    fn gen_id_start_value(&self, sequential: bool) -> u32 {
+        match self.config.local.id_start_value_seed {
+            Some(seed) => gen_id_start_value_seeded(sequential, seed),
+            None => gen_id_start_value(sequential),
+        }
+    }
+
     fn execute(
         idx_to_execute: TxnIndex,
         incarnation: Incarnation,
@@ -257,10 +268,26 @@ where
                 )
             },
             ExecutionStatus::Abort(err) => {
-                // Abort indicates an unrecoverable VM failure, but currently it seemingly
-                // can occur due to speculative execution (in particular for BlockMetadata txn).
-                // Therefore, we do not short circuit here. TODO: investigate if we can
-                // eliminate the scenarios when Abort status can happen speculatively.
+                if !err.is_retriable() {
+                    // Re-execution cannot resolve the error, so there is no need to wait
+                    // for the status to be committed before falling back.
+                    error!(
+                        "Non-retriable error from parallel execution {:?} at txn {}",
+                        err, idx_to_execute
+                    );
+                    return Err(PanicOr::Or(ParallelBlockExecutionError::FatalVMError));
+                }
+
+                // Abort can occur due to speculative execution (in particular for
+                // BlockMetadata txn), so the transaction is re-executed even if its reads
+                // stay valid. After MAX_RETRIABLE_ERROR_INCARNATION, the status is kept and
+                // only re-executed if the reads are invalidated, as any other status.
+                counters::BLOCK_EXECUTOR_RETRIABLE_ERRORS
+                    .with_label_values(&[err.error_kind().as_str()])
+                    .inc();
+                if incarnation < MAX_RETRIABLE_ERROR_INCARNATION {
+                    read_set.request_reexecution();
+                }
                 (ExecutionStatus::Abort(err), Vec::new())
             },
             ExecutionStatus::DelayedFieldsCodeInvariantError(msg) => {
@@ -316,6 +343,10 @@ where
             ));
         }
 
+        if read_set.is_reexecution_requested() {
+            return Ok(false);
+        }
+
         // Note: we validate delayed field reads only at try_commit.
         // TODO[agg_v2](optimize): potentially add some basic validation.
         // TODO[agg_v2](optimize): potentially add more sophisticated validation, but if it fails,
@@ -545,6 +576,7 @@ where
                         metadata_op,
                         is_read_needing_exchange,
                     )
+                    .map_err(PanicError::from)
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
@@ -636,6 +668,30 @@ where
         aggregator_v1_delta_writes
     }
 
+    /// Returns the keys of the serialized group writes that leave the group with the same bytes
+    /// and metadata as before the transaction. `group_values` holds the values of the groups
+    /// before the transaction, and is updated with the serialized ones.
+    fn unchanged_group_writes(
+        serialized_groups: &[(T::Key, T::Value)],
+        group_values: &mut HashMap<T::Key, Arc<T::Value>>,
+    ) -> Vec<T::Key> {
+        let mut unchanged_group_keys = Vec::new();
+        for (group_key, group_write) in serialized_groups {
+            let unchanged = match (group_values.get(group_key), group_write.bytes()) {
+                (Some(previous), Some(bytes)) => {
+                    previous.as_state_value_metadata() == group_write.as_state_value_metadata()
+                        && !group_changed(previous.bytes(), bytes)
+                },
+                _ => false,
+            };
+            if unchanged {
+                unchanged_group_keys.push(group_key.clone());
+            }
+            group_values.insert(group_key.clone(), Arc::new(group_write.clone()));
+        }
+        unchanged_group_keys
+    }
+
     fn materialize_txn_commit(
         &self,
         txn_idx: TxnIndex,
@@ -850,7 +906,7 @@ where
         );
 
         let versioned_cache = MVHashMap::new();
-        let start_shared_counter = gen_id_start_value(false);
+        let start_shared_counter = self.gen_id_start_value(false);
         let shared_counter = AtomicU32::new(start_shared_counter);
 
         if signature_verified_block.is_empty() {
@@ -1006,7 +1062,7 @@ where
         let executor = E::init(executor_arguments);
         drop(init_timer);
 
-        let start_counter = gen_id_start_value(true);
+        let start_counter = self.gen_id_start_value(true);
         let counter = RefCell::new(start_counter);
         let unsync_map = UnsyncMap::new();
         let mut ret = Vec::with_capacity(num_txns);
@@ -1017,6 +1073,9 @@ where
 
         let last_input_output: TxnLastInputOutput<T, E::Output, E::Error> =
             TxnLastInputOutput::new(num_txns as TxnIndex);
+        // Values of the groups as of the last committed transaction, for skipping unchanged
+        // group writes. Only tracked if enabled in the config.
+        let mut group_values_in_block: HashMap<T::Key, Arc<T::Value>> = HashMap::new();
 
         for (idx, txn) in signature_verified_block.iter().enumerate() {
             let latest_view = LatestView::<T, S, X>::new(
@@ -1138,18 +1197,17 @@ where
                         };
 
                         // The IDs are not exchanged but it doesn't change the types (Bytes) or size.
-                        let serialization_error = output
-                            .group_reads_needing_delayed_field_exchange()
-                            .iter()
-                            .any(|(group_key, _)| {
-                                fail_point!("fail-point-resource-group-serialization", |_| {
-                                    true
-                                });
-
-                                let finalized_group = finalize(group_key.clone());
-                                bcs::to_bytes(&finalized_group).is_err()
-                            })
-                            || output.resource_group_write_set().into_iter().any(
+                        let serialization_error =
+                            output.cached_group_reads_needing_exchange().iter().any(
+                                |(group_key, _)| {
+                                    fail_point!("fail-point-resource-group-serialization", |_| {
+                                        true
+                                    });
+
+                                    let finalized_group = finalize(group_key.clone());
+                                    bcs::to_bytes(&finalized_group).is_err()
+                                },
+                            ) || output.resource_group_write_set().into_iter().any(
                                 |(group_key, _, group_ops)| {
                                     fail_point!("fail-point-resource-group-serialization", |_| {
                                         true
@@ -1183,6 +1241,20 @@ where
                         }
                     };
 
+                    if self.config.local.skip_unchanged_group_writes {
+                        // The writes below replace the base values of the groups read from
+                        // storage, so record them first.
+                        for ((group_key, _), _) in groups_to_finalize!(output,) {
+                            if let Entry::Vacant(entry) = group_values_in_block.entry(group_key) {
+                                if let Some(ValueWithLayout::RawFromStorage(value)) =
+                                    unsync_map.fetch_data(entry.key())
+                                {
+                                    entry.insert(value);
+                                }
+                            }
+                        }
+                    }
+
                     // Apply the writes.
                     let resource_write_set = output.resource_write_set();
                     Self::apply_output_sequential(
@@ -1208,9 +1280,18 @@ where
                         let materialized_finalized_groups =
                             map_id_to_values_in_group_writes(finalized_groups, &latest_view)?;
                         let serialized_groups =
-                            serialize_groups::<T>(materialized_finalized_groups).map_err(|_| {
-                                SequentialBlockExecutionError::ResourceGroupSerializationError
-                            })?;
+                            serialize_groups::<T>(materialized_finalized_groups)?;
+                        let unchanged_group_writes =
+                            if self.config.local.skip_unchanged_group_writes {
+                                Self::unchanged_group_writes(
+                                    &serialized_groups,
+                                    &mut group_values_in_block,
+                                )
+                            } else {
+                                vec![]
+                            };
+                        counters::SKIPPED_UNCHANGED_GROUP_WRITES_COUNT
+                            .inc_by(unchanged_group_writes.len() as u64);
 
                         let resource_writes_to_materialize = resource_writes_to_materialize!(
                             resource_write_set,
@@ -1239,6 +1320,7 @@ where
                                 .chain(serialized_groups.into_iter())
                                 .collect(),
                             materialized_events,
+                            unchanged_group_writes,
                         )?;
                     }
                     // If dynamic change set is disabled, this can be used to assert nothing needs patching instead:
@@ -1319,7 +1401,7 @@ where
             Ok(output) => {
                 return Ok(output);
             },
-            Err(SequentialBlockExecutionError::ResourceGroupSerializationError) => {
+            Err(SequentialBlockExecutionError::ResourceGroupError(_)) => {
                 if !self.config.local.allow_fallback {
                     panic!("Parallel execution failed and fallback is not allowed");
                 }
@@ -1342,7 +1424,7 @@ where
                     Ok(output) => {
                         return Ok(output);
                     },
-                    Err(SequentialBlockExecutionError::ResourceGroupSerializationError) => {
+                    Err(SequentialBlockExecutionError::ResourceGroupError(_)) => {
                         BlockExecutionError::FatalBlockExecutorError(code_invariant_error(
                             "resource group serialization during bcs fallback should not happen",
                         ))
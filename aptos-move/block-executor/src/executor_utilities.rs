@@ -1,7 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{errors::*, view::LatestView};
+use crate::{
+    counters::{
+        DelayedFieldExchangeKind, DELAYED_FIELD_EXCHANGE_SECONDS, DELAYED_FIELD_IDS_REPLACED_COUNT,
+        RESOURCE_GROUP_FALLBACK_REASONS,
+    },
+    errors::*,
+    view::LatestView,
+};
 use aptos_aggregator::types::code_invariant_error;
 use aptos_logger::error;
 use aptos_mvhashmap::types::ValueWithLayout;
@@ -14,7 +21,7 @@ use aptos_vm_logging::{alert, prelude::*};
 use bytes::Bytes;
 use fail::fail_point;
 use move_core_types::value::MoveTypeLayout;
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 use std::{collections::BTreeMap, sync::Arc};
 
 // TODO(clean-up): refactor & replace these macros with functions for code clarity. Currently
@@ -87,7 +94,7 @@ pub(crate) fn map_finalized_group<T: Transaction>(
     finalized_group: anyhow::Result<Vec<(T::Tag, ValueWithLayout<T::Value>)>>,
     metadata_op: T::Value,
     is_read_needing_exchange: bool,
-) -> Result<(T::Key, T::Value, Vec<(T::Tag, ValueWithLayout<T::Value>)>), PanicError> {
+) -> Result<(T::Key, T::Value, Vec<(T::Tag, ValueWithLayout<T::Value>)>), ResourceGroupError> {
     let metadata_is_deletion = metadata_op.is_deletion();
 
     match finalized_group {
@@ -95,34 +102,28 @@ pub(crate) fn map_finalized_group<T: Transaction>(
             if is_read_needing_exchange && metadata_is_deletion {
                 // Value needed exchange but was not written / modified during the txn
                 // execution: may not be empty.
-                Err(code_invariant_error(
-                    "Value only read and exchanged, but metadata op is Deletion".to_string(),
-                ))
+                Err(record_fallback(ResourceGroupError::DeletionWithExchange))
             } else if finalized_group.is_empty() != metadata_is_deletion {
                 // finalize_group already applies the deletions.
-                Err(code_invariant_error(format!(
-                    "Group is empty = {} but op is deletion = {} in parallel execution",
-                    finalized_group.is_empty(),
-                    metadata_is_deletion
-                )))
+                Err(record_fallback(ResourceGroupError::EmptyMismatch {
+                    is_empty: finalized_group.is_empty(),
+                    is_deletion: metadata_is_deletion,
+                }))
             } else {
                 Ok((group_key, metadata_op, finalized_group))
             }
         },
-        Err(e) => Err(code_invariant_error(format!(
-            "Error committing resource group {:?}",
-            e
-        ))),
+        Err(e) => Err(ResourceGroupError::CommitFailed(format!("{:?}", e))),
     }
 }
 
 pub(crate) fn serialize_groups<T: Transaction>(
     finalized_groups: Vec<(T::Key, T::Value, Vec<(T::Tag, Arc<T::Value>)>)>,
-) -> Result<Vec<(T::Key, T::Value)>, ResourceGroupSerializationError> {
+) -> Result<Vec<(T::Key, T::Value)>, ResourceGroupError> {
     fail_point!(
         "fail-point-resource-group-serialization",
         !finalized_groups.is_empty(),
-        |_| Err(ResourceGroupSerializationError)
+        |_| Err(ResourceGroupError::SerializationFailed)
     );
 
     finalized_groups
@@ -131,18 +132,18 @@ pub(crate) fn serialize_groups<T: Transaction>(
             let btree: BTreeMap<T::Tag, Bytes> = finalized_group
                 .into_iter()
                 .map(|(resource_tag, arc_v)| {
-                    let bytes = arc_v
+                    // Deletions should already be applied when finalizing the group.
+                    arc_v
                         .extract_raw_bytes()
-                        .expect("Deletions should already be applied");
-                    (resource_tag, bytes)
+                        .map(|bytes| (resource_tag, bytes))
+                        .ok_or_else(|| {
+                            resource_group_error(format!("Group member {:?} has no bytes", arc_v))
+                        })
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
 
             bcs::to_bytes(&btree)
-                .map_err(|e| {
-                    alert!("Unexpected resource group error {:?}", e);
-                    ResourceGroupSerializationError
-                })
+                .map_err(|e| resource_group_error(format!("{:?}", e)))
                 .map(|group_bytes| {
                     metadata_op.set_bytes(group_bytes.into());
                     (group_key, metadata_op)
@@ -151,15 +152,46 @@ pub(crate) fn serialize_groups<T: Transaction>(
         .collect()
 }
 
+/// Whether the serialized bytes of a group differ from its previous bytes (`None` if the group
+/// didn't exist).
+pub(crate) fn group_changed(old_bytes: Option<&Bytes>, new_serialized: &Bytes) -> bool {
+    old_bytes != Some(new_serialized)
+}
+
+// Serialization failures fall back to sequential execution without the dynamic change set
+// optimizations instead of aborting the block.
+fn resource_group_error(err_msg: String) -> ResourceGroupError {
+    alert!("Unexpected resource group error {}", err_msg);
+    record_fallback(ResourceGroupError::SerializationFailed)
+}
+
+fn record_fallback(err: ResourceGroupError) -> ResourceGroupError {
+    if let Some(reason) = err.fallback_reason() {
+        RESOURCE_GROUP_FALLBACK_REASONS
+            .with_label_values(&[reason.as_str()])
+            .inc();
+    }
+    err
+}
+
 pub(crate) fn gen_id_start_value(sequential: bool) -> u32 {
     // IDs are ephemeral. Pick a random prefix, and different each time,
     // in case exchange is mistakenly not performed - to more easily catch it.
     // And in a bad case where it happens in prod, to and make sure incorrect
     // block doesn't get committed, but chain halts.
     // (take a different range from parallel execution, to even more easily differentiate)
+    gen_id_start_value_with_rng(sequential, &mut thread_rng())
+}
+
+/// Same as [gen_id_start_value], but reproducible for a given seed, so that failing tests can
+/// be replayed with the same ids.
+pub(crate) fn gen_id_start_value_seeded(sequential: bool, seed: u64) -> u32 {
+    gen_id_start_value_with_rng(sequential, &mut StdRng::seed_from_u64(seed))
+}
 
+fn gen_id_start_value_with_rng(sequential: bool, rng: &mut impl Rng) -> u32 {
     let offset = if sequential { 0 } else { 1000 };
-    thread_rng().gen_range(1 + offset, 1000 + offset) * 1_000_000
+    rng.gen_range(1 + offset, 1000 + offset) * 1_000_000
 }
 
 pub(crate) fn map_id_to_values_in_group_writes<
@@ -170,6 +202,9 @@ pub(crate) fn map_id_to_values_in_group_writes<
     finalized_groups: Vec<(T::Key, T::Value, Vec<(T::Tag, ValueWithLayout<T::Value>)>)>,
     latest_view: &LatestView<T, S, X>,
 ) -> ::std::result::Result<Vec<(T::Key, T::Value, Vec<(T::Tag, Arc<T::Value>)>)>, PanicError> {
+    let _timer = DELAYED_FIELD_EXCHANGE_SECONDS
+        .with_label_values(&[DelayedFieldExchangeKind::GROUP_WRITES])
+        .start_timer();
     let mut patched_finalized_groups = Vec::with_capacity(finalized_groups.len());
     for (group_key, group_metadata_op, resource_vec) in finalized_groups.into_iter() {
         let mut patched_resource_vec = Vec::with_capacity(resource_vec.len());
@@ -177,9 +212,14 @@ pub(crate) fn map_id_to_values_in_group_writes<
             let value = match value_with_layout {
                 ValueWithLayout::RawFromStorage(value) => value,
                 ValueWithLayout::Exchanged(value, None) => value,
-                ValueWithLayout::Exchanged(value, Some(layout)) => Arc::new(
-                    replace_ids_with_values(&value, layout.as_ref(), latest_view)?,
-                ),
+                ValueWithLayout::Exchanged(value, Some(layout)) => {
+                    Arc::new(replace_ids_with_values(
+                        &value,
+                        layout.as_ref(),
+                        latest_view,
+                        DelayedFieldExchangeKind::GROUP_WRITES,
+                    )?)
+                },
             };
             patched_resource_vec.push((tag, value));
         }
@@ -197,16 +237,29 @@ pub(crate) fn map_id_to_values_in_write_set<
 >(
     resource_write_set: Vec<(T::Key, Arc<T::Value>, Arc<MoveTypeLayout>)>,
     latest_view: &LatestView<T, S, X>,
-) -> Result<Vec<(T::Key, T::Value)>, PanicError> {
-    resource_write_set
-        .into_iter()
-        .map(|(key, write_op, layout)| {
-            Ok::<_, PanicError>((
-                key,
-                replace_ids_with_values(&write_op, &layout, latest_view)?,
-            ))
-        })
-        .collect::<std::result::Result<_, PanicError>>()
+) -> Result<BTreeMap<T::Key, T::Value>, PanicError> {
+    let _timer = DELAYED_FIELD_EXCHANGE_SECONDS
+        .with_label_values(&[DelayedFieldExchangeKind::RESOURCE_WRITES])
+        .start_timer();
+    let mut patched_resource_write_set = BTreeMap::new();
+    for (key, write_op, layout) in resource_write_set.into_iter() {
+        let patched_write_op = replace_ids_with_values(
+            &write_op,
+            &layout,
+            latest_view,
+            DelayedFieldExchangeKind::RESOURCE_WRITES,
+        )?;
+        if patched_resource_write_set
+            .insert(key.clone(), patched_write_op)
+            .is_some()
+        {
+            return Err(code_invariant_error(format!(
+                "Duplicate key {:?} in resource write set to materialize",
+                key
+            )));
+        }
+    }
+    Ok(patched_resource_write_set)
 }
 
 // For each delayed field in the event, replace delayed field identifier with value.
@@ -251,19 +304,22 @@ fn replace_ids_with_values<
     value: &Arc<T::Value>,
     layout: &MoveTypeLayout,
     latest_view: &LatestView<T, S, X>,
+    kind: &'static str,
 ) -> Result<T::Value, PanicError> {
     let mut value = (**value).clone();
 
     if let Some(value_bytes) = value.bytes() {
-        let patched_bytes = latest_view
+        let (patched_bytes, replaced_ids) = latest_view
             .replace_identifiers_with_values(value_bytes, layout)
             .map_err(|_| {
                 code_invariant_error(format!(
                     "Failed to replace identifiers with values in a resource {:?}",
                     layout
                 ))
-            })?
-            .0;
+            })?;
+        DELAYED_FIELD_IDS_REPLACED_COUNT
+            .with_label_values(&[kind])
+            .inc_by(replaced_ids.len() as u64);
         value.set_bytes(patched_bytes);
         Ok(value)
     } else {
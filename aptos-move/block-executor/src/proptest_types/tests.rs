@@ -569,7 +569,7 @@ fn non_empty_group(
         // TODO: test dynamic disabled as well.
 
         BaselineOutput::generate(&transactions, None).assert_output(&output.map_err(|e| match e {
-            SequentialBlockExecutionError::ResourceGroupSerializationError => {
+            SequentialBlockExecutionError::ResourceGroupError(_) => {
                 panic!("Unexpected error")
             },
             SequentialBlockExecutionError::ErrorToReturn(err) => err,
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    errors::{ErrorKind, ExecutorError},
     explicit_sync_wrapper::ExplicitSyncWrapper,
     task::{ExecutionStatus, ExecutorTask, TransactionOutput},
 };
@@ -818,6 +819,13 @@ impl<K, E> MockTask<K, E> {
     }
 }
 
+/// Mock aborts model transactions that fail regardless of the data they read.
+impl ExecutorError for usize {
+    fn error_kind(&self) -> ErrorKind {
+        ErrorKind::InvariantViolation
+    }
+}
+
 impl<K, E> ExecutorTask for MockTask<K, E>
 where
     K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
@@ -958,6 +966,7 @@ where
                     read_results,
                     read_group_sizes,
                     materialized_delta_writes: OnceCell::new(),
+                    unchanged_group_writes: OnceCell::new(),
                     total_gas: behavior.gas,
                     skipped: false,
                 })
@@ -990,6 +999,7 @@ pub(crate) struct MockOutput<K, E> {
     pub(crate) read_results: Vec<Option<Vec<u8>>>,
     pub(crate) read_group_sizes: Vec<(K, u64)>,
     pub(crate) materialized_delta_writes: OnceCell<Vec<(K, WriteOp)>>,
+    pub(crate) unchanged_group_writes: OnceCell<Vec<K>>,
     pub(crate) total_gas: u64,
     pub(crate) skipped: bool,
 }
@@ -1052,11 +1062,11 @@ where
         Vec::new()
     }
 
-    fn group_reads_needing_delayed_field_exchange(
+    fn cached_group_reads_needing_exchange(
         &self,
-    ) -> Vec<(<Self::Txn as Transaction>::Key, StateValueMetadata)> {
+    ) -> &[(<Self::Txn as Transaction>::Key, StateValueMetadata)] {
         // TODO[agg_v2](tests): add aggregators V2 to the proptest?
-        Vec::new()
+        &[]
     }
 
     // TODO[agg_v2](tests): Currently, appending None to all events, which means none of the
@@ -1095,6 +1105,7 @@ where
             read_results: vec![],
             read_group_sizes: vec![],
             materialized_delta_writes: OnceCell::new(),
+            unchanged_group_writes: OnceCell::new(),
             total_gas: 0,
             skipped: true,
         }
@@ -1109,6 +1120,7 @@ where
             read_results: vec![],
             read_group_sizes: vec![],
             materialized_delta_writes: OnceCell::new(),
+            unchanged_group_writes: OnceCell::new(),
             total_gas: 0,
             skipped: true,
         }
@@ -1130,8 +1142,10 @@ where
             <Self::Txn as Transaction>::Value,
         )>,
         _patched_events: Vec<<Self::Txn as Transaction>::Event>,
+        unchanged_resource_groups: Vec<<Self::Txn as Transaction>::Key>,
     ) -> Result<(), PanicError> {
         assert_ok!(self.materialized_delta_writes.set(aggregator_v1_writes));
+        assert_ok!(self.unchanged_group_writes.set(unchanged_resource_groups));
         // TODO[agg_v2](tests): Set the patched resource write set and events. But that requires the function
         // to take &mut self as input
         Ok(())
@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::InputOutputKey;
+use crate::{errors::ExecutorError, types::InputOutputKey};
 use aptos_aggregator::{
     delayed_change::DelayedChange, delta_change_set::DeltaOp, resolver::TAggregatorV1View,
 };
@@ -55,7 +55,7 @@ pub trait ExecutorTask: Sync {
     type Output: TransactionOutput<Txn = Self::Txn> + 'static;
 
     /// Type of error when the executor failed to process a transaction and needs to abort.
-    type Error: Debug + Clone + Send + Sync + Eq + 'static;
+    type Error: Debug + Clone + Send + Sync + Eq + ExecutorError + 'static;
 
     /// Type to initialize the single thread transaction executor. Copy and Sync are required because
     /// we will create an instance of executor on each individual thread.
@@ -127,9 +127,17 @@ pub trait TransactionOutput: Send + Sync + Debug {
         Arc<MoveTypeLayout>,
     )>;
 
+    /// Group reads needing delayed field exchange, computed at most once per output as they
+    /// are needed both to check and to finalize the groups when committing.
+    fn cached_group_reads_needing_exchange(
+        &self,
+    ) -> &[(<Self::Txn as Transaction>::Key, StateValueMetadata)];
+
     fn group_reads_needing_delayed_field_exchange(
         &self,
-    ) -> Vec<(<Self::Txn as Transaction>::Key, StateValueMetadata)>;
+    ) -> Vec<(<Self::Txn as Transaction>::Key, StateValueMetadata)> {
+        self.cached_group_reads_needing_exchange().to_vec()
+    }
 
     /// Get the events of a transaction from its output.
     fn get_events(&self) -> Vec<(<Self::Txn as Transaction>::Event, Option<MoveTypeLayout>)>;
@@ -174,6 +182,8 @@ pub trait TransactionOutput: Send + Sync + Debug {
     /// Will be called once per transaction when the output is ready to be committed.
     /// Ensures that any writes corresponding to materialized deltas and group updates
     /// (recorded in output separately) are incorporated into the transaction output.
+    /// The writes to `unchanged_resource_groups`, which leave the groups as they were,
+    /// are dropped from the output.
     fn incorporate_materialized_txn_output(
         &self,
         aggregator_v1_writes: Vec<(<Self::Txn as Transaction>::Key, WriteOp)>,
@@ -182,6 +192,7 @@ pub trait TransactionOutput: Send + Sync + Debug {
             <Self::Txn as Transaction>::Value,
         )>,
         patched_events: Vec<<Self::Txn as Transaction>::Event>,
+        unchanged_resource_groups: Vec<<Self::Txn as Transaction>::Key>,
     ) -> Result<(), PanicError>;
 
     fn set_txn_output_for_non_dynamic_change_set(&self);
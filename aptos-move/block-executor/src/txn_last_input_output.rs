@@ -411,6 +411,8 @@ impl<T: Transaction, O: TransactionOutput<Txn = T>, E: Debug + Send + Clone>
                     delta_writes,
                     patched_resource_write_set,
                     patched_events,
+                    // Unchanged group writes are only skipped in sequential execution.
+                    vec![],
                 )?;
             },
             ExecutionStatus::Abort(_)
@@ -3,8 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    errors::SequentialBlockExecutionError,
+    counters::{BLOCK_EXECUTOR_RETRIABLE_ERRORS, RESOURCE_GROUP_FALLBACK_REASONS},
+    errors::{
+        ErrorKind, ExecutorError, FallbackReason, ResourceGroupError, SequentialBlockExecutionError,
+    },
     executor::BlockExecutor,
+    executor_utilities::{
+        gen_id_start_value_seeded, group_changed, map_finalized_group, serialize_groups,
+    },
     proptest_types::{
         baseline::BaselineOutput,
         types::{
@@ -15,21 +21,30 @@ use crate::{
     scheduler::{
         DependencyResult, ExecutionTaskType, Scheduler, SchedulerTask, TWaitForDependency,
     },
+    task::{ExecutionStatus, ExecutorTask},
     txn_commit_hook::NoOpTransactionCommitHook,
 };
 use aptos_aggregator::{
     bounded_math::SignedU128,
     delta_change_set::{delta_add, delta_sub, DeltaOp},
     delta_math::DeltaHistory,
+    types::DelayedFieldID,
 };
-use aptos_mvhashmap::types::TxnIndex;
+use aptos_mvhashmap::types::{TxnIndex, ValueWithLayout};
 use aptos_types::{
     block_executor::config::BlockExecutorConfig,
     contract_event::TransactionEvent,
     executable::{ExecutableTestType, ModulePath},
+    transaction::BlockOutput,
 };
-use claims::assert_matches;
+use aptos_vm_types::resolver::{TExecutorView, TResourceGroupView};
+use bytes::Bytes;
+use claims::{assert_err, assert_matches, assert_ok};
 use fail::FailScenario;
+use move_core_types::{
+    value::MoveTypeLayout,
+    vm_status::{StatusCode, VMStatus},
+};
 use rand::{prelude::*, random};
 use std::{
     cmp::min,
@@ -37,7 +52,7 @@ use std::{
     fmt::Debug,
     hash::Hash,
     marker::PhantomData,
-    sync::Arc,
+    sync::{atomic::Ordering, Arc},
 };
 
 #[test]
@@ -121,14 +136,16 @@ fn resource_group_bcs_fallback() {
         block_executor.execute_transactions_sequential((), &transactions, &data_view, false);
     assert_matches!(
         seq_output,
-        Err(SequentialBlockExecutionError::ResourceGroupSerializationError)
+        Err(SequentialBlockExecutionError::ResourceGroupError(
+            ResourceGroupError::SerializationFailed
+        ))
     );
 
     // Now execute with fallback handling for resource group serialization error:
     let fallback_output = block_executor
         .execute_transactions_sequential((), &transactions, &data_view, true)
         .map_err(|e| match e {
-            SequentialBlockExecutionError::ResourceGroupSerializationError => {
+            SequentialBlockExecutionError::ResourceGroupError(_) => {
                 panic!("Unexpected error")
             },
             SequentialBlockExecutionError::ErrorToReturn(err) => err,
@@ -152,6 +169,138 @@ fn resource_group_bcs_fallback() {
     scenario.teardown();
 }
 
+#[test]
+fn serialize_groups_member_without_bytes() {
+    let finalized_groups = vec![(
+        KeyType::<u32>(1, false),
+        ValueType::from_value(vec![], true),
+        vec![
+            (0, Arc::new(ValueType::from_value(vec![5], true))),
+            // A deletion has no bytes, and should have already been applied.
+            (1, Arc::new(ValueType::from_value(vec![7], false))),
+        ],
+    )];
+
+    assert_matches!(
+        serialize_groups::<MockTransaction<KeyType<u32>, MockEvent>>(finalized_groups),
+        Err(ResourceGroupError::SerializationFailed)
+    );
+    // The failure falls back to sequential execution rather than aborting the block.
+    assert_matches!(
+        SequentialBlockExecutionError::<()>::from(ResourceGroupError::SerializationFailed),
+        SequentialBlockExecutionError::ResourceGroupError(ResourceGroupError::SerializationFailed)
+    );
+}
+
+#[test]
+fn map_finalized_group_records_empty_mismatch() {
+    let empty_mismatches = || {
+        RESOURCE_GROUP_FALLBACK_REASONS
+            .with_label_values(&[FallbackReason::EmptyMismatch.as_str()])
+            .get()
+    };
+    let empty_mismatches_before = empty_mismatches();
+
+    // The metadata op is a deletion, but the finalized group still has a member.
+    let finalized_group = vec![(
+        0,
+        ValueWithLayout::Exchanged(Arc::new(ValueType::from_value(vec![5], true)), None),
+    )];
+    assert_matches!(
+        map_finalized_group::<MockTransaction<KeyType<u32>, MockEvent>>(
+            KeyType::<u32>(1, false),
+            Ok(finalized_group),
+            ValueType::from_value(vec![], false),
+            false,
+        ),
+        Err(ResourceGroupError::EmptyMismatch {
+            is_empty: false,
+            is_deletion: true
+        })
+    );
+    assert!(empty_mismatches() > empty_mismatches_before);
+}
+
+#[test]
+fn group_changed_compares_bytes() {
+    let bytes = Bytes::from(vec![1, 2, 3]);
+    assert!(!group_changed(Some(&bytes), &Bytes::from(vec![1, 2, 3])));
+    assert!(group_changed(Some(&bytes), &Bytes::from(vec![1, 2])));
+    // A group that didn't exist is always changed, even by an empty serialization.
+    assert!(group_changed(None, &Bytes::new()));
+}
+
+#[test]
+fn skip_unchanged_group_writes_sequential() {
+    let group_key = KeyType::<u32>(100, false);
+    let mut group_incarnation: MockIncarnation<KeyType<u32>, MockEvent> =
+        MockIncarnation::new(vec![], vec![], vec![], vec![], 10);
+    group_incarnation.group_writes.push((
+        group_key,
+        HashMap::from([(101, ValueType::from_value(vec![5], true))]),
+    ));
+    // The second transaction writes the group exactly as the first one left it.
+    let txn = MockTransaction::from_behavior(group_incarnation);
+    let transactions = Vec::from([txn.clone(), txn]);
+
+    let data_view = NonEmptyGroupDataView::<KeyType<u32>> {
+        group_keys: HashSet::from([group_key]),
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+
+    for skip_unchanged_group_writes in [false, true] {
+        let mut config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+        config.local.skip_unchanged_group_writes = skip_unchanged_group_writes;
+        let block_executor = BlockExecutor::<
+            MockTransaction<KeyType<u32>, MockEvent>,
+            MockTask<KeyType<u32>, MockEvent>,
+            NonEmptyGroupDataView<KeyType<u32>>,
+            NoOpTransactionCommitHook<MockOutput<KeyType<u32>, MockEvent>, usize>,
+            ExecutableTestType,
+        >::new(config, executor_thread_pool.clone(), None);
+
+        let txn_outputs = block_executor
+            .execute_transactions_sequential((), &transactions, &data_view, false)
+            .unwrap()
+            .into_transaction_outputs_forced();
+        assert_eq!(txn_outputs.len(), 2);
+        // The first write changes the group in storage, so it must never be skipped.
+        assert_eq!(txn_outputs[0].unchanged_group_writes.get(), Some(&vec![]));
+        let expected_unchanged = if skip_unchanged_group_writes {
+            vec![group_key]
+        } else {
+            vec![]
+        };
+        assert_eq!(
+            txn_outputs[1].unchanged_group_writes.get(),
+            Some(&expected_unchanged)
+        );
+    }
+}
+
+#[test]
+fn gen_id_start_value_seeded_is_deterministic() {
+    for sequential in [true, false] {
+        let range = if sequential { 1..1000 } else { 1001..2000 };
+        for seed in 0..10 {
+            let start_value = gen_id_start_value_seeded(sequential, seed);
+            assert_eq!(start_value, gen_id_start_value_seeded(sequential, seed));
+            assert_eq!(start_value % 1_000_000, 0);
+            assert!(range.contains(&(start_value / 1_000_000)));
+        }
+
+        let start_values: HashSet<u32> = (0..10)
+            .map(|seed| gen_id_start_value_seeded(sequential, seed))
+            .collect();
+        assert!(start_values.len() > 1);
+    }
+}
+
 #[test]
 fn block_output_err_precedence() {
     let incarnation: MockIncarnation<KeyType<u32>, MockEvent> = MockIncarnation::new(
@@ -231,6 +380,170 @@ fn skip_rest_gas_limit() {
 }
 
 // TODO: add unit test for block gas limit!
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockExecutorError(ErrorKind);
+
+impl ExecutorError for MockExecutorError {
+    fn error_kind(&self) -> ErrorKind {
+        self.0
+    }
+}
+
+/// Executes the transactions like MockTask, except that the first incarnation of the
+/// transaction at FAIL_ONCE_TXN_IDX fails with an error of the given kind.
+struct FailOnceTask(ErrorKind);
+
+const FAIL_ONCE_TXN_IDX: TxnIndex = 1;
+
+impl ExecutorTask for FailOnceTask {
+    type Argument = ErrorKind;
+    type Error = MockExecutorError;
+    type Output = MockOutput<KeyType<u32>, MockEvent>;
+    type Txn = MockTransaction<KeyType<u32>, MockEvent>;
+
+    fn init(kind: Self::Argument) -> Self {
+        Self(kind)
+    }
+
+    fn execute_transaction(
+        &self,
+        view: &(impl TExecutorView<KeyType<u32>, u32, MoveTypeLayout, DelayedFieldID, ValueType>
+              + TResourceGroupView<
+            GroupKey = KeyType<u32>,
+            ResourceTag = u32,
+            Layout = MoveTypeLayout,
+        >),
+        txn: &Self::Txn,
+        txn_idx: TxnIndex,
+    ) -> ExecutionStatus<Self::Output, Self::Error> {
+        if let MockTransaction::Write {
+            incarnation_counter,
+            ..
+        } = txn
+        {
+            if txn_idx == FAIL_ONCE_TXN_IDX
+                && incarnation_counter
+                    .compare_exchange(0, 1, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+            {
+                return ExecutionStatus::Abort(MockExecutorError(self.0));
+            }
+        }
+
+        match MockTask::<KeyType<u32>, MockEvent>::new().execute_transaction(view, txn, txn_idx) {
+            ExecutionStatus::Success(output) => ExecutionStatus::Success(output),
+            status => unreachable!("Unexpected mock execution status {:?}", status),
+        }
+    }
+
+    fn is_transaction_dynamic_change_set_capable(_txn: &Self::Txn) -> bool {
+        true
+    }
+}
+
+// Returns the parallel execution result and the executed transactions.
+fn run_fail_once(
+    kind: ErrorKind,
+) -> (
+    Result<BlockOutput<MockOutput<KeyType<u32>, MockEvent>>, ()>,
+    Vec<MockTransaction<KeyType<u32>, MockEvent>>,
+) {
+    let transactions: Vec<_> = (0..3)
+        .map(|i| {
+            MockTransaction::from_behavior(MockIncarnation::<KeyType<u32>, MockEvent>::new(
+                vec![KeyType::<u32>(i, false)],
+                vec![(KeyType::<u32>(i, false), random_value(false))],
+                vec![],
+                vec![],
+                1,
+            ))
+        })
+        .collect();
+    let data_view = DeltaDataView::<KeyType<u32>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+
+    let output = BlockExecutor::<
+        MockTransaction<KeyType<u32>, MockEvent>,
+        FailOnceTask,
+        DeltaDataView<KeyType<u32>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<u32>, MockEvent>, MockExecutorError>,
+        ExecutableTestType,
+    >::new(
+        BlockExecutorConfig::new_no_block_limit(num_cpus::get()),
+        executor_thread_pool,
+        None,
+    )
+    .execute_transactions_parallel(kind, &transactions, &data_view);
+    (output, transactions)
+}
+
+fn num_executions(txn: &MockTransaction<KeyType<u32>, MockEvent>) -> usize {
+    match txn {
+        MockTransaction::Write {
+            incarnation_counter,
+            ..
+        } => incarnation_counter.load(Ordering::SeqCst),
+        _ => unreachable!("Only write transactions are executed"),
+    }
+}
+
+#[test]
+fn retriable_error_is_re_executed() {
+    let retriable_errors = || {
+        BLOCK_EXECUTOR_RETRIABLE_ERRORS
+            .with_label_values(&[ErrorKind::ResourceConflict.as_str()])
+            .get()
+    };
+    let retriable_errors_before = retriable_errors();
+
+    let (output, transactions) = run_fail_once(ErrorKind::ResourceConflict);
+    assert_eq!(num_executions(&transactions[FAIL_ONCE_TXN_IDX as usize]), 2);
+    BaselineOutput::generate(&transactions, None).assert_parallel_output(&output);
+    assert_ok!(output);
+    assert!(retriable_errors() > retriable_errors_before);
+}
+
+#[test]
+fn non_retriable_error_falls_back() {
+    assert!(!MockExecutorError(ErrorKind::InvariantViolation).is_retriable());
+    assert!(!MockExecutorError(ErrorKind::ExternalDependency).is_retriable());
+    assert!(!MockExecutorError(ErrorKind::Other).is_retriable());
+
+    // Parallel execution halts right away, without re-executing the transaction.
+    let (output, transactions) = run_fail_once(ErrorKind::InvariantViolation);
+    assert_eq!(num_executions(&transactions[FAIL_ONCE_TXN_IDX as usize]), 1);
+    assert_err!(output);
+}
+
+#[test]
+fn vm_status_error_kind() {
+    let kind = |status_code| VMStatus::error(status_code, None).error_kind();
+    assert_eq!(
+        kind(StatusCode::STORAGE_ERROR),
+        ErrorKind::ExternalDependency
+    );
+    assert_eq!(kind(StatusCode::OUT_OF_GAS), ErrorKind::GasExhausted);
+    assert_eq!(
+        kind(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR),
+        ErrorKind::InvariantViolation
+    );
+    assert_eq!(
+        kind(StatusCode::SPECULATIVE_EXECUTION_ABORT_ERROR),
+        ErrorKind::ResourceConflict
+    );
+    assert_eq!(kind(StatusCode::ABORTED), ErrorKind::Other);
+    assert!(!VMStatus::error(StatusCode::ABORTED, None).is_retriable());
+    assert!(VMStatus::error(StatusCode::OUT_OF_GAS, None).is_retriable());
+    assert!(!VMStatus::error(StatusCode::STORAGE_ERROR, None).is_retriable());
+}
 fn run_and_assert<K, E>(transactions: Vec<MockTransaction<K, E>>)
 where
     K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
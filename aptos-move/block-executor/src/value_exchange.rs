@@ -18,8 +18,8 @@ use move_binary_format::errors::PartialVMResult;
 use move_core_types::value::{IdentifierMappingKind, MoveTypeLayout};
 use move_vm_types::{
     delayed_values::delayed_field_id::{ExtractWidth, TryFromMoveValue},
-    value_serde::{deserialize_and_allow_delayed_values, ValueToIdentifierMapping},
-    value_traversal::find_identifiers_in_value,
+    value_serde::ValueToIdentifierMapping,
+    value_traversal::find_identifiers_in_serialized_value,
     values::Value,
 };
 use std::{cell::RefCell, collections::HashSet, sync::Arc};
@@ -105,22 +105,26 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> ValueToIden
     }
 }
 
+// Given bytes, where values were already exchanged with identifiers,
+// add identifiers present in it to the provided set.
+fn find_identifiers_in_bytes(
+    bytes: &Bytes,
+    layout: &MoveTypeLayout,
+    identifiers: &mut HashSet<u64>,
+) -> anyhow::Result<()> {
+    // Traverses the bytes directly, without constructing the value.
+    find_identifiers_in_serialized_value(bytes, layout, identifiers)?;
+    Ok(())
+}
+
 // Given bytes, where values were already exchanged with identifiers,
 // return a list of identifiers present in it.
-fn extract_identifiers_from_value<T: Transaction>(
+pub(crate) fn extract_identifiers_from_value<T: Transaction>(
     bytes: &Bytes,
     layout: &MoveTypeLayout,
 ) -> anyhow::Result<HashSet<T::Identifier>> {
-    // TODO[agg_v2](optimize): this performs 2 traversals of a value:
-    //   1) deserialize,
-    //   2) find identifiers to populate the set.
-    //   See if can cache identifiers in advance, or combine it with
-    //   deserialization.
-    let value = deserialize_and_allow_delayed_values(bytes, layout)
-        .ok_or_else(|| anyhow::anyhow!("Failed to deserialize resource during id replacement"))?;
-
     let mut identifiers = HashSet::new();
-    find_identifiers_in_value(&value, &mut identifiers)?;
+    find_identifiers_in_bytes(bytes, layout, &mut identifiers)?;
     // TODO[agg_v2](cleanup): ugly way of converting delayed ids to generic type params.
     Ok(identifiers.into_iter().map(T::Identifier::from).collect())
 }
@@ -143,6 +147,23 @@ pub(crate) fn does_value_need_exchange<T: Transaction>(
     }
 }
 
+// Same as does_value_need_exchange, for the resources read from a group. Stops at the
+// first resource that needs exchange. Deletion returns a PanicError.
+pub(crate) fn does_group_need_exchange<'a, T: Transaction>(
+    values: impl IntoIterator<Item = (&'a T::Value, &'a MoveTypeLayout)>,
+    delayed_write_set_ids: &HashSet<T::Identifier>,
+) -> Result<bool, PanicError>
+where
+    T::Value: 'a,
+{
+    for (value, layout) in values {
+        if does_value_need_exchange::<T>(value, layout, delayed_write_set_ids)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 // Exclude deletions, and values that do not contain any delayed field IDs that were written to.
 pub(crate) fn filter_value_for_exchange<T: Transaction>(
     value: &T::Value,
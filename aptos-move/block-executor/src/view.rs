@@ -11,7 +11,7 @@ use crate::{
     counters,
     scheduler::{DependencyResult, DependencyStatus, Scheduler, TWaitForDependency},
     value_exchange::{
-        does_value_need_exchange, filter_value_for_exchange, TemporaryValueToIdentifierMapping,
+        does_group_need_exchange, filter_value_for_exchange, TemporaryValueToIdentifierMapping,
     },
 };
 use aptos_aggregator::{
@@ -1165,31 +1165,18 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<
             .flat_map(|(key, group_read)| {
                 let GroupRead { inner_reads, .. } = group_read;
 
-                // TODO[agg_v2](clean-up): Once ids can be extracted without possible failure,
-                // the following is just an any call on iterator (same for resource reads).
-                let mut resources_needing_delayed_field_exchange = false;
-                for data_read in inner_reads.values() {
-                    if let DataRead::Versioned(_version, value, Some(layout)) = data_read {
-                        // TODO[agg_v2](optimize): Is it possible to avoid clones here?
-                        match does_value_need_exchange::<T>(
-                            value,
-                            layout.as_ref(),
-                            delayed_write_set_ids,
-                        ) {
-                            Ok(needs_exchange) => {
-                                if needs_exchange {
-                                    resources_needing_delayed_field_exchange = true;
-                                    break;
-                                }
-                            },
-                            Err(e) => {
-                                return Some(Err(e));
-                            },
-                        }
-                    }
-                }
-                if !resources_needing_delayed_field_exchange {
-                    return None;
+                match does_group_need_exchange::<T>(
+                    inner_reads.values().filter_map(|data_read| match data_read {
+                        DataRead::Versioned(_version, value, Some(layout)) => {
+                            Some((value.as_ref(), layout.as_ref()))
+                        },
+                        _ => None,
+                    }),
+                    delayed_write_set_ids,
+                ) {
+                    Ok(true) => {},
+                    Ok(false) => return None,
+                    Err(e) => return Some(Err(e)),
                 }
 
                 if let Ok(Some(metadata)) = self.get_resource_state_value_metadata(&key) {
@@ -1226,34 +1213,22 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<
             .filter(|(key, _tags)| !skip.contains(key))
             .flat_map(|(key, tags)| {
                 if let Some(value_vec) = unsync_map.fetch_group_data(key) {
-                    // TODO[agg_v2](cleanup) - can we use .any() instead?
-                    let mut resources_needing_delayed_field_exchange = false;
-                    for (tag, value_with_layout) in value_vec {
-                        if tags.contains(&tag) {
-                            if let ValueWithLayout::Exchanged(value, Some(layout)) =
-                                value_with_layout
-                            {
-                                // TODO[agg_v2](optimize): Is it possible to avoid clones here?
-                                match does_value_need_exchange::<T>(
-                                    &value,
-                                    layout.as_ref(),
-                                    delayed_write_set_ids,
-                                ) {
-                                    Ok(needs_exchange) => {
-                                        if needs_exchange {
-                                            resources_needing_delayed_field_exchange = true;
-                                            break;
-                                        }
-                                    },
-                                    Err(e) => {
-                                        return Some(Err(e));
-                                    },
-                                }
+                    match does_group_need_exchange::<T>(
+                        value_vec.iter().filter_map(|(tag, value_with_layout)| {
+                            match value_with_layout {
+                                ValueWithLayout::Exchanged(value, Some(layout))
+                                    if tags.contains(tag.as_ref()) =>
+                                {
+                                    Some((value.as_ref(), layout.as_ref()))
+                                },
+                                _ => None,
                             }
-                        }
-                    }
-                    if !resources_needing_delayed_field_exchange {
-                        return None;
+                        }),
+                        delayed_write_set_ids,
+                    ) {
+                        Ok(true) => {},
+                        Ok(false) => return None,
+                        Err(e) => return Some(Err(e)),
                     }
                     if let Ok(Some(metadata)) = self.get_resource_state_value_metadata(key) {
                         if let Ok(GroupReadResult::Size(group_size)) =
@@ -1760,6 +1735,8 @@ mod test {
     use super::*;
     use crate::{
         captured_reads::{CapturedReads, DelayedFieldRead, DelayedFieldReadKind},
+        counters,
+        executor_utilities::{map_id_to_values_in_group_writes, map_id_to_values_in_write_set},
         proptest_types::types::{KeyType, MockEvent, ValueType},
         scheduler::{DependencyResult, Scheduler, TWaitForDependency},
         view::{delayed_field_try_add_delta_outcome_impl, get_delayed_field_value_impl, ViewState},
@@ -2709,6 +2686,109 @@ mod test {
         assert_eq!(identifiers, identifiers2);
     }
 
+    #[test]
+    fn test_map_id_to_values_in_write_set_errors() {
+        let holder = Holder::new(HashMap::new(), 1000);
+        let latest_view = create_sequential_latest_view(&holder);
+        let layout = Arc::new(MoveTypeLayout::U64);
+        let valid_value = Arc::new(ValueType::from_value(
+            Value::u64(5).simple_serialize(&layout).unwrap(),
+            true,
+        ));
+
+        let ok_write_set = vec![
+            (KeyType(1, false), valid_value.clone(), layout.clone()),
+            (KeyType(2, false), valid_value.clone(), layout.clone()),
+        ];
+        let patched = map_id_to_values_in_write_set(ok_write_set, &latest_view).unwrap();
+        assert_eq!(patched.len(), 2);
+
+        // Value to exchange has no bytes.
+        let deletion = Arc::new(ValueType::from_value(Vec::<u8>::new(), false));
+        assert!(map_id_to_values_in_write_set(
+            vec![(KeyType(1, false), deletion, layout.clone())],
+            &latest_view
+        )
+        .is_err());
+
+        // Bytes can't be deserialized with the layout.
+        let malformed = Arc::new(ValueType::from_value(vec![1], true));
+        assert!(map_id_to_values_in_write_set(
+            vec![(KeyType(1, false), malformed, layout.clone())],
+            &latest_view
+        )
+        .is_err());
+
+        // The same key is materialized twice.
+        assert!(map_id_to_values_in_write_set(
+            vec![
+                (KeyType(1, false), valid_value.clone(), layout.clone()),
+                (KeyType(1, false), valid_value, layout),
+            ],
+            &latest_view
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_delayed_field_exchange_counters() {
+        let holder = Holder::new(HashMap::new(), 1000);
+        let latest_view = create_sequential_latest_view(&holder);
+
+        let storage_layout =
+            create_struct_layout(create_aggregator_storage_layout(MoveTypeLayout::U64));
+        let value = create_struct_value(create_aggregator_value_u64(25, 30));
+        let state_value =
+            StateValue::new_legacy(value.simple_serialize(&storage_layout).unwrap().into());
+        let layout = Arc::new(create_struct_layout(create_aggregator_layout_u64()));
+        let (patched_state_value, identifiers) = latest_view
+            .replace_values_with_identifiers(state_value, &layout)
+            .unwrap();
+        assert_eq!(identifiers.len(), 1);
+        let exchanged_value = Arc::new(ValueType::from_value(
+            patched_state_value.bytes().to_vec(),
+            true,
+        ));
+
+        let replaced_count = |kind| {
+            counters::DELAYED_FIELD_IDS_REPLACED_COUNT
+                .with_label_values(&[kind])
+                .get()
+        };
+        let exchange_count = |kind| {
+            counters::DELAYED_FIELD_EXCHANGE_SECONDS
+                .with_label_values(&[kind])
+                .get_sample_count()
+        };
+        let resource_kind = counters::DelayedFieldExchangeKind::RESOURCE_WRITES;
+        let group_kind = counters::DelayedFieldExchangeKind::GROUP_WRITES;
+        let (resource_replaced, resource_exchanges) =
+            (replaced_count(resource_kind), exchange_count(resource_kind));
+        let (group_replaced, group_exchanges) =
+            (replaced_count(group_kind), exchange_count(group_kind));
+
+        map_id_to_values_in_write_set(
+            vec![(KeyType(1, false), exchanged_value.clone(), layout.clone())],
+            &latest_view,
+        )
+        .unwrap();
+        map_id_to_values_in_group_writes(
+            vec![(
+                KeyType(2, false),
+                ValueType::from_value(vec![0], true),
+                vec![(0, ValueWithLayout::Exchanged(exchanged_value, Some(layout)))],
+            )],
+            &latest_view,
+        )
+        .unwrap();
+
+        // Other tests may run concurrently and advance the counters further.
+        assert!(replaced_count(resource_kind) > resource_replaced);
+        assert!(exchange_count(resource_kind) > resource_exchanges);
+        assert!(replaced_count(group_kind) > group_replaced);
+        assert!(exchange_count(group_kind) > group_exchanges);
+    }
+
     struct Holder {
         unsync_map: UnsyncMap<KeyType<u32>, u32, ValueType, MockExecutable, DelayedFieldID>,
         counter: RefCell<u32>,
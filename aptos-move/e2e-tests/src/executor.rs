@@ -490,6 +490,8 @@ impl FakeExecutor {
                 },
                 allow_fallback: self.allow_block_executor_fallback,
                 discard_failed_blocks: false,
+                id_start_value_seed: None,
+                skip_unchanged_group_writes: false,
             },
             onchain: onchain_config,
         };
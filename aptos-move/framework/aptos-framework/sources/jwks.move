@@ -28,6 +28,7 @@ module aptos_framework::jwks {
     const EUNKNOWN_JWK_VARIANT: u64 = 4;
     const EISSUER_NOT_FOUND: u64 = 5;
     const EJWK_ID_NOT_FOUND: u64 = 6;
+    const EINVALID_QUORUM_THRESHOLD: u64 = 7;
 
     const ENATIVE_MISSING_RESOURCE_VALIDATOR_SET: u64 = 0x0101;
     const ENATIVE_MISSING_RESOURCE_OBSERVED_JWKS: u64 = 0x0102;
@@ -50,6 +51,17 @@ module aptos_framework::jwks {
         providers: vector<OIDCProvider>,
     }
 
+    /// Parameters of the JWK consensus. Maintained by governance proposals and read by validators on every new epoch.
+    /// If it is not published, validators start certifying an update as soon as they observe it.
+    struct JWKConsensusConfig has drop, key {
+        /// For how long (in seconds) a validator needs to keep observing the same JWKs of a provider
+        /// before it starts certifying them.
+        min_observation_secs: u64,
+        /// The percentage of the total voting power required to certify an update.
+        /// A value lower than the BFT quorum has no effect.
+        quorum_threshold_pct: u64,
+    }
+
     /// An JWK variant that represents the JWKs which were observed but not yet supported by Aptos.
     /// Observing `UnsupportedJWK`s means the providers adopted a new key type/format, and the system should be updated.
     struct UnsupportedJWK has copy, drop, store {
@@ -198,6 +210,19 @@ module aptos_framework::jwks {
         remove_oidc_provider_internal(provider_set, name)
     }
 
+    /// Set the `JWKConsensusConfig`. Only called in governance proposals.
+    /// Validators pick up the new config at the beginning of the next epoch.
+    public fun set_jwk_consensus_config(fx: &signer, min_observation_secs: u64, quorum_threshold_pct: u64) acquires JWKConsensusConfig {
+        system_addresses::assert_aptos_framework(fx);
+        assert!(quorum_threshold_pct > 0 && quorum_threshold_pct <= 100, error::invalid_argument(EINVALID_QUORUM_THRESHOLD));
+        let config = JWKConsensusConfig { min_observation_secs, quorum_threshold_pct };
+        if (exists<JWKConsensusConfig>(@aptos_framework)) {
+            *borrow_global_mut<JWKConsensusConfig>(@aptos_framework) = config;
+        } else {
+            move_to(fx, config);
+        }
+    }
+
     /// Set the `Patches`. Only called in governance proposals.
     public fun set_patches(fx: &signer, patches: vector<Patch>) acquires Patches, PatchedJWKs, ObservedJWKs {
         system_addresses::assert_aptos_framework(fx);
@@ -558,6 +583,25 @@ module aptos_framework::jwks {
         assert!(expected == borrow_global<ObservedJWKs>(@aptos_framework).jwks, 4);
     }
 
+    #[test(fx = @aptos_framework)]
+    fun test_set_jwk_consensus_config(fx: &signer) acquires JWKConsensusConfig {
+        initialize_for_test(fx);
+        assert!(!exists<JWKConsensusConfig>(@aptos_framework), 1);
+        set_jwk_consensus_config(fx, 60, 67);
+        let config = borrow_global<JWKConsensusConfig>(@aptos_framework);
+        assert!(config.min_observation_secs == 60 && config.quorum_threshold_pct == 67, 2);
+        set_jwk_consensus_config(fx, 0, 100);
+        let config = borrow_global<JWKConsensusConfig>(@aptos_framework);
+        assert!(config.min_observation_secs == 0 && config.quorum_threshold_pct == 100, 3);
+    }
+
+    #[test(fx = @aptos_framework)]
+    #[expected_failure(abort_code = 0x010007, location = Self)]
+    fun test_set_jwk_consensus_config_invalid_threshold(fx: &signer) acquires JWKConsensusConfig {
+        initialize_for_test(fx);
+        set_jwk_consensus_config(fx, 60, 101);
+    }
+
     #[test]
     fun test_apply_patch() {
         let jwks = AllProvidersJWKs {
@@ -123,12 +123,16 @@ impl Default for ReliableBroadcastConfig {
 #[serde(default, deny_unknown_fields)]
 pub struct DagRoundStateConfig {
     pub adaptive_responsive_minimum_wait_time_ms: u64,
+    /// If a round has not advanced after this long, move to the next round as soon as there are
+    /// strong links for it. Zero disables the timeout.
+    pub round_timeout_ms: u64,
 }
 
 impl Default for DagRoundStateConfig {
     fn default() -> Self {
         Self {
             adaptive_responsive_minimum_wait_time_ms: 500,
+            round_timeout_ms: 0,
         }
     }
 }
@@ -151,6 +155,91 @@ impl Default for DagHealthConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DagStateSyncConfig {
+    /// An ongoing sync is cancelled once a certified node arrives more than
+    /// `cancel_threshold_multiplier * window` rounds beyond the sync target round.
+    pub cancel_threshold_multiplier: u64,
+    /// Number of times fetching the missing nodes is attempted before the sync fails.
+    pub max_fetch_attempts: usize,
+    /// Backoff before the first retry, doubled on every subsequent retry.
+    pub fetch_retry_backoff_base_ms: u64,
+    /// Time to wait for the commit proof of a sync target to be handed to the execution
+    /// pipeline before moving on without it.
+    pub proof_notify_timeout_ms: u64,
+    /// Time to wait for the messages being processed to finish once the network handler
+    /// stops for a sync or at the end of the epoch.
+    pub handler_shutdown_timeout_ms: u64,
+    /// Garbage collection of the DAG data of old epochs.
+    pub gc_policy: DagGcPolicy,
+}
+
+impl Default for DagStateSyncConfig {
+    fn default() -> Self {
+        Self {
+            cancel_threshold_multiplier: 2,
+            max_fetch_attempts: 3,
+            fetch_retry_backoff_base_ms: 500,
+            proof_notify_timeout_ms: 5_000,
+            handler_shutdown_timeout_ms: 1_000,
+            gc_policy: DagGcPolicy::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DagGcPolicy {
+    /// Number of most recent epochs, including the current one, whose DAG data is kept.
+    pub keep_epochs: u64,
+}
+
+impl DagGcPolicy {
+    /// Data of epochs older than the returned epoch can be deleted once `current_epoch` starts.
+    pub fn gc_epoch(&self, current_epoch: u64) -> u64 {
+        (current_epoch + 1).saturating_sub(self.keep_epochs)
+    }
+}
+
+impl Default for DagGcPolicy {
+    fn default() -> Self {
+        Self { keep_epochs: 2 }
+    }
+}
+
+impl ConfigSanitizer for DagStateSyncConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let state_sync_config = &node_config.dag_consensus.state_sync_config;
+
+        if state_sync_config.cancel_threshold_multiplier < 1 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "cancel_threshold_multiplier must be at least 1".to_string(),
+            ));
+        }
+        if state_sync_config.max_fetch_attempts < 1 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "max_fetch_attempts must be at least 1".to_string(),
+            ));
+        }
+        if state_sync_config.gc_policy.keep_epochs < 1 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "gc_policy.keep_epochs must be at least 1".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DagConsensusConfig {
@@ -159,6 +248,7 @@ pub struct DagConsensusConfig {
     pub fetcher_config: DagFetcherConfig,
     pub round_state_config: DagRoundStateConfig,
     pub health_config: DagHealthConfig,
+    pub state_sync_config: DagStateSyncConfig,
     #[serde(default = "QuorumStoreConfig::default_for_dag")]
     pub quorum_store: QuorumStoreConfig,
 }
@@ -170,6 +260,7 @@ impl ConfigSanitizer for DagConsensusConfig {
         chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
         DagPayloadConfig::sanitize(node_config, node_type, chain_id)?;
+        DagStateSyncConfig::sanitize(node_config, node_type, chain_id)?;
 
         Ok(())
     }
@@ -220,4 +311,64 @@ mod tests {
             DagPayloadConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_sanitize_cancel_threshold_multiplier() {
+        // Create a node config with a zero multiplier
+        let node_config = NodeConfig {
+            dag_consensus: DagConsensusConfig {
+                state_sync_config: DagStateSyncConfig {
+                    cancel_threshold_multiplier: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            DagStateSyncConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_max_fetch_attempts() {
+        // Create a node config that never attempts to fetch
+        let node_config = NodeConfig {
+            dag_consensus: DagConsensusConfig {
+                state_sync_config: DagStateSyncConfig {
+                    max_fetch_attempts: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            DagStateSyncConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_gc_keep_epochs() {
+        // Create a node config that would delete the data of the current epoch
+        let node_config = NodeConfig {
+            dag_consensus: DagConsensusConfig {
+                state_sync_config: DagStateSyncConfig {
+                    gc_policy: DagGcPolicy { keep_epochs: 0 },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            DagStateSyncConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
@@ -20,6 +20,10 @@ pub struct IndexerTableInfoConfig {
     pub parser_batch_size: u16,
 
     pub enable_expensive_logging: bool,
+
+    /// Alert when the table info indexer falls behind the latest committed version by more
+    /// than this many versions. No alert is raised if unset.
+    pub lag_alert_threshold: Option<u64>,
 }
 
 // Reminder, #[serde(default)] on IndexerTableInfoConfig means that the default values for
@@ -32,6 +36,7 @@ impl Default for IndexerTableInfoConfig {
             parser_task_count: DEFAULT_PARSER_TASK_COUNT,
             parser_batch_size: DEFAULT_PARSER_BATCH_SIZE,
             enable_expensive_logging: false,
+            lag_alert_threshold: None,
         }
     }
 }
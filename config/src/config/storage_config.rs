@@ -127,6 +127,9 @@ pub struct RocksdbConfigs {
     pub index_db_config: RocksdbConfig,
     // Note: Not ready for production use yet.
     pub enable_storage_sharding: bool,
+    /// Bits per key of the bloom filter on the transaction by hash index, which saves disk reads
+    /// when looking up hashes of transactions not in the DB. 0 disables the filter.
+    pub transaction_hash_bloom_bits: u32,
 }
 
 impl Default for RocksdbConfigs {
@@ -140,6 +143,7 @@ impl Default for RocksdbConfigs {
                 ..Default::default()
             },
             enable_storage_sharding: false,
+            transaction_hash_bloom_bits: 10,
         }
     }
 }
@@ -175,6 +179,31 @@ pub struct StorageConfig {
     /// If not specificed, will use `dir` as default.
     /// Only allowed when sharding is enabled.
     pub db_path_overrides: Option<DbPathConfig>,
+    /// Write-ahead log of the transactions being committed.
+    pub wal_config: WalConfig,
+}
+
+/// Configuration of the write-ahead log (WAL), which records every batch of transactions before
+/// it is committed, so that a commit interrupted by a crash can be replayed on startup.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WalConfig {
+    pub enabled: bool,
+    /// Path of the WAL file. A relative path is resolved against the storage directory.
+    pub path: PathBuf,
+    /// Whether to fsync the WAL after each write. Without it, a WAL entry can be lost together
+    /// with the commit it describes if the machine (rather than only the process) crashes.
+    pub sync_on_write: bool,
+}
+
+impl Default for WalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from("commit_wal"),
+            sync_on_write: true,
+        }
+    }
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -324,6 +353,7 @@ impl Default for StorageConfig {
             db_path_overrides: None,
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            wal_config: WalConfig::default(),
         }
     }
 }
@@ -44,11 +44,12 @@ use std::{
 };
 
 pub trait OrderedNotifier: Send + Sync {
+    /// Sends the ordered nodes for execution as a single block, and returns the block id.
     fn send_ordered_nodes(
         &self,
         ordered_nodes: Vec<Arc<CertifiedNode>>,
         failed_author: Vec<(Round, Author)>,
-    );
+    ) -> HashValue;
 }
 
 #[async_trait]
@@ -133,7 +134,7 @@ impl OrderedNotifier for OrderedNotifierAdapter {
         &self,
         ordered_nodes: Vec<Arc<CertifiedNode>>,
         failed_author: Vec<(Round, Author)>,
-    ) {
+    ) -> HashValue {
         let anchor = ordered_nodes.last().unwrap();
         let epoch = anchor.epoch();
         let round = anchor.round();
@@ -187,6 +188,7 @@ impl OrderedNotifier for OrderedNotifierAdapter {
             vec![],
             StateComputeResult::new_dummy(),
         );
+        let block_id = block.id();
         let block_info = block.block_info();
         let ledger_info_provider = self.ledger_info_provider.clone();
         let dag = self.dag.clone();
@@ -224,6 +226,7 @@ impl OrderedNotifier for OrderedNotifierAdapter {
         {
             error!("[DAG] execution pipeline closed");
         }
+        block_id
     }
 }
 
@@ -295,13 +298,25 @@ impl StorageAdapter {
     }
 
     fn convert(&self, new_block_event: NewBlockEvent) -> anyhow::Result<CommitEvent> {
-        let validators = &self.epoch_to_validators[&new_block_event.epoch()];
+        Self::new_block_event_to_commit_event(
+            &self.epoch_to_validators[&new_block_event.epoch()],
+            new_block_event,
+        )
+    }
+
+    /// Converts the `NewBlockEvent` of a committed block back to the `CommitEvent` recorded
+    /// when the block was ordered.
+    pub(super) fn new_block_event_to_commit_event(
+        validators: &[Author],
+        new_block_event: NewBlockEvent,
+    ) -> anyhow::Result<CommitEvent> {
         Ok(CommitEvent::new(
             NodeId::new(
                 new_block_event.epoch(),
                 new_block_event.round(),
                 new_block_event.proposer(),
             ),
+            new_block_event.hash()?,
             Self::bitvec_to_validators(
                 validators,
                 &new_block_event.previous_block_votes_bitvec().clone().into(),
@@ -4,6 +4,7 @@
 use crate::{
     dag::{
         anchor_election::{AnchorElection, CommitHistory},
+        observability::counters::PARTICIPATION_RATIO,
         storage::CommitEvent,
     },
     liveness::{
@@ -20,7 +21,10 @@ use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_types::account_config::NewBlockEvent;
 use move_core_types::account_address::AccountAddress;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
 
 pub struct MetadataBackendAdapter {
     epoch_to_validators: HashMap<u64, HashMap<Author, usize>>,
@@ -47,6 +51,30 @@ impl MetadataBackendAdapter {
         self.sliding_window.lock().push_front(event);
     }
 
+    /// Returns the events in the sliding window, from the latest to the oldest.
+    pub fn snapshot_window(&self) -> Vec<CommitEvent> {
+        self.sliding_window.lock().iter().cloned().collect()
+    }
+
+    /// Returns whether the sliding window holds no event yet.
+    pub fn is_window_empty(&self) -> bool {
+        self.sliding_window.lock().is_empty()
+    }
+
+    /// Returns whether the sliding window holds `window_size` events.
+    pub fn is_window_full(&self) -> bool {
+        self.sliding_window.lock().len() >= self.window_size
+    }
+
+    /// Returns the epochs of the events in the sliding window.
+    pub fn window_epochs(&self) -> BTreeSet<u64> {
+        self.sliding_window
+            .lock()
+            .iter()
+            .map(|event| event.epoch())
+            .collect()
+    }
+
     // TODO: we should change NewBlockEvent on LeaderReputation to take a trait
     fn convert(&self, event: CommitEvent) -> NewBlockEvent {
         let validators = self.epoch_to_validators.get(&event.epoch()).unwrap();
@@ -59,7 +87,7 @@ impl MetadataBackendAdapter {
             failed_authors.push(*validators.get(author).unwrap() as u64);
         }
         NewBlockEvent::new(
-            AccountAddress::ZERO,
+            AccountAddress::new(*event.block_id()),
             event.epoch(),
             event.round(),
             0,
@@ -77,24 +105,34 @@ impl MetadataBackend for MetadataBackendAdapter {
         _target_epoch: u64,
         _target_round: Round,
     ) -> (Vec<NewBlockEvent>, HashValue) {
-        let events: Vec<_> = self
-            .sliding_window
-            .lock()
-            .clone()
+        let sliding_window = self.sliding_window.lock().clone();
+        // the latest commit is at the front of the window
+        let root_hash = sliding_window
+            .iter()
+            .next()
+            .map_or(HashValue::zero(), |event| event.block_id());
+        let events: Vec<_> = sliding_window
             .into_iter()
             .map(|event| self.convert(event))
             .collect();
-        (
-            events,
-            // TODO: fill in the hash value
-            HashValue::zero(),
-        )
+        (events, root_hash)
     }
 }
 
+/// Fallback for low voting power participation ratios while the commit history is partial.
+#[derive(Clone, Copy, Debug)]
+pub struct MinParticipationConfig {
+    /// Ratios below this threshold are replaced with `fallback_ratio`.
+    pub threshold: VotingPowerRatio,
+    pub fallback_ratio: VotingPowerRatio,
+}
+
 pub struct LeaderReputationAdapter {
     reputation: LeaderReputation,
     data_source: Arc<MetadataBackendAdapter>,
+    /// If set, low ratios fall back while the sliding window is not full yet. Once it is
+    /// full, the ratio reflects the actual participation.
+    min_participation_config: Option<MinParticipationConfig>,
 }
 
 impl LeaderReputationAdapter {
@@ -105,6 +143,7 @@ impl LeaderReputationAdapter {
         backend: Arc<MetadataBackendAdapter>,
         heuristic: Box<dyn ReputationHeuristic>,
         window_for_chain_health: usize,
+        min_participation_config: Option<MinParticipationConfig>,
     ) -> Self {
         Self {
             reputation: LeaderReputation::new(
@@ -118,6 +157,7 @@ impl LeaderReputationAdapter {
                 window_for_chain_health,
             ),
             data_source: backend,
+            min_participation_config,
         }
     }
 }
@@ -133,13 +173,20 @@ impl AnchorElection for LeaderReputationAdapter {
 }
 
 impl CommitHistory for LeaderReputationAdapter {
-    fn get_voting_power_participation_ratio(&self, round: Round) -> VotingPowerRatio {
-        let mut voting_power_ratio = self.reputation.get_voting_power_participation_ratio(round);
-        // TODO: fix this once leader reputation is fixed
-        if voting_power_ratio < 0.67 {
-            voting_power_ratio = 1.0;
+    fn get_voting_power_participation_ratio(&self, round: Round) -> Option<VotingPowerRatio> {
+        if self.data_source.is_window_empty() {
+            return None;
         }
-
-        voting_power_ratio
+        let voting_power_ratio = self.reputation.get_voting_power_participation_ratio(round);
+        let voting_power_ratio = match self.min_participation_config {
+            Some(config)
+                if voting_power_ratio < config.threshold && !self.data_source.is_window_full() =>
+            {
+                config.fallback_ratio
+            },
+            _ => voting_power_ratio,
+        };
+        PARTICIPATION_RATIO.observe(voting_power_ratio);
+        Some(voting_power_ratio)
     }
 }
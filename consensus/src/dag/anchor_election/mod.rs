@@ -11,11 +11,14 @@ pub trait AnchorElection: Send + Sync {
 }
 
 pub trait CommitHistory: Send + Sync {
-    fn get_voting_power_participation_ratio(&self, round: Round) -> VotingPowerRatio;
+    /// Returns `None` if there is no commit history to compute the ratio from.
+    fn get_voting_power_participation_ratio(&self, round: Round) -> Option<VotingPowerRatio>;
 }
 
 mod leader_reputation_adapter;
 mod round_robin;
 
-pub use leader_reputation_adapter::{LeaderReputationAdapter, MetadataBackendAdapter};
+pub use leader_reputation_adapter::{
+    LeaderReputationAdapter, MetadataBackendAdapter, MinParticipationConfig,
+};
 pub use round_robin::RoundRobinAnchorElection;
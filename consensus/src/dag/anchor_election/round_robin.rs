@@ -27,7 +27,7 @@ impl AnchorElection for RoundRobinAnchorElection {
 }
 
 impl CommitHistory for RoundRobinAnchorElection {
-    fn get_voting_power_participation_ratio(&self, _round: Round) -> VotingPowerRatio {
-        1.0
+    fn get_voting_power_participation_ratio(&self, _round: Round) -> Option<VotingPowerRatio> {
+        Some(1.0)
     }
 }
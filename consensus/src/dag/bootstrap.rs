@@ -20,7 +20,7 @@ use crate::{
     dag::{
         adapter::{compute_initial_block_and_ledger_info, LedgerInfoProvider},
         anchor_election::{LeaderReputationAdapter, MetadataBackendAdapter},
-        dag_state_sync::{SyncModeMessageHandler, SyncOutcome},
+        dag_state_sync::SyncOutcome,
         observability::logging::{LogEvent, LogSchema},
         round_state::{AdaptiveResponsive, RoundState},
     },
@@ -158,7 +158,17 @@ impl ActiveMode {
         // Run the network handler until it returns with state sync status.
         let sync_outcome = self
             .handler
-            .run(dag_rpc_rx, bootstrapper.executor.clone(), self.buffer)
+            .run(
+                dag_rpc_rx,
+                bootstrapper.executor.clone(),
+                self.buffer,
+                Duration::from_millis(
+                    bootstrapper
+                        .config
+                        .state_sync_config
+                        .handler_shutdown_timeout_ms,
+                ),
+            )
             .await;
 
         info!(
@@ -211,6 +221,7 @@ impl SyncMode {
             bootstrapper
                 .onchain_config
                 .dag_ordering_causal_history_window as Round,
+            bootstrapper.config.state_sync_config.clone(),
         );
 
         let highest_committed_anchor_round = self
@@ -244,14 +255,7 @@ impl SyncMode {
 
         let commit_li = self.certified_node_msg.ledger_info().clone();
 
-        let network_handle = SyncModeMessageHandler::new(
-            bootstrapper.epoch_state.clone(),
-            request.start_round(),
-            request.target_round(),
-            bootstrapper
-                .onchain_config
-                .dag_ordering_causal_history_window as u64,
-        );
+        let network_handle = sync_manager.sync_mode_message_handler(&request);
 
         let (res_tx, res_rx) = oneshot::channel();
         let handle = tokio::spawn(async move {
@@ -428,6 +432,7 @@ impl DagBootstrapper {
             metadata_adapter,
             heuristic,
             100,
+            None,
         ))
     }
 
@@ -570,6 +575,7 @@ impl DagBootstrapper {
             dag_store.clone(),
             self.proof_notifier.clone(),
             self.onchain_config.dag_ordering_causal_history_window as Round,
+            Duration::from_millis(self.config.state_sync_config.proof_notify_timeout_ms),
         );
 
         let (dag_fetcher, fetch_requester, node_fetch_waiter, certified_node_fetch_waiter) =
@@ -624,6 +630,12 @@ impl DagBootstrapper {
             health_backoff.clone(),
             self.quorum_store_enabled,
         );
+        let dag_driver = if round_state_config.round_timeout_ms > 0 {
+            dag_driver
+                .with_round_timeout(Duration::from_millis(round_state_config.round_timeout_ms))
+        } else {
+            dag_driver
+        };
         let rb_handler = NodeBroadcastHandler::new(
             dag_store.clone(),
             self.signer.clone(),
@@ -675,6 +687,12 @@ impl DagBootstrapper {
             epoch = self.epoch_state.epoch,
         );
 
+        DagStateSynchronizer::spawn_gc(
+            self.storage.clone(),
+            self.epoch_state.epoch,
+            self.config.state_sync_config.gc_policy,
+        );
+
         let (base_state, handler, fetch_service) = self.full_bootstrap();
 
         let mut mode = Mode::Active(ActiveMode {
@@ -749,7 +767,17 @@ pub(super) fn bootstrap_dag_for_test(
     let dh_handle = tokio::spawn(async move {
         let mut dag_rpc_rx = dag_rpc_rx;
         handler
-            .run(&mut dag_rpc_rx, bootstraper.executor.clone(), Vec::new())
+            .run(
+                &mut dag_rpc_rx,
+                bootstraper.executor.clone(),
+                Vec::new(),
+                Duration::from_millis(
+                    bootstraper
+                        .config
+                        .state_sync_config
+                        .handler_shutdown_timeout_ms,
+                ),
+            )
             .await
     });
     let df_handle = tokio::spawn(fetch_service.start());
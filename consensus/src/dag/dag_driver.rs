@@ -55,11 +55,13 @@ pub(crate) struct DagDriver {
     order_rule: Mutex<OrderRule>,
     fetch_requester: Arc<dyn TFetchRequester>,
     ledger_info_provider: Arc<dyn TLedgerInfoProvider>,
-    round_state: RoundState,
+    round_state: Arc<RoundState>,
     window_size_config: Round,
     payload_config: DagPayloadConfig,
     health_backoff: HealthBackoff,
     quorum_store_enabled: bool,
+    round_timeout: Option<Duration>,
+    round_timer: Mutex<Option<DropGuard>>,
 }
 
 impl DagDriver {
@@ -99,11 +101,13 @@ impl DagDriver {
             order_rule: Mutex::new(order_rule),
             fetch_requester,
             ledger_info_provider,
-            round_state,
+            round_state: Arc::new(round_state),
             window_size_config,
             payload_config,
             health_backoff,
             quorum_store_enabled,
+            round_timeout: None,
+            round_timer: Mutex::new(None),
         };
 
         // If we were broadcasting the node for the round already, resume it
@@ -128,6 +132,72 @@ impl DagDriver {
         driver
     }
 
+    /// Forces progression once a round has not advanced for `round_timeout`.
+    pub fn with_round_timeout(mut self, round_timeout: Duration) -> Self {
+        self.round_timeout = Some(round_timeout);
+        let current_round = self.round_state.current_round();
+        if current_round > 0 {
+            self.start_round_timer(current_round);
+        }
+        self
+    }
+
+    /// Moves to the next round if the DAG has strong links for the current one, without
+    /// waiting for the responsive check. A round without strong links cannot be left, so
+    /// nothing happens then. Returns whether a new round was triggered.
+    pub fn force_advance_round(&self) -> bool {
+        Self::force_advance(
+            &self.dag,
+            &self.epoch_state,
+            &self.round_state,
+            self.round_state.current_round(),
+        )
+    }
+
+    fn force_advance(
+        dag: &DagStore,
+        epoch_state: &EpochState,
+        round_state: &RoundState,
+        current_round: Round,
+    ) -> bool {
+        let highest_strong_links_round =
+            dag.read().highest_strong_links_round(&epoch_state.verifier);
+        if highest_strong_links_round < current_round {
+            debug!(
+                LogSchema::new(LogEvent::RoundTimeout).round(current_round),
+                "cannot advance round without strong links"
+            );
+            return false;
+        }
+        round_state.force_new_round(highest_strong_links_round + 1);
+        true
+    }
+
+    fn start_round_timer(&self, round: Round) {
+        let Some(round_timeout) = self.round_timeout else {
+            return;
+        };
+        let time_service = self.time_service.clone();
+        let dag = self.dag.clone();
+        let epoch_state = self.epoch_state.clone();
+        let round_state = self.round_state.clone();
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let task = async move {
+            time_service.sleep(round_timeout).await;
+            if round_state.current_round() == round {
+                debug!(
+                    LogSchema::new(LogEvent::RoundTimeout).round(round),
+                    "round timed out"
+                );
+                counters::ROUND_TIMEOUT_COUNT.inc();
+                Self::force_advance(&dag, &epoch_state, &round_state, round);
+            }
+        };
+        tokio::spawn(Abortable::new(task, abort_registration));
+        // Replacing the guard aborts the timer of the previous round
+        *self.round_timer.lock() = Some(DropGuard::new(abort_handle));
+    }
+
     fn add_node(&self, node: CertifiedNode) -> anyhow::Result<()> {
         let (highest_strong_link_round, strong_links) = {
             {
@@ -185,6 +255,7 @@ impl DagDriver {
         }
         debug!(LogSchema::new(LogEvent::NewRound).round(new_round));
         counters::CURRENT_ROUND.set(new_round as i64);
+        self.start_round_timer(new_round);
 
         let strong_links = self
             .dag
@@ -22,9 +22,144 @@ use aptos_channels::aptos_channel;
 use aptos_consensus_types::common::{Author, Round};
 use aptos_logger::{debug, error, warn};
 use aptos_types::epoch_state::EpochState;
-use futures::{stream::FuturesUnordered, StreamExt};
-use std::sync::Arc;
-use tokio::{runtime::Handle, select};
+use futures::{stream::FuturesUnordered, Future, StreamExt};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    runtime::Handle,
+    select,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
+
+/// Order in which verified DAG messages are processed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Priority {
+    High,
+    Normal,
+    Low,
+}
+
+/// A verified DAG message along with what is needed to respond to it.
+pub(crate) struct PriorityDagMessage {
+    priority: Priority,
+    message: anyhow::Result<DAGMessage>,
+    epoch: u64,
+    author: Author,
+    responder: RpcResponder,
+}
+
+impl PriorityDagMessage {
+    pub(crate) fn new(
+        message: anyhow::Result<DAGMessage>,
+        epoch: u64,
+        author: Author,
+        responder: RpcResponder,
+    ) -> Self {
+        // Certified nodes are processed first as they drive the anchor election, while
+        // fetch requests only serve other peers.
+        let priority = match &message {
+            Ok(DAGMessage::CertifiedNodeMsg(_)) => Priority::High,
+            Ok(DAGMessage::NodeMsg(_)) => Priority::Normal,
+            _ => Priority::Low,
+        };
+        Self {
+            priority,
+            message,
+            epoch,
+            author,
+            responder,
+        }
+    }
+
+    pub(crate) fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub(crate) fn message(&self) -> &anyhow::Result<DAGMessage> {
+        &self.message
+    }
+}
+
+/// Maximum number of verified messages waiting to be dispatched. The network handler stops
+/// taking verified messages once reached, so that a flood of messages is back-pressured
+/// instead of buffered.
+const MAX_QUEUED_MESSAGES: usize = 8;
+
+/// Buffers verified messages in a channel per priority and hands them out highest priority
+/// first.
+pub(crate) struct PriorityMessageQueue {
+    high_tx: UnboundedSender<PriorityDagMessage>,
+    high_rx: UnboundedReceiver<PriorityDagMessage>,
+    normal_tx: UnboundedSender<PriorityDagMessage>,
+    normal_rx: UnboundedReceiver<PriorityDagMessage>,
+    low_tx: UnboundedSender<PriorityDagMessage>,
+    low_rx: UnboundedReceiver<PriorityDagMessage>,
+    len: usize,
+}
+
+impl PriorityMessageQueue {
+    pub(crate) fn new() -> Self {
+        let (high_tx, high_rx) = unbounded_channel();
+        let (normal_tx, normal_rx) = unbounded_channel();
+        let (low_tx, low_rx) = unbounded_channel();
+        Self {
+            high_tx,
+            high_rx,
+            normal_tx,
+            normal_rx,
+            low_tx,
+            low_rx,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn push(&mut self, message: PriorityDagMessage) {
+        let tx = match message.priority {
+            Priority::High => &self.high_tx,
+            Priority::Normal => &self.normal_tx,
+            Priority::Low => &self.low_tx,
+        };
+        // The receivers are owned by the queue, so the channels are never closed.
+        let _ = tx.send(message);
+        self.len += 1;
+    }
+
+    /// Waits for the next message, taking it from the highest priority channel that has one.
+    pub(crate) async fn next(&mut self) -> PriorityDagMessage {
+        let message = select! {
+            biased;
+            Some(message) = self.high_rx.recv() => message,
+            Some(message) = self.normal_rx.recv() => message,
+            Some(message) = self.low_rx.recv() => message,
+        };
+        self.len -= 1;
+        message
+    }
+}
+
+/// Waits for at most `timeout` for the futures still in flight to complete, so that the
+/// messages being processed (e.g. certified node acknowledgements) are not dropped midway.
+/// Returns the number of futures that did not complete in time.
+pub(crate) async fn drain_in_flight<F: Future>(
+    futures: &mut FuturesUnordered<F>,
+    timeout: Duration,
+) -> usize {
+    let in_flight = futures.len();
+    if tokio::time::timeout(timeout, async { while futures.next().await.is_some() {} })
+        .await
+        .is_err()
+    {
+        warn!(
+            in_flight = in_flight,
+            remaining = futures.len(),
+            "timed out draining in-flight DAG messages"
+        );
+    }
+    futures.len()
+}
 
 pub(crate) struct NetworkHandler {
     epoch_state: Arc<EpochState>,
@@ -71,6 +206,7 @@ impl NetworkHandler {
         dag_rpc_rx: &mut aptos_channel::Receiver<Author, IncomingDAGRequest>,
         executor: BoundedExecutor,
         _buffer: Vec<DAGMessage>,
+        shutdown_timeout: Duration,
     ) -> SyncOutcome {
         // TODO: process buffer
         let NetworkHandler {
@@ -119,37 +255,22 @@ impl NetworkHandler {
         });
         defer!(handle.abort());
 
+        let mut message_queue = PriorityMessageQueue::new();
         let mut futures = FuturesUnordered::new();
         // A separate executor to ensure the message verification sender (above) and receiver (below) are
         // not blocking each other.
         // TODO: make this configurable
         let executor = BoundedExecutor::new(8, Handle::current());
         loop {
+            // Completions are polled first, so that a sync outcome is acted upon and the
+            // executor slots are freed before taking more work. Verified messages are only
+            // taken while the queue has room; dispatching them is bounded by the executor.
             select! {
-                Some((msg, epoch, author, responder)) = verified_msg_stream.next() => {
-                    let verified_msg_processor = verified_msg_processor.clone();
-                    let f = executor.spawn(async move {
-                        monitor!("dag_on_verified_msg", {
-                            match verified_msg_processor.process_verified_message(msg, epoch, author, responder).await {
-                                Ok(sync_status) => {
-                                    if matches!(
-                                        sync_status,
-                                        SyncOutcome::NeedsSync(_) | SyncOutcome::EpochEnds
-                                    ) {
-                                        return Some(sync_status);
-                                    }
-                                },
-                                Err(e) => {
-                                    warn!(error = ?e, "error processing rpc");
-                                },
-                            };
-                            None
-                        })
-                    }).await;
-                    futures.push(f);
-                },
+                biased;
                 Some(status) = futures.next() => {
                     if let Some(status) = status.expect("future must not panic") {
+                        // Stop taking new messages, but let those being processed finish.
+                        drain_in_flight(&mut futures, shutdown_timeout).await;
                         return status;
                     }
                 },
@@ -183,6 +304,31 @@ impl NetworkHandler {
                         });
                     }).await;
                 },
+                Some((msg, epoch, author, responder)) = verified_msg_stream.next(), if message_queue.len() < MAX_QUEUED_MESSAGES => {
+                    message_queue.push(PriorityDagMessage::new(msg, epoch, author, responder));
+                },
+                PriorityDagMessage { message: msg, epoch, author, responder, .. } = message_queue.next() => {
+                    let verified_msg_processor = verified_msg_processor.clone();
+                    let f = executor.spawn(async move {
+                        monitor!("dag_on_verified_msg", {
+                            match verified_msg_processor.process_verified_message(msg, epoch, author, responder).await {
+                                Ok(sync_status) => {
+                                    if matches!(
+                                        sync_status,
+                                        SyncOutcome::NeedsSync(_) | SyncOutcome::EpochEnds
+                                    ) {
+                                        return Some(sync_status);
+                                    }
+                                },
+                                Err(e) => {
+                                    warn!(error = ?e, "error processing rpc");
+                                },
+                            };
+                            None
+                        })
+                    }).await;
+                    futures.push(f);
+                },
             }
         }
     }
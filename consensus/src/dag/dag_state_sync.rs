@@ -4,6 +4,7 @@ use super::{
     adapter::TLedgerInfoProvider,
     dag_fetcher::TDagFetcher,
     dag_store::DagStore,
+    observability::counters::PROOF_NOTIFY_TIMEOUT_COUNT,
     storage::DAGStorage,
     types::{CertifiedNodeMessage, RemoteFetchRequest},
     ProofNotifier,
@@ -14,15 +15,17 @@ use crate::{
 };
 use anyhow::ensure;
 use aptos_channels::aptos_channel;
+use aptos_config::config::{DagGcPolicy, DagStateSyncConfig};
 use aptos_consensus_types::common::{Author, Round};
-use aptos_logger::{debug, error};
-use aptos_time_service::TimeService;
+use aptos_logger::{debug, error, info, warn};
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     epoch_change::EpochChangeProof, epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
 };
 use core::fmt;
 use futures::StreamExt;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
 
 #[derive(Debug)]
 pub enum SyncOutcome {
@@ -41,12 +44,32 @@ impl fmt::Display for SyncOutcome {
     }
 }
 
+/// Why a sync to a ledger info is needed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncReason {
+    NotNeeded,
+    /// There's nothing in the DAG to fetch missing nodes against.
+    DagEmpty,
+    /// The highest DAG round is more than a window behind the ledger info round.
+    ConsensusBehind,
+    /// The highest committed anchor round is more than two windows behind the ledger info round,
+    /// so the nodes needed for execution are garbage collected.
+    ExecutionBehindWindow,
+}
+
+impl SyncReason {
+    pub fn is_needed(&self) -> bool {
+        *self != SyncReason::NotNeeded
+    }
+}
+
 pub(super) struct StateSyncTrigger {
     epoch_state: Arc<EpochState>,
     ledger_info_provider: Arc<dyn TLedgerInfoProvider>,
     dag_store: Arc<DagStore>,
     proof_notifier: Arc<dyn ProofNotifier>,
     dag_window_size_config: Round,
+    notify_timeout: Duration,
 }
 
 impl StateSyncTrigger {
@@ -56,6 +79,7 @@ impl StateSyncTrigger {
         dag_store: Arc<DagStore>,
         proof_notifier: Arc<dyn ProofNotifier>,
         dag_window_size_config: Round,
+        notify_timeout: Duration,
     ) -> Self {
         Self {
             epoch_state,
@@ -63,6 +87,7 @@ impl StateSyncTrigger {
             dag_store,
             proof_notifier,
             dag_window_size_config,
+            notify_timeout,
         }
     }
 
@@ -82,9 +107,15 @@ impl StateSyncTrigger {
     pub(super) async fn check(&self, node: CertifiedNodeMessage) -> anyhow::Result<SyncOutcome> {
         let ledger_info_with_sigs = node.ledger_info();
 
-        if !self.need_sync_for_ledger_info(ledger_info_with_sigs) {
+        let sync_reason = self.sync_reason(ledger_info_with_sigs);
+        if !sync_reason.is_needed() {
             return Ok(SyncOutcome::Synced(Some(node)));
         }
+        info!(
+            "sync needed to ledger info at round {}, reason: {:?}",
+            ledger_info_with_sigs.commit_info().round(),
+            sync_reason
+        );
 
         // Only verify the certificate if we need to sync
         self.verify_ledger_info(ledger_info_with_sigs)?;
@@ -104,7 +135,8 @@ impl StateSyncTrigger {
         Ok(SyncOutcome::NeedsSync(node))
     }
 
-    /// Fast forward in the decoupled-execution pipeline if the block exists there
+    /// Fast forward in the decoupled-execution pipeline if the block exists there.
+    /// Gives up after `notify_timeout` so that a stalled pipeline doesn't block the DAG handler.
     async fn notify_commit_proof(&self, ledger_info: &LedgerInfoWithSignatures) {
         // if the anchor exists between ledger info round and highest ordered round
         // Note: ledger info round <= highest ordered round
@@ -119,38 +151,54 @@ impl StateSyncTrigger {
                 .unwrap_or_default()
                 >= ledger_info.commit_info().round()
         {
-            self.proof_notifier
-                .send_commit_proof(ledger_info.clone())
+            let notify = self.proof_notifier.send_commit_proof(ledger_info.clone());
+            if tokio::time::timeout(self.notify_timeout, notify)
                 .await
+                .is_err()
+            {
+                warn!(
+                    "timed out notifying the commit proof at round {} after {:?}",
+                    ledger_info.commit_info().round(),
+                    self.notify_timeout
+                );
+                PROOF_NOTIFY_TIMEOUT_COUNT.inc();
+            }
         }
     }
 
-    /// Check if we're far away from this ledger info and need to sync.
+    /// Check if we're far away from this ledger info and need to sync, and why.
     /// This ensures that the block referred by the ledger info is not in buffer manager.
-    fn need_sync_for_ledger_info(&self, li: &LedgerInfoWithSignatures) -> bool {
+    pub(super) fn sync_reason(&self, li: &LedgerInfoWithSignatures) -> SyncReason {
         if li.commit_info().round()
             <= self
                 .ledger_info_provider
                 .get_highest_committed_anchor_round()
         {
-            return false;
+            return SyncReason::NotNeeded;
         }
 
-        let dag_reader = self.dag_store.read();
+        let summary = self.dag_store.round_summary();
         // check whether if DAG order round is behind the given ledger info committed round
         // (meaning consensus is behind) or
         // the local highest committed anchor round is 2*DAG_WINDOW behind the given ledger info round
         // (meaning execution is behind the DAG window)
 
         // fetch can't work since nodes are garbage collected
-        dag_reader.is_empty()
-            || dag_reader.highest_round() + 1 + self.dag_window_size_config
-                < li.commit_info().round()
-            || self
-                .ledger_info_provider
-                .get_highest_committed_anchor_round()
-                + 2 * self.dag_window_size_config
-                < li.commit_info().round()
+        if summary.is_empty {
+            SyncReason::DagEmpty
+        } else if summary.highest_round + 1 + self.dag_window_size_config < li.commit_info().round()
+        {
+            SyncReason::ConsensusBehind
+        } else if self
+            .ledger_info_provider
+            .get_highest_committed_anchor_round()
+            + 2 * self.dag_window_size_config
+            < li.commit_info().round()
+        {
+            SyncReason::ExecutionBehindWindow
+        } else {
+            SyncReason::NotNeeded
+        }
     }
 }
 
@@ -161,6 +209,7 @@ pub(super) struct DagStateSynchronizer {
     storage: Arc<dyn DAGStorage>,
     payload_manager: Arc<dyn TPayloadManager>,
     dag_window_size_config: Round,
+    config: DagStateSyncConfig,
 }
 
 impl DagStateSynchronizer {
@@ -171,7 +220,16 @@ impl DagStateSynchronizer {
         storage: Arc<dyn DAGStorage>,
         payload_manager: Arc<dyn TPayloadManager>,
         dag_window_size_config: Round,
+        config: DagStateSyncConfig,
     ) -> Self {
+        assert!(
+            config.cancel_threshold_multiplier >= 1,
+            "cancel_threshold_multiplier must be at least 1"
+        );
+        assert!(
+            config.max_fetch_attempts >= 1,
+            "max_fetch_attempts must be at least 1"
+        );
         Self {
             epoch_state,
             time_service,
@@ -179,9 +237,24 @@ impl DagStateSynchronizer {
             storage,
             payload_manager,
             dag_window_size_config,
+            config,
         }
     }
 
+    /// Creates the handler for messages received while syncing towards the target of `request`.
+    pub(crate) fn sync_mode_message_handler(
+        &self,
+        request: &RemoteFetchRequest,
+    ) -> SyncModeMessageHandler {
+        SyncModeMessageHandler::new(
+            self.epoch_state.clone(),
+            request.start_round(),
+            request.target_round(),
+            self.dag_window_size_config,
+            self.config.cancel_threshold_multiplier,
+        )
+    }
+
     pub(crate) fn build_request(
         &self,
         node: &CertifiedNodeMessage,
@@ -190,17 +263,15 @@ impl DagStateSynchronizer {
     ) -> (RemoteFetchRequest, Vec<Author>, Arc<DagStore>) {
         let commit_li = node.ledger_info();
 
-        {
-            let dag_reader = current_dag_store.read();
-            assert!(
-                dag_reader
-                    .highest_ordered_anchor_round()
-                    .unwrap_or_default()
+        assert!(
+            current_dag_store
+                .round_summary()
+                .highest_ordered_anchor_round
+                .unwrap_or_default()
+                < commit_li.commit_info().round()
+                || highest_committed_anchor_round + self.dag_window_size_config
                     < commit_li.commit_info().round()
-                    || highest_committed_anchor_round + self.dag_window_size_config
-                        < commit_li.commit_info().round()
-            );
-        }
+        );
 
         // TODO: there is a case where DAG fetches missing nodes in window and a crash happens and when we restart,
         // we end up with a gap between the DAG and we need to be smart enough to clean up the DAG before the gap.
@@ -218,7 +289,8 @@ impl DagStateSynchronizer {
             start_round,
             self.dag_window_size_config,
         ));
-        let bitmask = { sync_dag_store.read().bitmask(target_round) };
+        // Reuse the nodes the current DAG already has instead of fetching them again.
+        let bitmask = sync_dag_store.union_bitmask(&current_dag_store, target_round);
         let request = RemoteFetchRequest::new(
             self.epoch_state.epoch,
             vec![node.metadata().clone()],
@@ -237,26 +309,77 @@ impl DagStateSynchronizer {
     pub async fn sync_dag_to(
         &self,
         dag_fetcher: impl TDagFetcher,
-        request: RemoteFetchRequest,
+        mut request: RemoteFetchRequest,
         responders: Vec<Author>,
         sync_dag_store: Arc<DagStore>,
         commit_li: LedgerInfoWithSignatures,
     ) -> anyhow::Result<DagStore> {
-        match dag_fetcher
-            .fetch(request, responders, sync_dag_store.clone())
-            .await
-        {
-            Ok(_) => {},
-            Err(err) => {
-                error!("error fetching nodes {}", err);
-                return Err(err);
-            },
+        let target_round = request.target_round();
+        let mut attempt = 1;
+        loop {
+            match dag_fetcher
+                .fetch(request.clone(), responders.clone(), sync_dag_store.clone())
+                .await
+            {
+                Ok(_) => break,
+                Err(err) if attempt < self.config.max_fetch_attempts => {
+                    let backoff = Duration::from_millis(self.config.fetch_retry_backoff_base_ms)
+                        .saturating_mul(2u32.saturating_pow(attempt as u32 - 1));
+                    warn!(
+                        "error fetching nodes (attempt {}/{}), retrying in {:?}: {}",
+                        attempt, self.config.max_fetch_attempts, backoff, err
+                    );
+                    self.time_service.sleep(backoff).await;
+                    attempt += 1;
+
+                    // Only ask for the nodes that are still missing.
+                    let bitmask = { sync_dag_store.read().bitmask(target_round) };
+                    request = RemoteFetchRequest::new(
+                        request.epoch(),
+                        request.targets().cloned().collect(),
+                        bitmask,
+                    );
+                },
+                Err(err) => {
+                    error!(
+                        "error fetching nodes after {} attempts {}",
+                        self.config.max_fetch_attempts, err
+                    );
+                    return Err(err);
+                },
+            }
         }
 
+        // A fetch that left a gap would leave the DAG unable to order past it.
+        let lowest_round = sync_dag_store.read().lowest_round();
+        sync_dag_store.verify_contiguous(lowest_round, target_round)?;
+
         self.state_computer.sync_to(commit_li).await?;
 
         Ok(Arc::into_inner(sync_dag_store).unwrap())
     }
+
+    /// Deletes the stored DAG data of the epochs that `gc_policy` no longer keeps in the
+    /// background. Called whenever a new epoch starts.
+    pub(crate) fn spawn_gc(
+        storage: Arc<dyn DAGStorage>,
+        epoch: u64,
+        gc_policy: DagGcPolicy,
+    ) -> JoinHandle<()> {
+        tokio::task::spawn_blocking(move || {
+            let gc_epoch = gc_policy.gc_epoch(epoch);
+            match storage.gc_epoch(gc_epoch) {
+                Ok(bytes_freed) => info!(
+                    "garbage collected DAG data before epoch {}, freed ~{} bytes",
+                    gc_epoch, bytes_freed
+                ),
+                Err(err) => error!(
+                    "error garbage collecting DAG data before epoch {}: {}",
+                    gc_epoch, err
+                ),
+            }
+        })
+    }
 }
 
 pub(crate) struct SyncModeMessageHandler {
@@ -264,6 +387,7 @@ pub(crate) struct SyncModeMessageHandler {
     start_round: Round,
     target_round: Round,
     window: u64,
+    cancel_threshold_multiplier: u64,
 }
 
 impl SyncModeMessageHandler {
@@ -272,15 +396,27 @@ impl SyncModeMessageHandler {
         start_round: Round,
         target_round: Round,
         window: u64,
+        cancel_threshold_multiplier: u64,
     ) -> Self {
+        assert!(
+            cancel_threshold_multiplier >= 1,
+            "cancel_threshold_multiplier must be at least 1"
+        );
         Self {
             epoch_state,
             start_round,
             target_round,
             window,
+            cancel_threshold_multiplier,
         }
     }
 
+    /// A certified node far enough beyond the sync target means the sync is already stale, so
+    /// it is cancelled in favor of syncing to the newer node.
+    pub(crate) fn should_cancel_sync(&self, round: Round) -> bool {
+        round > self.target_round + (self.cancel_threshold_multiplier * self.window)
+    }
+
     pub(crate) async fn run(
         mut self,
         dag_rpc_rx: &mut aptos_channel::Receiver<Author, IncomingDAGRequest>,
@@ -322,7 +458,7 @@ impl SyncModeMessageHandler {
                 DAGMessage::CertifiedNodeMsg(ref cert_node_msg) => {
                     if cert_node_msg.round() < self.start_round {
                         debug!("ignoring stale certified node msg");
-                    } else if cert_node_msg.round() > self.target_round + (2 * self.window) {
+                    } else if self.should_cancel_sync(cert_node_msg.round()) {
                         debug!("cancelling current sync");
                         return Ok(Some(cert_node_msg.clone()));
                     } else {
@@ -430,6 +430,14 @@ impl InMemDag {
     }
 }
 
+/// Consistent snapshot of the rounds of a DAG.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DagRoundSummary {
+    pub highest_round: Round,
+    pub highest_ordered_anchor_round: Option<Round>,
+    pub is_empty: bool,
+}
+
 pub struct DagStore {
     dag: RwLock<InMemDag>,
     storage: Arc<dyn DAGStorage>,
@@ -518,6 +526,79 @@ impl DagStore {
         self.dag.write().add_validated_node(node)
     }
 
+    /// Returns the summary of the rounds in the DAG, read under a single lock.
+    pub fn round_summary(&self) -> DagRoundSummary {
+        let dag_reader = self.dag.read();
+        DagRoundSummary {
+            highest_round: dag_reader.highest_round(),
+            highest_ordered_anchor_round: dag_reader.highest_ordered_anchor_round(),
+            is_empty: dag_reader.is_empty(),
+        }
+    }
+
+    /// Checks that every round in [`start_round`, `target_round`) has nodes with a quorum of
+    /// voting power and `target_round` has nodes, i.e. there's no gap left by an incomplete fetch.
+    /// Every node links to a quorum of nodes of the previous round, so only the target round can
+    /// have less.
+    pub fn verify_contiguous(&self, start_round: Round, target_round: Round) -> anyhow::Result<()> {
+        let dag_reader = self.dag.read();
+        for round in start_round..=target_round {
+            let mut authors = dag_reader
+                .get_round_iter(round)
+                .into_iter()
+                .flatten()
+                .map(|node_status| node_status.as_node().metadata().author())
+                .peekable();
+            let complete = if round < target_round {
+                dag_reader
+                    .epoch_state
+                    .verifier
+                    .check_voting_power(authors, true)
+                    .is_ok()
+            } else {
+                authors.peek().is_some()
+            };
+            ensure!(
+                complete,
+                "gap in dag at round {}, expected a quorum of nodes in rounds [{}, {}) and nodes in round {}",
+                round,
+                start_round,
+                target_round,
+                target_round
+            );
+        }
+        Ok(())
+    }
+
+    /// Adds the nodes of `existing` between the lowest round of this store and `target_round`,
+    /// and returns the bitmask of the nodes now present, i.e. the union of the nodes of both
+    /// stores, so that a fetch request into this store skips the nodes `existing` already has.
+    /// The nodes of `existing` are already persisted, so they are only added in memory.
+    pub fn union_bitmask(&self, existing: &DagStore, target_round: Round) -> DagSnapshotBitmask {
+        let lowest_round = self.read().lowest_round();
+        let existing_nodes: Vec<_> = existing
+            .read()
+            .nodes_by_round
+            .range(lowest_round..=target_round)
+            .flat_map(|(_, round_nodes)| round_nodes.iter().flatten())
+            .map(|node_status| node_status.as_node().as_ref().clone())
+            .collect();
+        let mut dag_writer = self.dag.write();
+        for node in existing_nodes {
+            if dag_writer.exists(node.metadata()) {
+                continue;
+            }
+            // Nodes whose parents are missing from this store are fetched instead.
+            if let Err(e) = dag_writer
+                .validate_new_node(&node)
+                .and_then(|_| dag_writer.add_validated_node(node))
+            {
+                debug!("Not reusing existing node due to {}", e);
+            }
+        }
+        dag_writer.bitmask(target_round)
+    }
+
     pub fn commit_callback(&self, commit_round: Round) {
         let to_prune = self.dag.write().commit_callback(commit_round);
         if let Some(to_prune) = to_prune {
@@ -56,9 +56,10 @@ impl ChainHealthBackoff {
     }
 
     fn get_chain_health_backoff(&self, round: Round) -> Option<&ChainHealthBackoffValues> {
+        // without commit history there is nothing to back off from
         let voting_power_ratio = self
             .commit_history
-            .get_voting_power_participation_ratio(round);
+            .get_voting_power_participation_ratio(round)?;
         let chain_health_backoff = self.config.get_backoff(voting_power_ratio);
 
         chain_health_backoff
@@ -92,5 +93,6 @@ impl TChainHealth for ChainHealthBackoff {
     fn voting_power_ratio(&self, round: Round) -> VotingPowerRatio {
         self.commit_history
             .get_voting_power_participation_ratio(round)
+            .unwrap_or(1.0)
     }
 }
@@ -17,6 +17,7 @@ mod health;
 mod observability;
 mod order_rule;
 mod rb_handler;
+mod recent_node_filter;
 mod round_state;
 mod storage;
 #[cfg(test)]
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    register_histogram, register_histogram_vec, register_int_gauge, Histogram, HistogramVec,
-    IntGauge,
+    register_histogram, register_histogram_vec, register_int_counter, register_int_gauge,
+    Histogram, HistogramVec, IntCounter, IntGauge,
 };
 use once_cell::sync::Lazy;
 
@@ -60,6 +60,34 @@ pub static NUM_NODES_PER_BLOCK: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Histogram of the voting power participation ratio of the commit history
+pub static PARTICIPATION_RATIO: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_consensus_dag_participation_ratio",
+        "Histogram of the voting power participation ratio of the commit history",
+        vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.67, 0.7, 0.8, 0.9, 1.0],
+    )
+    .unwrap()
+});
+
+/// Count of rounds that timed out before advancing
+pub static ROUND_TIMEOUT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_dag_round_timeout_total",
+        "Count of rounds that timed out before advancing",
+    )
+    .unwrap()
+});
+
+/// Count of duplicate nodes answered with the existing vote without being validated again
+pub static DUPLICATE_NODES_FILTERED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_dag_duplicate_nodes_filtered_total",
+        "Count of duplicate nodes answered with the existing vote without being validated again",
+    )
+    .unwrap()
+});
+
 pub static NUM_ROUNDS_PER_BLOCK: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         "aptos_consensus_dag_num_rounds_per_block",
@@ -67,3 +95,12 @@ pub static NUM_ROUNDS_PER_BLOCK: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Count of commit proofs not handed to the execution pipeline in time during a state sync check
+pub static PROOF_NOTIFY_TIMEOUT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_dag_proof_notify_timeout_total",
+        "Count of commit proofs not handed to the execution pipeline in time",
+    )
+    .unwrap()
+});
@@ -25,6 +25,7 @@ pub enum LogEvent {
     ReceiveAck,
     OrderedAnchor,
     NewRound,
+    RoundTimeout,
     FetchNodes,
     ReceiveFetchNodes,
     ActiveMode,
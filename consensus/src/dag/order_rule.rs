@@ -14,12 +14,37 @@ use crate::dag::{
     types::NodeMetadata,
     CertifiedNode,
 };
-use aptos_consensus_types::common::Round;
+use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::HashValue;
 use aptos_infallible::RwLock;
 use aptos_logger::debug;
 use aptos_types::epoch_state::EpochState;
 use std::sync::Arc;
 
+/// Builds the commit event leader reputation learns from once `anchor` is ordered.
+/// `block_id` is the id of the block made from the ordered nodes, which is also what a
+/// restarted node reads back from the `NewBlockEvent` of that block.
+pub(super) fn commit_event(
+    anchor: &CertifiedNode,
+    block_id: HashValue,
+    failed_authors_and_rounds: &[(Round, Author)],
+) -> CommitEvent {
+    let parents = anchor
+        .parents()
+        .iter()
+        .map(|cert| *cert.metadata().author())
+        .collect();
+    CommitEvent::new(
+        anchor.id(),
+        block_id,
+        parents,
+        failed_authors_and_rounds
+            .iter()
+            .map(|(_, author)| *author)
+            .collect(),
+    )
+}
+
 #[derive(Clone)]
 pub struct OrderRule {
     epoch_state: Arc<EpochState>,
@@ -171,21 +196,6 @@ impl OrderRule {
             .step_by(2)
             .map(|failed_round| (failed_round, self.anchor_election.get_anchor(failed_round)))
             .collect();
-        let parents = anchor
-            .parents()
-            .iter()
-            .map(|cert| *cert.metadata().author())
-            .collect();
-        let event = CommitEvent::new(
-            anchor.id(),
-            parents,
-            failed_authors_and_rounds
-                .iter()
-                .map(|(_, author)| *author)
-                .collect(),
-        );
-        self.anchor_election.update_reputation(event);
-
         let mut dag_writer = self.dag.write();
         let mut ordered_nodes: Vec<_> = dag_writer
             .reachable_mut(&anchor, Some(lowest_round_to_reach))
@@ -211,8 +221,14 @@ impl OrderRule {
         );
 
         *self.lowest_unordered_anchor_round.write() = anchor.round() + 1;
-        self.notifier
-            .send_ordered_nodes(ordered_nodes, failed_authors_and_rounds);
+        let block_id = self
+            .notifier
+            .send_ordered_nodes(ordered_nodes, failed_authors_and_rounds.clone());
+        self.anchor_election.update_reputation(commit_event(
+            &anchor,
+            block_id,
+            &failed_authors_and_rounds,
+        ));
     }
 
     /// Check if this node can trigger anchors to be ordered
@@ -8,9 +8,11 @@ use crate::{
         dag_network::RpcHandler,
         errors::NodeBroadcastHandleError,
         observability::{
+            counters::DUPLICATE_NODES_FILTERED,
             logging::{LogEvent, LogSchema},
             tracing::{observe_node, NodeStage},
         },
+        recent_node_filter::RecentNodeFilter,
         storage::DAGStorage,
         types::{Node, NodeCertificate, Vote},
         NodeId,
@@ -33,12 +35,20 @@ use claims::assert_some;
 use dashmap::DashSet;
 use std::{collections::BTreeMap, mem, sync::Arc};
 
+/// Number of nodes the duplicate filter is sized for.
+const RECENT_NODE_FILTER_CAPACITY: usize = 10_000;
+const RECENT_NODE_FILTER_FALSE_POSITIVE_RATE: f64 = 0.001;
+
 pub(crate) struct NodeBroadcastHandler {
     dag: Arc<DagStore>,
     /// Note: The mutex around BTreeMap is to work around Rust Sync semantics.
     /// Fine grained concurrency is implemented by the DashSet below.
     votes_by_round_peer: Mutex<BTreeMap<Round, BTreeMap<Author, Vote>>>,
     votes_fine_grained_lock: DashSet<(Round, Author)>,
+    /// Digests of the nodes that passed validation, so that duplicates received from other peers
+    /// are answered with the existing vote without validating them again. The handler is created
+    /// per epoch, so the filter is reset at epoch boundaries.
+    recent_nodes: RecentNodeFilter,
     signer: Arc<ValidatorSigner>,
     epoch_state: Arc<EpochState>,
     storage: Arc<dyn DAGStorage>,
@@ -68,6 +78,10 @@ impl NodeBroadcastHandler {
             dag,
             votes_by_round_peer: Mutex::new(votes_by_round_peer),
             votes_fine_grained_lock: DashSet::with_capacity(epoch_state.verifier.len() * 10),
+            recent_nodes: RecentNodeFilter::new(
+                RECENT_NODE_FILTER_CAPACITY,
+                RECENT_NODE_FILTER_FALSE_POSITIVE_RATE,
+            ),
             signer,
             epoch_state,
             storage,
@@ -212,6 +226,24 @@ impl RpcHandler for NodeBroadcastHandler {
             NodeBroadcastHandleError::VoteRefused
         );
 
+        // The digest was checked against the content of the node before it got here, so a
+        // filter hit with an existing vote on the same digest means this exact node was
+        // validated and voted on before, and is answered without validating it again. A hit
+        // without such a vote is either a false positive or a node whose previous processing
+        // failed after validation, so it is processed as usual.
+        if self.recent_nodes.contains(&node.digest()) {
+            if let Some(ack) = self
+                .votes_by_round_peer
+                .lock()
+                .get(&node.round())
+                .and_then(|votes| votes.get(node.author()))
+                .filter(|vote| *vote.metadata().digest() == node.digest())
+            {
+                DUPLICATE_NODES_FILTERED.inc();
+                return Ok(ack.clone());
+            }
+        }
+
         let key = (node.round(), *node.author());
         ensure!(
             self.votes_fine_grained_lock.insert(key),
@@ -222,6 +254,7 @@ impl RpcHandler for NodeBroadcastHandler {
         });
 
         let node = self.validate(node)?;
+        self.recent_nodes.insert(&node.digest());
         observe_node(node.timestamp(), NodeStage::NodeReceived);
         debug!(LogSchema::new(LogEvent::ReceiveNode)
             .remote_peer(*node.author())
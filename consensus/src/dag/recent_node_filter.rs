@@ -0,0 +1,78 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+/// A Bloom filter over the digests of recently processed nodes.
+///
+/// It can report false positives but never false negatives, so a hit only means the node has
+/// probably been seen before. Once more nodes than it was sized for have been inserted, it is
+/// cleared to keep the false-positive rate bounded.
+pub(crate) struct RecentNodeFilter {
+    inner: Mutex<FilterState>,
+    num_bits: u64,
+    num_hashes: u32,
+    capacity: usize,
+}
+
+struct FilterState {
+    bits: Vec<u64>,
+    num_inserted: usize,
+}
+
+impl RecentNodeFilter {
+    /// Creates a filter for `capacity` nodes at the given `false_positive_rate`.
+    pub(crate) fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false positive rate must be in (0, 1)"
+        );
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-(capacity as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let num_hashes = ((num_bits as f64 / capacity as f64) * ln2).round().max(1.0) as u32;
+        Self {
+            inner: Mutex::new(FilterState {
+                bits: vec![0; num_bits.div_ceil(64) as usize],
+                num_inserted: 0,
+            }),
+            num_bits,
+            num_hashes,
+            capacity,
+        }
+    }
+
+    pub(crate) fn insert(&self, digest: &HashValue) {
+        let mut state = self.inner.lock();
+        if state.num_inserted >= self.capacity {
+            state.bits.fill(0);
+            state.num_inserted = 0;
+        }
+        for bit in self.bit_indices(digest) {
+            state.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        state.num_inserted += 1;
+    }
+
+    pub(crate) fn contains(&self, digest: &HashValue) -> bool {
+        let state = self.inner.lock();
+        self.bit_indices(digest)
+            .all(|bit| state.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Derives the bit indices of `digest` from two hashes (Kirsch-Mitzenmacher).
+    fn bit_indices(&self, digest: &HashValue) -> impl Iterator<Item = u64> {
+        let mut hasher = DefaultHasher::new();
+        digest.hash(&mut hasher);
+        let h1 = hasher.finish();
+        0xA5u8.hash(&mut hasher);
+        let h2 = hasher.finish() | 1;
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
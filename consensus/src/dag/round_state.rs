@@ -52,6 +52,12 @@ impl RoundState {
         }
     }
 
+    /// Moves to `new_round` without going through the responsive check.
+    pub fn force_new_round(&self, new_round: Round) {
+        // the receiver can be dropped if we move to a new epoch
+        let _ = self.event_sender.send(new_round);
+    }
+
     pub fn current_round(&self) -> Round {
         *self.current_round.lock()
     }
@@ -10,14 +10,21 @@ use aptos_types::ledger_info::LedgerInfoWithSignatures;
 #[derive(Clone)]
 pub struct CommitEvent {
     node_id: NodeId,
+    block_id: HashValue,
     parents: Vec<Author>,
     failed_authors: Vec<Author>,
 }
 
 impl CommitEvent {
-    pub fn new(node_id: NodeId, parents: Vec<Author>, failed_authors: Vec<Author>) -> Self {
+    pub fn new(
+        node_id: NodeId,
+        block_id: HashValue,
+        parents: Vec<Author>,
+        failed_authors: Vec<Author>,
+    ) -> Self {
         CommitEvent {
             node_id,
+            block_id,
             parents,
             failed_authors,
         }
@@ -35,6 +42,11 @@ impl CommitEvent {
         self.node_id.author()
     }
 
+    /// The id of the committed anchor, used to seed leader reputation.
+    pub fn block_id(&self) -> HashValue {
+        self.block_id
+    }
+
     pub fn parents(&self) -> &[Author] {
         &self.parents
     }
@@ -66,4 +78,57 @@ pub trait DAGStorage: Send + Sync {
     fn get_latest_k_committed_events(&self, k: u64) -> anyhow::Result<Vec<CommitEvent>>;
 
     fn get_latest_ledger_info(&self) -> anyhow::Result<LedgerInfoWithSignatures>;
+
+    /// Deletes the pending node, votes and certified nodes of all epochs older than `epoch`.
+    /// Returns an estimate of the bytes freed, based on the serialized size of the deleted
+    /// entries. Commit events are read from AptosDB, which prunes them on its own, so they
+    /// are left untouched.
+    fn gc_epoch(&self, epoch: u64) -> anyhow::Result<u64> {
+        let mut bytes_freed = 0;
+
+        if let Some(node) = self.get_pending_node()? {
+            if node.epoch() < epoch {
+                bytes_freed += bcs::serialized_size(&node)?;
+                self.delete_pending_node()?;
+            }
+        }
+
+        let mut stale_votes = vec![];
+        for (node_id, vote) in self.get_votes()? {
+            if node_id.epoch() < epoch {
+                bytes_freed += bcs::serialized_size(&node_id)? + bcs::serialized_size(&vote)?;
+                stale_votes.push(node_id);
+            }
+        }
+        self.delete_votes(stale_votes)?;
+
+        let mut stale_digests = vec![];
+        for (digest, node) in self.get_certified_nodes()? {
+            if node.epoch() < epoch {
+                bytes_freed += HashValue::LENGTH + bcs::serialized_size(&node)?;
+                stale_digests.push(digest);
+            }
+        }
+        self.delete_certified_nodes(stale_digests)?;
+
+        Ok(bytes_freed as u64)
+    }
+
+    /// The oldest epoch that still has a pending node, vote or certified node stored.
+    fn oldest_available_epoch(&self) -> anyhow::Result<Option<u64>> {
+        let pending_node_epoch = self.get_pending_node()?.map(|node| node.epoch());
+        let vote_epochs = self
+            .get_votes()?
+            .into_iter()
+            .map(|(node_id, _)| node_id.epoch());
+        let certified_node_epochs = self
+            .get_certified_nodes()?
+            .into_iter()
+            .map(|(_, node)| node.epoch());
+        Ok(pending_node_epoch
+            .into_iter()
+            .chain(vote_epochs)
+            .chain(certified_node_epochs)
+            .min())
+    }
 }
@@ -10,14 +10,15 @@ use crate::{
         dag_store::DagStore,
         errors::DagDriverError,
         health::{HealthBackoff, NoChainHealth, NoPipelineBackpressure},
+        observability::counters::ROUND_TIMEOUT_COUNT,
         order_rule::OrderRule,
-        round_state::{OptimisticResponsive, RoundState},
+        round_state::{OptimisticResponsive, ResponsiveCheck, RoundState},
         tests::{
             dag_test::MockStorage,
             helpers::{new_certified_node, MockPayloadManager, TEST_DAG_WINDOW},
             order_rule_tests::TestNotifier,
         },
-        types::{CertifiedAck, DAGMessage, TestAck},
+        types::{CertifiedAck, DAGMessage, NodeCertificate, TestAck},
         DAGRpcResult, RpcHandler,
     },
     test_utils::MockPayloadManager as MockPayloadClient,
@@ -108,10 +109,46 @@ impl TFetchRequester for MockFetchRequester {
     }
 }
 
+/// Never moves to a new round by itself, as if waiting for the nodes of all validators.
+struct NeverResponsive {}
+
+impl ResponsiveCheck for NeverResponsive {
+    fn check_for_new_round(
+        &self,
+        _highest_strong_links_round: Round,
+        _strong_links: Vec<NodeCertificate>,
+        _health_backoff_delay: Duration,
+    ) {
+    }
+
+    fn reset(&self) {}
+}
+
 fn setup(
     signers: &[ValidatorSigner],
     validator_verifier: ValidatorVerifier,
     network_sender: Arc<MockNetworkSender>,
+) -> DagDriver {
+    let (round_tx, _round_rx) = tokio::sync::mpsc::unbounded_channel();
+    let round_state = RoundState::new(
+        round_tx.clone(),
+        Box::new(OptimisticResponsive::new(round_tx)),
+    );
+    setup_with_round_state(
+        signers,
+        validator_verifier,
+        network_sender,
+        round_state,
+        TimeService::mock(),
+    )
+}
+
+fn setup_with_round_state(
+    signers: &[ValidatorSigner],
+    validator_verifier: ValidatorVerifier,
+    network_sender: Arc<MockNetworkSender>,
+    round_state: RoundState,
+    time_service: TimeService,
 ) -> DagDriver {
     let epoch_state = Arc::new(EpochState {
         epoch: 1,
@@ -137,7 +174,6 @@ fn setup(
         Duration::from_millis(500),
         BoundedExecutor::new(2, Handle::current()),
     ));
-    let time_service = TimeService::mock();
     let validators = signers.iter().map(|vs| vs.author()).collect();
     let (tx, _) = unbounded();
     let order_rule = OrderRule::new(
@@ -155,11 +191,6 @@ fn setup(
     let ledger_info_provider = Arc::new(MockLedgerInfoProvider {
         latest_ledger_info: mock_ledger_info,
     });
-    let (round_tx, _round_rx) = tokio::sync::mpsc::unbounded_channel();
-    let round_state = RoundState::new(
-        round_tx.clone(),
-        Box::new(OptimisticResponsive::new(round_tx)),
-    );
 
     DagDriver::new(
         signers[0].author(),
@@ -226,3 +257,50 @@ async fn test_dag_driver_drop() {
 
     let _ = rx.await;
 }
+
+#[tokio::test]
+async fn test_dag_driver_round_timeout() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let network_sender = Arc::new(MockNetworkSender {
+        _drop_notifier: None,
+    });
+    let (round_tx, mut round_rx) = tokio::sync::mpsc::unbounded_channel();
+    let round_state = RoundState::new(round_tx, Box::new(NeverResponsive {}));
+    let time_service = TimeService::mock();
+    let driver = setup_with_round_state(
+        &signers,
+        validator_verifier,
+        network_sender,
+        round_state,
+        time_service.clone(),
+    )
+    .with_round_timeout(Duration::from_secs(1));
+
+    driver.enter_new_round(1).await;
+    // Without strong links for round 1 there is no way to move forward
+    assert!(!driver.force_advance_round());
+
+    // The node of the first validator never arrives, so the round only has 2f+1 nodes and
+    // the responsive check keeps waiting.
+    for signer in &signers[1..] {
+        assert_ok!(
+            driver
+                .process(new_certified_node(1, signer.author(), vec![]))
+                .await
+        );
+    }
+    assert!(round_rx.try_recv().is_err());
+
+    let timeouts_before = ROUND_TIMEOUT_COUNT.get();
+    let mock_time = time_service.into_mock();
+    while mock_time.num_waiters() == 0 {
+        tokio::task::yield_now().await;
+    }
+    mock_time.advance_async(Duration::from_secs(1)).await;
+
+    let new_round = tokio::time::timeout(Duration::from_secs(5), round_rx.recv())
+        .await
+        .expect("round must time out");
+    assert_eq!(new_round, Some(2));
+    assert!(ROUND_TIMEOUT_COUNT.get() > timeouts_before);
+}
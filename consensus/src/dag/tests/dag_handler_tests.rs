@@ -0,0 +1,112 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dag::{
+        dag_handler::{drain_in_flight, Priority, PriorityDagMessage, PriorityMessageQueue},
+        tests::helpers::{new_certified_node, new_node},
+        types::{CertifiedNodeMessage, DagSnapshotBitmask, RemoteFetchRequest},
+        DAGMessage,
+    },
+    network::RpcResponder,
+};
+use aptos_network::ProtocolId;
+use aptos_types::{
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo},
+    validator_verifier::random_validator_verifier,
+};
+use futures::{channel::oneshot, future, stream::FuturesUnordered, StreamExt};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+fn new_responder() -> RpcResponder {
+    RpcResponder {
+        protocol: ProtocolId::ConsensusRpcBcs,
+        response_sender: oneshot::channel().0,
+    }
+}
+
+#[tokio::test]
+async fn test_certified_node_processed_before_fetch_request() {
+    let (signers, _) = random_validator_verifier(4, None, false);
+    let author = signers[0].author();
+
+    let certified_node = new_certified_node(1, author, vec![]);
+    let fetch_request = DAGMessage::FetchRequest(RemoteFetchRequest::new(
+        1,
+        vec![certified_node.metadata().clone()],
+        DagSnapshotBitmask::new(1, vec![vec![false; signers.len()]]),
+    ));
+    let node = DAGMessage::NodeMsg(new_node(2, 20, author, vec![]));
+    let certified_node_msg = DAGMessage::CertifiedNodeMsg(CertifiedNodeMessage::new(
+        certified_node,
+        generate_ledger_info_with_sig(&signers, LedgerInfo::mock_genesis(None)),
+    ));
+
+    // The fetch request and the node are delivered before the certified node.
+    let mut message_queue = PriorityMessageQueue::new();
+    for message in [fetch_request, node, certified_node_msg] {
+        message_queue.push(PriorityDagMessage::new(
+            Ok(message),
+            1,
+            author,
+            new_responder(),
+        ));
+    }
+    assert_eq!(message_queue.len(), 3);
+
+    let message = message_queue.next().await;
+    assert_eq!(message.priority(), Priority::High);
+    assert!(matches!(
+        message.message(),
+        Ok(DAGMessage::CertifiedNodeMsg(_))
+    ));
+    let message = message_queue.next().await;
+    assert_eq!(message.priority(), Priority::Normal);
+    assert!(matches!(message.message(), Ok(DAGMessage::NodeMsg(_))));
+    let message = message_queue.next().await;
+    assert_eq!(message.priority(), Priority::Low);
+    assert!(matches!(message.message(), Ok(DAGMessage::FetchRequest(_))));
+    assert_eq!(message_queue.len(), 0);
+}
+
+#[tokio::test]
+async fn test_drain_in_flight_after_sync_outcome() {
+    let completed = Arc::new(AtomicUsize::new(0));
+    let mut futures = FuturesUnordered::new();
+    // The sync outcome is returned while 10 messages are still being processed.
+    futures.push(tokio::spawn(async { Some("needs sync") }));
+    for i in 0..10 {
+        let completed = completed.clone();
+        futures.push(tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10 * (i + 1))).await;
+            completed.fetch_add(1, Ordering::SeqCst);
+            None
+        }));
+    }
+
+    assert_eq!(futures.next().await.unwrap().unwrap(), Some("needs sync"));
+    assert_eq!(futures.len(), 10);
+    assert_eq!(
+        drain_in_flight(&mut futures, Duration::from_secs(10)).await,
+        0
+    );
+    assert_eq!(completed.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test]
+async fn test_drain_in_flight_times_out() {
+    let mut futures = FuturesUnordered::new();
+    futures.push(tokio::spawn(async {}));
+    futures.push(tokio::spawn(future::pending::<()>()));
+
+    assert_eq!(
+        drain_in_flight(&mut futures, Duration::from_millis(50)).await,
+        1
+    );
+}
@@ -3,13 +3,15 @@
 use super::helpers::TEST_DAG_WINDOW;
 use crate::{
     dag::{
-        adapter::OrderedNotifier,
+        adapter::{OrderedNotifier, ProofNotifier, TLedgerInfoProvider},
         dag_fetcher::{FetchRequestHandler, TDagFetcher},
-        dag_state_sync::DagStateSynchronizer,
+        dag_state_sync::{
+            DagStateSynchronizer, StateSyncTrigger, SyncModeMessageHandler, SyncOutcome, SyncReason,
+        },
         dag_store::DagStore,
         storage::DAGStorage,
         tests::{
-            dag_test::MockStorage,
+            dag_test::{save_epoch_data, MockStorage},
             helpers::{generate_dag_nodes, MockPayloadManager},
         },
         types::{CertifiedNodeMessage, RemoteFetchRequest},
@@ -17,20 +19,32 @@ use crate::{
     },
     test_utils::EmptyStateComputer,
 };
+use anyhow::anyhow;
+use aptos_config::config::{DagGcPolicy, DagStateSyncConfig};
 use aptos_consensus_types::common::{Author, Round};
 use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
 use aptos_reliable_broadcast::RBNetworkSender;
-use aptos_time_service::TimeService;
+use aptos_time_service::{MockTimeService, TimeService, TimeServiceTrait};
 use aptos_types::{
     aggregate_signature::AggregateSignature,
     block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
     epoch_state::EpochState,
-    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
     validator_verifier::random_validator_verifier,
 };
 use async_trait::async_trait;
-use claims::assert_none;
-use std::{sync::Arc, time::Duration};
+use claims::{assert_err, assert_none};
+use futures::future;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 struct MockDAGNetworkSender {}
 
@@ -98,6 +112,83 @@ impl TDagFetcher for MockDagFetcher {
     }
 }
 
+/// Fetches only the lowest missing round and then fails for the first `num_failures` attempts,
+/// fetches everything afterwards.
+struct FlakyDagFetcher {
+    inner: MockDagFetcher,
+    num_failures: AtomicUsize,
+    missing_per_attempt: Arc<Mutex<Vec<usize>>>,
+}
+
+#[async_trait]
+impl TDagFetcher for FlakyDagFetcher {
+    async fn fetch(
+        &self,
+        remote_request: RemoteFetchRequest,
+        responders: Vec<Author>,
+        new_dag: Arc<DagStore>,
+    ) -> anyhow::Result<()> {
+        self.missing_per_attempt
+            .lock()
+            .push(remote_request.exists_bitmask().num_missing());
+        if self.num_failures.load(Ordering::SeqCst) == 0 {
+            return self.inner.fetch(remote_request, responders, new_dag).await;
+        }
+        self.num_failures.fetch_sub(1, Ordering::SeqCst);
+
+        let nodes = FetchRequestHandler::new(
+            self.inner.target_dag.clone(),
+            self.inner.epoch_state.clone(),
+        )
+        .process(remote_request)
+        .await
+        .unwrap()
+        .certified_nodes();
+        let lowest_round = nodes.iter().map(|node| node.round()).min().unwrap();
+        for node in nodes
+            .into_iter()
+            .filter(|node| node.round() == lowest_round)
+        {
+            new_dag.write().add_node_for_test(node).unwrap()
+        }
+
+        Err(anyhow!("fetch failed"))
+    }
+}
+
+/// Only fetches the lowest missing round but reports success.
+struct IncompleteDagFetcher {
+    inner: MockDagFetcher,
+}
+
+#[async_trait]
+impl TDagFetcher for IncompleteDagFetcher {
+    async fn fetch(
+        &self,
+        remote_request: RemoteFetchRequest,
+        _responders: Vec<Author>,
+        new_dag: Arc<DagStore>,
+    ) -> anyhow::Result<()> {
+        let nodes = FetchRequestHandler::new(
+            self.inner.target_dag.clone(),
+            self.inner.epoch_state.clone(),
+        )
+        .process(remote_request)
+        .await
+        .unwrap()
+        .certified_nodes();
+        let lowest_round = nodes.iter().map(|node| node.round()).min().unwrap();
+        for node in nodes
+            .into_iter()
+            .filter(|node| node.round() == lowest_round)
+        {
+            new_dag.write().add_node_for_test(node).unwrap()
+        }
+
+        Ok(())
+    }
+}
+
 struct MockNotifier {}
 
 #[async_trait]
@@ -106,12 +197,53 @@ impl OrderedNotifier for MockNotifier {
         &self,
         _ordered_nodes: Vec<Arc<CertifiedNode>>,
         _failed_author: Vec<(Round, Author)>,
-    ) {
+    ) -> HashValue {
+        HashValue::zero()
+    }
+}
+
+struct MockLedgerInfoProvider {
+    latest_ledger_info: LedgerInfoWithSignatures,
+    highest_committed_anchor_round: Round,
+}
+
+impl TLedgerInfoProvider for MockLedgerInfoProvider {
+    fn get_latest_ledger_info(&self) -> LedgerInfoWithSignatures {
+        self.latest_ledger_info.clone()
+    }
+
+    fn get_highest_committed_anchor_round(&self) -> Round {
+        self.highest_committed_anchor_round
+    }
+}
+
+struct MockProofNotifier {}
+
+/// Never hands the commit proofs over, like a stalled execution pipeline.
+struct StalledProofNotifier {}
+
+#[async_trait]
+impl ProofNotifier for StalledProofNotifier {
+    async fn send_epoch_change(&self, _proof: EpochChangeProof) {}
+
+    async fn send_commit_proof(&self, _ledger_info: LedgerInfoWithSignatures) {
+        future::pending::<()>().await
     }
 }
 
-fn setup(epoch_state: Arc<EpochState>, storage: Arc<dyn DAGStorage>) -> DagStateSynchronizer {
-    let time_service = TimeService::mock();
+#[async_trait]
+impl ProofNotifier for MockProofNotifier {
+    async fn send_epoch_change(&self, _proof: EpochChangeProof) {}
+
+    async fn send_commit_proof(&self, _ledger_info: LedgerInfoWithSignatures) {}
+}
+
+fn setup(
+    epoch_state: Arc<EpochState>,
+    storage: Arc<dyn DAGStorage>,
+    time_service: TimeService,
+    config: DagStateSyncConfig,
+) -> DagStateSynchronizer {
     let state_computer = Arc::new(EmptyStateComputer {});
     let payload_manager = Arc::new(MockPayloadManager {});
 
@@ -122,22 +254,39 @@ fn setup(epoch_state: Arc<EpochState>, storage: Arc<dyn DAGStorage>) -> DagState
         storage,
         payload_manager,
         TEST_DAG_WINDOW as Round,
+        config,
     )
 }
 
-#[tokio::test]
-async fn test_dag_state_sync() {
-    const NUM_ROUNDS: u64 = 90;
-    const LI_ROUNDS: u64 = NUM_ROUNDS * 2 / 3;
-    const SLOW_DAG_ROUNDS: u64 = NUM_ROUNDS / 3;
+const NUM_ROUNDS: u64 = 90;
+const LI_ROUNDS: u64 = NUM_ROUNDS * 2 / 3;
+const SLOW_DAG_ROUNDS: u64 = NUM_ROUNDS / 3;
 
+/// Returns a fast DAG with all rounds, a slow DAG lagging behind it, and the certified node to
+/// sync the slow DAG to.
+fn setup_dags() -> (
+    Arc<EpochState>,
+    Arc<DagStore>,
+    Arc<DagStore>,
+    CertifiedNodeMessage,
+) {
+    let (_, epoch_state, fast_dag, slow_dag, sync_node_li) = setup_dags_with_signers();
+    (epoch_state, fast_dag, slow_dag, sync_node_li)
+}
+
+fn setup_dags_with_signers() -> (
+    Vec<ValidatorSigner>,
+    Arc<EpochState>,
+    Arc<DagStore>,
+    Arc<DagStore>,
+    CertifiedNodeMessage,
+) {
     let (signers, validator_verifier) = random_validator_verifier(4, None, false);
     let validators = validator_verifier.get_ordered_account_addresses();
     let epoch_state = Arc::new(EpochState {
         epoch: 1,
         verifier: validator_verifier,
     });
-    let storage = Arc::new(MockStorage::new());
 
     let virtual_dag = (0..NUM_ROUNDS)
         .map(|_| {
@@ -203,7 +352,20 @@ async fn test_dag_state_sync() {
 
     let sync_node_li = CertifiedNodeMessage::new(sync_to_node, sync_to_li);
 
-    let state_sync = setup(epoch_state.clone(), storage.clone());
+    (signers, epoch_state, fast_dag, slow_dag, sync_node_li)
+}
+
+#[tokio::test]
+async fn test_dag_state_sync() {
+    let (epoch_state, fast_dag, slow_dag, sync_node_li) = setup_dags();
+    let storage = Arc::new(MockStorage::new());
+
+    let state_sync = setup(
+        epoch_state.clone(),
+        storage.clone(),
+        TimeService::mock(),
+        DagStateSyncConfig::default(),
+    );
     let dag_fetcher = MockDagFetcher {
         target_dag: fast_dag.clone(),
         epoch_state: epoch_state.clone(),
@@ -230,3 +392,395 @@ async fn test_dag_state_sync() {
     assert_eq!(new_dag.read().highest_round(), NUM_ROUNDS as Round);
     assert_none!(new_dag.read().highest_ordered_anchor_round(),);
 }
+
+#[tokio::test]
+async fn test_dag_state_sync_reuses_current_dag_nodes() {
+    let (epoch_state, fast_dag, _, sync_node_li) = setup_dags();
+    let validators = epoch_state.verifier.get_ordered_account_addresses();
+
+    // The current DAG has the first half of the rounds the sync needs.
+    let start_round = LI_ROUNDS - TEST_DAG_WINDOW;
+    let seeded_rounds = (NUM_ROUNDS - start_round + 1) / 2;
+    let current_dag = Arc::new(DagStore::new(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        Arc::new(MockPayloadManager {}),
+        1,
+        0,
+    ));
+    for round in 1..start_round + seeded_rounds {
+        for author in &validators {
+            let node = fast_dag
+                .read()
+                .get_node_by_round_author(round, author)
+                .unwrap()
+                .as_ref()
+                .clone();
+            current_dag.write().add_node_for_test(node).unwrap();
+        }
+    }
+
+    let state_sync = setup(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        TimeService::mock(),
+        DagStateSyncConfig::default(),
+    );
+    let (request, _, sync_dag_store) =
+        state_sync.build_request(&sync_node_li, current_dag.clone(), 0);
+
+    // Only the second half is requested.
+    let bitmask = request.exists_bitmask();
+    for round in start_round..start_round + seeded_rounds {
+        for (idx, author) in validators.iter().enumerate() {
+            assert!(round < bitmask.first_round() || bitmask.has(round, idx));
+            assert!(sync_dag_store
+                .read()
+                .get_node_by_round_author(round, author)
+                .is_some());
+        }
+    }
+    assert_eq!(request.start_round(), start_round + seeded_rounds);
+    assert_eq!(
+        bitmask.num_missing(),
+        (NUM_ROUNDS - start_round + 1 - seeded_rounds) as usize * validators.len()
+    );
+    assert_eq!(sync_dag_store.read().lowest_round(), start_round);
+}
+
+#[tokio::test]
+async fn test_dag_state_sync_retries_fetch() {
+    let (epoch_state, fast_dag, slow_dag, sync_node_li) = setup_dags();
+
+    let time_service = TimeService::from_mock(MockTimeService::new_auto_advance());
+    let config = DagStateSyncConfig {
+        max_fetch_attempts: 3,
+        fetch_retry_backoff_base_ms: 100,
+        ..Default::default()
+    };
+    let state_sync = setup(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        time_service.clone(),
+        config,
+    );
+    let missing_per_attempt = Arc::new(Mutex::new(vec![]));
+    let dag_fetcher = FlakyDagFetcher {
+        inner: MockDagFetcher {
+            target_dag: fast_dag,
+            epoch_state,
+        },
+        num_failures: AtomicUsize::new(2),
+        missing_per_attempt: missing_per_attempt.clone(),
+    };
+
+    let (request, responders, sync_dag_store) =
+        state_sync.build_request(&sync_node_li, slow_dag, 0);
+
+    let start_time = time_service.now_unix_time();
+    let new_dag = state_sync
+        .sync_dag_to(
+            dag_fetcher,
+            request,
+            responders,
+            sync_dag_store,
+            sync_node_li.ledger_info().clone(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(new_dag.read().highest_round(), NUM_ROUNDS as Round);
+    // Backed off 100ms after the first failure and 200ms after the second.
+    assert_eq!(
+        time_service.now_unix_time() - start_time,
+        Duration::from_millis(300)
+    );
+    // Every retry only asked for the nodes that were still missing.
+    let missing_per_attempt = missing_per_attempt.lock().clone();
+    assert_eq!(missing_per_attempt.len(), 3);
+    assert!(missing_per_attempt
+        .windows(2)
+        .all(|attempts| attempts[0] > attempts[1]));
+}
+
+#[tokio::test]
+async fn test_dag_state_sync_gives_up_after_max_fetch_attempts() {
+    let (epoch_state, fast_dag, slow_dag, sync_node_li) = setup_dags();
+
+    let config = DagStateSyncConfig {
+        max_fetch_attempts: 2,
+        fetch_retry_backoff_base_ms: 100,
+        ..Default::default()
+    };
+    let state_sync = setup(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        TimeService::from_mock(MockTimeService::new_auto_advance()),
+        config,
+    );
+    let missing_per_attempt = Arc::new(Mutex::new(vec![]));
+    let dag_fetcher = FlakyDagFetcher {
+        inner: MockDagFetcher {
+            target_dag: fast_dag,
+            epoch_state,
+        },
+        num_failures: AtomicUsize::new(2),
+        missing_per_attempt: missing_per_attempt.clone(),
+    };
+
+    let (request, responders, sync_dag_store) =
+        state_sync.build_request(&sync_node_li, slow_dag, 0);
+
+    assert_err!(
+        state_sync
+            .sync_dag_to(
+                dag_fetcher,
+                request,
+                responders,
+                sync_dag_store,
+                sync_node_li.ledger_info().clone(),
+            )
+            .await
+    );
+    assert_eq!(missing_per_attempt.lock().len(), 2);
+}
+
+#[tokio::test]
+async fn test_dag_state_sync_detects_gap() {
+    let (epoch_state, fast_dag, slow_dag, sync_node_li) = setup_dags();
+
+    let state_sync = setup(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        TimeService::mock(),
+        DagStateSyncConfig::default(),
+    );
+    let dag_fetcher = IncompleteDagFetcher {
+        inner: MockDagFetcher {
+            target_dag: fast_dag,
+            epoch_state,
+        },
+    };
+
+    let (request, responders, sync_dag_store) =
+        state_sync.build_request(&sync_node_li, slow_dag, 0);
+    let start_round = request.start_round();
+
+    let err = state_sync
+        .sync_dag_to(
+            dag_fetcher,
+            request,
+            responders,
+            sync_dag_store,
+            sync_node_li.ledger_info().clone(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&format!("gap in dag at round {}", start_round + 1)));
+}
+
+#[test]
+fn test_sync_cancel_threshold_multiplier() {
+    const TARGET_ROUND: Round = 50;
+    const WINDOW: u64 = TEST_DAG_WINDOW as u64;
+
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+
+    let handler = SyncModeMessageHandler::new(epoch_state, 1, TARGET_ROUND, WINDOW, 3);
+
+    assert!(!handler.should_cancel_sync(TARGET_ROUND + 2 * WINDOW + 1));
+    assert!(!handler.should_cancel_sync(TARGET_ROUND + 3 * WINDOW));
+    assert!(handler.should_cancel_sync(TARGET_ROUND + 3 * WINDOW + 1));
+}
+
+fn ledger_info_at_round(epoch: u64, round: Round) -> LedgerInfoWithSignatures {
+    LedgerInfoWithSignatures::new(
+        LedgerInfo::new(
+            BlockInfo::new(
+                epoch,
+                round,
+                HashValue::zero(),
+                HashValue::zero(),
+                0,
+                0,
+                None,
+            ),
+            HashValue::zero(),
+        ),
+        AggregateSignature::empty(),
+    )
+}
+
+const TEST_NOTIFY_TIMEOUT: Duration = Duration::from_millis(100);
+
+fn state_sync_trigger(
+    epoch_state: Arc<EpochState>,
+    dag_store: Arc<DagStore>,
+    highest_committed_anchor_round: Round,
+) -> StateSyncTrigger {
+    let ledger_info_provider = Arc::new(MockLedgerInfoProvider {
+        latest_ledger_info: ledger_info_at_round(epoch_state.epoch, highest_committed_anchor_round),
+        highest_committed_anchor_round,
+    });
+    StateSyncTrigger::new(
+        epoch_state,
+        ledger_info_provider,
+        dag_store,
+        Arc::new(MockProofNotifier {}),
+        TEST_DAG_WINDOW,
+        TEST_NOTIFY_TIMEOUT,
+    )
+}
+
+#[test]
+fn test_sync_reason_not_needed() {
+    let (epoch_state, fast_dag, _, _) = setup_dags();
+    let epoch = epoch_state.epoch;
+
+    let trigger = state_sync_trigger(epoch_state.clone(), fast_dag.clone(), LI_ROUNDS);
+    // already committed
+    let reason = trigger.sync_reason(&ledger_info_at_round(epoch, LI_ROUNDS));
+    assert_eq!(reason, SyncReason::NotNeeded);
+    assert!(!reason.is_needed());
+    // within the DAG and the execution window
+    let reason = trigger.sync_reason(&ledger_info_at_round(epoch, LI_ROUNDS + 1));
+    assert_eq!(reason, SyncReason::NotNeeded);
+}
+
+#[test]
+fn test_sync_reason_dag_empty() {
+    let (epoch_state, _, _, _) = setup_dags();
+    let epoch = epoch_state.epoch;
+    // a DAG starting past round 1 without any nodes, e.g. after a restart with nodes gc'ed
+    let empty_dag = Arc::new(DagStore::new_empty(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        Arc::new(MockPayloadManager {}),
+        LI_ROUNDS,
+        TEST_DAG_WINDOW,
+    ));
+    assert!(empty_dag.read().is_empty());
+
+    let trigger = state_sync_trigger(epoch_state, empty_dag, LI_ROUNDS - 1);
+    let reason = trigger.sync_reason(&ledger_info_at_round(epoch, LI_ROUNDS));
+    assert_eq!(reason, SyncReason::DagEmpty);
+    assert!(reason.is_needed());
+}
+
+#[test]
+fn test_sync_reason_consensus_behind() {
+    let (epoch_state, _, slow_dag, _) = setup_dags();
+    let epoch = epoch_state.epoch;
+
+    let trigger = state_sync_trigger(epoch_state, slow_dag, SLOW_DAG_ROUNDS);
+    // the boundary is still within reach of the DAG
+    let reason = trigger.sync_reason(&ledger_info_at_round(
+        epoch,
+        SLOW_DAG_ROUNDS + 1 + TEST_DAG_WINDOW,
+    ));
+    assert_eq!(reason, SyncReason::NotNeeded);
+
+    let reason = trigger.sync_reason(&ledger_info_at_round(
+        epoch,
+        SLOW_DAG_ROUNDS + 2 + TEST_DAG_WINDOW,
+    ));
+    assert_eq!(reason, SyncReason::ConsensusBehind);
+    assert!(reason.is_needed());
+}
+
+#[test]
+fn test_sync_reason_execution_behind_window() {
+    let (epoch_state, fast_dag, _, _) = setup_dags();
+    let epoch = epoch_state.epoch;
+
+    let trigger = state_sync_trigger(epoch_state, fast_dag, SLOW_DAG_ROUNDS);
+    // the boundary is still within the execution window
+    let reason = trigger.sync_reason(&ledger_info_at_round(
+        epoch,
+        SLOW_DAG_ROUNDS + 2 * TEST_DAG_WINDOW,
+    ));
+    assert_eq!(reason, SyncReason::NotNeeded);
+
+    let reason = trigger.sync_reason(&ledger_info_at_round(
+        epoch,
+        SLOW_DAG_ROUNDS + 2 * TEST_DAG_WINDOW + 1,
+    ));
+    assert_eq!(reason, SyncReason::ExecutionBehindWindow);
+    assert!(reason.is_needed());
+}
+
+#[tokio::test]
+async fn test_sync_check_stalled_proof_notifier() {
+    let (signers, epoch_state, fast_dag, _, _) = setup_dags_with_signers();
+
+    // The commit proof is only notified if the DAG ordered past the ledger info round.
+    let anchor = fast_dag
+        .read()
+        .get_node_by_round_author(LI_ROUNDS, &signers[0].author())
+        .unwrap()
+        .clone();
+    fast_dag
+        .write()
+        .reachable_mut(&anchor, None)
+        .for_each(|node_status| node_status.mark_as_ordered());
+
+    let ledger_info_provider = Arc::new(MockLedgerInfoProvider {
+        latest_ledger_info: ledger_info_at_round(epoch_state.epoch, SLOW_DAG_ROUNDS),
+        highest_committed_anchor_round: SLOW_DAG_ROUNDS,
+    });
+    let trigger = StateSyncTrigger::new(
+        epoch_state.clone(),
+        ledger_info_provider,
+        fast_dag,
+        Arc::new(StalledProofNotifier {}),
+        TEST_DAG_WINDOW,
+        TEST_NOTIFY_TIMEOUT,
+    );
+
+    let sync_round = SLOW_DAG_ROUNDS + 2 * TEST_DAG_WINDOW + 1;
+    let ledger_info = generate_ledger_info_with_sig(
+        &signers,
+        ledger_info_at_round(epoch_state.epoch, sync_round)
+            .ledger_info()
+            .clone(),
+    );
+    let node = anchor.as_ref().clone();
+    let outcome = tokio::time::timeout(
+        2 * TEST_NOTIFY_TIMEOUT,
+        trigger.check(CertifiedNodeMessage::new(node, ledger_info)),
+    )
+    .await
+    .expect("check must not wait for the stalled proof notifier")
+    .unwrap();
+    assert!(matches!(outcome, SyncOutcome::NeedsSync(_)));
+}
+
+#[tokio::test]
+async fn test_dag_state_sync_gc_old_epochs() {
+    let (signers, _) = random_validator_verifier(4, None, false);
+    let storage = Arc::new(MockStorage::new());
+    for (epoch, signer) in (1..=4).zip(&signers) {
+        save_epoch_data(storage.as_ref(), epoch, signer);
+    }
+
+    // Starting epoch 4 while keeping 2 epochs removes the data of epochs 1 and 2.
+    DagStateSynchronizer::spawn_gc(storage.clone(), 4, DagGcPolicy { keep_epochs: 2 })
+        .await
+        .unwrap();
+
+    assert_eq!(storage.oldest_available_epoch().unwrap(), Some(3));
+    let mut epochs: Vec<_> = storage
+        .get_certified_nodes()
+        .unwrap()
+        .into_iter()
+        .map(|(_, node)| node.epoch())
+        .collect();
+    epochs.sort();
+    assert_eq!(epochs, vec![3, 4]);
+}
@@ -3,17 +3,19 @@
 
 use super::helpers::MockPayloadManager;
 use crate::dag::{
-    dag_store::DagStore,
+    dag_store::{DagRoundSummary, DagStore},
     storage::{CommitEvent, DAGStorage},
     tests::helpers::{new_certified_node, TEST_DAG_WINDOW},
-    types::{CertifiedNode, DagSnapshotBitmask, Node},
+    types::{CertifiedNode, DagSnapshotBitmask, Extensions, Node},
     NodeId, Vote,
 };
+use aptos_consensus_types::common::Payload;
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_types::{
-    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
-    validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier,
+    aggregate_signature::AggregateSignature, epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures, validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
 };
 use std::{collections::HashMap, sync::Arc};
 
@@ -289,3 +291,139 @@ fn test_dag_bitmask() {
         DagSnapshotBitmask::new(5, vec![vec![false; 4]; 2])
     );
 }
+
+#[test]
+fn test_dag_verify_contiguous() {
+    let (signers, epoch_state, dag, _) = setup();
+
+    let add_nodes = |round, signers: &[ValidatorSigner]| {
+        let parents = dag
+            .read()
+            .get_strong_links_for_round(round - 1, &epoch_state.verifier)
+            .unwrap_or_default();
+        for signer in signers {
+            let node = new_certified_node(round, signer.author(), parents.clone());
+            assert!(dag.write().add_node_for_test(node).is_ok());
+        }
+    };
+    add_nodes(1, &signers);
+    add_nodes(2, &signers[0..1]);
+    add_nodes(3, &signers[0..1]);
+
+    assert!(dag.verify_contiguous(1, 1).is_ok());
+    // Only the target round can have less than a quorum of nodes.
+    assert!(dag.verify_contiguous(1, 2).is_ok());
+    assert!(dag
+        .verify_contiguous(1, 3)
+        .unwrap_err()
+        .to_string()
+        .contains("gap in dag at round 2"));
+
+    add_nodes(2, &signers[1..3]);
+    assert!(dag.verify_contiguous(1, 3).is_ok());
+    assert!(dag
+        .verify_contiguous(1, 4)
+        .unwrap_err()
+        .to_string()
+        .contains("gap in dag at round 3"));
+}
+
+#[test]
+fn test_dag_round_summary() {
+    let (signers, epoch_state, dag, _) = setup();
+    assert_eq!(dag.round_summary(), DagRoundSummary {
+        highest_round: 1,
+        highest_ordered_anchor_round: None,
+        is_empty: false,
+    });
+
+    for round in 1..5 {
+        let parents = dag
+            .read()
+            .get_strong_links_for_round(round, &epoch_state.verifier)
+            .unwrap_or_default();
+        for signer in &signers {
+            let node = new_certified_node(round, signer.author(), parents.clone());
+            assert!(dag.write().add_node_for_test(node).is_ok());
+        }
+    }
+    let anchor = dag
+        .read()
+        .get_node_by_round_author(3, &signers[0].author())
+        .unwrap()
+        .clone();
+    dag.write()
+        .reachable_mut(&anchor, None)
+        .for_each(|node_status| node_status.mark_as_ordered());
+    assert_eq!(dag.round_summary(), DagRoundSummary {
+        highest_round: 4,
+        highest_ordered_anchor_round: Some(3),
+        is_empty: false,
+    });
+
+    // a DAG starting past round 1 without any nodes
+    let empty_dag = DagStore::new_empty(
+        epoch_state,
+        Arc::new(MockStorage::new()),
+        Arc::new(MockPayloadManager {}),
+        10,
+        TEST_DAG_WINDOW,
+    );
+    assert_eq!(empty_dag.round_summary(), DagRoundSummary {
+        highest_round: 10,
+        highest_ordered_anchor_round: None,
+        is_empty: true,
+    });
+}
+
+/// Stores a pending node, a vote and a certified node of `epoch` authored by `signer`.
+pub(super) fn save_epoch_data(storage: &dyn DAGStorage, epoch: u64, signer: &ValidatorSigner) {
+    let node = Node::new(
+        epoch,
+        1,
+        signer.author(),
+        0,
+        vec![],
+        Payload::empty(false),
+        vec![],
+        Extensions::empty(),
+    );
+    let vote = Vote::new(node.metadata().clone(), node.sign_vote(signer).unwrap());
+    storage.save_pending_node(&node).unwrap();
+    storage.save_vote(&node.id(), &vote).unwrap();
+    storage
+        .save_certified_node(&CertifiedNode::new(node, AggregateSignature::empty()))
+        .unwrap();
+}
+
+#[test]
+fn test_dag_gc_epoch() {
+    let (signers, _, _, _) = setup();
+    let storage = MockStorage::new();
+    assert_eq!(storage.oldest_available_epoch().unwrap(), None);
+
+    for (epoch, signer) in (1..=3).zip(&signers) {
+        save_epoch_data(&storage, epoch, signer);
+    }
+    assert_eq!(storage.oldest_available_epoch().unwrap(), Some(1));
+
+    assert!(storage.gc_epoch(3).unwrap() > 0);
+    assert_eq!(storage.oldest_available_epoch().unwrap(), Some(3));
+    assert_eq!(storage.get_pending_node().unwrap().unwrap().epoch(), 3);
+    assert!(storage
+        .get_votes()
+        .unwrap()
+        .iter()
+        .all(|(node_id, _)| node_id.epoch() == 3));
+    assert_eq!(storage.get_votes().unwrap().len(), 1);
+    assert!(storage
+        .get_certified_nodes()
+        .unwrap()
+        .iter()
+        .all(|(_, node)| node.epoch() == 3));
+    assert_eq!(storage.get_certified_nodes().unwrap().len(), 1);
+
+    // Nothing is left to collect.
+    assert_eq!(storage.gc_epoch(3).unwrap(), 0);
+    assert_eq!(storage.oldest_available_epoch().unwrap(), Some(3));
+}
@@ -0,0 +1,288 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dag::{
+        adapter::{LedgerInfoProvider, OrderedNotifier, OrderedNotifierAdapter},
+        anchor_election::{
+            CommitHistory, LeaderReputationAdapter, MetadataBackendAdapter, MinParticipationConfig,
+        },
+        dag_store::DagStore,
+        order_rule::commit_event,
+        storage::CommitEvent,
+        tests::{
+            dag_test::MockStorage,
+            helpers::{new_certified_node, MockPayloadManager, TEST_DAG_WINDOW},
+        },
+        NodeId, StorageAdapter,
+    },
+    liveness::leader_reputation::{MetadataBackend, ProposerAndVoterHeuristic},
+};
+use aptos_consensus_types::common::Author;
+use aptos_crypto::HashValue;
+use aptos_infallible::RwLock;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo},
+    validator_verifier::random_validator_verifier,
+};
+use futures_channel::mpsc::unbounded;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+#[test]
+fn test_metadata_backend_adapter_is_deterministic() {
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let epoch_to_validators = HashMap::from([(
+        1,
+        validators
+            .iter()
+            .enumerate()
+            .map(|(idx, author)| (*author, idx))
+            .collect(),
+    )]);
+
+    let commit_events: Vec<_> = (1..=5)
+        .map(|round| {
+            CommitEvent::new(
+                NodeId::new(1, round, validators[round as usize % validators.len()]),
+                HashValue::random(),
+                validators[..3].to_vec(),
+                vec![validators[(round as usize + 1) % validators.len()]],
+            )
+        })
+        .collect();
+
+    let first = MetadataBackendAdapter::new(10, epoch_to_validators.clone());
+    let second = MetadataBackendAdapter::new(10, epoch_to_validators);
+    assert_eq!(first.get_block_metadata(1, 1).1, HashValue::zero());
+    for event in &commit_events {
+        first.push(event.clone());
+        second.push(event.clone());
+    }
+
+    let (first_events, first_hash) = first.get_block_metadata(1, 6);
+    let (second_events, second_hash) = second.get_block_metadata(1, 6);
+    assert_eq!(first_hash, second_hash);
+    assert_eq!(first_hash, commit_events.last().unwrap().block_id());
+    assert_eq!(
+        bcs::to_bytes(&first_events).unwrap(),
+        bcs::to_bytes(&second_events).unwrap()
+    );
+    for (event, commit_event) in first_events.iter().zip(commit_events.iter().rev()) {
+        assert_eq!(event.hash().unwrap(), commit_event.block_id());
+        assert_eq!(event.round(), commit_event.round());
+    }
+}
+
+#[test]
+fn test_metadata_backend_adapter_same_after_restart() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let dag = Arc::new(DagStore::new_empty(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        Arc::new(MockPayloadManager {}),
+        1,
+        TEST_DAG_WINDOW,
+    ));
+    let ledger_info = generate_ledger_info_with_sig(&signers, LedgerInfo::mock_genesis(None));
+    let (executor_tx, mut executor_rx) = unbounded();
+    let notifier = OrderedNotifierAdapter::new(
+        executor_tx,
+        dag,
+        epoch_state,
+        ledger_info.commit_info().clone(),
+        Arc::new(RwLock::new(LedgerInfoProvider::new(ledger_info))),
+    );
+    let epoch_to_validators = HashMap::from([(
+        1,
+        validators
+            .iter()
+            .enumerate()
+            .map(|(idx, author)| (*author, idx))
+            .collect(),
+    )]);
+    let live = MetadataBackendAdapter::new(10, epoch_to_validators.clone());
+    let restarted = MetadataBackendAdapter::new(10, epoch_to_validators);
+
+    let parents: Vec<_> = validators[..3]
+        .iter()
+        .map(|author| new_certified_node(1, *author, vec![]).certificate())
+        .collect();
+    for round in [2, 4, 6] {
+        let anchor = Arc::new(new_certified_node(
+            round,
+            validators[round as usize % validators.len()],
+            parents.clone(),
+        ));
+        let failed_authors = vec![(round - 2, validators[3])];
+
+        // A live node records the event when the anchor is ordered.
+        let block_id = notifier.send_ordered_nodes(vec![anchor.clone()], failed_authors.clone());
+        live.push(commit_event(&anchor, block_id, &failed_authors));
+
+        // A restarted node reads it back from the NewBlockEvent of the committed block.
+        let ordered_blocks = executor_rx.try_next().unwrap().unwrap();
+        let block_metadata = ordered_blocks.ordered_blocks[0]
+            .block()
+            .new_block_metadata(&validators);
+        let new_block_event = NewBlockEvent::new(
+            AccountAddress::new(*block_metadata.id()),
+            block_metadata.epoch(),
+            block_metadata.round(),
+            0,
+            block_metadata.previous_block_votes_bitvec().clone(),
+            block_metadata.proposer(),
+            block_metadata
+                .failed_proposer_indices()
+                .iter()
+                .map(|index| *index as u64)
+                .collect(),
+            block_metadata.timestamp_usecs(),
+        );
+        restarted.push(
+            StorageAdapter::new_block_event_to_commit_event(&validators, new_block_event).unwrap(),
+        );
+    }
+
+    let (live_events, live_root_hash) = live.get_block_metadata(1, 7);
+    let (restarted_events, restarted_root_hash) = restarted.get_block_metadata(1, 7);
+    assert_ne!(live_root_hash, HashValue::zero());
+    assert_eq!(live_root_hash, restarted_root_hash);
+    assert_eq!(
+        bcs::to_bytes(&live_events).unwrap(),
+        bcs::to_bytes(&restarted_events).unwrap()
+    );
+}
+
+#[test]
+fn test_metadata_backend_adapter_window_snapshot() {
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let validator_indices: HashMap<_, _> = validators
+        .iter()
+        .enumerate()
+        .map(|(idx, author)| (*author, idx))
+        .collect();
+    let adapter = MetadataBackendAdapter::new(
+        10,
+        HashMap::from([(1, validator_indices.clone()), (2, validator_indices)]),
+    );
+    assert!(adapter.snapshot_window().is_empty());
+    assert!(adapter.window_epochs().is_empty());
+
+    let commit_events: Vec<_> = [(1, 1), (1, 2), (2, 1), (2, 2)]
+        .into_iter()
+        .map(|(epoch, round)| {
+            CommitEvent::new(
+                NodeId::new(epoch, round, validators[0]),
+                HashValue::random(),
+                validators[..3].to_vec(),
+                vec![],
+            )
+        })
+        .collect();
+    for event in &commit_events {
+        adapter.push(event.clone());
+    }
+
+    let snapshot = adapter.snapshot_window();
+    assert_eq!(snapshot.len(), commit_events.len());
+    // the latest commit is at the front of the window
+    for (event, commit_event) in snapshot.iter().zip(commit_events.iter().rev()) {
+        assert_eq!(event.block_id(), commit_event.block_id());
+        assert_eq!(event.epoch(), commit_event.epoch());
+        assert_eq!(event.round(), commit_event.round());
+    }
+    assert_eq!(adapter.window_epochs(), BTreeSet::from([1, 2]));
+    // taking a snapshot doesn't drain the window
+    assert_eq!(adapter.snapshot_window().len(), commit_events.len());
+}
+
+fn leader_reputation_adapter(
+    validators: &[Author],
+    num_participants: usize,
+    num_commits: u64,
+    min_participation_config: Option<MinParticipationConfig>,
+) -> LeaderReputationAdapter {
+    // past the first epochs, where missing history is treated as a healthy chain
+    let epoch = 3;
+    let window_for_chain_health = 10;
+    let backend = Arc::new(MetadataBackendAdapter::new(
+        window_for_chain_health,
+        HashMap::from([(
+            epoch,
+            validators
+                .iter()
+                .enumerate()
+                .map(|(idx, author)| (*author, idx))
+                .collect(),
+        )]),
+    ));
+    for round in 1..=num_commits {
+        backend.push(CommitEvent::new(
+            NodeId::new(epoch, round, validators[0]),
+            HashValue::random(),
+            validators[..num_participants].to_vec(),
+            vec![],
+        ));
+    }
+    LeaderReputationAdapter::new(
+        epoch,
+        HashMap::from([(epoch, validators.to_vec())]),
+        vec![1; validators.len()],
+        backend,
+        Box::new(ProposerAndVoterHeuristic::new(
+            validators[0],
+            1000,
+            10,
+            1,
+            10,
+            window_for_chain_health,
+            window_for_chain_health,
+            false,
+        )),
+        window_for_chain_health,
+        min_participation_config,
+    )
+}
+
+#[test]
+fn test_min_participation_fallback() {
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let config = MinParticipationConfig {
+        threshold: 0.67,
+        fallback_ratio: 1.0,
+    };
+
+    // no commit history yet
+    let adapter = leader_reputation_adapter(&validators, 1, 0, Some(config));
+    assert_eq!(adapter.get_voting_power_participation_ratio(11), None);
+
+    // only one out of four validators participates while the window is filling up
+    let adapter = leader_reputation_adapter(&validators, 1, 5, Some(config));
+    assert_eq!(adapter.get_voting_power_participation_ratio(11), Some(1.0));
+    let adapter = leader_reputation_adapter(&validators, 1, 5, None);
+    assert_eq!(adapter.get_voting_power_participation_ratio(11), Some(0.25));
+
+    // once the window is full, low participation is reported as is
+    let adapter = leader_reputation_adapter(&validators, 1, 10, Some(config));
+    let ratio = adapter.get_voting_power_participation_ratio(11).unwrap();
+    assert!(ratio < 0.67);
+    assert_eq!(ratio, 0.25);
+
+    // ratios above the threshold pass through
+    let adapter = leader_reputation_adapter(&validators, 4, 5, Some(config));
+    assert_eq!(adapter.get_voting_power_participation_ratio(11), Some(1.0));
+}
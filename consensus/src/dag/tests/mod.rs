@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod dag_driver_tests;
+mod dag_handler_tests;
 mod dag_network_test;
 mod dag_state_sync_tests;
 mod dag_test;
 mod fetcher_test;
 mod helpers;
 mod integration_tests;
+mod leader_reputation_adapter_tests;
 mod order_rule_tests;
 mod rb_handler_tests;
 mod types_test;
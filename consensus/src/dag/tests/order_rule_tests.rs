@@ -14,6 +14,7 @@ use crate::dag::{
     CertifiedNode,
 };
 use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_types::{epoch_state::EpochState, validator_verifier::random_validator_verifier};
 use async_trait::async_trait;
@@ -86,8 +87,9 @@ impl OrderedNotifier for TestNotifier {
         &self,
         ordered_nodes: Vec<Arc<CertifiedNode>>,
         _failed_authors: Vec<(Round, Author)>,
-    ) {
-        self.tx.unbounded_send(ordered_nodes).unwrap()
+    ) -> HashValue {
+        self.tx.unbounded_send(ordered_nodes).unwrap();
+        HashValue::zero()
     }
 }
 
@@ -6,7 +6,9 @@ use crate::dag::{
     dag_store::DagStore,
     errors::NodeBroadcastHandleError,
     health::{HealthBackoff, NoChainHealth, NoPipelineBackpressure},
+    observability::counters::DUPLICATE_NODES_FILTERED,
     rb_handler::NodeBroadcastHandler,
+    recent_node_filter::RecentNodeFilter,
     storage::DAGStorage,
     tests::{
         dag_test::MockStorage,
@@ -16,6 +18,7 @@ use crate::dag::{
     NodeId, RpcHandler, Vote,
 };
 use aptos_config::config::DagPayloadConfig;
+use aptos_crypto::HashValue;
 use aptos_types::{
     aggregate_signature::PartialSignatures,
     epoch_state::EpochState,
@@ -23,7 +26,7 @@ use aptos_types::{
     validator_verifier::random_validator_verifier,
 };
 use claims::{assert_ok, assert_ok_eq};
-use futures::executor::block_on;
+use futures::{executor::block_on, future::join_all};
 use std::{collections::BTreeMap, sync::Arc};
 
 struct MockFetchRequester {}
@@ -245,3 +248,77 @@ async fn test_node_broadcast_receiver_storage() {
     assert_ok!(rb_receiver.gc_before_round(2));
     assert_eq!(storage.get_votes().unwrap().len(), 0);
 }
+
+#[test]
+fn test_recent_node_filter() {
+    let filter = RecentNodeFilter::new(10_000, 0.001);
+    let digest = |i: u64| HashValue::sha3_256_of(&i.to_le_bytes());
+
+    for i in 0..10_000 {
+        filter.insert(&digest(i));
+    }
+    // No false negatives
+    for i in 0..10_000 {
+        assert!(filter.contains(&digest(i)));
+    }
+    // False positives stay close to the configured rate
+    let false_positives = (10_000..20_000)
+        .filter(|i| filter.contains(&digest(*i)))
+        .count();
+    assert!(false_positives < 100, "{} false positives", false_positives);
+
+    // Inserting beyond the capacity starts over with an empty filter
+    filter.insert(&digest(20_000));
+    assert!(filter.contains(&digest(20_000)));
+    assert!(!(0..100).any(|i| filter.contains(&digest(i))));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_node_broadcast_receiver_concurrent_duplicates() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let signers: Vec<_> = signers.into_iter().map(Arc::new).collect();
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+
+    let storage = Arc::new(MockStorage::new());
+    let dag = Arc::new(DagStore::new(
+        epoch_state.clone(),
+        storage.clone(),
+        Arc::new(MockPayloadManager {}),
+        0,
+        TEST_DAG_WINDOW,
+    ));
+    let rb_receiver = Arc::new(NodeBroadcastHandler::new(
+        dag,
+        signers[3].clone(),
+        epoch_state.clone(),
+        storage,
+        Arc::new(MockFetchRequester {}),
+        DagPayloadConfig::default(),
+        ValidatorTxnConfig::default_disabled(),
+        Features::default(),
+        HealthBackoff::new(
+            epoch_state,
+            NoChainHealth::new(),
+            NoPipelineBackpressure::new(),
+        ),
+    ));
+
+    let node = new_node(1, 10, signers[0].author(), vec![]);
+    let vote = rb_receiver.process(node.clone()).await.unwrap();
+
+    // The same node relayed by several peers at once is answered with the existing vote,
+    // instead of failing on the concurrent insertion of a vote for it.
+    let filtered_before = DUPLICATE_NODES_FILTERED.get();
+    let handles = (0..8).map(|_| {
+        let rb_receiver = rb_receiver.clone();
+        let node = node.clone();
+        tokio::spawn(async move { rb_receiver.process(node).await })
+    });
+    for result in join_all(handles).await {
+        assert_ok_eq!(result.unwrap(), vote.clone());
+    }
+    assert!(DUPLICATE_NODES_FILTERED.get() >= filtered_before + 8);
+}
@@ -510,6 +510,10 @@ impl Vote {
         }
     }
 
+    pub fn metadata(&self) -> &NodeMetadata {
+        &self.metadata
+    }
+
     pub fn signature(&self) -> &Signature {
         &self.signature
     }
@@ -22,7 +22,7 @@ use aptos_reliable_broadcast::ReliableBroadcast;
 use aptos_types::{
     account_address::AccountAddress,
     epoch_state::EpochState,
-    jwks::{ObservedJWKs, ObservedJWKsUpdated, SupportedOIDCProviders},
+    jwks::{JWKConsensusConfig, ObservedJWKs, ObservedJWKsUpdated, SupportedOIDCProviders},
     on_chain_config::{
         FeatureFlag, Features, OnChainConfigPayload, OnChainConfigProvider, ValidatorSet,
     },
@@ -170,6 +170,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         if features.is_enabled(FeatureFlag::JWK_CONSENSUS) && my_index.is_some() {
             let onchain_oidc_provider_set = payload.get::<SupportedOIDCProviders>().ok();
             let onchain_observed_jwks = payload.get::<ObservedJWKs>().ok();
+            let jwk_consensus_config = payload.get::<JWKConsensusConfig>().unwrap_or_default();
             info!(epoch = epoch_state.epoch, "JWKManager starting.");
             let network_sender = NetworkSender::new(
                 self.my_addr,
@@ -184,7 +185,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 Duration::from_millis(1000),
                 BoundedExecutor::new(8, tokio::runtime::Handle::current()),
             );
-            let update_certifier = UpdateCertifier::new(rb);
+            let update_certifier =
+                UpdateCertifier::new(rb, jwk_consensus_config.quorum_threshold());
 
             let jwk_consensus_manager = JWKManager::new(
                 self.consensus_key.clone(),
@@ -192,6 +194,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 epoch_state.clone(),
                 Arc::new(update_certifier),
                 self.vtxn_pool.clone(),
+                Duration::from_secs(jwk_consensus_config.min_observation_secs),
+                aptos_time_service::TimeService::real(),
             );
 
             let (jwk_event_tx, jwk_event_rx) = aptos_channel::new(QueueStyle::KLAST, 1, None);
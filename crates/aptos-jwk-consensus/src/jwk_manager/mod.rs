@@ -10,6 +10,7 @@ use anyhow::{anyhow, bail, Result};
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_crypto::{bls12381::PrivateKey, SigningKey};
 use aptos_logger::{debug, error, info};
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     account_address::AccountAddress,
     epoch_state::EpochState,
@@ -50,6 +51,15 @@ pub struct JWKManager {
     /// The JWK consensus states of all the issuers.
     states_by_issuer: HashMap<Issuer, PerProviderState>,
 
+    /// For how long an observation needs to stay unchanged before its consensus session starts,
+    /// so that JWKs which only show up briefly are not committed.
+    min_observation_window: Duration,
+
+    /// Since when the current observation of each issuer has stayed unchanged.
+    observed_since: HashMap<Issuer, Duration>,
+
+    time_service: TimeService,
+
     /// Whether a CLOSE command has been received.
     stopped: bool,
 
@@ -65,6 +75,8 @@ impl JWKManager {
         epoch_state: Arc<EpochState>,
         update_certifier: Arc<dyn TUpdateCertifier>,
         vtxn_pool: VTxnPoolState,
+        min_observation_window: Duration,
+        time_service: TimeService,
     ) -> Self {
         let (qc_update_tx, qc_update_rx) = aptos_channel::new(QueueStyle::KLAST, 1, None);
         Self {
@@ -74,6 +86,9 @@ impl JWKManager {
             update_certifier,
             vtxn_pool,
             states_by_issuer: HashMap::default(),
+            min_observation_window,
+            observed_since: HashMap::default(),
+            time_service,
             stopped: false,
             qc_update_tx,
             qc_update_rx,
@@ -170,9 +185,28 @@ impl JWKManager {
             issuer = String::from_utf8(issuer.clone()).ok(),
             "Processing new observation."
         );
+        let now = self.time_service.now_unix_time();
         let state = self.states_by_issuer.entry(issuer.clone()).or_default();
+        let observation_changed = state.observed.as_ref() != Some(&jwks);
+        if observation_changed {
+            self.observed_since.insert(issuer.clone(), now);
+        }
+        let observed_since = *self.observed_since.entry(issuer.clone()).or_insert(now);
         state.observed = Some(jwks.clone());
         if state.observed.as_ref() != state.on_chain.as_ref().map(ProviderJWKs::jwks) {
+            if now.saturating_sub(observed_since) < self.min_observation_window {
+                if observation_changed {
+                    // The session of a superseded observation must not be committed.
+                    state.consensus_state = ConsensusState::NotStarted;
+                }
+                debug!(
+                    epoch = self.epoch_state.epoch,
+                    issuer = String::from_utf8(issuer.clone()).ok(),
+                    "Update observed but not stable yet."
+                );
+                return Ok(());
+            }
+
             let observed = ProviderJWKs {
                 issuer: issuer.clone(),
                 version: state.on_chain_version() + 1,
@@ -225,6 +259,8 @@ impl JWKManager {
 
         self.states_by_issuer
             .retain(|issuer, _| onchain_issuer_set.contains(issuer));
+        self.observed_since
+            .retain(|issuer, _| onchain_issuer_set.contains(issuer));
         for on_chain_provider_jwks in on_chain_state.entries {
             let issuer = on_chain_provider_jwks.issuer.clone();
             let locally_cached = self
@@ -14,6 +14,7 @@ use aptos_crypto::{
     SigningKey, Uniform,
 };
 use aptos_infallible::{Mutex, RwLock};
+use aptos_time_service::TimeService;
 use aptos_types::{
     account_address::AccountAddress,
     aggregate_signature::AggregateSignature,
@@ -61,6 +62,8 @@ async fn test_jwk_manager_state_transition() {
         Arc::new(epoch_state),
         Arc::new(update_certifier),
         vtxn_pool.clone(),
+        Duration::ZERO,
+        TimeService::mock(),
     );
 
     // In this example, Alice and Bob are 2 existing issuers; Carl was added in the last epoch so no JWKs of Carl is on chain.
@@ -446,6 +449,120 @@ async fn test_jwk_manager_state_transition() {
     assert_eq!(expected_states, jwk_manager.states_by_issuer);
 }
 
+#[tokio::test]
+async fn test_jwk_manager_min_observation_window() {
+    let private_keys: Vec<Arc<PrivateKey>> = (0..4)
+        .map(|_| Arc::new(PrivateKey::generate_for_testing()))
+        .collect();
+    let addrs: Vec<AccountAddress> = (0..4).map(|_| AccountAddress::random()).collect();
+    let validator_consensus_infos: Vec<ValidatorConsensusInfo> = (0..4)
+        .map(|i| {
+            ValidatorConsensusInfo::new(addrs[i], PublicKey::from(private_keys[i].as_ref()), 1)
+        })
+        .collect();
+    let epoch_state = EpochState {
+        epoch: 999,
+        verifier: ValidatorVerifier::new(validator_consensus_infos),
+    };
+
+    let update_certifier = Arc::new(DummyUpdateCertifier::default());
+    let time_service = TimeService::mock();
+    let mock_time = time_service.clone().into_mock();
+    let mut jwk_manager = JWKManager::new(
+        private_keys[0].clone(),
+        addrs[0],
+        Arc::new(epoch_state),
+        update_certifier.clone(),
+        VTxnPoolState::default(),
+        Duration::from_secs(30),
+        time_service,
+    );
+
+    let issuer_alice = issuer_from_str("https://alice.info");
+    let on_chain_state_alice_v1 = ProviderJWKs {
+        issuer: issuer_alice.clone(),
+        version: 1,
+        jwks: vec![JWK::Unsupported(UnsupportedJWK::new_for_testing(
+            "alice_jwk_id_0",
+            "jwk_payload_0",
+        ))
+        .into()],
+    };
+    assert!(jwk_manager
+        .reset_with_on_chain_state(AllProvidersJWKs {
+            entries: vec![on_chain_state_alice_v1],
+        })
+        .is_ok());
+    let alice_jwks_a = vec![JWK::Unsupported(UnsupportedJWK::new_for_testing(
+        "alice_jwk_id_1",
+        "jwk_payload_1",
+    ))
+    .into()];
+    let alice_jwks_b = vec![JWK::Unsupported(UnsupportedJWK::new_for_testing(
+        "alice_jwk_id_2",
+        "jwk_payload_2",
+    ))
+    .into()];
+    let alice_consensus_state = |jwk_manager: &JWKManager| {
+        jwk_manager
+            .states_by_issuer
+            .get(&issuer_alice)
+            .unwrap()
+            .consensus_state
+            .clone()
+    };
+
+    // A new observation is not certified right away.
+    assert!(jwk_manager
+        .process_new_observation(issuer_alice.clone(), alice_jwks_a.clone())
+        .is_ok());
+    assert_eq!(
+        ConsensusState::NotStarted,
+        alice_consensus_state(&jwk_manager)
+    );
+
+    // Alice changes her mind within the observation window, which restarts the window.
+    mock_time.advance_secs(20);
+    assert!(jwk_manager
+        .process_new_observation(issuer_alice.clone(), alice_jwks_b.clone())
+        .is_ok());
+    mock_time.advance_secs(20);
+    assert!(jwk_manager
+        .process_new_observation(issuer_alice.clone(), alice_jwks_b.clone())
+        .is_ok());
+    assert_eq!(
+        ConsensusState::NotStarted,
+        alice_consensus_state(&jwk_manager)
+    );
+    assert!(update_certifier.invocations.lock().is_empty());
+
+    // Once stable for the whole window, the latest observation gets certified.
+    mock_time.advance_secs(10);
+    assert!(jwk_manager
+        .process_new_observation(issuer_alice.clone(), alice_jwks_b.clone())
+        .is_ok());
+    let observed = ProviderJWKs {
+        issuer: issuer_alice.clone(),
+        version: 2,
+        jwks: alice_jwks_b,
+    };
+    let signature = private_keys[0].sign(&observed).unwrap();
+    assert_eq!(
+        ConsensusState::InProgress {
+            my_proposal: ObservedUpdate {
+                author: addrs[0],
+                observed: observed.clone(),
+                signature,
+            },
+            abort_handle_wrapper: QuorumCertProcessGuard::dummy(),
+        },
+        alice_consensus_state(&jwk_manager)
+    );
+    let invocations = update_certifier.invocations.lock();
+    assert_eq!(1, invocations.len());
+    assert_eq!(observed, invocations[0].1);
+}
+
 fn new_rpc_observation_request(
     epoch: u64,
     issuer: Issuer,
@@ -12,7 +12,6 @@ use aptos_types::{
     aggregate_signature::PartialSignatures,
     epoch_state::EpochState,
     jwks::{ProviderJWKs, QuorumCertifiedUpdate},
-    validator_verifier::VerifyError,
 };
 use move_core_types::account_address::AccountAddress;
 use std::{collections::BTreeSet, sync::Arc};
@@ -22,17 +21,33 @@ use std::{collections::BTreeSet, sync::Arc};
 pub struct ObservationAggregationState {
     epoch_state: Arc<EpochState>,
     local_view: ProviderJWKs,
+    /// The fraction of the total voting power required to certify the update.
+    quorum_threshold: f64,
     inner_state: Mutex<PartialSignatures>,
 }
 
 impl ObservationAggregationState {
-    pub fn new(epoch_state: Arc<EpochState>, local_view: ProviderJWKs) -> Self {
+    pub fn new(
+        epoch_state: Arc<EpochState>,
+        local_view: ProviderJWKs,
+        quorum_threshold: f64,
+    ) -> Self {
         Self {
             epoch_state,
             local_view,
+            quorum_threshold,
             inner_state: Mutex::new(PartialSignatures::empty()),
         }
     }
+
+    /// The voting power required to certify the update.
+    /// It is never below the BFT quorum, which is what the update is verified against on chain.
+    fn required_voting_power(&self) -> u128 {
+        let verifier = &self.epoch_state.verifier;
+        let configured =
+            (verifier.total_voting_power() as f64 * self.quorum_threshold).ceil() as u128;
+        verifier.quorum_voting_power().max(configured)
+    }
 }
 
 impl BroadcastStatus<JWKConsensusMsg> for Arc<ObservationAggregationState> {
@@ -85,15 +100,9 @@ impl BroadcastStatus<JWKConsensusMsg> for Arc<ObservationAggregationState> {
         // All checks passed. Aggregating.
         partial_sigs.add_signature(sender, signature);
         let voters: BTreeSet<AccountAddress> = partial_sigs.signatures().keys().copied().collect();
-        let power_check_result = self
-            .epoch_state
-            .verifier
-            .check_voting_power(voters.iter(), true);
-        let new_total_power = match &power_check_result {
-            Ok(x) => Some(*x),
-            Err(VerifyError::TooLittleVotingPower { voting_power, .. }) => Some(*voting_power),
-            _ => None,
-        };
+        let new_total_power = self.epoch_state.verifier.sum_voting_power(voters.iter())?;
+        let threshold = self.required_voting_power();
+        let threshold_exceeded = new_total_power >= threshold;
 
         info!(
             epoch = self.epoch_state.epoch,
@@ -101,12 +110,12 @@ impl BroadcastStatus<JWKConsensusMsg> for Arc<ObservationAggregationState> {
             issuer = String::from_utf8(self.local_view.issuer.clone()).ok(),
             peer_power = peer_power,
             new_total_power = new_total_power,
-            threshold = self.epoch_state.verifier.quorum_voting_power(),
-            threshold_exceeded = power_check_result.is_ok(),
+            threshold = threshold,
+            threshold_exceeded = threshold_exceeded,
             "Peer vote aggregated."
         );
 
-        if power_check_result.is_err() {
+        if !threshold_exceeded {
             return Ok(None);
         }
         let multi_sig = self.epoch_state.verifier.aggregate_signatures(&partial_sigs).map_err(|e|anyhow!("adding peer observation failed with partial-to-aggregated conversion error: {e}"))?;
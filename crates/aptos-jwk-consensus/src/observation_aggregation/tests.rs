@@ -54,6 +54,7 @@ fn test_observation_aggregation_state() {
     let ob_agg_state = Arc::new(ObservationAggregationState::new(
         epoch_state.clone(),
         view_0.clone(),
+        0.67,
     ));
 
     // `ObservedUpdate` with incorrect epoch should be rejected.
@@ -130,3 +131,63 @@ fn test_observation_aggregation_state() {
         .verify_multi_signatures(&observed, &multi_sig)
         .is_ok());
 }
+
+#[test]
+fn test_observation_aggregation_state_with_higher_threshold() {
+    let num_validators = 5;
+    let epoch = 999;
+    let addrs: Vec<AccountAddress> = (0..num_validators)
+        .map(|_| AccountAddress::random())
+        .collect();
+    let private_keys: Vec<bls12381::PrivateKey> = (0..num_validators)
+        .map(|_| bls12381::PrivateKey::generate_for_testing())
+        .collect();
+    let voting_powers = [1, 1, 1, 6, 6]; // total voting power: 15, threshold at 90%: 14
+    let validator_infos: Vec<ValidatorConsensusInfo> = (0..num_validators)
+        .map(|i| {
+            ValidatorConsensusInfo::new(
+                addrs[i],
+                bls12381::PublicKey::from(&private_keys[i]),
+                voting_powers[i],
+            )
+        })
+        .collect();
+    let verifier = ValidatorVerifier::new(validator_infos);
+    let epoch_state = Arc::new(EpochState { epoch, verifier });
+    let view = ProviderJWKs {
+        issuer: b"https::/alice.com".to_vec(),
+        version: 123,
+        jwks: vec![JWKMoveStruct::from(JWK::Unsupported(
+            UnsupportedJWK::new_for_testing("id1", "payload1"),
+        ))],
+    };
+    let ob_agg_state = Arc::new(ObservationAggregationState::new(
+        epoch_state.clone(),
+        view.clone(),
+        0.9,
+    ));
+    let add = |i: usize| {
+        ob_agg_state.add(addrs[i], ObservedUpdateResponse {
+            epoch,
+            update: ObservedUpdate {
+                author: addrs[i],
+                observed: view.clone(),
+                signature: private_keys[i].sign(&view).unwrap(),
+            },
+        })
+    };
+
+    // The BFT quorum (11) is reached, but not the configured threshold.
+    assert!(matches!(add(3), Ok(None)));
+    assert!(matches!(add(4), Ok(None)));
+    assert!(matches!(add(0), Ok(None)));
+    let QuorumCertifiedUpdate {
+        update: observed,
+        multi_sig,
+    } = add(1).unwrap().unwrap();
+    assert_eq!(view, observed);
+    assert!(epoch_state
+        .verifier
+        .verify_multi_signatures(&observed, &multi_sig)
+        .is_ok());
+}
@@ -30,12 +30,17 @@ pub trait TUpdateCertifier: Send + Sync {
 
 pub struct UpdateCertifier {
     reliable_broadcast: Arc<ReliableBroadcast<JWKConsensusMsg, ExponentialBackoff>>,
+    quorum_threshold: f64,
 }
 
 impl UpdateCertifier {
-    pub fn new(reliable_broadcast: ReliableBroadcast<JWKConsensusMsg, ExponentialBackoff>) -> Self {
+    pub fn new(
+        reliable_broadcast: ReliableBroadcast<JWKConsensusMsg, ExponentialBackoff>,
+        quorum_threshold: f64,
+    ) -> Self {
         Self {
             reliable_broadcast: Arc::new(reliable_broadcast),
+            quorum_threshold,
         }
     }
 }
@@ -61,7 +66,11 @@ impl TUpdateCertifier for UpdateCertifier {
             epoch: epoch_state.epoch,
             issuer: issuer.clone(),
         };
-        let agg_state = Arc::new(ObservationAggregationState::new(epoch_state, payload));
+        let agg_state = Arc::new(ObservationAggregationState::new(
+            epoch_state,
+            payload,
+            self.quorum_threshold,
+        ));
         let task = async move {
             let qc_update = rb.broadcast(req, agg_state).await;
             info!(
@@ -30,9 +30,11 @@ mod batch_transfer;
 mod bounded_batch_wrapper;
 mod call_custom_modules;
 mod entry_points;
+mod no_op_generator;
 mod p2p_transaction_generator;
 pub mod publish_modules;
 pub mod publishing;
+mod rate_limited_stage;
 mod transaction_mix_generator;
 mod workflow_delegator;
 use self::{
@@ -83,12 +85,35 @@ pub enum TransactionType {
         num_modules: usize,
         use_account_pool: bool,
         progress_type: WorkflowProgress,
+        /// Stop creating transactions once this many were created across all stages.
+        max_total_txns: Option<usize>,
     },
 }
 
 #[derive(Debug, Copy, Clone)]
 pub enum WorkflowKind {
-    CreateThenMint { count: usize, creation_balance: u64 },
+    CreateThenMint {
+        count: usize,
+        creation_balance: u64,
+        /// If set, accounts are created at no more than this many per second.
+        max_creation_rate_per_sec: Option<f64>,
+        /// If set, an idle stage is inserted between stages, skipping this many
+        /// transactions before moving to the next stage. Only supported with
+        /// `WorkflowProgress::WhenDone`, as phases set externally have no idle stages.
+        cooldown_between_stages: Option<usize>,
+    },
+}
+
+impl WorkflowKind {
+    /// Maximum rate at which each stage creates transactions, if limited.
+    pub fn stage_rate_limits(&self) -> Vec<Option<f64>> {
+        match self {
+            WorkflowKind::CreateThenMint {
+                max_creation_rate_per_sec,
+                ..
+            } => vec![*max_creation_rate_per_sec, None],
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -326,6 +351,7 @@ pub async fn create_txn_generator_creator(
                     use_account_pool,
                     workflow_kind,
                     progress_type,
+                    max_total_txns,
                 } => Box::new(
                     WorkflowTxnGeneratorCreator::create_workload(
                         *workflow_kind,
@@ -337,6 +363,8 @@ pub async fn create_txn_generator_creator(
                         use_account_pool.then(|| accounts_pool.clone()),
                         cur_phase.clone(),
                         *progress_type,
+                        *max_total_txns,
+                        workflow_kind.stage_rate_limits(),
                     )
                     .await,
                 ),
@@ -0,0 +1,27 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
+
+/// Generator that never creates any transactions.
+/// Used for idle (cooldown) stages in workflows, letting the system settle between stages.
+pub struct NoOpGenerator;
+
+impl TransactionGenerator for NoOpGenerator {
+    fn generate_transactions(
+        &mut self,
+        _account: &LocalAccount,
+        _num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        Vec::new()
+    }
+}
+
+pub struct NoOpGeneratorCreator;
+
+impl TransactionGeneratorCreator for NoOpGeneratorCreator {
+    fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(NoOpGenerator)
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_infallible::Mutex;
+use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
+use std::{cmp, sync::Arc, time::Instant};
+
+/// Token bucket, refilled at max_rate_per_sec, holding at most one second worth of tokens.
+struct TokenBucket {
+    max_rate_per_sec: f64,
+    capacity: f64,
+    // (available tokens, time of the last refill)
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(max_rate_per_sec: f64) -> Self {
+        assert!(max_rate_per_sec > 0.0);
+        let capacity = max_rate_per_sec.max(1.0);
+        Self {
+            max_rate_per_sec,
+            capacity,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Takes up to `num` tokens, and returns how many were taken.
+    fn take(&self, num: usize) -> usize {
+        let mut state = self.state.lock();
+        let (tokens, last_refill) = &mut *state;
+        let now = Instant::now();
+        *tokens = (*tokens
+            + now.duration_since(*last_refill).as_secs_f64() * self.max_rate_per_sec)
+            .min(self.capacity);
+        *last_refill = now;
+
+        let taken = cmp::min(num, *tokens as usize);
+        *tokens -= taken as f64;
+        taken
+    }
+
+    fn give_back(&self, num: usize) {
+        let mut state = self.state.lock();
+        state.0 = (state.0 + num as f64).min(self.capacity);
+    }
+}
+
+struct RateLimitedStageGenerator {
+    inner: Box<dyn TransactionGenerator>,
+    bucket: Arc<TokenBucket>,
+}
+
+impl TransactionGenerator for RateLimitedStageGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let num_to_create = self.bucket.take(num_to_create);
+        if num_to_create == 0 {
+            return Vec::new();
+        }
+        let txns = self.inner.generate_transactions(account, num_to_create);
+        // Inner generators may return more than requested, in which case nothing is given back.
+        self.bucket.give_back(num_to_create.saturating_sub(txns.len()));
+        txns
+    }
+}
+
+/// Wrapper limiting the rate at which a workflow stage creates transactions, across all the
+/// generators created from it, so that a fast stage cannot grow the pool consumed by the next
+/// stage faster than it is consumed.
+pub struct RateLimitedStage {
+    inner: Box<dyn TransactionGeneratorCreator>,
+    bucket: Arc<TokenBucket>,
+}
+
+impl RateLimitedStage {
+    pub fn new(inner: Box<dyn TransactionGeneratorCreator>, max_rate_per_sec: f64) -> Self {
+        Self {
+            inner,
+            bucket: Arc::new(TokenBucket::new(max_rate_per_sec)),
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for RateLimitedStage {
+    fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(RateLimitedStageGenerator {
+            inner: self.inner.create_transaction_generator(),
+            bucket: self.bucket.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        account_generator::AccountGeneratorCreator, create_account_transaction, ObjectPool,
+    };
+    use aptos_sdk::{transaction_builder::TransactionFactory, types::chain_id::ChainId};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Creates twice as many transactions as requested.
+    struct OverProducingGenerator {
+        txn_factory: TransactionFactory,
+    }
+
+    impl TransactionGenerator for OverProducingGenerator {
+        fn generate_transactions(
+            &mut self,
+            account: &LocalAccount,
+            num_to_create: usize,
+        ) -> Vec<SignedTransaction> {
+            (0..num_to_create * 2)
+                .map(|_| {
+                    create_account_transaction(
+                        account,
+                        LocalAccount::generate(&mut StdRng::from_entropy()).address(),
+                        &self.txn_factory,
+                        0,
+                    )
+                })
+                .collect()
+        }
+    }
+
+    struct OverProducingGeneratorCreator;
+
+    impl TransactionGeneratorCreator for OverProducingGeneratorCreator {
+        fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+            Box::new(OverProducingGenerator {
+                txn_factory: TransactionFactory::new(ChainId::test()),
+            })
+        }
+    }
+
+    #[test]
+    fn test_rate_limited_stage_bounds_pool() {
+        let pool = Arc::new(ObjectPool::new());
+        let stage = RateLimitedStage::new(
+            Box::new(AccountGeneratorCreator::new(
+                TransactionFactory::new(ChainId::test()),
+                None,
+                Some(pool.clone()),
+                1_000_000,
+                0,
+            )),
+            10.0,
+        );
+        let sender = LocalAccount::generate(&mut StdRng::from_entropy());
+
+        let start = Instant::now();
+        let mut generators: Vec<_> = (0..4)
+            .map(|_| stage.create_transaction_generator())
+            .collect();
+        for _ in 0..50 {
+            for generator in generators.iter_mut() {
+                generator.generate_transactions(&sender, 100);
+            }
+        }
+
+        // One second worth of burst, plus the refill while generating.
+        let max_pool_size = 10 + (start.elapsed().as_secs_f64() * 10.0).ceil() as usize;
+        assert!(pool.len() > 0);
+        assert!(pool.len() <= max_pool_size);
+    }
+
+    #[test]
+    fn test_rate_limited_stage_over_producing_inner() {
+        let stage = RateLimitedStage::new(Box::new(OverProducingGeneratorCreator), 10.0);
+        let sender = LocalAccount::generate(&mut StdRng::from_entropy());
+        let mut generator = stage.create_transaction_generator();
+
+        assert_eq!(generator.generate_transactions(&sender, 4).len(), 8);
+        // Only the 4 requested tokens were taken, nothing is given back.
+        assert_eq!(stage.bucket.state.lock().0 as usize, 6);
+    }
+}
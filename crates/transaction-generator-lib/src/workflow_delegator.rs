@@ -4,9 +4,9 @@
 use crate::{
     account_generator::AccountGeneratorCreator, accounts_pool_wrapper::AccountsPoolWrapperCreator,
     call_custom_modules::CustomModulesDelegationGeneratorCreator,
-    entry_points::EntryPointTransactionGenerator, EntryPoints, ObjectPool,
-    ReliableTransactionSubmitter, TransactionGenerator, TransactionGeneratorCreator, WorkflowKind,
-    WorkflowProgress,
+    entry_points::EntryPointTransactionGenerator, no_op_generator::NoOpGeneratorCreator,
+    rate_limited_stage::RateLimitedStage, EntryPoints, ObjectPool, ReliableTransactionSubmitter,
+    TransactionGenerator, TransactionGeneratorCreator, WorkflowKind, WorkflowProgress,
 };
 use aptos_logger::{info, sample, sample::SampleRate};
 use aptos_sdk::{
@@ -62,6 +62,37 @@ impl StageTracking {
     }
 }
 
+/// Moves from `stage` to the next stage, once `delay_between_stages` passes.
+fn move_to_next_stage(
+    stage_counter: &AtomicUsize,
+    stage_start_time: &AtomicU64,
+    delay_between_stages: &Duration,
+    stage: usize,
+) {
+    stage_start_time.store(
+        StageTracking::current_timestamp() + delay_between_stages.as_secs(),
+        Ordering::Relaxed,
+    );
+    let _ = stage_counter.compare_exchange(stage, stage + 1, Ordering::Relaxed, Ordering::Relaxed);
+}
+
+/// Idle stage, which creates no transactions, and is done once it skipped `length`
+/// transactions worth of generate calls (across all workers).
+#[derive(Clone)]
+struct Cooldown {
+    length: usize,
+    skipped: Arc<AtomicUsize>,
+}
+
+impl Cooldown {
+    fn new(length: usize) -> Self {
+        Self {
+            length,
+            skipped: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
 /// Generator allowing for multi-stage workflows.
 /// List of generators are passed:
 /// gen_0, gen_1, ... gen_n
@@ -82,14 +113,24 @@ impl StageTracking {
 /// - ExternallySet means we wait for external signal to move to next stage, and we stop creating transactions
 ///   until we receive it (or will move early if pool hasn't been consumed yet)
 ///
+/// Optionally, an idle (cooldown) stage is inserted between every two stages. It creates no transactions,
+/// and passes pool_i on unchanged to the next stage. As its pool never fills, with WhenDone it moves to
+/// the next stage after skipping a set count of transactions instead. Not supported with ExternallySet.
+///
 /// Use WorkflowTxnGeneratorCreator::create_workload to create this generator.
 struct WorkflowTxnGenerator {
     stage: StageTracking,
     generators: Vec<Box<dyn TransactionGenerator>>,
     pool_per_stage: Vec<Arc<ObjectPool<LocalAccount>>>,
+    // Set for idle stages
+    cooldown_per_stage: Vec<Option<Cooldown>>,
     num_for_first_stage: usize,
     // Internal counter, so multiple workers (WorkflowTxnGenerator) can coordinate how many times to execute the first stage
     completed_for_first_stage: Arc<AtomicUsize>,
+    // If set, no more transactions are created once this many were created across all stages
+    max_total_txns: Option<usize>,
+    // Number of transactions created across all stages, shared by all workers
+    total_txns: Arc<AtomicUsize>,
 }
 
 impl WorkflowTxnGenerator {
@@ -97,19 +138,36 @@ impl WorkflowTxnGenerator {
         stage: StageTracking,
         generators: Vec<Box<dyn TransactionGenerator>>,
         pool_per_stage: Vec<Arc<ObjectPool<LocalAccount>>>,
+        cooldown_per_stage: Vec<Option<Cooldown>>,
         num_for_first_stage: usize,
         completed_for_first_stage: Arc<AtomicUsize>,
+        max_total_txns: Option<usize>,
+        total_txns: Arc<AtomicUsize>,
     ) -> Self {
         Self {
             stage,
             generators,
             pool_per_stage,
+            cooldown_per_stage,
             num_for_first_stage,
             completed_for_first_stage,
+            max_total_txns,
+            total_txns,
         }
     }
 }
 
+/// Reserves up to `num_to_create` transactions from what is left of `max_total_txns`,
+/// and returns how many were reserved.
+fn reserve_txns(total_txns: &AtomicUsize, max_total_txns: usize, num_to_create: usize) -> usize {
+    let mut reserved = 0;
+    let _ = total_txns.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+        reserved = cmp::min(num_to_create, max_total_txns.saturating_sub(total));
+        (reserved > 0).then_some(total + reserved)
+    });
+    reserved
+}
+
 impl TransactionGenerator for WorkflowTxnGenerator {
     fn generate_transactions(
         &mut self,
@@ -150,15 +208,27 @@ impl TransactionGenerator for WorkflowTxnGenerator {
                 if stage == 0 {
                     if num_to_create == 0 {
                         info!("TransactionGenerator Workflow: Stage 0 is full with {} accounts, moving to stage 1", self.pool_per_stage.first().unwrap().len());
-                        stage_start_time.store(
-                            StageTracking::current_timestamp() + delay_between_stages.as_secs(),
-                            Ordering::Relaxed,
-                        );
-                        let _ = stage_counter.compare_exchange(
+                        move_to_next_stage(
+                            stage_counter,
+                            stage_start_time,
+                            delay_between_stages,
                             0,
-                            1,
-                            Ordering::Relaxed,
-                            Ordering::Relaxed,
+                        );
+                        return Vec::new();
+                    }
+                } else if let Some(cooldown) =
+                    self.cooldown_per_stage.get(stage).and_then(Option::as_ref)
+                {
+                    // Pool of an idle stage never changes, so we count skipped transactions instead.
+                    if cooldown.skipped.fetch_add(num_to_create, Ordering::Relaxed)
+                        >= cooldown.length
+                    {
+                        info!("TransactionGenerator Workflow: Stage {} has finished cooldown, moving to stage {}", stage, stage + 1);
+                        move_to_next_stage(
+                            stage_counter,
+                            stage_start_time,
+                            delay_between_stages,
+                            stage,
                         );
                         return Vec::new();
                     }
@@ -166,15 +236,11 @@ impl TransactionGenerator for WorkflowTxnGenerator {
                     && self.pool_per_stage.get(stage - 1).unwrap().len() == 0
                 {
                     info!("TransactionGenerator Workflow: Stage {} has consumed all accounts, moving to stage {}", stage, stage + 1);
-                    stage_start_time.store(
-                        StageTracking::current_timestamp() + delay_between_stages.as_secs(),
-                        Ordering::Relaxed,
-                    );
-                    let _ = stage_counter.compare_exchange(
+                    move_to_next_stage(
+                        stage_counter,
+                        stage_start_time,
+                        delay_between_stages,
                         stage,
-                        stage + 1,
-                        Ordering::Relaxed,
-                        Ordering::Relaxed,
                     );
                     return Vec::new();
                 }
@@ -191,12 +257,32 @@ impl TransactionGenerator for WorkflowTxnGenerator {
             info!("Cur stage: {}, pool sizes: {:?}", stage, self.pool_per_stage.iter().map(|p| p.len()).collect::<Vec<_>>());
         );
 
+        if let Some(max_total_txns) = self.max_total_txns {
+            num_to_create = reserve_txns(&self.total_txns, max_total_txns, num_to_create);
+            if num_to_create == 0 {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(2)),
+                    info!("TransactionGenerator Workflow: Created {} transactions, stopping", max_total_txns);
+                );
+                return Vec::new();
+            }
+        }
+
         let result = if let Some(generator) = self.generators.get_mut(stage) {
             generator.generate_transactions(account, num_to_create)
         } else {
             Vec::new()
         };
 
+        if self.max_total_txns.is_some() {
+            // Give back what was reserved but not created. Generators may return more than
+            // requested, in which case nothing is given back.
+            self.total_txns.fetch_sub(
+                num_to_create.saturating_sub(result.len()),
+                Ordering::Relaxed,
+            );
+        }
+
         result
     }
 }
@@ -205,8 +291,11 @@ pub struct WorkflowTxnGeneratorCreator {
     stage: StageTracking,
     creators: Vec<Box<dyn TransactionGeneratorCreator>>,
     pool_per_stage: Vec<Arc<ObjectPool<LocalAccount>>>,
+    cooldown_per_stage: Vec<Option<Cooldown>>,
     num_for_first_stage: usize,
     completed_for_first_stage: Arc<AtomicUsize>,
+    max_total_txns: Option<usize>,
+    total_txns: Arc<AtomicUsize>,
 }
 
 impl WorkflowTxnGeneratorCreator {
@@ -215,14 +304,81 @@ impl WorkflowTxnGeneratorCreator {
         creators: Vec<Box<dyn TransactionGeneratorCreator>>,
         pool_per_stage: Vec<Arc<ObjectPool<LocalAccount>>>,
         num_for_first_stage: usize,
+        max_total_txns: Option<usize>,
+        cooldown_between_stages: Option<usize>,
     ) -> Self {
+        // Externally set phases index the workflow stages, and would land on the idle ones.
+        assert!(
+            cooldown_between_stages.is_none() || matches!(stage, StageTracking::WhenDone { .. }),
+            "Cooldown between stages requires moving to the next stage when done"
+        );
+        let (creators, pool_per_stage, cooldown_per_stage) = match cooldown_between_stages {
+            Some(length) => Self::interleave_cooldowns(creators, pool_per_stage, length),
+            None => {
+                let cooldown_per_stage = vec![None; creators.len()];
+                (creators, pool_per_stage, cooldown_per_stage)
+            },
+        };
         Self {
             stage,
             creators,
             pool_per_stage,
+            cooldown_per_stage,
             num_for_first_stage,
             completed_for_first_stage: Arc::new(AtomicUsize::new(0)),
+            max_total_txns,
+            total_txns: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Inserts an idle stage, skipping `length` transactions, between every two stages.
+    /// Idle stage passes the accounts created by the previous stage on to the next one unchanged.
+    #[allow(clippy::type_complexity)]
+    fn interleave_cooldowns(
+        creators: Vec<Box<dyn TransactionGeneratorCreator>>,
+        pool_per_stage: Vec<Arc<ObjectPool<LocalAccount>>>,
+        length: usize,
+    ) -> (
+        Vec<Box<dyn TransactionGeneratorCreator>>,
+        Vec<Arc<ObjectPool<LocalAccount>>>,
+        Vec<Option<Cooldown>>,
+    ) {
+        let mut all_creators: Vec<Box<dyn TransactionGeneratorCreator>> = Vec::new();
+        let mut all_pools = Vec::new();
+        let mut cooldown_per_stage = Vec::new();
+        for (stage, creator) in creators.into_iter().enumerate() {
+            if stage > 0 {
+                all_creators.push(Box::new(NoOpGeneratorCreator));
+                all_pools.push(pool_per_stage[stage - 1].clone());
+                cooldown_per_stage.push(Some(Cooldown::new(length)));
+            }
+            all_creators.push(creator);
+            if let Some(pool) = pool_per_stage.get(stage) {
+                all_pools.push(pool.clone());
+            }
+            cooldown_per_stage.push(None);
         }
+        (all_creators, all_pools, cooldown_per_stage)
+    }
+
+    /// Wraps the creator of each stage with a limit in a RateLimitedStage.
+    fn rate_limit_stages(
+        creators: Vec<Box<dyn TransactionGeneratorCreator>>,
+        stage_rate_limits: Vec<Option<f64>>,
+    ) -> Vec<Box<dyn TransactionGeneratorCreator>> {
+        assert!(stage_rate_limits.len() <= creators.len());
+        creators
+            .into_iter()
+            .enumerate()
+            .map(
+                |(stage, creator)| match stage_rate_limits.get(stage).copied().flatten() {
+                    Some(max_rate_per_sec) => {
+                        Box::new(RateLimitedStage::new(creator, max_rate_per_sec)) as _
+                    },
+                    None => creator,
+                },
+            )
+            .collect()
     }
 
     pub async fn create_workload(
@@ -235,6 +391,8 @@ impl WorkflowTxnGeneratorCreator {
         _initial_account_pool: Option<Arc<ObjectPool<LocalAccount>>>,
         cur_phase: Arc<AtomicUsize>,
         progress_type: WorkflowProgress,
+        max_total_txns: Option<usize>,
+        stage_rate_limits: Vec<Option<f64>>,
     ) -> Self {
         let stage_tracking = match progress_type {
             WorkflowProgress::MoveByPhases => StageTracking::ExternallySet(cur_phase),
@@ -257,6 +415,8 @@ impl WorkflowTxnGeneratorCreator {
             WorkflowKind::CreateThenMint {
                 count,
                 creation_balance,
+                cooldown_between_stages,
+                ..
             } => {
                 let created_pool = Arc::new(ObjectPool::new());
                 let minted_pool = Arc::new(ObjectPool::new());
@@ -289,9 +449,11 @@ impl WorkflowTxnGeneratorCreator {
                 ];
                 Self::new(
                     stage_tracking,
-                    creators,
+                    Self::rate_limit_stages(creators, stage_rate_limits),
                     vec![created_pool, minted_pool],
                     count,
+                    max_total_txns,
+                    cooldown_between_stages,
                 )
             },
         }
@@ -307,8 +469,106 @@ impl TransactionGeneratorCreator for WorkflowTxnGeneratorCreator {
                 .map(|c| c.create_transaction_generator())
                 .collect(),
             self.pool_per_stage.clone(),
+            self.cooldown_per_stage.clone(),
             self.num_for_first_stage,
             self.completed_for_first_stage.clone(),
+            self.max_total_txns,
+            self.total_txns.clone(),
         ))
     }
 }
+
+#[test]
+fn test_reserve_txns() {
+    let total_txns = AtomicUsize::new(0);
+    assert_eq!(reserve_txns(&total_txns, 10, 4), 4);
+    assert_eq!(reserve_txns(&total_txns, 10, 4), 4);
+    // Only 2 are left under the limit.
+    assert_eq!(reserve_txns(&total_txns, 10, 4), 2);
+    assert_eq!(reserve_txns(&total_txns, 10, 4), 0);
+    assert_eq!(total_txns.load(Ordering::Relaxed), 10);
+
+    // Giving back unused reservations makes them available again.
+    total_txns.fetch_sub(3, Ordering::Relaxed);
+    assert_eq!(reserve_txns(&total_txns, 10, 4), 3);
+}
+
+#[test]
+fn test_cooldown_stage_advances() {
+    use aptos_sdk::{transaction_builder::TransactionFactory, types::chain_id::ChainId};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let txn_factory = TransactionFactory::new(ChainId::test());
+    let created_pool = Arc::new(ObjectPool::new());
+    let minted_pool = Arc::new(ObjectPool::new());
+    let stage_counter = Arc::new(AtomicUsize::new(0));
+    let creators: Vec<Box<dyn TransactionGeneratorCreator>> = vec![
+        Box::new(AccountGeneratorCreator::new(
+            txn_factory.clone(),
+            None,
+            Some(created_pool.clone()),
+            10,
+            0,
+        )),
+        Box::new(AccountsPoolWrapperCreator::new(
+            Box::new(AccountGeneratorCreator::new(
+                txn_factory,
+                None,
+                None,
+                1_000_000,
+                0,
+            )),
+            created_pool.clone(),
+            Some(minted_pool.clone()),
+        )),
+    ];
+    let creator = WorkflowTxnGeneratorCreator::new(
+        StageTracking::WhenDone {
+            stage_counter: stage_counter.clone(),
+            stage_start_time: Arc::new(AtomicU64::new(0)),
+            delay_between_stages: Duration::ZERO,
+        },
+        creators,
+        vec![created_pool.clone(), minted_pool.clone()],
+        10,
+        None,
+        Some(20),
+    );
+    let mut generator = creator.create_transaction_generator();
+    let sender = LocalAccount::generate(&mut StdRng::from_entropy());
+
+    assert_eq!(generator.generate_transactions(&sender, 10).len(), 10);
+    assert!(generator.generate_transactions(&sender, 10).is_empty());
+    assert_eq!(stage_counter.load(Ordering::Relaxed), 1);
+
+    // Cooldown stage creates nothing, and leaves the pool for the next stage untouched.
+    for _ in 0..2 {
+        assert!(generator.generate_transactions(&sender, 10).is_empty());
+        assert_eq!(stage_counter.load(Ordering::Relaxed), 1);
+    }
+    assert_eq!(created_pool.len(), 10);
+    // 20 transactions were skipped, so it moves to the next stage.
+    assert!(generator.generate_transactions(&sender, 10).is_empty());
+    assert_eq!(stage_counter.load(Ordering::Relaxed), 2);
+
+    assert_eq!(generator.generate_transactions(&sender, 10).len(), 10);
+    assert_eq!(created_pool.len(), 0);
+    assert_eq!(minted_pool.len(), 10);
+    assert!(generator.generate_transactions(&sender, 10).is_empty());
+    assert_eq!(stage_counter.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+#[should_panic(expected = "Cooldown between stages requires moving to the next stage when done")]
+fn test_cooldown_stage_rejected_when_externally_set() {
+    let creators: Vec<Box<dyn TransactionGeneratorCreator>> =
+        vec![Box::new(NoOpGeneratorCreator), Box::new(NoOpGeneratorCreator)];
+    WorkflowTxnGeneratorCreator::new(
+        StageTracking::ExternallySet(Arc::new(AtomicUsize::new(0))),
+        creators,
+        vec![Arc::new(ObjectPool::new())],
+        10,
+        None,
+        Some(20),
+    );
+}
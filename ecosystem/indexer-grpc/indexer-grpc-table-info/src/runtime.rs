@@ -40,10 +40,15 @@ pub fn bootstrap(
 
     let indexer_async_v2 =
         Arc::new(IndexerAsyncV2::new(db).expect("Failed to initialize indexer async v2"));
+    if let Some(lag_alert_threshold) = node_config.indexer_table_info.lag_alert_threshold {
+        indexer_async_v2.set_lag_alert_threshold(lag_alert_threshold);
+    }
     let indexer_async_v2_clone = Arc::clone(&indexer_async_v2);
 
     // Spawn the runtime for table info parsing
     runtime.spawn(async move {
+        indexer_async_v2_clone.spawn_lag_monitor(db_rw.reader.clone());
+
         let context = Arc::new(Context::new(
             chain_id,
             db_rw.reader.clone(),
@@ -251,6 +251,7 @@ mock! {
             &self,
             address: AccountAddress,
             seq_num: u64,
+            order: Order,
             limit: u64,
             include_events: bool,
             ledger_version: Version,
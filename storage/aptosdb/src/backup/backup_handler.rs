@@ -3,25 +3,33 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup::incremental_backup::{
+        apply_state_changes, write_state_backup, IncrementalBackupManifest, StateBackupReader,
+        CHUNK_SIZE,
+    },
     ledger_db::LedgerDb,
     metrics::{
         BACKUP_EPOCH_ENDING_EPOCH, BACKUP_STATE_SNAPSHOT_LEAF_IDX, BACKUP_STATE_SNAPSHOT_VERSION,
         BACKUP_TXN_VERSION,
     },
+    state_restore::{StateSnapshotRestore, StateSnapshotRestoreMode},
     state_store::StateStore,
 };
-use aptos_crypto::hash::HashValue;
-use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
+use aptos_crypto::hash::{CryptoHash, HashValue};
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_storage_interface::{
+    db_ensure as ensure, AptosDbError, DbReader, Result, StateSnapshotReceiver,
+};
 use aptos_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
     proof::{SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionInfoWithProof},
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{Transaction, TransactionInfo, Version},
-    write_set::WriteSet,
+    write_set::{TransactionWrite, WriteSet},
 };
 use serde::{Deserialize, Serialize};
-use std::{fmt, sync::Arc};
+use std::{cmp, collections::BTreeMap, fmt, io::Write, path::Path, sync::Arc};
 
 /// `BackupHandler` provides functionalities for AptosDB data backup.
 #[derive(Clone)]
@@ -179,6 +187,143 @@ impl BackupHandler {
         Ok((txn_info, ledger_info))
     }
 
+    /// Writes all the state at `version` to `output`, in the format read by
+    /// [`Self::restore_incremental_backup`] as the base snapshot.
+    pub fn create_state_snapshot_backup(
+        &self,
+        version: Version,
+        output: &mut dyn Write,
+    ) -> Result<IncrementalBackupManifest> {
+        let root_hash = self.state_store.state_merkle_db.get_root_hash(version)?;
+        let chunk_proofs = self.get_state_chunk_proofs(version)?;
+        let kvs = self
+            .get_account_iter(version)?
+            .map(|res| res.map(|(key, value)| (key, Some(value))));
+        write_state_backup(output, None, version, root_hash, chunk_proofs, kvs)
+    }
+
+    /// Writes the net state changes between `base_version` and `target_version` to `output`. Keys
+    /// written in between but ending up with the same value as at `base_version` are left out.
+    pub fn create_incremental_backup(
+        &self,
+        base_version: Version,
+        target_version: Version,
+        output: &mut dyn Write,
+    ) -> Result<IncrementalBackupManifest> {
+        ensure!(
+            base_version <= target_version,
+            "Bad incremental backup range: [{}, {}]",
+            base_version,
+            target_version
+        );
+        let root_hash = self
+            .state_store
+            .state_merkle_db
+            .get_root_hash(target_version)?;
+        let chunk_proofs = self.get_state_chunk_proofs(target_version)?;
+
+        // Ordered by key hash, to be merged with the base snapshot on restore.
+        let mut changes = BTreeMap::new();
+        let write_set_iter = self
+            .ledger_db
+            .write_set_db()
+            .get_write_set_iter(base_version + 1, (target_version - base_version) as usize)?;
+        for write_set in write_set_iter {
+            for (key, write_op) in write_set?.iter() {
+                changes.insert(CryptoHash::hash(key), (key.clone(), write_op.as_state_value()));
+            }
+        }
+
+        let delta = changes.into_values().filter_map(|(key, value)| {
+            match self.state_store.get_state_value_by_version(&key, base_version) {
+                Ok(base_value) if base_value == value => None,
+                Ok(_) => Some(Ok((key, value))),
+                Err(err) => Some(Err(err)),
+            }
+        });
+        write_state_backup(
+            output,
+            Some(base_version),
+            target_version,
+            root_hash,
+            chunk_proofs,
+            delta,
+        )
+    }
+
+    /// Gets the range proofs of the state at `version` split into chunks of `CHUNK_SIZE` keys.
+    fn get_state_chunk_proofs(&self, version: Version) -> Result<Vec<SparseMerkleRangeProof>> {
+        let leaf_count = self.state_store.get_value_count(version)?;
+        (0..leaf_count)
+            .step_by(CHUNK_SIZE)
+            .map(|first_idx| {
+                let rightmost_idx = cmp::min(first_idx + CHUNK_SIZE, leaf_count) - 1;
+                let (rightmost_key, _) = JellyfishMerkleIterator::new_by_index(
+                    Arc::clone(&self.state_store.state_merkle_db),
+                    version,
+                    rightmost_idx,
+                )?
+                .next()
+                .ok_or_else(|| {
+                    AptosDbError::NotFound(format!("Leaf {} at version {}", rightmost_idx, version))
+                })??;
+                self.get_account_state_range_proof(rightmost_key, version)
+            })
+            .collect()
+    }
+
+    /// Restores the state at the target version of the incremental backup by applying it on top
+    /// of the full snapshot at its base version. Both files are streamed, and the resulting state
+    /// is added in chunks verified against the range proofs and root hash in the manifest.
+    pub fn restore_incremental_backup(
+        &self,
+        base_snapshot_path: &Path,
+        incremental_path: &Path,
+    ) -> Result<()> {
+        let base = StateBackupReader::open(base_snapshot_path)?;
+        ensure!(
+            base.manifest().base_version.is_none(),
+            "{:?} is not a full state snapshot backup.",
+            base_snapshot_path
+        );
+        let delta = StateBackupReader::open(incremental_path)?;
+        let manifest = delta.manifest().clone();
+        ensure!(
+            manifest.base_version == Some(base.manifest().target_version),
+            "Incremental backup is based on version {:?}, but the snapshot is at version {}.",
+            manifest.base_version,
+            base.manifest().target_version
+        );
+
+        let mut receiver = StateSnapshotRestore::new(
+            &self.state_store.state_merkle_db,
+            &self.state_store,
+            manifest.target_version,
+            manifest.root_hash,
+            false, /* async_commit */
+            StateSnapshotRestoreMode::Default,
+        )?;
+        let mut chunk_proofs = manifest.chunk_proofs.into_iter();
+        let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+        let mut state = apply_state_changes(base, delta).peekable();
+        while let Some(kv) = state.next() {
+            chunk.push(kv?);
+            if chunk.len() == CHUNK_SIZE || state.peek().is_none() {
+                let proof = chunk_proofs.next().ok_or_else(|| {
+                    AptosDbError::Other(
+                        "Restored state has more chunks than proven by the manifest.".to_string(),
+                    )
+                })?;
+                receiver.add_chunk(std::mem::take(&mut chunk), proof)?;
+            }
+        }
+        ensure!(
+            chunk_proofs.next().is_none(),
+            "Restored state has fewer chunks than proven by the manifest."
+        );
+        receiver.finish()
+    }
+
     pub fn get_epoch_ending_ledger_info_iter(
         &self,
         start_epoch: u64,
@@ -0,0 +1,288 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! File format shared by full state snapshot backups and incremental state backups.
+//!
+//! A backup file is a sequence of records, each encoded as a big endian `u32` length followed by
+//! the BCS bytes of a [`StateBackupRecord`]. All records but the last one are chunks, the last one
+//! is the manifest.
+
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
+use aptos_types::{
+    proof::SparseMerkleRangeProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    fs::File,
+    io::{BufReader, Read, Write},
+    iter::Peekable,
+    path::{Path, PathBuf},
+};
+
+/// Maximum number of state keys in a chunk.
+pub(crate) const CHUNK_SIZE: usize = 10_000;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct IncrementalBackupManifest {
+    /// Version of the state the changes apply on top of, `None` for a full snapshot.
+    pub base_version: Option<Version>,
+    pub target_version: Version,
+    /// Root hash of the state tree at `target_version`, which the restored state is verified
+    /// against.
+    pub root_hash: HashValue,
+    /// SHA3-256 hashes of the encoded chunks, in order.
+    pub chunk_hashes: Vec<HashValue>,
+    /// Range proofs of the state at `target_version` split into chunks of `CHUNK_SIZE` keys, so
+    /// the restored state can be added and verified one chunk at a time.
+    pub chunk_proofs: Vec<SparseMerkleRangeProof>,
+}
+
+#[derive(Deserialize, Serialize)]
+enum StateBackupRecord {
+    /// `None` means the key is deleted. Full snapshots only have `Some` values.
+    Chunk(Vec<(StateKey, Option<StateValue>)>),
+    Manifest(IncrementalBackupManifest),
+}
+
+fn write_record(output: &mut dyn Write, record: &StateBackupRecord) -> Result<HashValue> {
+    let bytes = bcs::to_bytes(record)?;
+    output.write_all(&u32::try_from(bytes.len())?.to_be_bytes())?;
+    output.write_all(&bytes)?;
+    Ok(HashValue::sha3_256_of(&bytes))
+}
+
+fn read_record_len(input: &mut impl Read) -> Result<usize> {
+    let mut len_bytes = [0; 4];
+    input.read_exact(&mut len_bytes)?;
+    Ok(u32::from_be_bytes(len_bytes) as usize)
+}
+
+/// Writes `changes`, ordered by key hash, in chunks followed by the manifest, and returns the
+/// manifest.
+pub(crate) fn write_state_backup(
+    output: &mut dyn Write,
+    base_version: Option<Version>,
+    target_version: Version,
+    root_hash: HashValue,
+    chunk_proofs: Vec<SparseMerkleRangeProof>,
+    changes: impl Iterator<Item = Result<(StateKey, Option<StateValue>)>>,
+) -> Result<IncrementalBackupManifest> {
+    let mut chunk_hashes = Vec::new();
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    for change in changes {
+        chunk.push(change?);
+        if chunk.len() == CHUNK_SIZE {
+            let record = StateBackupRecord::Chunk(std::mem::take(&mut chunk));
+            chunk_hashes.push(write_record(output, &record)?);
+        }
+    }
+    if !chunk.is_empty() {
+        chunk_hashes.push(write_record(output, &StateBackupRecord::Chunk(chunk))?);
+    }
+
+    let manifest = IncrementalBackupManifest {
+        base_version,
+        target_version,
+        root_hash,
+        chunk_hashes,
+        chunk_proofs,
+    };
+    write_record(output, &StateBackupRecord::Manifest(manifest.clone()))?;
+    output.flush()?;
+    Ok(manifest)
+}
+
+/// Reads a backup file one chunk at a time, verifying each chunk against the chunk hashes in the
+/// manifest, and that the keys are ordered by key hash.
+pub(crate) struct StateBackupReader {
+    path: PathBuf,
+    input: BufReader<File>,
+    manifest: IncrementalBackupManifest,
+    next_chunk_idx: usize,
+    chunk: std::vec::IntoIter<(StateKey, Option<StateValue>)>,
+    last_key_hash: Option<HashValue>,
+}
+
+impl StateBackupReader {
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            input: BufReader::new(File::open(path)?),
+            manifest: Self::read_manifest(path)?,
+            next_chunk_idx: 0,
+            chunk: Vec::new().into_iter(),
+            last_key_hash: None,
+        })
+    }
+
+    pub(crate) fn manifest(&self) -> &IncrementalBackupManifest {
+        &self.manifest
+    }
+
+    /// Skips over the chunks to the manifest, which is the last record in the file.
+    fn read_manifest(path: &Path) -> Result<IncrementalBackupManifest> {
+        let file_len = std::fs::metadata(path)?.len();
+        let mut input = BufReader::new(File::open(path)?);
+        let mut offset = 0;
+        let mut num_chunks = 0;
+        loop {
+            ensure!(offset < file_len, "No manifest found in backup file {:?}.", path);
+            ensure!(
+                offset + 4 <= file_len,
+                "Truncated record length at offset {} in {:?}.",
+                offset,
+                path
+            );
+            let len = read_record_len(&mut input)?;
+            offset += 4;
+            ensure!(
+                offset + len as u64 <= file_len,
+                "Truncated record at offset {} in {:?}.",
+                offset,
+                path
+            );
+            offset += len as u64;
+            if offset < file_len {
+                input.seek_relative(len as i64)?;
+                num_chunks += 1;
+                continue;
+            }
+
+            let mut bytes = vec![0; len];
+            input.read_exact(&mut bytes)?;
+            let StateBackupRecord::Manifest(manifest) = bcs::from_bytes(&bytes)? else {
+                return Err(AptosDbError::Other(format!(
+                    "No manifest found in backup file {:?}.",
+                    path
+                )));
+            };
+            ensure!(
+                manifest.chunk_hashes.len() == num_chunks,
+                "Found {} chunks in {:?}, but the manifest has {}.",
+                num_chunks,
+                path,
+                manifest.chunk_hashes.len()
+            );
+            return Ok(manifest);
+        }
+    }
+
+    fn read_next_chunk(&mut self) -> Result<()> {
+        let mut bytes = vec![0; read_record_len(&mut self.input)?];
+        self.input.read_exact(&mut bytes)?;
+        ensure!(
+            HashValue::sha3_256_of(&bytes) == self.manifest.chunk_hashes[self.next_chunk_idx],
+            "Chunk {} in {:?} doesn't match the manifest.",
+            self.next_chunk_idx,
+            self.path
+        );
+        self.next_chunk_idx += 1;
+        match bcs::from_bytes(&bytes)? {
+            StateBackupRecord::Chunk(chunk) => {
+                self.chunk = chunk.into_iter();
+                Ok(())
+            },
+            StateBackupRecord::Manifest(_) => Err(AptosDbError::Other(format!(
+                "Found a manifest in place of a chunk in {:?}.",
+                self.path
+            ))),
+        }
+    }
+
+    fn check_order(
+        &mut self,
+        key: StateKey,
+        value: Option<StateValue>,
+    ) -> Result<(HashValue, StateKey, Option<StateValue>)> {
+        let key_hash = CryptoHash::hash(&key);
+        ensure!(
+            self.last_key_hash.map_or(true, |last| last < key_hash),
+            "Keys in {:?} are not ordered by key hash.",
+            self.path
+        );
+        self.last_key_hash = Some(key_hash);
+        Ok((key_hash, key, value))
+    }
+}
+
+impl Iterator for StateBackupReader {
+    type Item = Result<(HashValue, StateKey, Option<StateValue>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, value)) = self.chunk.next() {
+                return Some(self.check_order(key, value));
+            }
+            if self.next_chunk_idx == self.manifest.chunk_hashes.len() {
+                return None;
+            }
+            if let Err(err) = self.read_next_chunk() {
+                // Stop at the first error.
+                self.next_chunk_idx = self.manifest.chunk_hashes.len();
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+/// Applies the changes read from `delta` on top of the full state read from `base`, yielding the
+/// resulting state ordered by key hash.
+pub(crate) fn apply_state_changes(
+    base: StateBackupReader,
+    delta: StateBackupReader,
+) -> impl Iterator<Item = Result<(StateKey, StateValue)>> {
+    MergedState {
+        base: base.peekable(),
+        delta: delta.peekable(),
+    }
+}
+
+struct MergedState {
+    base: Peekable<StateBackupReader>,
+    delta: Peekable<StateBackupReader>,
+}
+
+impl Iterator for MergedState {
+    type Item = Result<(StateKey, StateValue)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ordering = match (self.base.peek(), self.delta.peek()) {
+                (Some(Ok((base_hash, ..))), Some(Ok((delta_hash, ..)))) => {
+                    base_hash.cmp(delta_hash)
+                },
+                (Some(_), None) | (Some(Err(_)), Some(_)) => Ordering::Less,
+                (None, Some(_)) | (Some(Ok(_)), Some(Err(_))) => Ordering::Greater,
+                (None, None) => return None,
+            };
+            match ordering {
+                Ordering::Less => {
+                    return self.base.next().map(|res| {
+                        let (_, key, value) = res?;
+                        let value = value.ok_or_else(|| {
+                            AptosDbError::Other(format!(
+                                "Deleted key {:?} found in a full state snapshot.",
+                                key
+                            ))
+                        })?;
+                        Ok((key, value))
+                    });
+                },
+                // The value in the delta replaces the one in the base.
+                Ordering::Equal => {
+                    self.base.next();
+                },
+                Ordering::Greater => (),
+            }
+            match self.delta.next().expect("Peeked a change.") {
+                Ok((_, key, Some(value))) => return Some(Ok((key, value))),
+                Ok((_, _, None)) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod backup_handler;
+pub mod incremental_backup;
 pub mod restore_handler;
 pub mod restore_utils;
 
@@ -60,7 +60,9 @@ impl RestoreHandler {
     }
 
     pub fn save_ledger_infos(&self, ledger_infos: &[LedgerInfoWithSignatures]) -> Result<()> {
-        restore_utils::save_ledger_infos(self.aptosdb.ledger_db.metadata_db(), ledger_infos, None)
+        restore_utils::save_ledger_infos(self.aptosdb.ledger_db.metadata_db(), ledger_infos, None)?;
+        self.aptosdb.state_proof_cache.invalidate();
+        Ok(())
     }
 
     pub fn confirm_or_save_frozen_subtrees(
@@ -2,14 +2,21 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::db::{
-    test_helper::{arb_blocks_to_commit, update_in_memory_state},
-    AptosDB,
+use crate::{
+    backup::incremental_backup::StateBackupReader,
+    db::{
+        test_helper::{
+            arb_blocks_to_commit, arb_blocks_to_commit_with_block_nums, update_in_memory_state,
+        },
+        AptosDB,
+    },
 };
 use anyhow::Result;
+use aptos_storage_interface::DbReader;
 use aptos_temppath::TempPath;
 use aptos_types::transaction::Version;
 use proptest::prelude::*;
+use std::fs::File;
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -72,4 +79,84 @@ proptest! {
             .unwrap();
         prop_assert_eq!(&non_existent, &[]);
     }
+
+    #[test]
+    fn test_incremental_backup((input, _) in arb_blocks_to_commit_with_block_nums(2, 10)) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let mut in_memory_state = db.state_store.buffered_state().lock().current_state().clone();
+        let mut cur_ver: Version = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+            update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+            db.save_transactions_for_test(
+                txns_to_commit,
+                cur_ver,
+                cur_ver.checked_sub(1),
+                Some(ledger_info_with_sigs),
+                true, // sync commit
+                in_memory_state.clone(),
+            )
+            .unwrap();
+            cur_ver += txns_to_commit.len() as u64;
+        }
+        let (target_version, target_root_hash) =
+            db.get_state_snapshot_before(Version::MAX).unwrap().unwrap();
+        let (base_version, _) = db.get_state_snapshot_before(target_version).unwrap().unwrap();
+
+        let bh = db.get_backup_handler();
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let base_path = backup_dir.path().join("base");
+        let incremental_path = backup_dir.path().join("incremental");
+        let full_path = backup_dir.path().join("full");
+        bh.create_state_snapshot_backup(base_version, &mut File::create(&base_path).unwrap())
+            .unwrap();
+        let manifest = bh
+            .create_incremental_backup(
+                base_version,
+                target_version,
+                &mut File::create(&incremental_path).unwrap(),
+            )
+            .unwrap();
+        prop_assert_eq!(manifest.base_version, Some(base_version));
+        prop_assert_eq!(manifest.target_version, target_version);
+        prop_assert_eq!(manifest.root_hash, target_root_hash);
+        let full_manifest = bh
+            .create_state_snapshot_backup(target_version, &mut File::create(&full_path).unwrap())
+            .unwrap();
+        prop_assert_eq!(full_manifest.root_hash, target_root_hash);
+        prop_assert_eq!(&manifest.chunk_proofs, &full_manifest.chunk_proofs);
+
+        let restore_dir = TempPath::new();
+        let restored_db = AptosDB::new_for_test(&restore_dir);
+        let restored_bh = restored_db.get_backup_handler();
+
+        // The base snapshot and the incremental backup can't be swapped.
+        prop_assert!(restored_bh
+            .restore_incremental_backup(&incremental_path, &base_path)
+            .is_err());
+        // A corrupted chunk is rejected.
+        let corrupted_path = backup_dir.path().join("corrupted");
+        let mut corrupted = std::fs::read(&base_path).unwrap();
+        corrupted[5] ^= 0xff;
+        std::fs::write(&corrupted_path, corrupted).unwrap();
+        prop_assert!(restored_bh
+            .restore_incremental_backup(&corrupted_path, &incremental_path)
+            .is_err());
+
+        restored_bh
+            .restore_incremental_backup(&base_path, &incremental_path)
+            .unwrap();
+        let restored = restored_bh
+            .get_account_iter(target_version)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let full = StateBackupReader::open(&full_path)
+            .unwrap()
+            .map(|res| res.map(|(_, key, value)| (key, value.unwrap())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        prop_assert_eq!(restored, full);
+    }
 }
@@ -0,0 +1,97 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::{AptosDB, IntegrityCheckOptions};
+use aptos_storage_interface::DbReader;
+use aptos_types::transaction::Version;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(
+    name = "aptos-db-integrity-check",
+    about = "Verify that the transactions, the transaction accumulator and the state Merkle tree in a DB are consistent."
+)]
+pub struct Args {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long)]
+    enable_storage_sharding: bool,
+
+    #[clap(long)]
+    start_version: Version,
+
+    /// Defaults to the latest version in the DB.
+    #[clap(long)]
+    end_version: Option<Version>,
+
+    #[clap(long)]
+    skip_accumulator: bool,
+
+    #[clap(long)]
+    skip_state_merkle: bool,
+
+    #[clap(long)]
+    skip_event_indices: bool,
+
+    #[clap(long)]
+    skip_write_set_hashes: bool,
+
+    #[clap(long, default_value_t = IntegrityCheckOptions::default().state_merkle_sample_size)]
+    state_merkle_sample_size: usize,
+}
+
+fn main() -> Result<()> {
+    aptos_logger::Logger::new().init();
+    let args = Args::parse();
+
+    let db = AptosDB::open(
+        StorageDirPaths::from_path(&args.db_dir),
+        true,                        /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+        RocksdbConfigs {
+            enable_storage_sharding: args.enable_storage_sharding,
+            ..Default::default()
+        },
+        false, /* indexer */
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )?;
+    let end_version = match args.end_version {
+        Some(version) => version,
+        None => db.get_latest_version()?,
+    };
+    let options = IntegrityCheckOptions {
+        verify_accumulator: !args.skip_accumulator,
+        verify_state_merkle: !args.skip_state_merkle,
+        verify_event_indices: !args.skip_event_indices,
+        verify_write_set_hashes: !args.skip_write_set_hashes,
+        state_merkle_sample_size: args.state_merkle_sample_size,
+    };
+
+    let report = db.verify_ledger_integrity(args.start_version, end_version, options)?;
+    for violation in &report.violations {
+        println!("{:?}", violation);
+    }
+    println!(
+        "Checked versions [{}, {}] and {} state leaves, found {} violations.",
+        report.start_version,
+        report.end_version,
+        report.num_state_leaves_checked,
+        report.violations.len(),
+    );
+    ensure!(report.is_consistent(), "DB is inconsistent.");
+    Ok(())
+}
+
+#[test]
+fn verify_tool() {
+    use clap::CommandFactory;
+    Args::command().debug_assert()
+}
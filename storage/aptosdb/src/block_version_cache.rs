@@ -0,0 +1,41 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_infallible::Mutex;
+use aptos_types::{account_config::NewBlockEvent, transaction::Version};
+use lru::LruCache;
+use std::fmt;
+
+pub(crate) const BLOCK_VERSION_CACHE_CAPACITY: usize = 4096;
+
+/// Caches the (first version, last version, NewBlockEvent) of the block containing a version.
+/// Only blocks followed by another committed block are cached, as the last one can still grow.
+pub(crate) struct BlockVersionCache {
+    cache: Mutex<LruCache<Version, (Version, Version, NewBlockEvent)>>,
+}
+
+impl fmt::Debug for BlockVersionCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "BlockVersionCache with {} entries.",
+            self.cache.lock().len()
+        )
+    }
+}
+
+impl BlockVersionCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub fn get(&self, version: Version) -> Option<(Version, Version, NewBlockEvent)> {
+        self.cache.lock().get(&version).cloned()
+    }
+
+    pub fn put(&self, version: Version, block_info: (Version, Version, NewBlockEvent)) {
+        self.cache.lock().put(version, block_info);
+    }
+}
@@ -2,32 +2,72 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    common::MAX_NUM_EPOCH_ENDING_LEDGER_INFO,
     db::{
-        get_first_seq_num_and_limit, test_helper,
-        test_helper::{arb_blocks_to_commit, put_as_state_root, put_transaction_infos},
-        AptosDB,
+        get_first_seq_num_and_limit,
+        integrity_check::{IntegrityCheckOptions, IntegrityViolation},
+        test_helper,
+        test_helper::{
+            arb_blocks_to_commit, arb_blocks_to_commit_with_block_nums, put_as_state_root,
+            put_transaction_infos,
+        },
+        AptosDB, CommitValidationIssue, RecoveryReport,
+    },
+    pruner::{LedgerPrunerManager, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager},
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema,
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        stale_node_index::StaleNodeIndexSchema,
+        stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
+        stale_state_value_index::StaleStateValueIndexSchema,
+        state_value::StateValueSchema,
+        transaction::TransactionSchema,
+        transaction_accumulator::TransactionAccumulatorSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+        transaction_info::TransactionInfoSchema,
+        version_data::VersionDataSchema,
+        write_set::WriteSetSchema,
     },
-    pruner::{LedgerPrunerManager, PrunerManager, StateMerklePrunerManager},
-    schema::stale_node_index::StaleNodeIndexSchema,
+    utils::truncation_helper::get_state_kv_commit_progress,
 };
 use aptos_config::config::{
     EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, RocksdbConfigs,
-    StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
-    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    StateMerklePrunerConfig, StorageDirPaths, WalConfig, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
-use aptos_storage_interface::{DbReader, ExecutedTrees, Order};
+use aptos_jellyfish_merkle::node_type::Node;
+use aptos_schemadb::{schema::Schema, ReadOptions, SchemaBatch, DB};
+use aptos_storage_interface::{DbReader, ExecutedTrees, Order, Result, MAX_REQUEST_LIMIT};
 use aptos_temppath::TempPath;
 use aptos_types::{
-    ledger_info::LedgerInfoWithSignatures,
-    proof::SparseMerkleLeafNode,
+    account_address::AccountAddress,
+    account_config::events::new_block::{new_block_event_key, NewBlockEvent},
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    contract_event::{ContractEvent, EventWithVersion},
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{position::Position, SparseMerkleLeafNode},
     state_store::{
-        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        errors::StateviewError,
+        state_key::StateKey,
+        state_storage_usage::{StateStorageUsage, StateStorageUsageDelta},
+        state_value::StateValue,
+        TStateView,
     },
     transaction::{ExecutionStatus, TransactionInfo, TransactionToCommit, Version},
+    write_set::WriteSet,
 };
+use move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
 use proptest::prelude::*;
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use test_helper::{test_save_blocks_impl, test_sync_transactions_impl};
 
 proptest! {
@@ -42,6 +82,50 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit(), threshold in 10..20usize) {
         test_sync_transactions_impl(input, threshold);
     }
+
+    #[test]
+    fn test_replica_catches_up_with_primary(input in arb_blocks_to_commit()) {
+        test_replica_catch_up_impl(input);
+    }
+
+    #[test]
+    fn test_recover_interrupted_commit(
+        (input, _) in arb_blocks_to_commit_with_block_nums(2, 10),
+    ) {
+        test_recover_interrupted_commit_impl(input);
+    }
+
+    #[test]
+    fn test_recover_from_wal(
+        (input, _) in arb_blocks_to_commit_with_block_nums(2, 10),
+    ) {
+        test_recover_from_wal_impl(input);
+    }
+
+    #[test]
+    fn test_deferred_index(input in arb_blocks_to_commit()) {
+        test_deferred_index_impl(input);
+    }
+
+    #[test]
+    fn test_prune_to_version_blocking(input in arb_blocks_to_commit()) {
+        test_prune_to_version_blocking_impl(input);
+    }
+
+    #[test]
+    fn test_get_write_set_and_events(input in arb_blocks_to_commit()) {
+        test_get_write_set_and_events_impl(input);
+    }
+
+    #[test]
+    fn test_state_proof_cache(input in arb_blocks_to_commit()) {
+        test_state_proof_cache_impl(input);
+    }
+
+    #[test]
+    fn test_historical_state_view(input in arb_blocks_to_commit()) {
+        test_historical_state_view_impl(input);
+    }
 }
 
 #[test]
@@ -77,6 +161,25 @@ fn test_get_first_seq_num_and_limit() {
     );
 }
 
+#[test]
+fn test_diagnose_next_commit() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    assert!(db.diagnose_next_commit(0, None).is_empty());
+    // Both the base state version and the first version are off.
+    assert_eq!(db.diagnose_next_commit(5, Some(3)), vec![
+        CommitValidationIssue::BaseVersionMismatch {
+            base_state_version: Some(3),
+            buffered_base_version: None,
+        },
+        CommitValidationIssue::FirstVersionNotNext {
+            first_version: 5,
+            next_version_in_db: 0,
+        },
+    ]);
+}
+
 #[test]
 fn test_too_many_requested() {
     let tmp_dir = TempPath::new();
@@ -140,6 +243,123 @@ fn test_error_if_version_pruned() {
     assert!(db.error_if_ledger_pruned("Transaction", 10).is_ok());
 }
 
+#[test]
+fn test_get_epoch_ending_ledger_info_iter() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let num_epochs = 2 * MAX_NUM_EPOCH_ENDING_LEDGER_INFO as u64 + 1;
+    let lis: Vec<_> = (0..num_epochs)
+        .map(|epoch| {
+            LedgerInfoWithSignatures::new(
+                LedgerInfo::new(
+                    BlockInfo::new(
+                        epoch,
+                        0,
+                        HashValue::zero(),
+                        HashValue::zero(),
+                        epoch,
+                        epoch,
+                        Some(EpochState::empty()),
+                    ),
+                    HashValue::zero(),
+                ),
+                AggregateSignature::empty(),
+            )
+        })
+        .collect();
+    let batch = SchemaBatch::new();
+    for li in &lis {
+        db.ledger_db
+            .metadata_db()
+            .put_ledger_info(li, &batch)
+            .unwrap();
+    }
+    db.ledger_db.metadata_db().write_schemas(batch).unwrap();
+    db.ledger_db
+        .metadata_db()
+        .set_latest_ledger_info(lis.last().unwrap().clone());
+
+    // A single call is capped.
+    let (page, more) = db.get_epoch_ending_ledger_infos(0, num_epochs).unwrap();
+    assert_eq!(page.len(), MAX_NUM_EPOCH_ENDING_LEDGER_INFO);
+    assert!(more);
+
+    let iterated = db
+        .get_epoch_ending_ledger_info_iter(0, num_epochs)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(iterated, lis);
+    let iterated = db
+        .get_epoch_ending_ledger_info_iter(5, num_epochs - 5)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(iterated, lis[5..lis.len() - 5]);
+    assert_eq!(
+        db.get_epoch_ending_ledger_info_iter(3, 3).unwrap().count(),
+        0
+    );
+
+    // The epoch range is still validated.
+    assert!(db.get_epoch_ending_ledger_info_iter(3, 2).is_err());
+    assert!(db
+        .get_epoch_ending_ledger_info_iter(0, num_epochs + 1)
+        .is_err());
+}
+
+#[test]
+fn test_get_state_storage_usage_delta() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let key_0 = StateKey::raw(b"key_0".to_vec());
+    let key_1 = StateKey::raw(b"key_1".to_vec());
+    let value = |len: usize| StateValue::from(vec![0u8; len]);
+    test_helper::update_store(
+        &db.state_store,
+        vec![
+            (key_0.clone(), Some(value(10))),
+            (key_1.clone(), Some(value(20))),
+            (key_0.clone(), Some(value(30))),
+            (key_1.clone(), None),
+        ]
+        .into_iter(),
+        0,
+    );
+    let size = |key: &StateKey, len: usize| (key.size() + value(len).size()) as i64;
+
+    assert_eq!(
+        db.get_state_storage_usage_delta(0, 0).unwrap(),
+        StateStorageUsageDelta::default()
+    );
+    assert_eq!(
+        db.get_state_storage_usage_delta(0, 1).unwrap(),
+        StateStorageUsageDelta {
+            items: 1,
+            bytes: size(&key_1, 20),
+        }
+    );
+    assert_eq!(
+        db.get_state_storage_usage_delta(1, 2).unwrap(),
+        StateStorageUsageDelta {
+            items: 0,
+            bytes: 20,
+        }
+    );
+    assert_eq!(
+        db.get_state_storage_usage_delta(1, 3).unwrap(),
+        StateStorageUsageDelta {
+            items: -1,
+            bytes: 20 - size(&key_1, 20),
+        }
+    );
+    assert!(db.get_state_storage_usage_delta(2, 1).is_err());
+    // No usage at a version that is not committed.
+    assert!(db.get_state_storage_usage_delta(0, 4).is_err());
+}
+
 #[test]
 fn test_get_latest_executed_trees() {
     let tmp_dir = TempPath::new();
@@ -290,4 +510,1189 @@ proptest! {
         aptos_logger::Logger::new().init();
         test_state_merkle_pruning_impl(input);
     }
+
+    #[test]
+    fn test_dry_run_pruning(input in arb_blocks_to_commit()) {
+        test_dry_run_pruning_impl(input);
+    }
+
+    #[test]
+    fn test_verify_ledger_integrity(input in arb_blocks_to_commit()) {
+        test_verify_ledger_integrity_impl(input);
+    }
+
+    #[test]
+    fn test_get_accumulator_root_hashes(input in arb_blocks_to_commit()) {
+        test_get_accumulator_root_hashes_impl(input);
+    }
+
+    #[test]
+    fn test_is_state_checkpoint_version(input in arb_blocks_to_commit()) {
+        test_is_state_checkpoint_version_impl(input);
+    }
+}
+
+fn test_replica_catch_up_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let secondary_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let stale_read_tolerance = Duration::from_millis(100);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    let mut replica = None;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+
+        // open the replica once the primary is bootstrapped
+        let replica = replica.get_or_insert_with(|| {
+            AptosDB::open_replica(
+                StorageDirPaths::from_path(&tmp_dir),
+                stale_read_tolerance,
+                RocksdbConfigs::default(),
+                &secondary_dir,
+                BUFFERED_STATE_TARGET_ITEMS,
+                DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            )
+            .unwrap()
+        });
+
+        while replica.get_latest_ledger_info_option().unwrap().as_ref()
+            != Some(ledger_info_with_sigs)
+        {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(replica.get_latest_version().unwrap(), next_ver - 1);
+        assert_eq!(
+            replica
+                .state_store
+                .buffered_state()
+                .lock()
+                .current_state()
+                .current_version,
+            Some(next_ver - 1)
+        );
+        let last_txn = txns_to_commit.last().unwrap();
+        assert_eq!(
+            replica
+                .get_transaction_by_version(next_ver - 1, next_ver - 1, false)
+                .unwrap()
+                .transaction,
+            last_txn.transaction().clone()
+        );
+        assert_eq!(replica.replica_lag().unwrap(), Duration::ZERO);
+    }
+}
+
+fn test_recover_interrupted_commit_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let ((txns_to_crash, _), blocks_to_commit) = input.split_last().unwrap();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in blocks_to_commit {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+    let committed_version = next_ver - 1;
+
+    // Write the data of the last block but crash before committing its ledger info.
+    test_helper::update_in_memory_state(&mut in_memory_state, txns_to_crash.as_slice());
+    let crashed_version = next_ver + txns_to_crash.len() as u64 - 1;
+    db.calculate_and_commit_ledger_and_state_kv(
+        txns_to_crash,
+        next_ver,
+        in_memory_state.current.usage(),
+        None,  /* sharded_state_cache */
+        false, /* skip_index_and_usage */
+    )
+    .unwrap();
+    assert_eq!(
+        db.ledger_db
+            .metadata_db()
+            .get_ledger_commit_progress()
+            .unwrap(),
+        crashed_version
+    );
+    drop(db);
+
+    let (ledger_db, _state_merkle_db, state_kv_db) = AptosDB::open_dbs(
+        &StorageDirPaths::from_path(tmp_dir.path()),
+        RocksdbConfigs::default(),
+        /*readonly=*/ false,
+        /*max_num_nodes_per_lru_cache_shard=*/ 0,
+    )
+    .unwrap();
+    let ledger_db = Arc::new(ledger_db);
+    let state_kv_db = Arc::new(state_kv_db);
+    let report = AptosDB::recover_interrupted_commit(&ledger_db, &state_kv_db).unwrap();
+    assert_eq!(report, RecoveryReport {
+        interrupted_version: Some(crashed_version),
+        recovered_version: Some(committed_version),
+        ledger_db_rolled_back: true,
+        state_kv_db_rolled_back: true,
+    });
+    assert_eq!(
+        ledger_db
+            .metadata_db()
+            .get_ledger_commit_progress()
+            .unwrap(),
+        committed_version
+    );
+    assert_eq!(
+        get_state_kv_commit_progress(&state_kv_db).unwrap(),
+        Some(committed_version)
+    );
+    drop(ledger_db);
+    drop(state_kv_db);
+
+    let db = AptosDB::new_for_test(&tmp_dir);
+    assert!(db.check_and_recover_consistency().unwrap().is_clean());
+    assert_eq!(db.get_latest_version().unwrap(), committed_version);
+    assert!(db
+        .get_transaction_by_version(crashed_version, committed_version, false)
+        .is_err());
+}
+
+fn open_db_with_wal(tmp_dir: &TempPath) -> AptosDB {
+    AptosDB::open_with_wal(
+        StorageDirPaths::from_path(tmp_dir.path()),
+        /*readonly=*/ false,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        /*enable_indexer=*/ false,
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        &WalConfig {
+            enabled: true,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn test_recover_from_wal_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = open_db_with_wal(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let ((txns_to_crash, li_to_crash), blocks_to_commit) = input.split_last().unwrap();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in blocks_to_commit {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+        assert!(db.wal.as_ref().unwrap().is_empty().unwrap());
+    }
+
+    // Log the last block and write part of it, but crash before committing its ledger info.
+    let crashed_version = next_ver + txns_to_crash.len() as u64 - 1;
+    db.wal
+        .as_ref()
+        .unwrap()
+        .append(next_ver, Some(li_to_crash), txns_to_crash)
+        .unwrap();
+    db.calculate_and_commit_ledger_and_state_kv(
+        txns_to_crash,
+        next_ver,
+        in_memory_state.current.usage(),
+        None,  /* sharded_state_cache */
+        false, /* skip_index_and_usage */
+    )
+    .unwrap();
+    drop(db);
+
+    // The partial writes are rolled back, and the block is re-applied from the WAL, which brings
+    // the DB to where it would have been without the crash.
+    let db = open_db_with_wal(&tmp_dir);
+    assert_eq!(db.get_latest_version().unwrap(), crashed_version);
+    assert_eq!(&db.get_latest_ledger_info().unwrap(), li_to_crash);
+    for (version, txn) in (next_ver..).zip(txns_to_crash) {
+        assert_eq!(
+            db.get_transaction_by_version(version, crashed_version, false)
+                .unwrap()
+                .transaction,
+            txn.transaction().clone()
+        );
+    }
+    test_helper::update_in_memory_state(&mut in_memory_state, txns_to_crash.as_slice());
+    let current_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    assert_eq!(current_state.current_version, Some(crashed_version));
+    assert_eq!(
+        current_state.current.root_hash(),
+        in_memory_state.current.root_hash()
+    );
+    assert!(db.wal.as_ref().unwrap().is_empty().unwrap());
+    assert!(db.recover_from_wal().unwrap().is_empty());
+
+    // An entry left behind by a failed truncation after its commit is skipped, and cleared.
+    db.wal
+        .as_ref()
+        .unwrap()
+        .append(next_ver, Some(li_to_crash), txns_to_crash)
+        .unwrap();
+    drop(db);
+    let db = open_db_with_wal(&tmp_dir);
+    assert_eq!(db.get_latest_version().unwrap(), crashed_version);
+    assert!(db.wal.as_ref().unwrap().is_empty().unwrap());
+}
+
+fn open_db_with_deferred_index(tmp_dir: &TempPath, deferred_index_mode: bool) -> AptosDB {
+    AptosDB::open_with_deferred_index(
+        StorageDirPaths::from_path(tmp_dir.path()),
+        /*readonly=*/ false,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        /*enable_indexer=*/ false,
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        deferred_index_mode,
+    )
+    .unwrap()
+}
+
+fn verify_blocks(db: &AptosDB, input: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)]) {
+    let mut cur_ver: Version = 0;
+    for (batch_idx, (txns_to_commit, ledger_info_with_sigs)) in input.iter().enumerate() {
+        test_helper::verify_committed_transactions(
+            db,
+            txns_to_commit,
+            cur_ver,
+            ledger_info_with_sigs,
+            batch_idx + 1 == input.len(), /* is_latest */
+        );
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+fn test_deferred_index_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = open_db_with_deferred_index(&tmp_dir, true);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+
+    // Everything is indexed once the queue is flushed.
+    db.flush_index_queue().unwrap();
+    assert_eq!(
+        db.index_update_queue
+            .as_ref()
+            .unwrap()
+            .index_ready_version(),
+        next_ver
+    );
+    assert_eq!(
+        db.ledger_db
+            .metadata_db()
+            .get_index_commit_progress()
+            .unwrap(),
+        Some(next_ver - 1)
+    );
+    verify_blocks(&db, &input);
+
+    // Pretend the indices were left behind by a crash, they are written again on the next open,
+    // which goes back to writing the indices in the commits.
+    db.ledger_db
+        .metadata_db()
+        .write_index_commit_progress(0)
+        .unwrap();
+    drop(db);
+    let db = open_db_with_deferred_index(&tmp_dir, false);
+    assert!(db.index_update_queue.is_none());
+    assert_eq!(
+        db.ledger_db
+            .metadata_db()
+            .get_index_commit_progress()
+            .unwrap(),
+        None
+    );
+    verify_blocks(&db, &input);
+}
+
+fn test_prune_to_version_blocking_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    // The prune window is never reached, so nothing is pruned unless asked for.
+    let ledger_pruner_config = LedgerPrunerConfig {
+        enable: true,
+        prune_window: 1_000_000,
+        batch_size: 3,
+        user_pruning_window_offset: 0,
+    };
+    let db = AptosDB::open(
+        StorageDirPaths::from_path(tmp_dir.path()),
+        /*readonly=*/ false,
+        PrunerConfig {
+            ledger_pruner_config,
+            ..NO_OP_STORAGE_PRUNER_CONFIG
+        },
+        RocksdbConfigs::default(),
+        /*enable_indexer=*/ false,
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )
+    .unwrap();
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = next_ver - 1;
+    assert_eq!(db.get_first_txn_version().unwrap(), Some(0));
+
+    assert!(db
+        .prune_to_version_blocking(latest_version + 1, Duration::from_secs(60))
+        .is_err());
+
+    let target_version = latest_version / 2;
+    db.prune_to_version_blocking(target_version, Duration::from_secs(60))
+        .unwrap();
+    assert_eq!(db.get_first_txn_version().unwrap(), Some(target_version));
+    assert!(!db.ledger_pruner.is_pruning_pending());
+    assert!(!db.state_store.state_kv_pruner.is_pruning_pending());
+    if let Some(pruned_version) = target_version.checked_sub(1) {
+        assert!(db
+            .get_transaction_by_version(pruned_version, latest_version, false)
+            .is_err());
+    }
+    db.get_transaction_by_version(target_version, latest_version, false)
+        .unwrap();
+
+    // Going backwards is a no-op.
+    db.prune_to_version_blocking(0, Duration::from_secs(60))
+        .unwrap();
+    assert_eq!(db.get_first_txn_version().unwrap(), Some(target_version));
+}
+
+fn test_get_write_set_and_events_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = next_ver - 1;
+
+    for version in 0..=latest_version {
+        let (write_set, events) = db.get_write_set_and_events(version).unwrap();
+        let outputs = db
+            .get_transaction_outputs(version, 1, latest_version)
+            .unwrap();
+        let (_, output) = &outputs.transactions_and_outputs[0];
+        assert_eq!(&write_set, output.write_set());
+        assert_eq!(&events, output.events());
+    }
+    assert!(db.get_write_set_and_events(latest_version + 1).is_err());
+}
+
+fn test_state_proof_cache_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    // Long enough for the entries not to expire during the test.
+    let db = AptosDB::new_for_test(&tmp_dir).with_state_proof_cache_ttl(Duration::from_secs(600));
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (i, (txns_to_commit, ledger_info_with_sigs)) in input.iter().enumerate() {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+
+        // A steady state client asking for the same known version over and over.
+        let stats_before = db.state_proof_cache_stats();
+        let expected = db
+            .get_state_proof_with_ledger_info(0, ledger_info_with_sigs.clone())
+            .unwrap();
+        for _ in 0..100 {
+            assert_eq!(db.get_state_proof(0).unwrap(), expected);
+        }
+        let stats = db.state_proof_cache_stats();
+        assert_eq!(stats.misses - stats_before.misses, 1);
+        assert_eq!(stats.hits - stats_before.hits, 99);
+        // Committing the previous ledger info evicted the entry cached for it.
+        if i > 0 {
+            assert_eq!(stats.evictions - stats_before.evictions, 1);
+        }
+    }
+
+    // Entries expire after the TTL.
+    let db = db.with_state_proof_cache_ttl(Duration::ZERO);
+    db.get_state_proof(0).unwrap();
+    db.get_state_proof(0).unwrap();
+    let stats = db.state_proof_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (0, 2, 1));
+}
+
+fn test_historical_state_view_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    // The prune window is never reached, so nothing is pruned unless asked for.
+    let ledger_pruner_config = LedgerPrunerConfig {
+        enable: true,
+        prune_window: 1_000_000,
+        batch_size: 3,
+        user_pruning_window_offset: 0,
+    };
+    let db = AptosDB::open(
+        StorageDirPaths::from_path(tmp_dir.path()),
+        /*readonly=*/ false,
+        PrunerConfig {
+            ledger_pruner_config,
+            ..NO_OP_STORAGE_PRUNER_CONFIG
+        },
+        RocksdbConfigs::default(),
+        /*enable_indexer=*/ false,
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )
+    .unwrap();
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    let mut state_keys = HashSet::new();
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+        for txn_to_commit in txns_to_commit {
+            state_keys.extend(
+                txn_to_commit
+                    .state_updates()
+                    .iter()
+                    .flatten()
+                    .map(|(key, _)| key.clone()),
+            );
+        }
+    }
+    let latest_version = next_ver - 1;
+    assert!(db.get_historical_state_view(latest_version + 1).is_err());
+
+    // Before pruning, the view reads the same values as `get_state_value_by_version`.
+    for version in 0..=latest_version {
+        let view = db.get_historical_state_view(version).unwrap();
+        assert_eq!(view.version(), version);
+        for state_key in &state_keys {
+            assert!(!view.is_pruned(state_key).unwrap());
+            assert_eq!(
+                view.get_state_value(state_key).unwrap(),
+                db.get_state_value_by_version(state_key, version).unwrap()
+            );
+        }
+    }
+
+    let target_version = latest_version / 2;
+    db.prune_to_version_blocking(target_version, Duration::from_secs(60))
+        .unwrap();
+    for version in 0..=latest_version {
+        let view = db.get_historical_state_view(version).unwrap();
+        for state_key in &state_keys {
+            let value = db.get_state_value_by_version(state_key, version).unwrap();
+            if view.is_pruned(state_key).unwrap() {
+                assert!(version < target_version);
+                assert!(value.is_none());
+                assert!(matches!(
+                    view.get_state_value(state_key),
+                    Err(StateviewError::Other(msg)) if msg.contains("is pruned")
+                ));
+            } else {
+                assert_eq!(view.get_state_value(state_key).unwrap(), value);
+            }
+        }
+    }
+}
+
+fn num_keys<S: Schema>(db: &DB) -> usize {
+    let mut iter = db.iter::<S>(ReadOptions::default()).unwrap();
+    iter.seek_to_first();
+    iter.count()
+}
+
+/// Counts the keys in all the column families the pruners delete from.
+fn num_prunable_keys(db: &AptosDB) -> usize {
+    let ledger_db = &db.ledger_db;
+    let state_kv_db = db.state_kv_db.metadata_db();
+    let state_merkle_db = db.state_merkle_db();
+    let state_merkle_db = state_merkle_db.metadata_db();
+
+    num_keys::<VersionDataSchema>(&ledger_db.metadata_db_arc())
+        + num_keys::<EventSchema>(ledger_db.event_db_raw())
+        + num_keys::<EventByKeySchema>(ledger_db.event_db_raw())
+        + num_keys::<EventByVersionSchema>(ledger_db.event_db_raw())
+        + num_keys::<EventByTypeSchema>(ledger_db.event_db_raw())
+        + num_keys::<EventAccumulatorSchema>(ledger_db.event_db_raw())
+        + num_keys::<TransactionAccumulatorSchema>(ledger_db.transaction_accumulator_db_raw())
+        + num_keys::<TransactionInfoSchema>(ledger_db.transaction_info_db_raw())
+        + num_keys::<TransactionSchema>(ledger_db.transaction_db_raw())
+        + num_keys::<TransactionByHashSchema>(ledger_db.transaction_db_raw())
+        + num_keys::<TransactionByAccountSchema>(ledger_db.transaction_db_raw())
+        + num_keys::<WriteSetSchema>(ledger_db.write_set_db_raw())
+        + num_keys::<StateValueSchema>(state_kv_db)
+        + num_keys::<StaleStateValueIndexSchema>(state_kv_db)
+        + num_keys::<JellyfishMerkleNodeSchema>(state_merkle_db)
+        + num_keys::<StaleNodeIndexSchema>(state_merkle_db)
+        + num_keys::<StaleNodeIndexCrossEpochSchema>(state_merkle_db)
+}
+
+fn test_dry_run_pruning_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = next_ver - 1;
+
+    let pruner_config = PrunerConfig {
+        ledger_pruner_config: LedgerPrunerConfig {
+            enable: true,
+            prune_window: 2,
+            batch_size: 3,
+            user_pruning_window_offset: 0,
+        },
+        state_merkle_pruner_config: StateMerklePrunerConfig {
+            enable: true,
+            prune_window: 2,
+            batch_size: 1,
+        },
+        epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig {
+            enable: true,
+            prune_window: 5,
+            batch_size: 1,
+        },
+    };
+    let num_keys_before = num_prunable_keys(&db);
+    let report = db.dry_run_pruning(pruner_config).unwrap();
+    assert_eq!(num_prunable_keys(&db), num_keys_before);
+
+    let ledger_target_version =
+        latest_version.saturating_sub(pruner_config.ledger_pruner_config.prune_window);
+    let ledger_pruner = LedgerPrunerManager::new(
+        Arc::clone(&db.ledger_db),
+        pruner_config.ledger_pruner_config,
+    );
+    ledger_pruner.set_worker_target_version(ledger_target_version);
+    ledger_pruner.wait_for_pruner().unwrap();
+    let state_kv_pruner = StateKvPrunerManager::new(
+        Arc::clone(&db.state_kv_db),
+        pruner_config.ledger_pruner_config,
+    );
+    state_kv_pruner.set_worker_target_version(ledger_target_version);
+    state_kv_pruner.wait_for_pruner().unwrap();
+
+    let (snapshot_version, _) = db
+        .state_store
+        .get_state_snapshot_before(Version::MAX)
+        .unwrap()
+        .unwrap();
+    let state_merkle_pruner = StateMerklePrunerManager::<StaleNodeIndexSchema>::new(
+        db.state_merkle_db(),
+        pruner_config.state_merkle_pruner_config,
+    );
+    state_merkle_pruner.set_worker_target_version(
+        snapshot_version.saturating_sub(pruner_config.state_merkle_pruner_config.prune_window),
+    );
+    state_merkle_pruner.wait_for_pruner().unwrap();
+    let epoch_snapshot_pruner = StateMerklePrunerManager::<StaleNodeIndexCrossEpochSchema>::new(
+        db.state_merkle_db(),
+        pruner_config.epoch_snapshot_pruner_config.into(),
+    );
+    epoch_snapshot_pruner.set_worker_target_version(
+        snapshot_version.saturating_sub(pruner_config.epoch_snapshot_pruner_config.prune_window),
+    );
+    epoch_snapshot_pruner.wait_for_pruner().unwrap();
+
+    assert_eq!(
+        (num_keys_before - num_prunable_keys(&db)) as u64,
+        report.estimated_keys_deleted
+    );
+    assert_eq!(
+        report.affected_column_families.is_empty(),
+        report.estimated_keys_deleted == 0
+    );
+}
+
+fn test_verify_ledger_integrity_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = next_ver - 1;
+    let txns_to_commit: Vec<_> = input.iter().flat_map(|(txns, _)| txns).collect();
+
+    let options = IntegrityCheckOptions::default();
+    let report = db
+        .verify_ledger_integrity(0, latest_version, options)
+        .unwrap();
+    assert!(report.is_consistent(), "{:?}", report.violations);
+
+    // Corrupt the accumulator leaf and the write set of the same version.
+    let corrupted_version = latest_version / 2;
+    db.ledger_db
+        .transaction_accumulator_db_raw()
+        .put::<TransactionAccumulatorSchema>(
+            &Position::from_leaf_index(corrupted_version),
+            &HashValue::random(),
+        )
+        .unwrap();
+    let corrupt_write_set =
+        CryptoHash::hash(txns_to_commit[corrupted_version as usize].write_set())
+            != CryptoHash::hash(&WriteSet::default());
+    if corrupt_write_set {
+        db.ledger_db
+            .write_set_db_raw()
+            .put::<WriteSetSchema>(&corrupted_version, &WriteSet::default())
+            .unwrap();
+    }
+
+    // Corrupt the first event found.
+    let event_version = txns_to_commit
+        .iter()
+        .position(|txn_to_commit| !txn_to_commit.events().is_empty())
+        .map(|idx| idx as Version);
+    if let Some(version) = event_version {
+        db.ledger_db
+            .event_db_raw()
+            .put::<EventSchema>(
+                &(version, 0),
+                &ContractEvent::new_v2(TypeTag::Bool, b"corrupted".to_vec()),
+            )
+            .unwrap();
+    }
+
+    // Corrupt the state value of the first tree leaf found.
+    let state_merkle_db = db.state_merkle_db();
+    let mut iter = state_merkle_db
+        .metadata_db()
+        .iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())
+        .unwrap();
+    iter.seek_to_first();
+    let leaf = iter.map(|res| res.unwrap().1).find_map(|node| match node {
+        Node::Leaf(leaf) => Some(leaf),
+        _ => None,
+    });
+    if let Some(leaf) = &leaf {
+        let (state_key, version) = leaf.value_index();
+        db.state_kv_db
+            .db_shard(state_key.get_shard_id())
+            .put::<StateValueSchema>(
+                &(state_key.clone(), *version),
+                &Some(StateValue::from(b"corrupted".to_vec())),
+            )
+            .unwrap();
+    }
+
+    let report = db
+        .verify_ledger_integrity(0, latest_version, options)
+        .unwrap();
+    assert!(report.violations.iter().any(|violation| matches!(
+        violation,
+        IntegrityViolation::AccumulatorLeafMismatch { version, .. } if *version == corrupted_version
+    )));
+    assert_eq!(
+        report.violations.iter().any(|violation| matches!(
+            violation,
+            IntegrityViolation::WriteSetHashMismatch { version, .. } if *version == corrupted_version
+        )),
+        corrupt_write_set
+    );
+    if let Some(event_version) = event_version {
+        assert!(report.violations.iter().any(|violation| matches!(
+            violation,
+            IntegrityViolation::EventRootHashMismatch { version, .. } if *version == event_version
+        )));
+    }
+    if let Some(leaf) = leaf {
+        assert!(report
+            .violations
+            .contains(&IntegrityViolation::StateValueHashMismatch {
+                version: leaf.value_index().1,
+                key_hash: leaf.account_key(),
+                expected: leaf.value_hash(),
+                actual: Some(CryptoHash::hash(&StateValue::from(b"corrupted".to_vec()))),
+            }));
+    }
+
+    // Only the selected checks run.
+    let report = db
+        .verify_ledger_integrity(0, latest_version, IntegrityCheckOptions {
+            verify_accumulator: false,
+            verify_state_merkle: false,
+            verify_event_indices: false,
+            verify_write_set_hashes: false,
+            ..options
+        })
+        .unwrap();
+    assert!(report.is_consistent());
+}
+
+fn test_get_accumulator_root_hashes_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = next_ver - 1;
+
+    // Out of order, with duplicates.
+    let versions: Vec<Version> = (0..=latest_version)
+        .rev()
+        .chain([0, latest_version, 0])
+        .collect();
+    let root_hashes = db.get_accumulator_root_hashes(&versions).unwrap();
+    assert_eq!(root_hashes.len(), versions.len());
+    for (version, root_hash) in versions.iter().zip(root_hashes) {
+        assert_eq!(root_hash, db.get_accumulator_root_hash(*version).unwrap());
+    }
+    for (_, ledger_info_with_sigs) in input.iter() {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        assert_eq!(
+            db.get_accumulator_root_hashes(&[ledger_info.version()])
+                .unwrap(),
+            vec![ledger_info.transaction_accumulator_hash()]
+        );
+    }
+
+    assert!(db.get_accumulator_root_hashes(&[]).unwrap().is_empty());
+}
+
+fn test_is_state_checkpoint_version_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+
+    let mut version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        // Each block ends with a checkpoint, the transactions before it are not checkpoints.
+        let checkpoint_version = ledger_info_with_sigs.ledger_info().version();
+        assert!(db.is_state_checkpoint_version(checkpoint_version).unwrap());
+        if checkpoint_version > version {
+            assert!(!db
+                .is_state_checkpoint_version(checkpoint_version - 1)
+                .unwrap());
+        }
+        for txn_to_commit in txns_to_commit {
+            assert_eq!(
+                db.is_state_checkpoint_version(version).unwrap(),
+                txn_to_commit.is_state_checkpoint()
+            );
+            version += 1;
+        }
+    }
+    assert!(db.is_state_checkpoint_version(next_ver).is_err());
+}
+
+fn put_new_block_events(db: &AptosDB, num_blocks: u64) {
+    let event_batch = SchemaBatch::new();
+    let metadata_batch = SchemaBatch::new();
+    for block_height in 0..num_blocks {
+        // Each block has 2 transactions.
+        let version = block_height * 2;
+        let new_block_event = NewBlockEvent::new(
+            AccountAddress::random(),
+            0,            /* epoch */
+            block_height, /* round */
+            block_height, /* height */
+            vec![],       /* previous_block_votes_bitvec */
+            AccountAddress::random(),
+            vec![], /* failed_proposer_indices */
+            block_height * 1000,
+        );
+        let event = ContractEvent::new_v1(
+            new_block_event_key(),
+            block_height,
+            TypeTag::from(NewBlockEvent::struct_tag()),
+            bcs::to_bytes(&new_block_event).unwrap(),
+        );
+        db.ledger_db
+            .event_db()
+            .put_events(
+                version,
+                &[event.clone()],
+                db.skip_index_and_usage,
+                &event_batch,
+            )
+            .unwrap();
+        if db.skip_index_and_usage {
+            db.ledger_db
+                .metadata_db()
+                .put_block_info(version, &event, &metadata_batch)
+                .unwrap();
+        }
+    }
+    metadata_batch
+        .put::<DbMetadataSchema>(
+            &DbMetadataKey::OverallCommitProgress,
+            &DbMetadataValue::Version(num_blocks * 2 - 1),
+        )
+        .unwrap();
+    db.ledger_db.event_db().write_schemas(event_batch).unwrap();
+    db.ledger_db
+        .metadata_db()
+        .write_schemas(metadata_batch)
+        .unwrap();
+}
+
+#[test]
+fn test_get_block_height_containing_version() {
+    for skip_index_and_usage in [false, true] {
+        let tmp_dir = TempPath::new();
+        let mut db = AptosDB::new_for_test(&tmp_dir);
+        db.skip_index_and_usage = skip_index_and_usage;
+        put_new_block_events(&db, 10);
+
+        // Block 3 spans versions 6 and 7.
+        assert_eq!(db.get_block_height_containing_version(6).unwrap(), 3);
+        assert_eq!(db.get_block_height_containing_version(7).unwrap(), 3);
+        assert_eq!(db.get_block_height_containing_version(8).unwrap(), 4);
+        assert_eq!(db.get_block_height_containing_version(19).unwrap(), 9);
+        assert!(db.get_block_height_containing_version(20).is_err());
+    }
+}
+
+#[test]
+fn test_get_block_info_by_version_cache() {
+    for skip_index_and_usage in [false, true] {
+        let tmp_dir = TempPath::new();
+        let mut db = AptosDB::new_for_test(&tmp_dir);
+        db.skip_index_and_usage = skip_index_and_usage;
+        put_new_block_events(&db, 10);
+        db.ledger_db
+            .metadata_db()
+            .set_latest_ledger_info(LedgerInfoWithSignatures::new(
+                LedgerInfo::new(
+                    BlockInfo::new(0, 0, HashValue::zero(), HashValue::zero(), 19, 0, None),
+                    HashValue::zero(),
+                ),
+                AggregateSignature::empty(),
+            ));
+
+        for _ in 0..2 {
+            for version in 0..20 {
+                let (first_version, last_version, new_block_event) =
+                    db.get_block_info_by_version(version).unwrap();
+                assert_eq!(new_block_event.height(), version / 2);
+                assert_eq!(first_version, version / 2 * 2);
+                assert_eq!(last_version, version / 2 * 2 + 1);
+            }
+        }
+        // The last block can still grow, so it's not cached.
+        for version in 0..18 {
+            assert!(db.block_version_cache.get(version).is_some());
+        }
+        assert!(db.block_version_cache.get(18).is_none());
+        assert!(db.block_version_cache.get(19).is_none());
+    }
+}
+
+#[test]
+fn test_get_latest_block_events_before() {
+    let block_heights = |events: Vec<EventWithVersion>| {
+        events
+            .into_iter()
+            .map(|event| {
+                let new_block_event: NewBlockEvent = (&event.event).try_into().unwrap();
+                new_block_event.height()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for skip_index_and_usage in [false, true] {
+        let tmp_dir = TempPath::new();
+        let mut db = AptosDB::new_for_test(&tmp_dir);
+        db.skip_index_and_usage = skip_index_and_usage;
+        put_new_block_events(&db, 10);
+
+        // Block 4 spans versions 8 and 9.
+        assert_eq!(
+            block_heights(db.get_latest_block_events_before(3, 9).unwrap()),
+            vec![4, 3, 2]
+        );
+        assert_eq!(
+            block_heights(db.get_latest_block_events_before(3, 8).unwrap()),
+            vec![4, 3, 2]
+        );
+        assert_eq!(
+            block_heights(db.get_latest_block_events_before(10, 1).unwrap()),
+            vec![0]
+        );
+        assert_eq!(
+            block_heights(db.get_latest_block_events_before(3, 19).unwrap()),
+            block_heights(db.get_latest_block_events(3).unwrap())
+        );
+        assert!(db.get_latest_block_events_before(3, 20).is_err());
+    }
+}
+
+fn paginate_block_events(db: &AptosDB, page_size: usize, order: Order) -> Vec<u64> {
+    let mut block_heights = vec![];
+    let mut cursor = None;
+    loop {
+        let (events, next_cursor) = db.get_block_events_page(cursor, page_size, order).unwrap();
+        assert!(events.len() <= page_size);
+        for event in events {
+            let new_block_event: NewBlockEvent = (&event.event).try_into().unwrap();
+            assert_eq!(event.transaction_version, new_block_event.height() * 2);
+            block_heights.push(new_block_event.height());
+        }
+        match next_cursor {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => break,
+        }
+    }
+    block_heights
+}
+
+#[test]
+fn test_get_block_events_page() {
+    for skip_index_and_usage in [false, true] {
+        let tmp_dir = TempPath::new();
+        let mut db = AptosDB::new_for_test(&tmp_dir);
+        db.skip_index_and_usage = skip_index_and_usage;
+        put_new_block_events(&db, 500);
+
+        // Every block is returned exactly once, in order.
+        assert_eq!(
+            paginate_block_events(&db, 10, Order::Ascending),
+            (0..500).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            paginate_block_events(&db, 10, Order::Descending),
+            (0..500).rev().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            paginate_block_events(&db, 7, Order::Ascending),
+            (0..500).collect::<Vec<_>>()
+        );
+
+        // Start from a cursor.
+        let (events, next_cursor) = db
+            .get_block_events_page(Some(495), 10, Order::Ascending)
+            .unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(next_cursor, None);
+        let (events, next_cursor) = db
+            .get_block_events_page(Some(4), 10, Order::Descending)
+            .unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(next_cursor, None);
+        let (events, next_cursor) = db
+            .get_block_events_page(Some(100), 10, Order::Descending)
+            .unwrap();
+        assert_eq!(events.first().unwrap().transaction_version, 200);
+        assert_eq!(next_cursor, Some(90));
+
+        assert!(db
+            .get_block_events_page(None, MAX_REQUEST_LIMIT as usize + 1, Order::Ascending)
+            .is_err());
+    }
 }
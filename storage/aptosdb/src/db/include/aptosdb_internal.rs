@@ -3,18 +3,16 @@
 
 impl AptosDB {
     fn new_with_dbs(
-        ledger_db: LedgerDb,
-        state_merkle_db: StateMerkleDb,
-        state_kv_db: StateKvDb,
+        ledger_db: Arc<LedgerDb>,
+        state_merkle_db: Arc<StateMerkleDb>,
+        state_kv_db: Arc<StateKvDb>,
         pruner_config: PrunerConfig,
         buffered_state_target_items: usize,
         hack_for_tests: bool,
         empty_buffered_state_for_restore: bool,
         skip_index_and_usage: bool,
+        read_only: bool,
     ) -> Self {
-        let ledger_db = Arc::new(ledger_db);
-        let state_merkle_db = Arc::new(state_merkle_db);
-        let state_kv_db = Arc::new(state_kv_db);
         let state_merkle_pruner = StateMerklePrunerManager::new(
             Arc::clone(&state_merkle_db),
             pruner_config.state_merkle_pruner_config,
@@ -36,6 +34,7 @@ impl AptosDB {
             hack_for_tests,
             empty_buffered_state_for_restore,
             skip_index_and_usage,
+            read_only,
         ));
 
         let ledger_pruner =
@@ -56,6 +55,11 @@ impl AptosDB {
             ledger_commit_lock: std::sync::Mutex::new(()),
             indexer: None,
             skip_index_and_usage,
+            replica_catch_up: None,
+            wal: None,
+            index_update_queue: None,
+            block_version_cache: BlockVersionCache::new(BLOCK_VERSION_CACHE_CAPACITY),
+            state_proof_cache: StateProofCache::new(DEFAULT_STATE_PROOF_CACHE_TTL),
         }
     }
 
@@ -68,6 +72,8 @@ impl AptosDB {
         buffered_state_target_items: usize,
         max_num_nodes_per_lru_cache_shard: usize,
         empty_buffered_state_for_restore: bool,
+        wal_config: &WalConfig,
+        deferred_index_mode: bool,
     ) -> Result<Self> {
         ensure!(
             pruner_config.eq(&NO_OP_STORAGE_PRUNER_CONFIG) || !readonly,
@@ -80,18 +86,36 @@ impl AptosDB {
             readonly,
             max_num_nodes_per_lru_cache_shard,
         )?;
+        let ledger_db = Arc::new(ledger_db);
+        let state_kv_db = Arc::new(state_kv_db);
+
+        if !readonly {
+            // Has to happen before the state store is created, which would otherwise silently
+            // truncate the partial writes while syncing the commit progress of the DBs.
+            Self::recover_interrupted_commit(&ledger_db, &state_kv_db)?;
+        }
 
         let mut myself = Self::new_with_dbs(
             ledger_db,
-            state_merkle_db,
+            Arc::new(state_merkle_db),
             state_kv_db,
             pruner_config,
             buffered_state_target_items,
             readonly,
             empty_buffered_state_for_restore,
             rocksdb_configs.enable_storage_sharding,
+            /*read_only=*/ false,
         );
 
+        if !readonly && !myself.skip_index_and_usage {
+            if deferred_index_mode {
+                myself.index_update_queue =
+                    Some(IndexUpdateQueue::new(Arc::clone(&myself.ledger_db))?);
+            } else {
+                catch_up_indices(&myself.ledger_db)?;
+            }
+        }
+
         if !readonly && enable_indexer {
             myself.open_indexer(
                 db_paths.default_root_path(),
@@ -99,9 +123,74 @@ impl AptosDB {
             )?;
         }
 
+        if !readonly && wal_config.enabled {
+            let wal = Wal::open(
+                db_paths.default_root_path().join(&wal_config.path),
+                wal_config.sync_on_write,
+            )?;
+            let wal_is_empty = wal.is_empty()?;
+            myself.wal = Some(wal);
+            if !wal_is_empty {
+                match myself.reapply_from_wal() {
+                    Ok(num_txns) => info!(
+                        num_txns = num_txns,
+                        "Found non-empty WAL, re-applied transactions of the interrupted commit.",
+                    ),
+                    // The DB is consistent without them, and the WAL is left as is.
+                    Err(e) => warn!(
+                        error = ?e,
+                        "Found non-empty WAL, failed to re-apply transactions of the interrupted commit.",
+                    ),
+                }
+            }
+        }
+
         Ok(myself)
     }
 
+    /// Rolls back what an interrupted `save_transactions` left in the ledger DB and the state K/V
+    /// DB past the overall commit progress, which is only written together with the ledger info
+    /// once everything else is committed.
+    fn recover_interrupted_commit(
+        ledger_db: &Arc<LedgerDb>,
+        state_kv_db: &Arc<StateKvDb>,
+    ) -> Result<RecoveryReport> {
+        let ledger_metadata_db = ledger_db.metadata_db();
+        let committed_version = match ledger_metadata_db.get_latest_version() {
+            Ok(version) => version,
+            // Nothing was committed yet, the partial writes will be overwritten by the next
+            // commit.
+            Err(_) => return Ok(RecoveryReport::default()),
+        };
+        let ledger_commit_progress = ledger_metadata_db.get_ledger_commit_progress()?;
+        let state_kv_commit_progress = get_state_kv_commit_progress(state_kv_db)?
+            .ok_or_else(|| anyhow!("State K/V commit progress cannot be None."))?;
+        let interrupted_version = ledger_commit_progress.max(state_kv_commit_progress);
+        if interrupted_version <= committed_version {
+            return Ok(RecoveryReport::default());
+        }
+
+        warn!(
+            interrupted_version = interrupted_version,
+            committed_version = committed_version,
+            "Found interrupted commit, rolling back partial writes.",
+        );
+        StateStore::sync_commit_progress(
+            Arc::clone(ledger_db),
+            Arc::clone(state_kv_db),
+            /*crash_if_difference_is_too_large=*/ true,
+        );
+        let report = RecoveryReport {
+            interrupted_version: Some(interrupted_version),
+            recovered_version: Some(committed_version),
+            ledger_db_rolled_back: ledger_commit_progress > committed_version,
+            state_kv_db_rolled_back: state_kv_commit_progress > committed_version,
+        };
+        info!(report = ?report, "Recovered from interrupted commit.");
+
+        Ok(report)
+    }
+
     fn open_indexer(
         &mut self,
         db_root_path: impl AsRef<Path>,
@@ -176,6 +265,20 @@ impl AptosDB {
         Ok(())
     }
 
+    fn error_if_index_not_ready(&self, data_type: &str, version: Version) -> Result<()> {
+        if let Some(queue) = &self.index_update_queue {
+            let index_ready_version = queue.index_ready_version();
+            ensure!(
+                version < index_ready_version,
+                "{} index at version {} is not written yet, indexed up to version {:?}.",
+                data_type,
+                version,
+                index_ready_version.checked_sub(1),
+            );
+        }
+        Ok(())
+    }
+
     fn error_if_state_merkle_pruned(&self, data_type: &str, version: Version) -> Result<()> {
         let min_readable_version = self
             .state_store
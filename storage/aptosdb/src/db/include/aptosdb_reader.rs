@@ -14,6 +14,20 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_epoch_ending_ledger_info_iter(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<LedgerInfoWithSignatures>> + '_>> {
+        gauged_api("get_epoch_ending_ledger_info_iter", || {
+            Ok(Box::new(self.get_epoch_ending_ledger_info_iter_impl(
+                start_epoch,
+                end_epoch,
+                MAX_NUM_EPOCH_ENDING_LEDGER_INFO,
+            )?) as Box<dyn Iterator<Item = Result<LedgerInfoWithSignatures>>>)
+        })
+    }
+
     fn get_prefixed_state_value_iterator(
         &self,
         key_prefix: &StateKeyPrefix,
@@ -51,6 +65,8 @@ impl DbReader for AptosDB {
         ledger_version: Version,
     ) -> Result<Option<TransactionWithProof>> {
         gauged_api("get_account_transaction", || {
+            self.error_if_index_not_ready("Account transaction", ledger_version)?;
+
             self.transaction_store
                 .get_account_transaction_version(address, seq_num, ledger_version)?
                 .map(|txn_version| {
@@ -64,23 +80,38 @@ impl DbReader for AptosDB {
         &self,
         address: AccountAddress,
         start_seq_num: u64,
+        order: Order,
         limit: u64,
         include_events: bool,
         ledger_version: Version,
     ) -> Result<AccountTransactionsWithProof> {
         gauged_api("get_account_transactions", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-
-            let txns_with_proofs = self
-                .transaction_store
-                .get_account_transaction_version_iter(
-                    address,
-                    start_seq_num,
-                    limit,
-                    ledger_version,
-                )?
-                .map(|result| {
-                    let (_seq_num, txn_version) = result?;
+            self.error_if_index_not_ready("Account transaction", ledger_version)?;
+
+            let txn_versions = match order {
+                Order::Ascending => self
+                    .transaction_store
+                    .get_account_transaction_version_iter(
+                        address,
+                        start_seq_num,
+                        limit,
+                        ledger_version,
+                    )?
+                    .collect::<Result<Vec<_>>>()?,
+                Order::Descending => self
+                    .transaction_store
+                    .get_account_transaction_version_iter_desc(
+                        address,
+                        start_seq_num,
+                        limit,
+                        ledger_version,
+                    )?
+                    .collect::<Result<Vec<_>>>()?,
+            };
+            let txns_with_proofs = txn_versions
+                .into_iter()
+                .map(|(_seq_num, txn_version)| {
                     self.get_transaction_with_proof(txn_version, ledger_version, include_events)
                 })
                 .collect::<Result<Vec<_>>>()?;
@@ -89,6 +120,22 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Finds the account creation version by scanning the history of the account resource from
+    /// its oldest retained entry. Accounts are never deleted, so the first version the resource
+    /// shows up at is the creation version, given it hasn't been pruned.
+    fn get_account_creation_version(&self, address: AccountAddress) -> Result<Option<Version>> {
+        gauged_api("get_account_creation_version", || {
+            let ledger_version = self.get_latest_version()?;
+            let state_key = StateKey::access_path(AccessPath::resource_access_path(
+                address,
+                AccountResource::struct_tag(),
+            )?);
+
+            self.state_store
+                .get_earliest_state_value_version(&state_key, ledger_version)
+        })
+    }
+
     /// This API is best-effort in that it CANNOT provide absence proof.
     fn get_transaction_by_hash(
         &self,
@@ -97,6 +144,9 @@ impl DbReader for AptosDB {
         fetch_events: bool,
     ) -> Result<Option<TransactionWithProof>> {
         gauged_api("get_transaction_by_hash", || {
+            if !self.transaction_store.might_contain_hash(hash) {
+                return Ok(None);
+            }
             self.ledger_db
                 .transaction_db()
                 .get_transaction_version_by_hash(&hash, ledger_version)?
@@ -210,7 +260,7 @@ impl DbReader for AptosDB {
         limit: u64,
         ledger_version: Version,
     ) -> Result<TransactionOutputListWithProof> {
-        gauged_api("get_transactions_outputs", || {
+        gauged_api("get_transactions_outputs_parallel", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
 
             if start_version > ledger_version || limit == 0 {
@@ -221,8 +271,12 @@ impl DbReader for AptosDB {
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
-            let (txn_infos, txns_and_outputs) = (start_version..start_version + limit)
-                .map(|version| {
+            // Collecting a parallel iterator preserves the order of the versions.
+            let (txn_infos, txns_and_outputs) = (0..limit as usize)
+                .into_par_iter()
+                .with_min_len(optimal_min_len(limit as usize, 32))
+                .map(|offset| {
+                    let version = start_version + offset as Version;
                     let txn_info = self
                         .ledger_db
                         .transaction_info_db()
@@ -269,6 +323,71 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_events_by_account_and_type(
+        &self,
+        address: AccountAddress,
+        event_type_tag: &StructTag,
+        start: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        gauged_api("get_events_by_account_and_type", || {
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+            self.error_if_index_not_ready("Event", ledger_version)?;
+
+            self.event_store
+                .lookup_events_by_account_and_type(
+                    address,
+                    event_type_tag,
+                    start,
+                    order,
+                    limit,
+                    ledger_version,
+                )?
+                .into_iter()
+                .map(|(ver, idx)| {
+                    let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+                    Ok(EventWithVersion::new(ver, event))
+                })
+                .collect()
+        })
+    }
+
+    fn get_event_proof(
+        &self,
+        version: Version,
+        event_index: u64,
+        ledger_version: Version,
+    ) -> Result<(ContractEvent, EventProof)> {
+        gauged_api("get_event_proof", || {
+            ensure!(
+                version <= ledger_version,
+                "Requested version {} > ledger version {}",
+                version,
+                ledger_version
+            );
+            self.error_if_ledger_pruned("Event", version)?;
+
+            let (event, transaction_info_to_event_proof) = self
+                .event_store
+                .get_event_with_proof_by_version_and_index(version, event_index)?;
+            let transaction_info_with_proof = self
+                .ledger_db
+                .transaction_info_db()
+                .get_transaction_info_with_proof(
+                    version,
+                    ledger_version,
+                    self.ledger_db.transaction_accumulator_db(),
+                )?;
+
+            Ok((
+                event,
+                EventProof::new(transaction_info_with_proof, transaction_info_to_event_proof),
+            ))
+        })
+    }
+
     fn get_transaction_iterator(
         &self,
         start_version: Version,
@@ -394,7 +513,14 @@ impl DbReader for AptosDB {
     fn get_state_proof(&self, known_version: u64) -> Result<StateProof> {
         gauged_api("get_state_proof", || {
             let ledger_info_with_sigs = self.ledger_db.metadata_db().get_latest_ledger_info()?;
-            self.get_state_proof_with_ledger_info(known_version, ledger_info_with_sigs)
+            let ledger_version = ledger_info_with_sigs.ledger_info().version();
+            if let Some(state_proof) = self.state_proof_cache.get(known_version, ledger_version) {
+                return Ok(state_proof);
+            }
+            let state_proof =
+                self.get_state_proof_with_ledger_info(known_version, ledger_info_with_sigs)?;
+            self.state_proof_cache.put(known_version, ledger_version, state_proof.clone());
+            Ok(state_proof)
         })
     }
 
@@ -424,6 +550,21 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_last_write_version(
+        &self,
+        state_key: &StateKey,
+        ledger_version: Version,
+    ) -> Result<Option<Version>> {
+        gauged_api("get_last_write_version", || {
+            self.error_if_state_kv_pruned("StateValue", ledger_version)?;
+
+            Ok(self
+                .state_store
+                .get_state_value_with_version_by_version(state_key, ledger_version)?
+                .map(|(version, _)| version))
+        })
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version_ext(
         &self,
@@ -451,6 +592,19 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_values_with_proofs_by_version_ext(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<(Option<StateValue>, SparseMerkleProofExt)>> {
+        gauged_api("get_state_values_with_proofs_by_version_ext", || {
+            self.error_if_state_merkle_pruned("State merkle", version)?;
+
+            self.state_store
+                .get_state_values_with_proofs_by_version_ext(state_keys, version)
+        })
+    }
+
     fn get_latest_epoch_state(&self) -> Result<EpochState> {
         gauged_api("get_latest_epoch_state", || {
             let latest_ledger_info = self.ledger_db.metadata_db().get_latest_ledger_info()?;
@@ -500,7 +654,10 @@ impl DbReader for AptosDB {
                 "version older than latest version"
             );
 
-            match self.event_store.get_block_metadata(version) {
+            match self
+                .error_if_index_not_ready("NewBlockEvent", version)
+                .and_then(|_| self.event_store.get_block_metadata(version))
+            {
                 Ok((_first_version, new_block_event)) => Ok(new_block_event.proposed_time()),
                 Err(err) => {
                     // when event index is disabled, we won't be able to search the NewBlock event stream.
@@ -524,6 +681,7 @@ impl DbReader for AptosDB {
     fn get_next_block_event(&self, version: Version) -> Result<(Version, NewBlockEvent)> {
         gauged_api("get_next_block_event", || {
             self.error_if_ledger_pruned("NewBlockEvent", version)?;
+            self.error_if_index_not_ready("NewBlockEvent", version)?;
             if let Some((block_version, _, _)) = self
                 .event_store
                 .lookup_event_at_or_after_version(&new_block_event_key(), version)?
@@ -559,28 +717,138 @@ impl DbReader for AptosDB {
             let mut events = Vec::with_capacity(num_events);
             for item in iter.take(num_events) {
                 let (block_height, block_info) = item?;
-                let first_version = block_info.first_version();
-                let event = self
-                    .ledger_db
-                    .event_db()
-                    .get_events_by_version(first_version)?
-                    .into_iter()
-                    .find(|event| {
-                        if let Some(key) = event.event_key() {
-                            if *key == new_block_event_key() {
-                                return true;
-                            }
-                        }
-                        false
-                    })
-                    .ok_or_else(|| anyhow!("Event for block_height {block_height} at version {first_version} is not found."))?;
-                events.push(EventWithVersion::new(first_version, event));
+                events.push(self.get_new_block_event(block_height, block_info.first_version())?);
             }
 
             Ok(events)
         })
     }
 
+    fn get_latest_block_events_before(
+        &self,
+        num_events: usize,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        gauged_api("get_latest_block_events_before", || {
+            let latest_version = self.get_latest_version()?;
+            ensure!(
+                ledger_version <= latest_version,
+                "Requested version {} > latest version {}",
+                ledger_version,
+                latest_version
+            );
+
+            if !self.skip_index_and_usage {
+                return self.get_events(
+                    &new_block_event_key(),
+                    u64::max_value(),
+                    Order::Descending,
+                    num_events as u64,
+                    ledger_version,
+                );
+            }
+
+            let db = self.ledger_db.metadata_db_arc();
+            let mut version_iter = db.rev_iter::<BlockByVersionSchema>(ReadOptions::default())?;
+            version_iter.seek_for_prev(&ledger_version)?;
+            let block_height = match version_iter.next().transpose()? {
+                Some((_, block_height)) => block_height,
+                None => return Ok(Vec::new()),
+            };
+
+            let mut iter = db.rev_iter::<BlockInfoSchema>(ReadOptions::default())?;
+            iter.seek_for_prev(&block_height)?;
+
+            let mut events = Vec::with_capacity(num_events);
+            for item in iter.take(num_events) {
+                let (block_height, block_info) = item?;
+                events.push(self.get_new_block_event(block_height, block_info.first_version())?);
+            }
+
+            Ok(events)
+        })
+    }
+
+    fn get_block_events_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        order: Order,
+    ) -> Result<(Vec<EventWithVersion>, Option<u64>)> {
+        gauged_api("get_block_events_page", || {
+            error_if_too_many_requested(limit as u64, MAX_REQUEST_LIMIT)?;
+            if limit == 0 {
+                return Ok((Vec::new(), cursor));
+            }
+
+            if !self.skip_index_and_usage {
+                // The sequence number of a NewBlockEvent is the block height.
+                let event_key = new_block_event_key();
+                let ledger_version = match self.get_latest_version() {
+                    Ok(version) => version,
+                    // Nothing is committed yet.
+                    Err(AptosDbError::NotFound(_)) => return Ok((Vec::new(), None)),
+                    Err(err) => return Err(err),
+                };
+                let start = cursor.unwrap_or(match order {
+                    Order::Ascending => 0,
+                    Order::Descending => u64::max_value(),
+                });
+                let events = self.get_events_by_event_key(
+                    &event_key,
+                    start,
+                    order,
+                    limit as u64,
+                    ledger_version,
+                )?;
+                let next_cursor = match events.last() {
+                    Some(last) if events.len() == limit => {
+                        let block_height = last.event.v1()?.sequence_number();
+                        match order {
+                            Order::Ascending => self
+                                .event_store
+                                .get_latest_sequence_number(ledger_version, &event_key)?
+                                .filter(|latest| *latest > block_height)
+                                .map(|_| block_height + 1),
+                            Order::Descending => block_height.checked_sub(1),
+                        }
+                    },
+                    _ => None,
+                };
+                return Ok((events, next_cursor));
+            }
+
+            let db = self.ledger_db.metadata_db_arc();
+            let mut iter = match order {
+                Order::Ascending => {
+                    let mut iter = db.iter::<BlockInfoSchema>(ReadOptions::default())?;
+                    iter.seek(&cursor.unwrap_or(0))?;
+                    iter
+                },
+                Order::Descending => {
+                    let mut iter = db.rev_iter::<BlockInfoSchema>(ReadOptions::default())?;
+                    match cursor {
+                        Some(block_height) => iter.seek_for_prev(&block_height)?,
+                        None => iter.seek_to_last(),
+                    }
+                    iter
+                },
+            };
+
+            let mut events = Vec::with_capacity(limit);
+            for item in iter.by_ref().take(limit) {
+                let (block_height, block_info) = item?;
+                events.push(self.get_new_block_event(block_height, block_info.first_version())?);
+            }
+            let next_cursor = iter
+                .next()
+                .transpose()?
+                .map(|(block_height, _block_info)| block_height);
+
+            Ok((events, next_cursor))
+        })
+    }
+
     fn get_block_info_by_version(
         &self,
         version: Version,
@@ -597,24 +865,39 @@ impl DbReader for AptosDB {
                 committed_version
             );
 
-            if !self.skip_index_and_usage {
-                let (first_version, new_block_event) =
-                    self.event_store.get_block_metadata(version)?;
+            if let Some(block_info) = self.block_version_cache.get(version) {
+                return Ok(block_info);
+            }
+            let block_info = self.get_block_info_by_version_uncached(version, committed_version)?;
+            // The last committed block can still grow, so only the ones before it are cached.
+            if block_info.1 < committed_version {
+                self.block_version_cache.put(version, block_info.clone());
+            }
+            Ok(block_info)
+        })
+    }
 
-                let last_version = self
-                    .event_store
-                    .lookup_event_after_version(&new_block_event_key(), version)?
-                    .map_or(committed_version, |(v, _, _)| v - 1);
+    fn get_block_height_containing_version(&self, version: Version) -> Result<u64> {
+        gauged_api("get_block_height_containing_version", || {
+            self.error_if_ledger_pruned("NewBlockEvent", version)?;
 
-                return Ok((first_version, last_version, new_block_event));
+            let committed_version = self.ledger_db.metadata_db().get_latest_version()?;
+            ensure!(
+                version <= committed_version,
+                "Requested version {} > committed version {}",
+                version,
+                committed_version
+            );
+
+            if !self.skip_index_and_usage {
+                self.error_if_index_not_ready("NewBlockEvent", version)?;
+                let (_, new_block_event) = self.event_store.get_block_metadata(version)?;
+                return Ok(new_block_event.height());
             }
 
-            let block_height = self
-                .ledger_db
+            self.ledger_db
                 .metadata_db()
-                .get_block_height_by_version(version)?;
-
-            self.get_block_info_by_height(block_height)
+                .get_block_height_by_version(version)
         })
     }
 
@@ -627,6 +910,7 @@ impl DbReader for AptosDB {
             let committed_version = latest_li.ledger_info().version();
 
             if !self.skip_index_and_usage {
+                self.error_if_index_not_ready("NewBlockEvent", committed_version)?;
                 let event_key = new_block_event_key();
                 let (first_version, new_block_event) = self.event_store.get_event_by_key(
                     &event_key,
@@ -684,6 +968,55 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_participation_stats(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> Result<Vec<BlockParticipationStats>> {
+        gauged_api("get_participation_stats", || {
+            ensure!(
+                start_block <= end_block,
+                "start_block {} > end_block {}",
+                start_block,
+                end_block
+            );
+            error_if_too_many_requested(end_block - start_block, MAX_REQUEST_LIMIT)?;
+
+            (start_block..end_block)
+                .map(|block_height| {
+                    if self.skip_index_and_usage {
+                        if let Some(stats) = self
+                            .ledger_db
+                            .metadata_db()
+                            .get_block_info(block_height)?
+                            .and_then(|block_info| block_info.participation_stats(block_height))
+                        {
+                            return Ok(stats);
+                        }
+                    }
+
+                    // Block info is either not persisted or not upgraded to V1 yet, replay the
+                    // NewBlockEvent instead.
+                    let (_, _, new_block_event) = self.get_block_info_by_height(block_height)?;
+                    let epoch_state = self
+                        .ledger_db
+                        .metadata_db()
+                        .get_epoch_state(new_block_event.epoch())?;
+                    let (proposer_vote_count, total_voting_power) =
+                        participation_from_event(&new_block_event, &epoch_state.verifier);
+                    Ok(BlockParticipationStats {
+                        block_height,
+                        epoch: new_block_event.epoch(),
+                        round: new_block_event.round(),
+                        proposer: new_block_event.proposer(),
+                        proposer_vote_count,
+                        total_voting_power,
+                    })
+                })
+                .collect()
+        })
+    }
+
     fn get_last_version_before_timestamp(
         &self,
         timestamp: u64,
@@ -705,6 +1038,26 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn is_state_checkpoint_version(&self, version: Version) -> Result<bool> {
+        gauged_api("is_state_checkpoint_version", || {
+            let latest_version = self.ledger_db.metadata_db().get_latest_version()?;
+            ensure!(
+                version <= latest_version,
+                "Requested version {} > latest version {}",
+                version,
+                latest_version
+            );
+            self.error_if_ledger_pruned("Transaction info", version)?;
+
+            Ok(self
+                .ledger_db
+                .transaction_info_db()
+                .get_transaction_info(version)?
+                .state_checkpoint_hash()
+                .is_some())
+        })
+    }
+
     fn get_state_snapshot_before(
         &self,
         next_version: Version,
@@ -724,6 +1077,19 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_accumulator_root_hashes(&self, versions: &[Version]) -> Result<Vec<HashValue>> {
+        gauged_api("get_accumulator_root_hashes", || {
+            if let Some(min_version) = versions.iter().min() {
+                self.error_if_ledger_pruned("Transaction accumulator", *min_version)?;
+            }
+            let transaction_accumulator_db = self.ledger_db.transaction_accumulator_db();
+            versions
+                .iter()
+                .map(|version| transaction_accumulator_db.get_root_hash(*version))
+                .collect()
+        })
+    }
+
     fn get_accumulator_consistency_proof(
         &self,
         client_known_version: Option<Version>,
@@ -817,6 +1183,18 @@ impl DbReader for AptosDB {
         self.indexer.is_some()
     }
 
+    fn get_indexer_lag(&self) -> Result<Option<u64>> {
+        gauged_api("get_indexer_lag", || match &self.indexer {
+            Some(indexer) => {
+                let latest_version = self.ledger_db.metadata_db().get_latest_version()?;
+                Ok(Some(
+                    (latest_version + 1).saturating_sub(indexer.next_version()),
+                ))
+            },
+            None => Ok(None),
+        })
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         gauged_api("get_state_storage_usage", || {
             if let Some(v) = version {
@@ -825,6 +1203,27 @@ impl DbReader for AptosDB {
             self.state_store.get_usage(version)
         })
     }
+
+    fn get_state_storage_usage_delta(
+        &self,
+        from: Version,
+        to: Version,
+    ) -> Result<StateStorageUsageDelta> {
+        gauged_api("get_state_storage_usage_delta", || {
+            ensure!(from <= to, "from version {} > to version {}", from, to);
+            self.error_if_ledger_pruned("state storage usage", from)?;
+
+            let from_usage = self.state_store.get_usage(Some(from))?;
+            let to_usage = self.state_store.get_usage(Some(to))?;
+            StateStorageUsageDelta::between(&from_usage, &to_usage).ok_or_else(|| {
+                anyhow!(
+                    "State storage usage is not tracked at version {} or {}.",
+                    from,
+                    to
+                )
+            })
+        })
+    }
 }
 
 impl AptosDB {
@@ -844,6 +1243,41 @@ impl AptosDB {
         )
     }
 
+    /// Pages through the epoch ending ledger infos `page_size` at a time, see
+    /// `get_epoch_ending_ledger_infos_impl`.
+    fn get_epoch_ending_ledger_info_iter_impl(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+        page_size: usize,
+    ) -> Result<impl Iterator<Item = Result<LedgerInfoWithSignatures>> + '_> {
+        // Fetching the first page validates the epoch range.
+        let (lis, mut more) =
+            self.get_epoch_ending_ledger_infos_impl(start_epoch, end_epoch, page_size)?;
+        let mut next_epoch = start_epoch + lis.len() as u64;
+        let mut page = lis.into_iter();
+
+        Ok(std::iter::from_fn(move || loop {
+            if let Some(li) = page.next() {
+                return Some(Ok(li));
+            }
+            if !more {
+                return None;
+            }
+            match self.get_epoch_ending_ledger_infos_impl(next_epoch, end_epoch, page_size) {
+                Ok((lis, next_more)) => {
+                    next_epoch += lis.len() as u64;
+                    more = next_more;
+                    page = lis.into_iter();
+                },
+                Err(e) => {
+                    more = false;
+                    return Some(Err(e));
+                },
+            }
+        }))
+    }
+
     fn get_epoch_ending_ledger_infos_impl(
         &self,
         start_epoch: u64,
@@ -938,6 +1372,7 @@ impl AptosDB {
         ledger_version: Version,
     ) -> Result<Vec<EventWithVersion>> {
         error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+        self.error_if_index_not_ready("Event", ledger_version)?;
         let get_latest = order == Order::Descending && start_seq_num == u64::max_value();
 
         let cursor = if get_latest {
@@ -999,6 +1434,60 @@ impl AptosDB {
         Ok(events_with_version)
     }
 
+    /// Returns the NewBlockEvent of the block at `block_height`, which is emitted by the first
+    /// transaction of the block.
+    fn get_new_block_event(
+        &self,
+        block_height: u64,
+        first_version: Version,
+    ) -> Result<EventWithVersion> {
+        let event = self
+            .ledger_db
+            .event_db()
+            .get_events_by_version(first_version)?
+            .into_iter()
+            .find(|event| {
+                if let Some(key) = event.event_key() {
+                    if *key == new_block_event_key() {
+                        return true;
+                    }
+                }
+                false
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Event for block_height {block_height} at version {first_version} is not found."
+                )
+            })?;
+        Ok(EventWithVersion::new(first_version, event))
+    }
+
+    /// Looks up the block containing `version`, bypassing the block version cache.
+    fn get_block_info_by_version_uncached(
+        &self,
+        version: Version,
+        committed_version: Version,
+    ) -> Result<(Version, Version, NewBlockEvent)> {
+        if !self.skip_index_and_usage {
+            self.error_if_index_not_ready("NewBlockEvent", committed_version)?;
+            let (first_version, new_block_event) = self.event_store.get_block_metadata(version)?;
+
+            let last_version = self
+                .event_store
+                .lookup_event_after_version(&new_block_event_key(), version)?
+                .map_or(committed_version, |(v, _, _)| v - 1);
+
+            return Ok((first_version, last_version, new_block_event));
+        }
+
+        let block_height = self
+            .ledger_db
+            .metadata_db()
+            .get_block_height_by_version(version)?;
+
+        self.get_block_info_by_height(block_height)
+    }
+
     /// TODO(jill): deprecate Indexer once Indexer Async V2 is ready
     fn get_table_info_option(&self, handle: TableHandle) -> Result<Option<TableInfo>> {
         match &self.indexer {
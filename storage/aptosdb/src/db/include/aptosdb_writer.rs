@@ -42,6 +42,13 @@ impl DbWriter for AptosDB {
                 &latest_in_memory_state,
             )?;
 
+            let last_version = first_version + txns_to_commit.len() as u64 - 1;
+            if let Some(wal) = &self.wal {
+                if !txns_to_commit.is_empty() {
+                    wal.append(first_version, ledger_info_with_sigs, txns_to_commit)?;
+                }
+            }
+
             let new_root_hash = self.calculate_and_commit_ledger_and_state_kv(
                 txns_to_commit,
                 first_version,
@@ -53,9 +60,16 @@ impl DbWriter for AptosDB {
             let _timer = OTHER_TIMERS_SECONDS.timer_with(&["save_transactions__others"]);
             {
                 let mut buffered_state = self.state_store.buffered_state().lock();
-                let last_version = first_version + txns_to_commit.len() as u64 - 1;
 
                 self.commit_ledger_info(last_version, new_root_hash, ledger_info_with_sigs)?;
+                if let Some(wal) = &self.wal {
+                    // The commit is durable by now, so failing it would make the caller retry an
+                    // already committed block. Stale WAL entries are skipped on recovery.
+                    if let Err(e) = wal.truncate() {
+                        WAL_TRUNCATE_FAILURES.inc();
+                        error!(error = ?e, path = ?wal.path(), "Failed to truncate WAL.");
+                    }
+                }
 
                 if !txns_to_commit.is_empty() {
                     let _timer = OTHER_TIMERS_SECONDS.timer_with(&["buffered_state___update"]);
@@ -67,6 +81,12 @@ impl DbWriter for AptosDB {
                 }
             }
 
+            if let Some(queue) = &self.index_update_queue {
+                if !txns_to_commit.is_empty() {
+                    queue.push(last_version);
+                }
+            }
+
             self.post_commit(txns_to_commit, first_version, ledger_info_with_sigs)
         })
     }
@@ -193,7 +213,12 @@ impl DbWriter for AptosDB {
                 .save_min_readable_version(version)?;
 
             restore_utils::update_latest_ledger_info(self.ledger_db.metadata_db(), ledger_infos)?;
+            self.state_proof_cache.invalidate();
             self.state_store.reset();
+            if let Some(queue) = &self.index_update_queue {
+                // The restored version is saved with its indices, and nothing before it exists.
+                queue.skip_to(version + 1);
+            }
 
             Ok(())
         })
@@ -297,7 +322,11 @@ impl AptosDB {
             s.spawn(|_| {
                 self.ledger_db
                     .transaction_db()
-                    .commit_transactions(txns_to_commit, first_version, skip_index_and_usage)
+                    .commit_transactions(
+                        txns_to_commit,
+                        first_version,
+                        skip_index_and_usage || self.index_update_queue.is_some(),
+                    )
                     .unwrap()
             });
             s.spawn(|_| {
@@ -369,7 +398,7 @@ impl AptosDB {
                     if let Some(event_key) = event.event_key() {
                         if *event_key == new_block_event_key() {
                             let version = first_version + i as Version;
-                            LedgerMetadataDb::put_block_info(
+                            self.ledger_db.metadata_db().put_block_info(
                                 version,
                                 event,
                                 &ledger_metadata_batch,
@@ -423,17 +452,27 @@ impl AptosDB {
             .start_timer();
         let batch = SchemaBatch::new();
         let num_txns = txns_to_commit.len();
+        let defer_index = self.index_update_queue.is_some();
         txns_to_commit
             .par_iter()
             .with_min_len(optimal_min_len(num_txns, 128))
             .enumerate()
             .try_for_each(|(i, txn_to_commit)| -> Result<()> {
+                let version = first_version + i as u64;
                 self.ledger_db.event_db().put_events(
-                    first_version + i as u64,
+                    version,
                     txn_to_commit.events(),
-                    skip_index,
+                    skip_index || defer_index,
                     &batch,
                 )?;
+                if defer_index {
+                    // Not an index, the event proofs are served from it.
+                    self.ledger_db.event_db().put_event_accumulator(
+                        version,
+                        txn_to_commit.events(),
+                        &batch,
+                    )?;
+                }
 
                 Ok(())
             })?;
@@ -589,6 +628,7 @@ impl AptosDB {
             self.ledger_db
                 .metadata_db()
                 .set_latest_ledger_info(x.clone());
+            self.state_proof_cache.invalidate();
 
             LEDGER_VERSION.set(x.ledger_info().version() as i64);
             NEXT_BLOCK_EPOCH.set(x.ledger_info().next_block_epoch() as i64);
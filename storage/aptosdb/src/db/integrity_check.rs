@@ -0,0 +1,389 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline consistency checks between the stored transactions, the transaction accumulator and
+//! the state Merkle tree.
+
+use crate::{
+    db::AptosDB,
+    schema::{
+        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        transaction_accumulator::TransactionAccumulatorSchema,
+    },
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_jellyfish_merkle::node_type::{LeafNode, Node, NodeKey};
+use aptos_logger::prelude::*;
+use aptos_schemadb::{ReadOptions, DB};
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, DbReader, Result};
+use aptos_types::{
+    contract_event::ContractEvent,
+    proof::{
+        accumulator::{InMemoryEventAccumulator, InMemoryTransactionAccumulator},
+        position::Position,
+    },
+    state_store::state_key::StateKey,
+    transaction::{TransactionInfo, Version},
+};
+use std::cmp::min;
+
+/// Number of versions loaded into memory at a time.
+const CHUNK_SIZE: u64 = 10_000;
+
+/// Selects the checks run by [`AptosDB::verify_ledger_integrity`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IntegrityCheckOptions {
+    /// Check the transactions and transaction infos against the transaction accumulator, and
+    /// recompute the accumulator root.
+    pub verify_accumulator: bool,
+    /// Re-hash the state values referenced by a sample of the state Merkle tree leaves.
+    pub verify_state_merkle: bool,
+    /// Check the event root hashes and the event indices.
+    pub verify_event_indices: bool,
+    /// Check the write set hashes recorded in the transaction infos.
+    pub verify_write_set_hashes: bool,
+    /// Maximum number of state Merkle tree leaves to re-hash.
+    pub state_merkle_sample_size: usize,
+}
+
+impl Default for IntegrityCheckOptions {
+    fn default() -> Self {
+        Self {
+            verify_accumulator: true,
+            verify_state_merkle: true,
+            verify_event_indices: true,
+            verify_write_set_hashes: true,
+            state_merkle_sample_size: 10_000,
+        }
+    }
+}
+
+/// A mismatch found by [`AptosDB::verify_ledger_integrity`]. `expected` is what the
+/// authenticated data (transaction info, accumulator or tree leaf) commits to, `actual` is what
+/// is stored.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IntegrityViolation {
+    TransactionHashMismatch {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    AccumulatorLeafMismatch {
+        version: Version,
+        expected: HashValue,
+        actual: Option<HashValue>,
+    },
+    AccumulatorRootMismatch {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    EventRootHashMismatch {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    /// An event index entry is missing or points to a different event.
+    EventIndexMismatch { version: Version, index: u64 },
+    WriteSetHashMismatch {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    /// The state value a tree leaf points to is missing or hashes to something else.
+    StateValueHashMismatch {
+        version: Version,
+        key_hash: HashValue,
+        expected: HashValue,
+        actual: Option<HashValue>,
+    },
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IntegrityReport {
+    pub start_version: Version,
+    pub end_version: Version,
+    pub num_state_leaves_checked: usize,
+    pub violations: Vec<IntegrityViolation>,
+}
+
+impl IntegrityReport {
+    pub fn is_consistent(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    fn add_violation(&mut self, violation: IntegrityViolation) {
+        error!(violation = ?violation, "Ledger integrity violation found.");
+        self.violations.push(violation);
+    }
+}
+
+impl AptosDB {
+    /// Checks that the data stored for versions in `[start_version, end_version]` is consistent
+    /// with the transaction accumulator and the state Merkle tree. Mismatches are collected in the
+    /// returned report, while failing to read the data is returned as an error.
+    pub fn verify_ledger_integrity(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        options: IntegrityCheckOptions,
+    ) -> Result<IntegrityReport> {
+        ensure!(
+            start_version <= end_version,
+            "start_version {} is greater than end_version {}.",
+            start_version,
+            end_version,
+        );
+        let latest_version = self.get_latest_version()?;
+        ensure!(
+            end_version <= latest_version,
+            "end_version {} is newer than the latest version {}.",
+            end_version,
+            latest_version,
+        );
+        self.error_if_ledger_pruned("Transaction", start_version)?;
+
+        let mut report = IntegrityReport {
+            start_version,
+            end_version,
+            ..Default::default()
+        };
+        let mut accumulator = if options.verify_accumulator {
+            Some(InMemoryTransactionAccumulator::new(
+                self.ledger_db
+                    .transaction_accumulator_db()
+                    .get_frozen_subtree_hashes(start_version)?,
+                start_version,
+            )?)
+        } else {
+            None
+        };
+
+        let mut first_version = start_version;
+        while first_version <= end_version {
+            let num_versions = min(end_version - first_version + 1, CHUNK_SIZE) as usize;
+            let txn_infos = self
+                .ledger_db
+                .transaction_info_db()
+                .get_transaction_info_iter(first_version, num_versions)?
+                .collect::<Result<Vec<_>>>()?;
+
+            if let Some(acc) = accumulator.as_mut() {
+                self.verify_transaction_hashes(first_version, &txn_infos, &mut report)?;
+                let txn_info_hashes: Vec<_> = txn_infos.iter().map(CryptoHash::hash).collect();
+                *acc = acc.append(&txn_info_hashes);
+            }
+            if options.verify_write_set_hashes {
+                self.verify_write_set_hashes(first_version, &txn_infos, &mut report)?;
+            }
+            if options.verify_event_indices {
+                self.verify_events(first_version, &txn_infos, &mut report)?;
+            }
+
+            first_version += num_versions as u64;
+        }
+
+        if let Some(acc) = accumulator {
+            let stored_root_hash = self
+                .ledger_db
+                .transaction_accumulator_db()
+                .get_root_hash(end_version)?;
+            if acc.root_hash() != stored_root_hash {
+                report.add_violation(IntegrityViolation::AccumulatorRootMismatch {
+                    version: end_version,
+                    expected: acc.root_hash(),
+                    actual: stored_root_hash,
+                });
+            }
+        }
+        if options.verify_state_merkle {
+            self.verify_state_merkle_sample(
+                start_version,
+                end_version,
+                options.state_merkle_sample_size,
+                &mut report,
+            )?;
+        }
+
+        info!(
+            start_version = start_version,
+            end_version = end_version,
+            num_violations = report.violations.len(),
+            "Ledger integrity check finished."
+        );
+        Ok(report)
+    }
+
+    fn verify_transaction_hashes(
+        &self,
+        first_version: Version,
+        txn_infos: &[TransactionInfo],
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let txn_iter = self
+            .ledger_db
+            .transaction_db()
+            .get_transaction_iter(first_version, txn_infos.len())?;
+        for ((version, txn_info), txn) in (first_version..).zip(txn_infos).zip(txn_iter) {
+            let txn_hash = txn?.hash();
+            if txn_hash != txn_info.transaction_hash() {
+                report.add_violation(IntegrityViolation::TransactionHashMismatch {
+                    version,
+                    expected: txn_info.transaction_hash(),
+                    actual: txn_hash,
+                });
+            }
+
+            let txn_info_hash = txn_info.hash();
+            let leaf_hash =
+                self.ledger_db
+                    .transaction_accumulator_db_raw()
+                    .get::<TransactionAccumulatorSchema>(&Position::from_leaf_index(version))?;
+            if leaf_hash != Some(txn_info_hash) {
+                report.add_violation(IntegrityViolation::AccumulatorLeafMismatch {
+                    version,
+                    expected: txn_info_hash,
+                    actual: leaf_hash,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_write_set_hashes(
+        &self,
+        first_version: Version,
+        txn_infos: &[TransactionInfo],
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let write_set_iter = self
+            .ledger_db
+            .write_set_db()
+            .get_write_set_iter(first_version, txn_infos.len())?;
+        for ((version, txn_info), write_set) in (first_version..).zip(txn_infos).zip(write_set_iter)
+        {
+            let write_set_hash = CryptoHash::hash(&write_set?);
+            if write_set_hash != txn_info.state_change_hash() {
+                report.add_violation(IntegrityViolation::WriteSetHashMismatch {
+                    version,
+                    expected: txn_info.state_change_hash(),
+                    actual: write_set_hash,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_events(
+        &self,
+        first_version: Version,
+        txn_infos: &[TransactionInfo],
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let event_db = self.ledger_db.event_db_raw();
+        let events_iter = self
+            .ledger_db
+            .event_db()
+            .get_events_by_version_iter(first_version, txn_infos.len())?;
+        for ((version, txn_info), events) in (first_version..).zip(txn_infos).zip(events_iter) {
+            let events = events?;
+            let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+            let event_root_hash = InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash();
+            if event_root_hash != txn_info.event_root_hash() {
+                report.add_violation(IntegrityViolation::EventRootHashMismatch {
+                    version,
+                    expected: txn_info.event_root_hash(),
+                    actual: event_root_hash,
+                });
+            }
+
+            // The indices are not written at all when indexing is skipped.
+            if self.skip_index_and_usage {
+                continue;
+            }
+            for (idx, event) in events.iter().enumerate() {
+                if let ContractEvent::V1(v1) = event {
+                    let index = idx as u64;
+                    let by_key =
+                        event_db.get::<EventByKeySchema>(&(*v1.key(), v1.sequence_number()))?;
+                    let by_version = event_db.get::<EventByVersionSchema>(&(
+                        *v1.key(),
+                        version,
+                        v1.sequence_number(),
+                    ))?;
+                    if by_key != Some((version, index)) || by_version != Some(index) {
+                        report.add_violation(IntegrityViolation::EventIndexMismatch {
+                            version,
+                            index,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-hashes the state values referenced by leaves created in `[start_version, end_version]`,
+    /// spreading the sample evenly across the state merkle DB shards.
+    fn verify_state_merkle_sample(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        sample_size: usize,
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let state_merkle_db = &self.state_store.state_db.state_merkle_db;
+        let dbs: Vec<&DB> = if state_merkle_db.sharding_enabled() {
+            (0..state_merkle_db.num_shards())
+                .map(|shard_id| state_merkle_db.db_shard(shard_id))
+                .collect()
+        } else {
+            vec![state_merkle_db.metadata_db()]
+        };
+        let sample_size_per_db = (sample_size + dbs.len() - 1) / dbs.len();
+
+        for db in dbs {
+            let mut iter = db.iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+            iter.seek(&NodeKey::new_empty_path(start_version))?;
+            let mut num_leaves_checked = 0;
+            for res in iter {
+                if num_leaves_checked >= sample_size_per_db {
+                    break;
+                }
+                let (node_key, node) = res?;
+                if node_key.version() > end_version {
+                    break;
+                }
+                if let Node::Leaf(leaf) = node {
+                    self.verify_state_leaf(&leaf, report)?;
+                    num_leaves_checked += 1;
+                }
+            }
+            report.num_state_leaves_checked += num_leaves_checked;
+        }
+        Ok(())
+    }
+
+    fn verify_state_leaf(
+        &self,
+        leaf: &LeafNode<StateKey>,
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let (state_key, version) = leaf.value_index();
+        let value_hash = self
+            .state_store
+            .get_state_value_with_version_by_version(state_key, *version)?
+            .filter(|(value_version, _)| value_version == version)
+            .map(|(_, value)| CryptoHash::hash(&value));
+        if value_hash != Some(leaf.value_hash()) {
+            report.add_violation(IntegrityViolation::StateValueHashMismatch {
+                version: *version,
+                key_hash: leaf.account_key(),
+                expected: leaf.value_hash(),
+                actual: value_hash,
+            });
+        }
+        Ok(())
+    }
+}
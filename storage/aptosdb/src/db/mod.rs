@@ -4,30 +4,44 @@
 
 use crate::{
     backup::{backup_handler::BackupHandler, restore_utils},
+    block_version_cache::{BlockVersionCache, BLOCK_VERSION_CACHE_CAPACITY},
     common::MAX_NUM_EPOCH_ENDING_LEDGER_INFO,
     event_store::EventStore,
+    historical_state_view::HistoricalStateView,
+    index_update_queue::{catch_up_indices, IndexUpdateQueue},
     ledger_db::{
-        ledger_metadata_db::LedgerMetadataDb, transaction_info_db::TransactionInfoDb, LedgerDb,
-        LedgerDbSchemaBatches,
+        ledger_metadata_db::participation_from_event, transaction_info_db::TransactionInfoDb,
+        LedgerDb, LedgerDbSchemaBatches,
     },
     metrics::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
-        OTHER_TIMERS_SECONDS,
+        OTHER_TIMERS_SECONDS, WAL_TRUNCATE_FAILURES,
     },
-    pruner::{LedgerPrunerManager, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager},
+    pruner::{
+        LedgerPrunerManager, PrunerManager, PruningImpactReport, StateKvPrunerManager,
+        StateMerklePrunerManager,
+    },
+    replica_catch_up::ReplicaCatchUp,
     rocksdb_property_reporter::RocksdbPropertyReporter,
     schema::{
+        block_by_version::BlockByVersionSchema,
         block_info::BlockInfoSchema,
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
     },
     state_kv_db::StateKvDb,
     state_merkle_db::StateMerkleDb,
+    state_proof_cache::{CacheStats, StateProofCache, DEFAULT_STATE_PROOF_CACHE_TTL},
     state_store::StateStore,
     transaction_store::TransactionStore,
-    utils::new_sharded_kv_schema_batch,
+    utils::{
+        new_sharded_kv_schema_batch,
+        truncation_helper::get_state_kv_commit_progress,
+    },
+    wal::Wal,
 };
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
+    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, WalConfig,
+    NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::HashValue;
 use aptos_db_indexer::Indexer;
@@ -37,13 +51,14 @@ use aptos_metrics_core::TimerHelper;
 use aptos_schemadb::{ReadOptions, SchemaBatch};
 use aptos_scratchpad::SparseMerkleTree;
 use aptos_storage_interface::{
-    cached_state_view::ShardedStateCache, db_anyhow as anyhow, db_ensure as ensure,
-    db_other_bail as bail, state_delta::StateDelta, AptosDbError, DbReader, DbWriter,
-    ExecutedTrees, Order, Result, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
+    block_info::BlockParticipationStats, cached_state_view::ShardedStateCache, db_anyhow as anyhow,
+    db_ensure as ensure, db_other_bail as bail, state_delta::StateDelta, AptosDbError, DbReader,
+    DbWriter, ExecutedTrees, Order, Result, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
 };
 use aptos_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
-    account_config::{new_block_event_key, NewBlockEvent},
+    account_config::{new_block_event_key, AccountResource, NewBlockEvent},
     contract_event::{ContractEvent, EventWithVersion},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
@@ -51,15 +66,15 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{CurrentTimeMicroseconds, OnChainConfig},
     proof::{
-        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleProofExt,
-        TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
+        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, EventProof,
+        SparseMerkleProofExt, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
         TransactionInfoListWithProof,
     },
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
         state_key_prefix::StateKeyPrefix,
-        state_storage_usage::StateStorageUsage,
+        state_storage_usage::{StateStorageUsage, StateStorageUsageDelta},
         state_value::{StateValue, StateValueChunkWithProof},
         table::{TableHandle, TableInfo},
         ShardedStateUpdates,
@@ -72,6 +87,8 @@ use aptos_types::{
     write_set::WriteSet,
 };
 use aptos_vm::data_cache::AsMoveResolver;
+use futures::Stream;
+use move_core_types::{language_storage::StructTag, move_resource::MoveStructType};
 use move_resource_viewer::MoveValueAnnotator;
 use rayon::prelude::*;
 use std::{
@@ -79,11 +96,12 @@ use std::{
     iter::Iterator,
     path::Path,
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 #[cfg(test)]
 mod aptosdb_test;
+pub mod integrity_check;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod test_helper;
 
@@ -100,6 +118,50 @@ pub struct AptosDB {
     ledger_commit_lock: std::sync::Mutex<()>,
     indexer: Option<Indexer>,
     skip_index_and_usage: bool,
+    replica_catch_up: Option<ReplicaCatchUp>,
+    wal: Option<Wal>,
+    index_update_queue: Option<IndexUpdateQueue>,
+    block_version_cache: BlockVersionCache,
+    pub(crate) state_proof_cache: StateProofCache,
+}
+
+/// Outcome of checking the DBs for a commit that was interrupted before it finished.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RecoveryReport {
+    /// Last version of the interrupted commit, if one was found.
+    pub interrupted_version: Option<Version>,
+    /// Last fully committed version, which the DBs were rolled back to.
+    pub recovered_version: Option<Version>,
+    /// Whether partial writes were removed from the ledger DB.
+    pub ledger_db_rolled_back: bool,
+    /// Whether partial writes were removed from the state K/V DB.
+    pub state_kv_db_rolled_back: bool,
+}
+
+impl RecoveryReport {
+    pub fn is_clean(&self) -> bool {
+        self.interrupted_version.is_none()
+    }
+}
+
+/// Inconsistency that makes the next commit fail validation, see `diagnose_next_commit`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CommitValidationIssue {
+    /// The base state version passed in differs from the base version of the buffered state.
+    BaseVersionMismatch {
+        base_state_version: Option<Version>,
+        buffered_base_version: Option<Version>,
+    },
+    /// The first version passed in is not the next version in the DB.
+    FirstVersionNotNext {
+        first_version: Version,
+        next_version_in_db: Version,
+    },
+    /// The buffered state is not at the latest version in the DB.
+    BufferedStateMisaligned {
+        next_version_in_buffered_state: Version,
+        next_version_in_db: Version,
+    },
 }
 
 // DbReader implementations and private functions used by them.
@@ -131,6 +193,65 @@ impl AptosDB {
             buffered_state_target_items,
             max_num_nodes_per_lru_cache_shard,
             false,
+            &WalConfig::default(),
+            /*deferred_index_mode=*/ false,
+        )
+    }
+
+    /// Same as `open`, but records every commit in a write-ahead log according to `wal_config`.
+    /// If the log is found non-empty, the transactions of the interrupted commit are re-applied
+    /// from it, see `recover_from_wal`.
+    pub fn open_with_wal(
+        db_paths: StorageDirPaths,
+        readonly: bool,
+        pruner_config: PrunerConfig,
+        rocksdb_configs: RocksdbConfigs,
+        enable_indexer: bool,
+        buffered_state_target_items: usize,
+        max_num_nodes_per_lru_cache_shard: usize,
+        wal_config: &WalConfig,
+    ) -> Result<Self> {
+        Self::open_internal(
+            &db_paths,
+            readonly,
+            pruner_config,
+            rocksdb_configs,
+            enable_indexer,
+            buffered_state_target_items,
+            max_num_nodes_per_lru_cache_shard,
+            false,
+            wal_config,
+            /*deferred_index_mode=*/ false,
+        )
+    }
+
+    /// Same as `open`, but when `deferred_index_mode` is set, the event and account transaction
+    /// indices are kept out of the commits and written by a background thread after them, which
+    /// saves write I/O on the commit path at the cost of the indices lagging behind the commits.
+    /// Reads served from those indices fail for versions not indexed yet, see
+    /// `flush_index_queue`. Has no effect if the DB is opened readonly or with storage sharding,
+    /// which skips the indices altogether.
+    pub fn open_with_deferred_index(
+        db_paths: StorageDirPaths,
+        readonly: bool,
+        pruner_config: PrunerConfig,
+        rocksdb_configs: RocksdbConfigs,
+        enable_indexer: bool,
+        buffered_state_target_items: usize,
+        max_num_nodes_per_lru_cache_shard: usize,
+        deferred_index_mode: bool,
+    ) -> Result<Self> {
+        Self::open_internal(
+            &db_paths,
+            readonly,
+            pruner_config,
+            rocksdb_configs,
+            enable_indexer,
+            buffered_state_target_items,
+            max_num_nodes_per_lru_cache_shard,
+            false,
+            &WalConfig::default(),
+            deferred_index_mode,
         )
     }
 
@@ -152,9 +273,426 @@ impl AptosDB {
             buffered_state_target_items,
             max_num_nodes_per_lru_cache_shard,
             true,
+            &WalConfig::default(),
+            /*deferred_index_mode=*/ false,
         )
     }
 
+    /// Opens the DB of a primary at `db_paths` in RocksDB secondary mode, so that it can be read
+    /// from another process while the primary is running. The replica catches up with the primary
+    /// every `stale_read_tolerance` in a background thread, rebuilding its in-memory state before
+    /// exposing the new latest ledger info. `secondary_db_root_path` holds the replica's own files
+    /// and must be different from the primary's paths.
+    /// Only supported when storage sharding is disabled.
+    pub fn open_replica(
+        db_paths: StorageDirPaths,
+        stale_read_tolerance: Duration,
+        rocksdb_configs: RocksdbConfigs,
+        secondary_db_root_path: impl AsRef<Path>,
+        buffered_state_target_items: usize,
+        max_num_nodes_per_lru_cache_shard: usize,
+    ) -> Result<Self> {
+        let ledger_db = LedgerDb::new_secondary(
+            db_paths.ledger_db_root_path(),
+            secondary_db_root_path.as_ref(),
+            rocksdb_configs,
+        )?;
+        let state_kv_db = StateKvDb::new(
+            &db_paths,
+            rocksdb_configs,
+            /*readonly=*/ true,
+            ledger_db.metadata_db_arc(),
+        )?;
+        let state_merkle_db = StateMerkleDb::new_secondary(
+            &db_paths,
+            secondary_db_root_path.as_ref(),
+            rocksdb_configs,
+            max_num_nodes_per_lru_cache_shard,
+        )?;
+        let primary_ledger_info_probe = LedgerDb::new_secondary_ledger_info_probe(
+            db_paths.ledger_db_root_path(),
+            secondary_db_root_path.as_ref().join("primary_ledger_info_probe"),
+            rocksdb_configs,
+        )?;
+        StateStore::check_commit_progress(&ledger_db, &state_kv_db)?;
+
+        let mut myself = Self::new_with_dbs(
+            Arc::new(ledger_db),
+            Arc::new(state_merkle_db),
+            Arc::new(state_kv_db),
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            buffered_state_target_items,
+            /*hack_for_tests=*/ false,
+            /*empty_buffered_state_for_restore=*/ false,
+            rocksdb_configs.enable_storage_sharding,
+            /*read_only=*/ true,
+        );
+        myself.replica_catch_up = Some(ReplicaCatchUp::new(
+            Arc::clone(&myself.ledger_db),
+            Arc::clone(&myself.state_store),
+            primary_ledger_info_probe,
+            stale_read_tolerance,
+        ));
+
+        Ok(myself)
+    }
+
+    /// Checks whether the last commit was interrupted halfway, i.e. the ledger DB or the state K/V
+    /// DB committed past the overall commit progress, and if so rolls back the partial writes it
+    /// left there. The state merkle DB is only written for committed versions, so it never needs a
+    /// rollback. Done on startup when the DB is opened for writing.
+    pub fn check_and_recover_consistency(&self) -> Result<RecoveryReport> {
+        let _lock = self
+            .ledger_commit_lock
+            .try_lock()
+            .map_err(|_| AptosDbError::Other("Concurrent committing detected.".to_string()))?;
+        Self::recover_interrupted_commit(&self.ledger_db, &self.state_kv_db)
+    }
+
+    /// Checks the invariants on the DB state that `save_transactions` validates before committing
+    /// transactions from `first_version` on top of `base_state_version`, and returns every issue
+    /// found instead of stopping at the first one. Nothing is written.
+    pub fn diagnose_next_commit(
+        &self,
+        first_version: Version,
+        base_state_version: Option<Version>,
+    ) -> Vec<CommitValidationIssue> {
+        let mut issues = vec![];
+        let buffered_state = self.state_store.buffered_state().lock();
+        let current_state = buffered_state.current_state();
+        if base_state_version != current_state.base_version {
+            issues.push(CommitValidationIssue::BaseVersionMismatch {
+                base_state_version,
+                buffered_base_version: current_state.base_version,
+            });
+        }
+
+        let next_version_in_buffered_state = current_state
+            .current_version
+            .map_or(0, |version| version + 1);
+        let next_version_in_db = self.get_latest_version().map_or(0, |v| v + 1);
+        if first_version != next_version_in_db {
+            issues.push(CommitValidationIssue::FirstVersionNotNext {
+                first_version,
+                next_version_in_db,
+            });
+        }
+        if next_version_in_buffered_state != next_version_in_db {
+            issues.push(CommitValidationIssue::BufferedStateMisaligned {
+                next_version_in_buffered_state,
+                next_version_in_db,
+            });
+        }
+        issues
+    }
+
+    /// Caches the proofs returned by `get_state_proof` for `ttl` instead of the default 500ms.
+    pub fn with_state_proof_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.state_proof_cache = StateProofCache::new(ttl);
+        self
+    }
+
+    pub fn state_proof_cache_stats(&self) -> CacheStats {
+        self.state_proof_cache.stats()
+    }
+
+    /// Returns the transactions recorded in the write-ahead log beyond the latest committed
+    /// version, i.e. those of a commit that was interrupted, in version order. Empty if the WAL
+    /// is disabled. They are re-applied when the DB is opened, so this is only non-empty if that
+    /// failed.
+    pub fn recover_from_wal(&self) -> Result<Vec<TransactionToCommit>> {
+        Ok(self.read_interrupted_commit_from_wal()?.1)
+    }
+
+    /// Re-applies the transactions of the interrupted commit recorded in the write-ahead log, with
+    /// the ledger info they were committed with if any. Versions at or below the latest committed
+    /// version are skipped. Returns the number of transactions.
+    fn reapply_from_wal(&self) -> Result<usize> {
+        let (first_version, txns_to_commit, ledger_info_with_sigs) =
+            self.read_interrupted_commit_from_wal()?;
+        if txns_to_commit.is_empty() {
+            // Only committed entries, left by a failed truncation after the commit.
+            if let Some(wal) = &self.wal {
+                wal.truncate()?;
+            }
+            return Ok(0);
+        }
+
+        let (state_updates_until_last_checkpoint, latest_in_memory_state) = self
+            .state_store
+            .calculate_state_for_txns(&txns_to_commit)?;
+        let base_state_version = self
+            .state_store
+            .buffered_state()
+            .lock()
+            .current_state()
+            .base_version;
+        self.save_transactions(
+            &txns_to_commit,
+            first_version,
+            base_state_version,
+            ledger_info_with_sigs.as_ref(),
+            /*sync_commit=*/ true,
+            latest_in_memory_state,
+            state_updates_until_last_checkpoint,
+            /*sharded_state_cache=*/ None,
+        )?;
+        Ok(txns_to_commit.len())
+    }
+
+    /// Returns the first version of the interrupted commit recorded in the write-ahead log, its
+    /// transactions beyond the latest committed version and the ledger info it carried.
+    fn read_interrupted_commit_from_wal(
+        &self,
+    ) -> Result<(
+        Version,
+        Vec<TransactionToCommit>,
+        Option<LedgerInfoWithSignatures>,
+    )> {
+        let next_version = self
+            .ledger_db
+            .metadata_db()
+            .get_latest_version()
+            .map_or(0, |version| version + 1);
+        let wal = match &self.wal {
+            Some(wal) => wal,
+            None => return Ok((next_version, Vec::new(), None)),
+        };
+
+        let mut txns = Vec::new();
+        let mut ledger_info = None;
+        for (first_version, ledger_info_with_sigs, entry) in wal.read_all()? {
+            if first_version + entry.len() as Version <= next_version {
+                continue;
+            }
+            // The ledger info certifies the last transaction of the entry, which ends up last.
+            ledger_info = ledger_info_with_sigs;
+            for (version, txn) in (first_version..).zip(entry) {
+                if version < next_version {
+                    continue;
+                }
+                let index = (version - next_version) as usize;
+                ensure!(
+                    index <= txns.len(),
+                    "Gap in WAL: expecting version {}, got {}.",
+                    next_version + txns.len() as u64,
+                    version,
+                );
+                // A commit retried after a failure is logged again, superseding the earlier entry.
+                txns.truncate(index);
+                txns.push(txn);
+            }
+        }
+        Ok((next_version, txns, ledger_info))
+    }
+
+    /// Iterates over all the state values at `version`, in chunks of at most `chunk_size` values,
+    /// each with a proof against the state root hash at `version`. Chunks are only read from the
+    /// DB as the iterator advances, and the iteration ends after the chunk whose
+    /// `is_last_chunk()` is true, or after the first error.
+    pub fn get_state_snapshot_chunk_iter(
+        &self,
+        version: Version,
+        chunk_size: usize,
+    ) -> Result<impl Iterator<Item = Result<StateValueChunkWithProof>> + '_> {
+        ensure!(chunk_size > 0, "chunk_size must be positive.");
+        let num_values = self.get_state_leaf_count(version)?;
+        let mut next_index = Some(0);
+        Ok(std::iter::from_fn(move || {
+            let first_index = next_index.filter(|index| *index < num_values)?;
+            let chunk = self.get_state_value_chunk_with_proof(version, first_index, chunk_size);
+            next_index = chunk
+                .as_ref()
+                .ok()
+                .map(|chunk| chunk.last_index as usize + 1);
+            Some(chunk)
+        }))
+    }
+
+    /// Streams all the state values at `version`, in chunks of at most `chunk_size` values, each
+    /// with a proof against the state root hash at `version`. Chunks are only read from the DB as
+    /// the stream is polled, and the final chunk is the one whose `is_last_chunk()` is true.
+    pub fn get_state_stream(
+        &self,
+        version: Version,
+        chunk_size: usize,
+    ) -> Result<impl Stream<Item = Result<StateValueChunkWithProof>> + '_> {
+        ensure!(chunk_size > 0, "chunk_size must be positive.");
+        let num_values = self.get_state_leaf_count(version)?;
+        Ok(async_stream::stream! {
+            let mut first_index = 0;
+            while first_index < num_values {
+                match self.get_state_value_chunk_with_proof(version, first_index, chunk_size) {
+                    Ok(chunk) => {
+                        first_index = chunk.last_index as usize + 1;
+                        yield Ok(chunk);
+                    },
+                    Err(err) => {
+                        yield Err(err);
+                        break;
+                    },
+                }
+            }
+        })
+    }
+
+    /// Returns the write set and events of the transaction at `version`, without the proofs
+    /// `get_transaction_outputs` computes.
+    pub fn get_write_set_and_events(
+        &self,
+        version: Version,
+    ) -> Result<(WriteSet, Vec<ContractEvent>)> {
+        gauged_api("get_write_set_and_events", || {
+            self.error_if_ledger_pruned("Transaction", version)?;
+
+            let write_set = self.ledger_db.write_set_db().get_write_set(version)?;
+            let events = self.ledger_db.event_db().get_events_by_version(version)?;
+            Ok((write_set, events))
+        })
+    }
+
+    /// Returns a state view reading the state K/V DB at `version`, which doesn't need a state
+    /// snapshot at `version`. See `HistoricalStateView` for reads of pruned values.
+    pub fn get_historical_state_view(&self, version: Version) -> Result<HistoricalStateView> {
+        let latest_version = self.get_latest_version()?;
+        ensure!(
+            version <= latest_version,
+            "Version {} > latest version {}",
+            version,
+            latest_version
+        );
+        Ok(HistoricalStateView::new(
+            Arc::clone(&self.state_store),
+            version,
+        ))
+    }
+
+    /// Estimates how much data the pruners would delete right now if the DB were opened with
+    /// `config`, without deleting anything. Only the pruners enabled in `config` are considered.
+    pub fn dry_run_pruning(&self, config: PrunerConfig) -> Result<PruningImpactReport> {
+        let mut report = PruningImpactReport::default();
+
+        let latest_version = match self.get_latest_version() {
+            Ok(version) => version,
+            Err(AptosDbError::NotFound(_)) => return Ok(report),
+            Err(err) => return Err(err),
+        };
+        let ledger_pruner_config = config.ledger_pruner_config;
+        if ledger_pruner_config.enable {
+            let target_version = latest_version.saturating_sub(ledger_pruner_config.prune_window);
+            report.merge(self.ledger_pruner.dry_run_prune(target_version)?);
+            report.merge(
+                self.state_store
+                    .state_db
+                    .state_kv_pruner
+                    .dry_run_prune(target_version)?,
+            );
+        }
+
+        // State merkle pruners work off the latest snapshot instead of the latest version.
+        if let Some((snapshot_version, _)) =
+            self.state_store.get_state_snapshot_before(Version::MAX)?
+        {
+            let state_merkle_pruner_config = config.state_merkle_pruner_config;
+            if state_merkle_pruner_config.enable {
+                let target_version =
+                    snapshot_version.saturating_sub(state_merkle_pruner_config.prune_window);
+                report.merge(
+                    self.state_store
+                        .state_db
+                        .state_merkle_pruner
+                        .dry_run_prune(target_version)?,
+                );
+            }
+            let epoch_snapshot_pruner_config = config.epoch_snapshot_pruner_config;
+            if epoch_snapshot_pruner_config.enable {
+                let target_version =
+                    snapshot_version.saturating_sub(epoch_snapshot_pruner_config.prune_window);
+                report.merge(
+                    self.state_store
+                        .state_db
+                        .epoch_snapshot_pruner
+                        .dry_run_prune(target_version)?,
+                );
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Sets the ledger and state K/V pruner targets to `target_version`, regardless of the prune
+    /// window, and blocks until everything before it is pruned. Errors if that takes longer than
+    /// `timeout`, in which case the pruning carries on in the background. Both pruners must be
+    /// enabled.
+    pub fn prune_to_version_blocking(
+        &self,
+        target_version: Version,
+        timeout: Duration,
+    ) -> Result<()> {
+        let latest_version = self.ledger_db.metadata_db().get_latest_version()?;
+        ensure!(
+            target_version <= latest_version,
+            "Target version {} > latest version {}",
+            target_version,
+            latest_version
+        );
+        let state_kv_pruner = &self.state_store.state_kv_pruner;
+        ensure!(
+            self.ledger_pruner.is_pruner_enabled() && state_kv_pruner.is_pruner_enabled(),
+            "Ledger pruner and state kv pruner must be enabled."
+        );
+
+        self.ledger_pruner.set_pruner_target_version(target_version);
+        state_kv_pruner.set_pruner_target_version(target_version);
+
+        let deadline = Instant::now() + timeout;
+        while self.ledger_pruner.is_pruning_pending() || state_kv_pruner.is_pruning_pending() {
+            if Instant::now() >= deadline {
+                bail!(
+                    "Timeout pruning to version {} after {:?}.",
+                    target_version,
+                    timeout
+                );
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        ensure!(
+            self.ledger_pruner.get_min_readable_version() >= target_version
+                && state_kv_pruner.get_min_readable_version() >= target_version,
+            "Pruners stopped short of version {}.",
+            target_version
+        );
+
+        Ok(())
+    }
+
+    /// Blocks until the indices of all the versions committed so far are written, if the DB is
+    /// opened in deferred index mode.
+    pub fn flush_index_queue(&self) -> Result<()> {
+        match &self.index_update_queue {
+            Some(queue) => queue.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns how far the latest ledger info visible on this replica is behind the latest ledger
+    /// info committed by the primary, measured in ledger info timestamps.
+    pub fn replica_lag(&self) -> Result<Duration> {
+        let primary_ledger_info = self
+            .replica_catch_up
+            .as_ref()
+            .ok_or_else(|| anyhow!("DB is not opened as a replica."))?
+            .primary_latest_ledger_info()?;
+        let timestamp_usecs = |ledger_info: Option<LedgerInfoWithSignatures>| {
+            ledger_info.map_or(0, |li| li.ledger_info().timestamp_usecs())
+        };
+        let primary_timestamp = timestamp_usecs(primary_ledger_info);
+        let replica_timestamp = timestamp_usecs(self.get_latest_ledger_info_option()?);
+        Ok(Duration::from_micros(
+            primary_timestamp.saturating_sub(replica_timestamp),
+        ))
+    }
+
     pub fn open_dbs(
         db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
@@ -291,6 +291,12 @@ fn verify_epochs(db: &AptosDB, ledger_infos_with_sigs: &[LedgerInfoWithSignature
         .cloned()
         .collect();
     assert_eq!(actual_epoch_change_lis, expected_epoch_change_lis);
+    let iterated_epoch_change_lis = db
+        .get_epoch_ending_ledger_info_iter_impl(0, latest_epoch, LIMIT)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(iterated_epoch_change_lis, expected_epoch_change_lis);
 
     let mut last_ver = 0;
     for li in ledger_infos_with_sigs {
@@ -687,6 +693,7 @@ fn verify_account_txns(
                 .get_account_transactions(
                     account,
                     first_seq_num,
+                    Order::Ascending,
                     limit,
                     true, /* include_events */
                     ledger_info.version(),
@@ -703,6 +710,21 @@ fn verify_account_txns(
                 )
                 .unwrap();
 
+            // Walking the index backwards yields the same transactions, in reverse.
+            let mut acct_txns_desc = db
+                .get_account_transactions(
+                    account,
+                    last_seq_num,
+                    Order::Descending,
+                    last_seq_num - first_seq_num + 1,
+                    true, /* include_events */
+                    ledger_info.version(),
+                )
+                .unwrap()
+                .into_inner();
+            acct_txns_desc.reverse();
+            assert_eq!(acct_txns_desc.as_slice(), acct_txns_with_proof.inner());
+
             let txns_and_events = acct_txns_with_proof
                 .into_inner()
                 .into_iter()
@@ -863,6 +885,7 @@ pub fn verify_committed_transactions(
                 .get_account_transactions(
                     txn.sender(),
                     txn.sequence_number(),
+                    Order::Ascending,
                     1,
                     true,
                     ledger_version,
@@ -895,10 +918,43 @@ pub fn verify_committed_transactions(
                 .verify(ledger_info, Some(cur_ver))
                 .unwrap();
             assert_eq!(txn_output_list_with_proof.transactions_and_outputs.len(), 1);
+
+            for (idx, event) in txn_to_commit.events().iter().enumerate() {
+                let (event_in_db, event_proof) = db
+                    .get_event_proof(cur_ver, idx as u64, ledger_version)
+                    .unwrap();
+                assert_eq!(&event_in_db, event);
+                event_proof
+                    .verify(ledger_info, event.hash(), cur_ver, idx as u64)
+                    .unwrap();
+            }
         }
         cur_ver += 1;
     }
 
+    // Fetch and verify all the transaction outputs in one go.
+    if !txns_to_commit.is_empty() {
+        let txn_output_list_with_proof = db
+            .get_transaction_outputs(first_version, txns_to_commit.len() as u64, ledger_version)
+            .unwrap();
+        txn_output_list_with_proof
+            .verify(ledger_info, Some(first_version))
+            .unwrap();
+        assert_eq!(
+            txn_output_list_with_proof.transactions_and_outputs.len(),
+            txns_to_commit.len()
+        );
+        for ((txn, txn_output), txn_to_commit) in txn_output_list_with_proof
+            .transactions_and_outputs
+            .iter()
+            .zip(txns_to_commit)
+        {
+            assert_eq!(txn, txn_to_commit.transaction());
+            assert_eq!(txn_output.write_set(), txn_to_commit.write_set());
+            assert_eq!(txn_output.events(), txn_to_commit.events());
+        }
+    }
+
     // Fetch and verify events.
     verify_events_by_event_key(
         db,
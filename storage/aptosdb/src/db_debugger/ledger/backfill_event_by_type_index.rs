@@ -0,0 +1,63 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, ledger_db::LedgerDb};
+use aptos_config::config::RocksdbConfigs;
+use aptos_schemadb::SchemaBatch;
+use aptos_storage_interface::Result;
+use aptos_types::transaction::Version;
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(about = "Back-fill the event by account and type index from existing event data.")]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    /// First version to index, usually the first version not pruned yet.
+    #[clap(long, default_value_t = 0)]
+    start_version: Version,
+
+    #[clap(long, default_value_t = 10000)]
+    batch_size: usize,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let ledger_db = LedgerDb::new(
+            self.db_dir.as_ref(),
+            RocksdbConfigs {
+                enable_storage_sharding: self.db_dir.sharding_config.enable_storage_sharding,
+                ..Default::default()
+            },
+            /*readonly=*/ false,
+        )?;
+        let event_db = ledger_db.event_db();
+
+        let end_version = match event_db.latest_version()? {
+            Some(latest_version) => latest_version + 1,
+            None => {
+                println!("No events found, nothing to do.");
+                return Ok(());
+            },
+        };
+
+        println!(
+            "Back-filling event by type index for versions [{}, {})...",
+            self.start_version, end_version
+        );
+        let mut version = self.start_version;
+        let mut num_entries = 0;
+        while version < end_version {
+            let batch_end = std::cmp::min(end_version, version + self.batch_size as Version);
+            let batch = SchemaBatch::new();
+            num_entries += event_db.backfill_event_by_type_index(version, batch_end, &batch)?;
+            event_db.write_schemas(batch)?;
+            println!("Indexed until version {}.", batch_end);
+            version = batch_end;
+        }
+        println!("Done. Wrote {} index entries.", num_entries);
+
+        Ok(())
+    }
+}
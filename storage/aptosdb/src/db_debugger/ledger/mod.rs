@@ -1,8 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod backfill_event_by_type_index;
 mod check_range_proof;
 mod check_txn_info_hashes;
+mod upgrade_block_info;
 
 use aptos_storage_interface::Result;
 
@@ -11,6 +13,8 @@ use aptos_storage_interface::Result;
 pub enum Cmd {
     CheckTransactionInfoHashes(check_txn_info_hashes::Cmd),
     CheckRangeProof(check_range_proof::Cmd),
+    BackfillEventByTypeIndex(backfill_event_by_type_index::Cmd),
+    UpgradeBlockInfo(upgrade_block_info::Cmd),
 }
 
 impl Cmd {
@@ -18,6 +22,8 @@ impl Cmd {
         match self {
             Self::CheckTransactionInfoHashes(cmd) => cmd.run(),
             Self::CheckRangeProof(cmd) => cmd.run(),
+            Self::BackfillEventByTypeIndex(cmd) => cmd.run(),
+            Self::UpgradeBlockInfo(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,61 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, ledger_db::LedgerDb};
+use aptos_config::config::RocksdbConfigs;
+use aptos_schemadb::SchemaBatch;
+use aptos_storage_interface::Result;
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(about = "Upgrade V0 block infos to V1 by replaying the NewBlockEvents.")]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long, default_value_t = 0)]
+    start_height: u64,
+
+    /// Exclusive, defaults to upgrading all the way to the latest block.
+    #[clap(long, default_value_t = u64::MAX)]
+    end_height: u64,
+
+    #[clap(long, default_value_t = 10000)]
+    batch_size: u64,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let ledger_db = LedgerDb::new(
+            self.db_dir.as_ref(),
+            RocksdbConfigs {
+                enable_storage_sharding: self.db_dir.sharding_config.enable_storage_sharding,
+                ..Default::default()
+            },
+            /*readonly=*/ false,
+        )?;
+        let metadata_db = ledger_db.metadata_db();
+
+        println!(
+            "Upgrading block infos for heights [{}, {})...",
+            self.start_height, self.end_height
+        );
+        let mut height = self.start_height;
+        let mut num_upgraded = 0;
+        while height < self.end_height {
+            let batch_end = self.end_height.min(height.saturating_add(self.batch_size));
+            let batch = SchemaBatch::new();
+            num_upgraded +=
+                metadata_db.upgrade_block_info(ledger_db.event_db(), height, batch_end, &batch)?;
+            metadata_db.write_schemas(batch)?;
+            match metadata_db.get_block_info(batch_end)? {
+                Some(_) => println!("Upgraded until height {}.", batch_end),
+                None => break,
+            }
+            height = batch_end;
+        }
+        println!("Done. Upgraded {} block infos.", num_upgraded);
+
+        Ok(())
+    }
+}
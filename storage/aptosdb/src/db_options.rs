@@ -19,6 +19,7 @@ pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
         EPOCH_BY_VERSION_CF_NAME,
         EVENT_ACCUMULATOR_CF_NAME,
         EVENT_BY_KEY_CF_NAME,
+        EVENT_BY_TYPE_CF_NAME,
         EVENT_BY_VERSION_CF_NAME,
         EVENT_CF_NAME,
         LEDGER_INFO_CF_NAME,
@@ -41,6 +42,7 @@ pub(super) fn event_db_column_families() -> Vec<ColumnFamilyName> {
         DB_METADATA_CF_NAME,
         EVENT_ACCUMULATOR_CF_NAME,
         EVENT_BY_KEY_CF_NAME,
+        EVENT_BY_TYPE_CF_NAME,
         EVENT_BY_VERSION_CF_NAME,
         EVENT_CF_NAME,
     ]
@@ -118,25 +120,30 @@ fn gen_cfds<F>(
     cf_opts_post_processor: F,
 ) -> Vec<ColumnFamilyDescriptor>
 where
-    F: Fn(ColumnFamilyName, &mut Options),
+    F: Fn(ColumnFamilyName, &mut Options, &mut BlockBasedOptions),
 {
-    let mut table_options = BlockBasedOptions::default();
-    table_options.set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
-    table_options.set_block_size(rocksdb_config.block_size as usize);
     let cache = Cache::new_lru_cache(rocksdb_config.block_cache_size as usize);
-    table_options.set_block_cache(&cache);
     let mut cfds = Vec::with_capacity(cfs.len());
     for cf_name in cfs {
+        let mut table_options = BlockBasedOptions::default();
+        table_options
+            .set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
+        table_options.set_block_size(rocksdb_config.block_size as usize);
+        table_options.set_block_cache(&cache);
         let mut cf_opts = Options::default();
         cf_opts.set_compression_type(DBCompressionType::Lz4);
+        cf_opts_post_processor(cf_name, &mut cf_opts, &mut table_options);
         cf_opts.set_block_based_table_factory(&table_options);
-        cf_opts_post_processor(cf_name, &mut cf_opts);
         cfds.push(ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts));
     }
     cfds
 }
 
-fn with_state_key_extractor_processor(cf_name: ColumnFamilyName, cf_opts: &mut Options) {
+fn with_state_key_extractor_processor(
+    cf_name: ColumnFamilyName,
+    cf_opts: &mut Options,
+    _table_options: &mut BlockBasedOptions,
+) {
     if cf_name == STATE_VALUE_CF_NAME {
         let prefix_extractor =
             SliceTransform::create("state_key_extractor", state_key_extractor, None);
@@ -144,50 +151,77 @@ fn with_state_key_extractor_processor(cf_name: ColumnFamilyName, cf_opts: &mut O
     }
 }
 
+/// Adds a bloom filter to each SST file of the transaction by hash index, so that looking up the
+/// hash of a transaction not in the DB rarely needs to read data blocks.
+fn with_transaction_hash_bloom_filter_processor(
+    bits_per_key: u32,
+) -> impl Fn(ColumnFamilyName, &mut Options, &mut BlockBasedOptions) {
+    move |cf_name, _cf_opts, table_options| {
+        if cf_name == TRANSACTION_BY_HASH_CF_NAME && bits_per_key > 0 {
+            table_options.set_bloom_filter(bits_per_key as f64, false);
+        }
+    }
+}
+
 pub(super) fn gen_event_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
     let cfs = event_db_column_families();
-    gen_cfds(rocksdb_config, cfs, |_, _| {})
+    gen_cfds(rocksdb_config, cfs, |_, _, _| {})
 }
 
 pub(super) fn gen_transaction_accumulator_cfds(
     rocksdb_config: &RocksdbConfig,
 ) -> Vec<ColumnFamilyDescriptor> {
     let cfs = transaction_accumulator_db_column_families();
-    gen_cfds(rocksdb_config, cfs, |_, _| {})
+    gen_cfds(rocksdb_config, cfs, |_, _, _| {})
 }
 
-pub(super) fn gen_transaction_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
+pub(super) fn gen_transaction_cfds(
+    rocksdb_config: &RocksdbConfig,
+    transaction_hash_bloom_bits: u32,
+) -> Vec<ColumnFamilyDescriptor> {
     let cfs = transaction_db_column_families();
-    gen_cfds(rocksdb_config, cfs, |_, _| {})
+    gen_cfds(
+        rocksdb_config,
+        cfs,
+        with_transaction_hash_bloom_filter_processor(transaction_hash_bloom_bits),
+    )
 }
 
 pub(super) fn gen_transaction_info_cfds(
     rocksdb_config: &RocksdbConfig,
 ) -> Vec<ColumnFamilyDescriptor> {
     let cfs = transaction_info_db_column_families();
-    gen_cfds(rocksdb_config, cfs, |_, _| {})
+    gen_cfds(rocksdb_config, cfs, |_, _, _| {})
 }
 
 pub(super) fn gen_write_set_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
     let cfs = write_set_db_column_families();
-    gen_cfds(rocksdb_config, cfs, |_, _| {})
+    gen_cfds(rocksdb_config, cfs, |_, _, _| {})
 }
 
 pub(super) fn gen_ledger_metadata_cfds(
     rocksdb_config: &RocksdbConfig,
 ) -> Vec<ColumnFamilyDescriptor> {
     let cfs = ledger_metadata_db_column_families();
-    gen_cfds(rocksdb_config, cfs, |_, _| {})
+    gen_cfds(rocksdb_config, cfs, |_, _, _| {})
 }
 
-pub(super) fn gen_ledger_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
+pub(super) fn gen_ledger_cfds(
+    rocksdb_config: &RocksdbConfig,
+    transaction_hash_bloom_bits: u32,
+) -> Vec<ColumnFamilyDescriptor> {
     let cfs = ledger_db_column_families();
-    gen_cfds(rocksdb_config, cfs, with_state_key_extractor_processor)
+    let with_transaction_hash_bloom_filter =
+        with_transaction_hash_bloom_filter_processor(transaction_hash_bloom_bits);
+    gen_cfds(rocksdb_config, cfs, |cf_name, cf_opts, table_options| {
+        with_state_key_extractor_processor(cf_name, cf_opts, table_options);
+        with_transaction_hash_bloom_filter(cf_name, cf_opts, table_options);
+    })
 }
 
 pub(super) fn gen_state_merkle_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
     let cfs = state_merkle_db_column_families();
-    gen_cfds(rocksdb_config, cfs, |_, _| {})
+    gen_cfds(rocksdb_config, cfs, |_, _, _| {})
 }
 
 pub(super) fn gen_state_kv_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
@@ -9,8 +9,11 @@
 use super::AptosDB;
 use crate::{
     schema::{
-        event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        event_by_type::{event_type_hash, EventByTypeSchema},
+        event_by_version::EventByVersionSchema,
     },
     utils::iterators::EventsByVersionIter,
 };
@@ -21,15 +24,16 @@ use aptos_crypto::{
     HashValue,
 };
 use aptos_schemadb::{iterator::SchemaIterator, schema::ValueCodec, ReadOptions, SchemaBatch, DB};
-use aptos_storage_interface::{db_ensure as ensure, db_other_bail, AptosDbError, Result};
+use aptos_storage_interface::{db_ensure as ensure, db_other_bail, AptosDbError, Order, Result};
 use aptos_types::{
     account_address::AccountAddress,
     account_config::{new_block_event_key, NewBlockEvent},
     contract_event::ContractEvent,
     event::EventKey,
-    proof::position::Position,
+    proof::{position::Position, EventAccumulatorProof},
     transaction::Version,
 };
+use move_core_types::language_storage::StructTag;
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     convert::{TryFrom, TryInto},
@@ -57,6 +61,58 @@ impl EventStore {
             .ok_or_else(|| AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)))
     }
 
+    /// Returns the event at `index` of the transaction at `version`, together with the proof of
+    /// its inclusion in the event accumulator of that transaction.
+    pub fn get_event_with_proof_by_version_and_index(
+        &self,
+        version: Version,
+        index: u64,
+    ) -> Result<(ContractEvent, EventAccumulatorProof)> {
+        let mut events = vec![];
+        let mut iter = self.event_db.iter::<EventSchema>(ReadOptions::default())?;
+        iter.seek(&version)?;
+        while let Some(((ver, _index), event)) = iter.next().transpose()? {
+            if ver != version {
+                break;
+            }
+            events.push(event);
+        }
+        let num_events = events.len() as u64;
+        let event = events
+            .get(index as usize)
+            .cloned()
+            .ok_or_else(|| AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)))?;
+
+        let proof = if self
+            .event_db
+            .get::<EventAccumulatorSchema>(&(version, Position::from_leaf_index(index)))?
+            .is_some()
+        {
+            MerkleAccumulator::<_, EventAccumulatorHasher>::get_proof(
+                &EventHashReader::new(self, version),
+                num_events,
+                index,
+            )?
+        } else {
+            // The accumulator is not persisted if the events were written with indices skipped,
+            // rebuild it from the events instead.
+            let event_hashes: Vec<HashValue> = events.iter().map(ContractEvent::hash).collect();
+            let (_root_hash, writes) =
+                MerkleAccumulator::<EmptyReader, EventAccumulatorHasher>::append(
+                    &EmptyReader,
+                    0,
+                    &event_hashes,
+                )?;
+            MerkleAccumulator::<_, EventAccumulatorHasher>::get_proof(
+                &InMemoryHashReader(writes.into_iter().collect()),
+                num_events,
+                index,
+            )?
+        };
+
+        Ok((event, proof))
+    }
+
     pub fn get_txn_ver_by_seq_num(&self, event_key: &EventKey, seq_num: u64) -> Result<u64> {
         let (ver, _) = self
             .event_db
@@ -154,6 +210,61 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Given `address` and `event_type`, returns events of that type emitted under any event key
+    /// created by `address`, identified by transaction version and index among all events emitted
+    /// by the same transaction.
+    ///
+    /// `start_version` is a cursor: for `Order::Ascending` the result starts from the first event
+    /// at or after it, for `Order::Descending` from the last event at or before it. Result won't
+    /// contain records with a transaction version > `ledger_version` and follows `order`.
+    pub fn lookup_events_by_account_and_type(
+        &self,
+        address: AccountAddress,
+        event_type: &StructTag,
+        start_version: Version,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        let type_hash = event_type_hash(event_type)?;
+        let mut iter = match order {
+            Order::Ascending => {
+                let mut iter = self
+                    .event_db
+                    .iter::<EventByTypeSchema>(ReadOptions::default())?;
+                iter.seek(&(address, type_hash, start_version, 0))?;
+                iter
+            },
+            Order::Descending => {
+                let mut iter = self
+                    .event_db
+                    .rev_iter::<EventByTypeSchema>(ReadOptions::default())?;
+                let cursor = std::cmp::min(start_version, ledger_version);
+                iter.seek_for_prev(&(address, type_hash, cursor, u64::MAX))?;
+                iter
+            },
+        };
+
+        let mut result = Vec::new();
+        while result.len() < limit as usize {
+            let ((addr, hash, ver, idx), ()) = match iter.next().transpose()? {
+                Some(item) => item,
+                None => break,
+            };
+            if addr != address || hash != type_hash || ver > ledger_version {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
     fn lookup_event_by_key(
         &self,
         event_key: &EventKey,
@@ -385,6 +496,17 @@ impl<'a> HashReader for EventHashReader<'a> {
     }
 }
 
+struct InMemoryHashReader(HashMap<Position, HashValue>);
+
+impl HashReader for InMemoryHashReader {
+    fn get(&self, position: Position) -> Result<HashValue, anyhow::Error> {
+        self.0
+            .get(&position)
+            .cloned()
+            .ok_or_else(|| anyhow!("Hash at position {:?} not found.", position))
+    }
+}
+
 pub(crate) struct EmptyReader;
 
 // Asserts `get()` is never called.
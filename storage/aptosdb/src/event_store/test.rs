@@ -6,11 +6,13 @@ use super::*;
 use crate::{db::AptosDB, event_store::EventStore};
 use aptos_crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH;
 use aptos_proptest_helpers::Index;
+use aptos_storage_interface::MAX_REQUEST_LIMIT;
 use aptos_temppath::TempPath;
 use aptos_types::{
     account_address::AccountAddress,
     contract_event::ContractEvent,
     event::EventKey,
+    proof::accumulator::InMemoryEventAccumulator,
     proptest_types::{AccountInfoUniverse, ContractEventGen},
 };
 use itertools::Itertools;
@@ -93,6 +95,60 @@ proptest! {
     }
 }
 
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_get_event_with_proof(
+        mut universe in any_with::<AccountInfoUniverse>(3),
+        gen_batches in vec(vec((any::<Index>(), any::<ContractEventGen>()), 1..=10), 1..20),
+        skip_index in any::<bool>(),
+    ) {
+        let event_batches: Vec<Vec<_>> = gen_batches
+            .into_iter()
+            .map(|gens| {
+                gens.into_iter()
+                    .map(|(index, gen)| gen.materialize(*index, &mut universe))
+                    .collect()
+            })
+            .collect();
+
+        test_get_event_with_proof_impl(event_batches, skip_index);
+    }
+}
+
+fn test_get_event_with_proof_impl(event_batches: Vec<Vec<ContractEvent>>, skip_index: bool) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.event_store;
+    let event_db = db.ledger_db.event_db();
+
+    let batch = SchemaBatch::new();
+    event_batches.iter().enumerate().for_each(|(ver, events)| {
+        event_db
+            .put_events(ver as u64, events, skip_index, &batch)
+            .unwrap();
+    });
+    event_db.write_schemas(batch).unwrap();
+
+    for (ver, events) in event_batches.iter().enumerate() {
+        let event_hashes: Vec<_> = events.iter().map(ContractEvent::hash).collect();
+        let event_root_hash = InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash();
+        for (idx, event) in events.iter().enumerate() {
+            let (event_in_db, proof) = store
+                .get_event_with_proof_by_version_and_index(ver as Version, idx as u64)
+                .unwrap();
+            assert_eq!(&event_in_db, event);
+            proof
+                .verify(event_root_hash, event.hash(), idx as u64)
+                .unwrap();
+        }
+        assert!(store
+            .get_event_with_proof_by_version_and_index(ver as Version, events.len() as u64)
+            .is_err());
+    }
+}
+
 fn test_index_get_impl(event_batches: Vec<Vec<ContractEvent>>) {
     // Put into db.
     let tmp_dir = TempPath::new();
@@ -294,3 +350,236 @@ proptest! {
         test_get_last_version_before_timestamp_impl(new_block_events)
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_lookup_events_by_account_and_type(
+        mut universe in any_with::<AccountInfoUniverse>(3),
+        gen_batches in vec(vec((any::<Index>(), any::<ContractEventGen>()), 0..=2), 0..100),
+        limit in 1..5u64,
+    ) {
+        let event_batches = gen_batches
+            .into_iter()
+            .map(|gens| {
+                gens.into_iter()
+                    .map(|(index, gen)| gen.materialize(*index, &mut universe))
+                    .collect()
+            })
+            .collect();
+
+        test_lookup_events_by_account_and_type_impl(event_batches, limit);
+    }
+}
+
+fn expected_events_by_account_and_type(
+    event_batches: &[Vec<ContractEvent>],
+) -> HashMap<(AccountAddress, StructTag), Vec<(Version, u64)>> {
+    let mut expected = HashMap::new();
+    for (ver, events) in event_batches.iter().enumerate() {
+        for (idx, event) in events.iter().enumerate() {
+            if let (ContractEvent::V1(v1), TypeTag::Struct(struct_tag)) = (event, event.type_tag())
+            {
+                expected
+                    .entry((v1.key().get_creator_address(), struct_tag.as_ref().clone()))
+                    .or_insert_with(Vec::new)
+                    .push((ver as Version, idx as u64));
+            }
+        }
+    }
+    expected
+}
+
+fn test_lookup_events_by_account_and_type_impl(event_batches: Vec<Vec<ContractEvent>>, limit: u64) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.event_store;
+    let event_db = &db.ledger_db.event_db();
+
+    let batch = SchemaBatch::new();
+    event_batches.iter().enumerate().for_each(|(ver, events)| {
+        event_db
+            .put_events(ver as u64, events, /*skip_index=*/ false, &batch)
+            .unwrap();
+    });
+    event_db.write_schemas(batch).unwrap();
+    let ledger_version = event_batches.len() as u64;
+
+    for ((address, event_type), expected) in expected_events_by_account_and_type(&event_batches) {
+        assert_eq!(
+            store
+                .lookup_events_by_account_and_type(
+                    address,
+                    &event_type,
+                    0,
+                    Order::Ascending,
+                    MAX_REQUEST_LIMIT,
+                    ledger_version,
+                )
+                .unwrap(),
+            expected,
+        );
+
+        let mut expected_rev = expected.clone();
+        expected_rev.reverse();
+        assert_eq!(
+            store
+                .lookup_events_by_account_and_type(
+                    address,
+                    &event_type,
+                    u64::MAX,
+                    Order::Descending,
+                    MAX_REQUEST_LIMIT,
+                    ledger_version,
+                )
+                .unwrap(),
+            expected_rev,
+        );
+
+        // Limit caps the result from the cursor on, in both directions.
+        let (first_version, _) = expected[0];
+        let (last_version, _) = *expected.last().unwrap();
+        assert_eq!(
+            store
+                .lookup_events_by_account_and_type(
+                    address,
+                    &event_type,
+                    first_version,
+                    Order::Ascending,
+                    limit,
+                    ledger_version,
+                )
+                .unwrap(),
+            expected
+                .iter()
+                .take(limit as usize)
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            store
+                .lookup_events_by_account_and_type(
+                    address,
+                    &event_type,
+                    last_version,
+                    Order::Descending,
+                    limit,
+                    ledger_version,
+                )
+                .unwrap(),
+            expected_rev
+                .iter()
+                .take(limit as usize)
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+
+        // Nothing after the last event, nor at or before a version earlier than the first one.
+        assert!(store
+            .lookup_events_by_account_and_type(
+                address,
+                &event_type,
+                last_version + 1,
+                Order::Ascending,
+                limit,
+                ledger_version,
+            )
+            .unwrap()
+            .is_empty());
+        if first_version > 0 {
+            assert!(store
+                .lookup_events_by_account_and_type(
+                    address,
+                    &event_type,
+                    first_version - 1,
+                    Order::Descending,
+                    limit,
+                    ledger_version,
+                )
+                .unwrap()
+                .is_empty());
+
+            // Events after ledger_version are invisible.
+            assert!(store
+                .lookup_events_by_account_and_type(
+                    address,
+                    &event_type,
+                    0,
+                    Order::Ascending,
+                    MAX_REQUEST_LIMIT,
+                    first_version - 1,
+                )
+                .unwrap()
+                .is_empty());
+        }
+    }
+}
+
+#[test]
+fn test_backfill_event_by_type_index() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.event_store;
+    let event_db = &db.ledger_db.event_db();
+
+    let address = AccountAddress::random();
+    let event_type = NewBlockEvent::struct_tag();
+    let events = (0..10)
+        .map(|seq| {
+            ContractEvent::new_v1(
+                EventKey::new(seq % 2, address),
+                seq / 2,
+                TypeTag::Struct(Box::new(event_type.clone())),
+                vec![],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Events written without indices are not visible through the index.
+    let batch = SchemaBatch::new();
+    events.iter().enumerate().for_each(|(ver, event)| {
+        event_db
+            .put_events(
+                ver as u64,
+                &[event.clone()],
+                /*skip_index=*/ true,
+                &batch,
+            )
+            .unwrap();
+    });
+    event_db.write_schemas(batch).unwrap();
+    let lookup = || {
+        store
+            .lookup_events_by_account_and_type(
+                address,
+                &event_type,
+                0,
+                Order::Ascending,
+                MAX_REQUEST_LIMIT,
+                events.len() as u64,
+            )
+            .unwrap()
+    };
+    assert!(lookup().is_empty());
+
+    // Back-filling in two overlapping rounds indexes every event exactly once.
+    let batch = SchemaBatch::new();
+    assert_eq!(
+        event_db.backfill_event_by_type_index(0, 6, &batch).unwrap(),
+        6
+    );
+    assert_eq!(
+        event_db
+            .backfill_event_by_type_index(4, events.len() as u64, &batch)
+            .unwrap(),
+        6
+    );
+    event_db.write_schemas(batch).unwrap();
+    assert_eq!(
+        lookup(),
+        (0..events.len() as u64)
+            .map(|ver| (ver, 0))
+            .collect::<Vec<_>>()
+    );
+}
@@ -14,20 +14,20 @@ use aptos_crypto::{
 };
 use aptos_infallible::Mutex;
 use aptos_storage_interface::{
-    cached_state_view::ShardedStateCache, state_delta::StateDelta, AptosDbError, DbReader,
-    DbWriter, ExecutedTrees, MAX_REQUEST_LIMIT,
+    block_info::BlockParticipationStats, cached_state_view::ShardedStateCache,
+    state_delta::StateDelta, AptosDbError, DbReader, DbWriter, ExecutedTrees, MAX_REQUEST_LIMIT,
 };
 use aptos_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::{AccountResource, NewBlockEvent},
-    contract_event::EventWithVersion,
+    contract_event::{ContractEvent, EventWithVersion},
     epoch_state::EpochState,
     event::{EventHandle, EventKey},
     ledger_info::LedgerInfoWithSignatures,
     proof::{
         accumulator::InMemoryAccumulator, position::Position, AccumulatorConsistencyProof,
-        AccumulatorRangeProof, SparseMerkleProofExt, TransactionAccumulatorProof,
+        AccumulatorRangeProof, EventProof, SparseMerkleProofExt, TransactionAccumulatorProof,
         TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
         TransactionInfoListWithProof, TransactionInfoWithProof,
     },
@@ -35,7 +35,7 @@ use aptos_types::{
     state_store::{
         state_key::StateKey,
         state_key_prefix::StateKeyPrefix,
-        state_storage_usage::StateStorageUsage,
+        state_storage_usage::{StateStorageUsage, StateStorageUsageDelta},
         state_value::{StateValue, StateValueChunkWithProof},
         table, ShardedStateUpdates,
     },
@@ -47,7 +47,7 @@ use aptos_types::{
 };
 use dashmap::DashMap;
 use itertools::zip_eq;
-use move_core_types::move_resource::MoveStructType;
+use move_core_types::{language_storage::StructTag, move_resource::MoveStructType};
 use std::{
     borrow::Borrow,
     collections::HashMap,
@@ -409,6 +409,15 @@ impl DbReader for FakeAptosDB {
         (&self.inner as &dyn DbReader).get_epoch_ending_ledger_infos(start_epoch, end_epoch)
     }
 
+    fn get_epoch_ending_ledger_info_iter(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<LedgerInfoWithSignatures>> + '_>> {
+        self.inner
+            .get_epoch_ending_ledger_info_iter(start_epoch, end_epoch)
+    }
+
     fn get_transactions(
         &self,
         start_version: Version,
@@ -584,6 +593,35 @@ impl DbReader for FakeAptosDB {
             .get_events(event_key, start, order, limit, ledger_version)
     }
 
+    fn get_events_by_account_and_type(
+        &self,
+        address: AccountAddress,
+        event_type_tag: &StructTag,
+        start: u64,
+        order: aptos_storage_interface::Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.inner.get_events_by_account_and_type(
+            address,
+            event_type_tag,
+            start,
+            order,
+            limit,
+            ledger_version,
+        )
+    }
+
+    fn get_event_proof(
+        &self,
+        version: Version,
+        event_index: u64,
+        ledger_version: Version,
+    ) -> Result<(ContractEvent, EventProof)> {
+        self.inner
+            .get_event_proof(version, event_index, ledger_version)
+    }
+
     fn get_block_timestamp(&self, version: Version) -> Result<u64> {
         gauged_api("get_block_timestamp", || {
             ensure!(version <= self.get_latest_version()?);
@@ -608,10 +646,22 @@ impl DbReader for FakeAptosDB {
         self.inner.get_block_info_by_version(version)
     }
 
+    fn get_block_height_containing_version(&self, version: Version) -> Result<u64> {
+        self.inner.get_block_height_containing_version(version)
+    }
+
     fn get_block_info_by_height(&self, height: u64) -> Result<(Version, Version, NewBlockEvent)> {
         self.inner.get_block_info_by_height(height)
     }
 
+    fn get_participation_stats(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> Result<Vec<BlockParticipationStats>> {
+        self.inner.get_participation_stats(start_block, end_block)
+    }
+
     fn get_last_version_before_timestamp(
         &self,
         timestamp: u64,
@@ -649,6 +699,19 @@ impl DbReader for FakeAptosDB {
         })
     }
 
+    fn is_state_checkpoint_version(&self, version: Version) -> Result<bool> {
+        gauged_api("is_state_checkpoint_version", || {
+            ensure!(version <= self.get_latest_version()?);
+
+            Ok(self
+                .txn_info_by_version
+                .get(&version)
+                .ok_or_else(|| format_err!("No transaction info at version {}", version))?
+                .state_checkpoint_hash()
+                .is_some())
+        })
+    }
+
     fn get_state_snapshot_before(
         &self,
         next_version: Version,
@@ -671,12 +734,19 @@ impl DbReader for FakeAptosDB {
         &self,
         address: aptos_types::PeerId,
         seq_num: u64,
+        order: aptos_storage_interface::Order,
         limit: u64,
         include_events: bool,
         ledger_version: Version,
     ) -> Result<aptos_types::transaction::AccountTransactionsWithProof> {
-        self.inner
-            .get_account_transactions(address, seq_num, limit, include_events, ledger_version)
+        self.inner.get_account_transactions(
+            address,
+            seq_num,
+            order,
+            limit,
+            include_events,
+            ledger_version,
+        )
     }
 
     fn get_state_proof_with_ledger_info(
@@ -749,6 +819,15 @@ impl DbReader for FakeAptosDB {
             .get_state_value_with_proof_by_version_ext(state_key, version)
     }
 
+    fn get_state_values_with_proofs_by_version_ext(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<(Option<StateValue>, SparseMerkleProofExt)>> {
+        self.inner
+            .get_state_values_with_proofs_by_version_ext(state_keys, version)
+    }
+
     fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
         // If the genesis is not executed yet, we need to get the executed trees from the inner AptosDB
         // This is because when we call save_transactions for the genesis block, we call [AptosDB::save_transactions]
@@ -788,6 +867,10 @@ impl DbReader for FakeAptosDB {
         Ok(HashValue::zero())
     }
 
+    fn get_accumulator_root_hashes(&self, versions: &[Version]) -> Result<Vec<HashValue>> {
+        Ok(vec![HashValue::zero(); versions.len()])
+    }
+
     fn get_accumulator_consistency_proof(
         &self,
         client_known_version: Option<Version>,
@@ -844,9 +927,21 @@ impl DbReader for FakeAptosDB {
         self.inner.indexer_enabled()
     }
 
+    fn get_indexer_lag(&self) -> Result<Option<u64>> {
+        self.inner.get_indexer_lag()
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         self.inner.get_state_storage_usage(version)
     }
+
+    fn get_state_storage_usage_delta(
+        &self,
+        from: Version,
+        to: Version,
+    ) -> Result<StateStorageUsageDelta> {
+        self.inner.get_state_storage_usage_delta(from, to)
+    }
 }
 
 /// This is necessary for constructing the [ExecutedTrees] to serve [DbReader::get_latest_executed_trees]
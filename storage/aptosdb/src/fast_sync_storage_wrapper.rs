@@ -41,7 +41,7 @@ impl FastSyncStorageWrapper {
     /// If the db is empty and configured to do fast sync, we return a FastSyncStorageWrapper
     /// Otherwise, we returns AptosDB directly and the FastSyncStorageWrapper is None
     pub fn initialize_dbs(config: &NodeConfig) -> Result<Either<AptosDB, Self>> {
-        let db_main = AptosDB::open(
+        let db_main = AptosDB::open_with_wal(
             config.storage.get_dir_paths(),
             /*readonly=*/ false,
             config.storage.storage_pruner_config,
@@ -49,6 +49,7 @@ impl FastSyncStorageWrapper {
             config.storage.enable_indexer,
             config.storage.buffered_state_target_items,
             config.storage.max_num_nodes_per_lru_cache_shard,
+            &config.storage.wal_config,
         )
         .map_err(|err| anyhow!("fast sync DB failed to open {}", err))?;
 
@@ -67,7 +68,7 @@ impl FastSyncStorageWrapper {
                 == 0)
         {
             db_dir.push(SECONDARY_DB_DIR);
-            let secondary_db = AptosDB::open(
+            let secondary_db = AptosDB::open_with_wal(
                 StorageDirPaths::from_path(db_dir.as_path()),
                 /*readonly=*/ false,
                 config.storage.storage_pruner_config,
@@ -75,6 +76,7 @@ impl FastSyncStorageWrapper {
                 config.storage.enable_indexer,
                 config.storage.buffered_state_target_items,
                 config.storage.max_num_nodes_per_lru_cache_shard,
+                &config.storage.wal_config,
             )
             .map_err(|err| anyhow!("Secondary DB failed to open {}", err))?;
 
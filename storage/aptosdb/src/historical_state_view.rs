@@ -0,0 +1,81 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{pruner::PrunerManager, state_store::StateStore};
+use aptos_storage_interface::{errors::AptosDbError, DbReader, Result};
+use aptos_types::{
+    state_store::{
+        errors::StateviewError, state_key::StateKey, state_storage_usage::StateStorageUsage,
+        state_value::StateValue, TStateView,
+    },
+    transaction::Version,
+};
+use std::sync::Arc;
+
+/// State view reading the state K/V DB at a past version, without a state snapshot at that
+/// version.
+///
+/// Once the state K/V pruner passed the version, the values overwritten since are gone, and
+/// reading them errors with `AptosDbError::Pruned`. Values still current at the version can be
+/// read even then.
+pub struct HistoricalStateView {
+    state_store: Arc<StateStore>,
+    version: Version,
+}
+
+impl HistoricalStateView {
+    pub(crate) fn new(state_store: Arc<StateStore>, version: Version) -> Self {
+        Self {
+            state_store,
+            version,
+        }
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Whether the value of `state_key` at the version is pruned.
+    pub fn is_pruned(&self, state_key: &StateKey) -> Result<bool> {
+        Ok(self.lookup(state_key)?.is_err())
+    }
+
+    /// Reads `state_key`, returning the min readable version as the inner error if it is pruned.
+    fn lookup(&self, state_key: &StateKey) -> Result<Result<Option<StateValue>, Version>> {
+        let value = self
+            .state_store
+            .get_state_value_by_version(state_key, self.version)?;
+        let min_readable_version = self.state_store.state_kv_pruner.get_min_readable_version();
+        // Before the min readable version, a missing value is either pruned or never existed,
+        // which can't be told apart.
+        Ok(if value.is_none() && self.version < min_readable_version {
+            Err(min_readable_version)
+        } else {
+            Ok(value)
+        })
+    }
+
+    fn get(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        self.lookup(state_key)?.map_err(|min_readable_version| {
+            AptosDbError::Pruned(
+                format!("State value of {:?}", state_key),
+                self.version,
+                min_readable_version,
+            )
+        })
+    }
+}
+
+impl TStateView for HistoricalStateView {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateviewError> {
+        self.get(state_key).map_err(Into::into)
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage, StateviewError> {
+        self.state_store
+            .get_usage(Some(self.version))
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ledger_db::LedgerDb;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_schemadb::SchemaBatch;
+use aptos_storage_interface::{AptosDbError, Result};
+use aptos_types::transaction::Version;
+use std::{
+    cmp,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    thread::JoinHandle,
+};
+
+/// Max number of versions whose indices are written in a single batch.
+const MAX_VERSIONS_PER_INDEX_BATCH: usize = 10_000;
+
+enum Command {
+    /// Index all the versions up to and including the given one.
+    Index(Version),
+    /// Consider all the versions before the given one indexed, e.g. after a state snapshot is
+    /// restored together with its indices.
+    SkipTo(Version),
+    /// Index all the versions requested so far, and reply with the outcome.
+    Flush(mpsc::Sender<Result<()>>),
+    Stop,
+}
+
+/// Writes the event and account transaction indices of the versions in
+/// [first_version, first_version + num_versions), reading the transactions and events from the DB.
+fn write_indices(ledger_db: &LedgerDb, first_version: Version, num_versions: usize) -> Result<()> {
+    let transactions = ledger_db
+        .transaction_db()
+        .get_transaction_iter(first_version, num_versions)?;
+    let events = ledger_db
+        .event_db()
+        .get_events_by_version_iter(first_version, num_versions)?;
+
+    let event_batch = SchemaBatch::new();
+    let transaction_batch = SchemaBatch::new();
+    for (version, (transaction, events)) in (first_version..).zip(transactions.zip(events)) {
+        ledger_db
+            .event_db()
+            .put_event_indices(version, &events?, &event_batch)?;
+        ledger_db.transaction_db().put_transaction_indices(
+            version,
+            &transaction?,
+            &transaction_batch,
+        )?;
+    }
+    ledger_db.event_db().write_schemas(event_batch)?;
+    ledger_db.transaction_db().write_schemas(transaction_batch)
+}
+
+/// Indexes the versions from `next_version` up to and including `target_version`, in batches,
+/// persisting the progress and advancing `next_version` after each of them.
+fn index_up_to(
+    ledger_db: &LedgerDb,
+    next_version: &AtomicU64,
+    target_version: Version,
+) -> Result<()> {
+    loop {
+        let first_version = next_version.load(Ordering::Acquire);
+        if first_version > target_version {
+            return Ok(());
+        }
+        let num_versions = cmp::min(
+            target_version - first_version + 1,
+            MAX_VERSIONS_PER_INDEX_BATCH as u64,
+        ) as usize;
+        write_indices(ledger_db, first_version, num_versions)?;
+        let last_version = first_version + num_versions as Version - 1;
+        ledger_db
+            .metadata_db()
+            .write_index_commit_progress(last_version)?;
+        next_version.store(last_version + 1, Ordering::Release);
+    }
+}
+
+/// Returns the first version whose indices are not written yet according to the persisted
+/// progress. Versions committed while the deferred mode was disabled are indexed with the commit,
+/// so without a progress everything up to the latest version is indexed.
+fn get_index_ready_version(ledger_db: &LedgerDb) -> Result<Version> {
+    let next_version = ledger_db
+        .metadata_db()
+        .get_latest_version()
+        .map_or(0, |version| version + 1);
+    Ok(match ledger_db.metadata_db().get_index_commit_progress()? {
+        // The progress can be ahead of the latest version if an interrupted commit was rolled back.
+        Some(progress) => cmp::min(progress + 1, next_version),
+        None => next_version,
+    })
+}
+
+/// Writes the indices left behind by a previous run in deferred index mode, so that the DB can be
+/// opened with the indices written in the commits again.
+pub(crate) fn catch_up_indices(ledger_db: &LedgerDb) -> Result<()> {
+    if ledger_db
+        .metadata_db()
+        .get_index_commit_progress()?
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let next_version = AtomicU64::new(get_index_ready_version(ledger_db)?);
+    if let Ok(latest_version) = ledger_db.metadata_db().get_latest_version() {
+        info!(
+            next_version = next_version.load(Ordering::Relaxed),
+            latest_version = latest_version,
+            "Writing indices deferred by a previous run.",
+        );
+        index_up_to(ledger_db, &next_version, latest_version)?;
+    }
+    ledger_db.metadata_db().delete_index_commit_progress()
+}
+
+/// Writes the event and account transaction indices of the committed versions in a background
+/// thread, in the order they are committed, so that they are kept out of the commits.
+#[derive(Debug)]
+pub(crate) struct IndexUpdateQueue {
+    /// All the versions before this one are indexed.
+    index_ready_version: Arc<AtomicU64>,
+    sender: Mutex<mpsc::Sender<Command>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl IndexUpdateQueue {
+    pub(crate) fn new(ledger_db: Arc<LedgerDb>) -> Result<Self> {
+        let index_ready_version = get_index_ready_version(&ledger_db)?;
+        // Persist the progress right away, otherwise the versions committed before the first
+        // batch is indexed would be considered indexed after a restart.
+        if let Some(version) = index_ready_version.checked_sub(1) {
+            ledger_db
+                .metadata_db()
+                .write_index_commit_progress(version)?;
+        }
+        let index_ready_version = Arc::new(AtomicU64::new(index_ready_version));
+        // Pick up the versions left behind by a previous run.
+        let mut target_version = ledger_db.metadata_db().get_latest_version().ok();
+
+        let index_ready_version_clone = Arc::clone(&index_ready_version);
+        let (send, recv) = mpsc::channel();
+        let join_handle = Some(thread::spawn(move || {
+            let index = |target_version: Option<Version>| match target_version {
+                Some(version) => index_up_to(&ledger_db, &index_ready_version_clone, version),
+                None => Ok(()),
+            };
+            if let Err(e) = index(target_version) {
+                warn!(error = ?e, "Writing deferred indices failed.");
+            }
+            loop {
+                match recv.recv() {
+                    Ok(Command::Index(version)) => {
+                        target_version = cmp::max(target_version, Some(version));
+                        if let Err(e) = index(target_version) {
+                            warn!(error = ?e, "Writing deferred indices failed.");
+                        }
+                    },
+                    Ok(Command::SkipTo(version)) => {
+                        if let Err(e) = ledger_db
+                            .metadata_db()
+                            .write_index_commit_progress(version - 1)
+                        {
+                            warn!(error = ?e, "Writing index commit progress failed.");
+                        }
+                        index_ready_version_clone.fetch_max(version, Ordering::AcqRel);
+                    },
+                    Ok(Command::Flush(reply)) => {
+                        // Retries the batch that failed last, if any.
+                        let _ = reply.send(index(target_version));
+                    },
+                    Ok(Command::Stop) | Err(mpsc::RecvError) => break,
+                }
+            }
+        }));
+
+        Ok(Self {
+            index_ready_version,
+            sender: Mutex::new(send),
+            join_handle,
+        })
+    }
+
+    /// Queues the indices of the versions up to and including `version` to be written.
+    pub(crate) fn push(&self, version: Version) {
+        self.send(Command::Index(version));
+    }
+
+    /// Considers all the versions before `version` indexed.
+    pub(crate) fn skip_to(&self, version: Version) {
+        self.send(Command::SkipTo(version));
+    }
+
+    /// Blocks until the indices of all the queued versions are written.
+    pub(crate) fn flush(&self) -> Result<()> {
+        let (send, recv) = mpsc::channel();
+        self.send(Command::Flush(send));
+        recv.recv()
+            .map_err(|e| AptosDbError::RecvError(e.to_string()))?
+    }
+
+    pub(crate) fn index_ready_version(&self) -> Version {
+        self.index_ready_version.load(Ordering::Acquire)
+    }
+
+    fn send(&self, command: Command) {
+        self.sender
+            .lock()
+            .send(command)
+            .expect("Index update thread must be alive.");
+    }
+}
+
+impl Drop for IndexUpdateQueue {
+    fn drop(&mut self) {
+        // Notify the index update thread to exit, after it's done with the queued versions.
+        self.send(Command::Stop);
+        self.join_handle
+            .take()
+            .expect("Index update thread must exist.")
+            .join()
+            .expect("Index update thread should join peacefully.");
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
         event::EventSchema,
         event_accumulator::EventAccumulatorSchema,
         event_by_key::EventByKeySchema,
+        event_by_type::{event_type_hash, EventByTypeSchema},
         event_by_version::EventByVersionSchema,
     },
     utils::iterators::EventsByVersionIter,
@@ -19,7 +20,10 @@ use aptos_crypto::{
 };
 use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::{AptosDbError, Result};
-use aptos_types::{contract_event::ContractEvent, transaction::Version};
+use aptos_types::{
+    account_address::AccountAddress, contract_event::ContractEvent, transaction::Version,
+};
+use move_core_types::language_storage::TypeTag;
 use std::{path::Path, sync::Arc};
 
 #[derive(Debug)]
@@ -128,44 +132,71 @@ impl EventDb {
         skip_index: bool,
         batch: &SchemaBatch,
     ) -> Result<()> {
-        // Event table and indices updates
+        // Event table updates
         events
             .iter()
             .enumerate()
             .try_for_each::<_, Result<_>>(|(idx, event)| {
-                if let ContractEvent::V1(v1) = event {
-                    if !skip_index {
-                        batch.put::<EventByKeySchema>(
-                            &(*v1.key(), v1.sequence_number()),
-                            &(version, idx as u64),
-                        )?;
-                        batch.put::<EventByVersionSchema>(
-                            &(*v1.key(), version, v1.sequence_number()),
-                            &(idx as u64),
-                        )?;
-                    }
-                }
                 batch.put::<EventSchema>(&(version, idx as u64), event)
             })?;
 
         if !skip_index {
-            // EventAccumulatorSchema updates
-            let event_hashes: Vec<HashValue> = events.iter().map(ContractEvent::hash).collect();
-            let (_root_hash, writes) =
-                MerkleAccumulator::<EmptyReader, EventAccumulatorHasher>::append(
-                    &EmptyReader,
-                    0,
-                    &event_hashes,
-                )?;
-
-            writes.into_iter().try_for_each(|(pos, hash)| {
-                batch.put::<EventAccumulatorSchema>(&(version, pos), &hash)
-            })?;
+            self.put_event_indices(version, events, batch)?;
+            self.put_event_accumulator(version, events, batch)?;
         }
 
         Ok(())
     }
 
+    /// Saves the EventByKey, EventByVersion and EventByType indices of the contract events yielded
+    /// by the transaction at `version`.
+    pub(crate) fn put_event_indices(
+        &self,
+        version: u64,
+        events: &[ContractEvent],
+        batch: &SchemaBatch,
+    ) -> Result<()> {
+        events
+            .iter()
+            .enumerate()
+            .try_for_each::<_, Result<_>>(|(idx, event)| {
+                if let ContractEvent::V1(v1) = event {
+                    batch.put::<EventByKeySchema>(
+                        &(*v1.key(), v1.sequence_number()),
+                        &(version, idx as u64),
+                    )?;
+                    batch.put::<EventByVersionSchema>(
+                        &(*v1.key(), version, v1.sequence_number()),
+                        &(idx as u64),
+                    )?;
+                    if let Some(key) = Self::event_by_type_key(event, version, idx as u64)? {
+                        batch.put::<EventByTypeSchema>(&key, &())?;
+                    }
+                }
+                Ok(())
+            })
+    }
+
+    /// Saves the accumulator of the contract events yielded by the transaction at `version`.
+    pub(crate) fn put_event_accumulator(
+        &self,
+        version: u64,
+        events: &[ContractEvent],
+        batch: &SchemaBatch,
+    ) -> Result<()> {
+        let event_hashes: Vec<HashValue> = events.iter().map(ContractEvent::hash).collect();
+        let (_root_hash, writes) =
+            MerkleAccumulator::<EmptyReader, EventAccumulatorHasher>::append(
+                &EmptyReader,
+                0,
+                &event_hashes,
+            )?;
+
+        writes
+            .into_iter()
+            .try_for_each(|(pos, hash)| batch.put::<EventAccumulatorSchema>(&(version, pos), &hash))
+    }
+
     /// Deletes a set of events in the range of version in [begin, end), and all related indices.
     pub(crate) fn prune_events(
         &self,
@@ -176,6 +207,9 @@ impl EventDb {
         let mut current_version = start;
         for events in self.get_events_by_version_iter(start, (end - start) as usize)? {
             for (idx, event) in (events?).into_iter().enumerate() {
+                if let Some(key) = Self::event_by_type_key(&event, current_version, idx as u64)? {
+                    db_batch.delete::<EventByTypeSchema>(&key)?;
+                }
                 if let ContractEvent::V1(v1) = event {
                     db_batch.delete::<EventByVersionSchema>(&(
                         *v1.key(),
@@ -192,4 +226,47 @@ impl EventDb {
             .prune_event_accumulator(start, end, db_batch)?;
         Ok(())
     }
+
+    /// Rebuilds the `EventByTypeSchema` index for events in the range of version in
+    /// [begin, end), for databases that were written before the index existed.
+    ///
+    /// Writing an index entry is idempotent, so it is safe to rerun on overlapping ranges.
+    /// Returns the number of index entries written.
+    pub(crate) fn backfill_event_by_type_index(
+        &self,
+        begin: Version,
+        end: Version,
+        batch: &SchemaBatch,
+    ) -> Result<usize> {
+        let mut num_entries = 0;
+        let mut current_version = begin;
+        for events in self.get_events_by_version_iter(begin, (end - begin) as usize)? {
+            for (idx, event) in events?.iter().enumerate() {
+                if let Some(key) = Self::event_by_type_key(event, current_version, idx as u64)? {
+                    batch.put::<EventByTypeSchema>(&key, &())?;
+                    num_entries += 1;
+                }
+            }
+            current_version += 1;
+        }
+        Ok(num_entries)
+    }
+
+    /// Returns the `EventByTypeSchema` key for an event, or `None` if the event can't be
+    /// attributed to an account (module events) or its type is not a struct.
+    fn event_by_type_key(
+        event: &ContractEvent,
+        version: Version,
+        index: u64,
+    ) -> Result<Option<(AccountAddress, HashValue, Version, u64)>> {
+        match (event, event.type_tag()) {
+            (ContractEvent::V1(v1), TypeTag::Struct(struct_tag)) => Ok(Some((
+                v1.key().get_creator_address(),
+                event_type_hash(struct_tag)?,
+                version,
+                index,
+            ))),
+            _ => Ok(None),
+        }
+    }
 }
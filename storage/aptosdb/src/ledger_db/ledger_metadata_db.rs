@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    ledger_db::event_db::EventDb,
     schema::{
         block_by_version::BlockByVersionSchema,
         block_info::BlockInfoSchema,
@@ -13,15 +14,21 @@ use crate::{
     utils::{get_progress, iterators::EpochEndingLedgerInfoIter},
 };
 use anyhow::anyhow;
+use aptos_bitvec::BitVec;
+use aptos_logger::prelude::warn;
 use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::{
-    block_info::{BlockInfo, BlockInfoV0},
+    block_info::{BlockInfo, BlockInfoV0, BlockInfoV1},
     db_ensure as ensure, AptosDbError, Result,
 };
 use aptos_types::{
-    account_config::NewBlockEvent, contract_event::ContractEvent, epoch_state::EpochState,
-    ledger_info::LedgerInfoWithSignatures, state_store::state_storage_usage::StateStorageUsage,
+    account_config::{new_block_event_key, NewBlockEvent},
+    contract_event::ContractEvent,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::state_storage_usage::StateStorageUsage,
     transaction::Version,
+    validator_verifier::ValidatorVerifier,
 };
 use arc_swap::ArcSwap;
 use std::{ops::Deref, path::Path, sync::Arc};
@@ -93,6 +100,24 @@ impl LedgerMetadataDb {
             "No LedgerPrunerProgress in db.".to_string(),
         ))
     }
+
+    /// Returns the last version whose indices were written, if they are written in deferred mode.
+    pub(crate) fn get_index_commit_progress(&self) -> Result<Option<Version>> {
+        get_progress(&self.db, &DbMetadataKey::IndexCommitProgress)
+    }
+
+    pub(crate) fn write_index_commit_progress(&self, version: Version) -> Result<()> {
+        self.db.put::<DbMetadataSchema>(
+            &DbMetadataKey::IndexCommitProgress,
+            &DbMetadataValue::Version(version),
+        )
+    }
+
+    pub(crate) fn delete_index_commit_progress(&self) -> Result<()> {
+        let batch = SchemaBatch::new();
+        batch.delete::<DbMetadataSchema>(&DbMetadataKey::IndexCommitProgress)?;
+        self.db.write_schemas(batch)
+    }
 }
 
 /// LedgerInfo APIs.
@@ -185,6 +210,18 @@ impl LedgerMetadataDb {
             .store(Arc::new(Some(ledger_info_with_sigs)));
     }
 
+    /// Makes the DB see the latest writes of the primary, if opened as a secondary instance.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<()> {
+        Ok(self.db.try_catch_up_with_primary()?)
+    }
+
+    /// Reloads the latest ledger info in memory from DB, e.g. after catching up with the primary.
+    pub(crate) fn refresh_latest_ledger_info(&self) -> Result<()> {
+        let ledger_info = get_latest_ledger_info_in_db_impl(&self.db)?;
+        self.latest_ledger_info.store(Arc::new(ledger_info));
+        Ok(())
+    }
+
     /// Writes `ledger_info_with_sigs` to `batch`.
     pub(crate) fn put_ledger_info(
         &self,
@@ -288,30 +325,157 @@ impl LedgerMetadataDb {
     }
 
     pub(crate) fn put_block_info(
+        &self,
         version: Version,
         event: &ContractEvent,
         batch: &SchemaBatch,
     ) -> Result<()> {
         let new_block_event = NewBlockEvent::try_from_bytes(event.event_data())?;
         let block_height = new_block_event.height();
-        let id = new_block_event.hash()?;
-        let epoch = new_block_event.epoch();
-        let round = new_block_event.round();
-        let proposer = new_block_event.proposer();
-        let block_timestamp_usecs = new_block_event.proposed_time();
-        let block_info = BlockInfo::V0(BlockInfoV0::new(
-            id,
-            epoch,
-            round,
-            proposer,
-            block_timestamp_usecs,
-            version,
-        ));
+        let block_info = self.block_info_from_event(version, &new_block_event)?;
         batch.put::<BlockInfoSchema>(&block_height, &block_info)?;
         batch.put::<BlockByVersionSchema>(&version, &block_height)?;
 
         Ok(())
     }
+
+    /// Upgrades the V0 block infos with height in [begin_height, end_height) to V1, by replaying
+    /// the NewBlockEvent of each block. Returns the number of block infos upgraded.
+    pub(crate) fn upgrade_block_info(
+        &self,
+        event_db: &EventDb,
+        begin_height: u64,
+        end_height: u64,
+        batch: &SchemaBatch,
+    ) -> Result<usize> {
+        let mut iter = self.db.iter::<BlockInfoSchema>(ReadOptions::default())?;
+        iter.seek(&begin_height)?;
+
+        let mut num_upgraded = 0;
+        for item in iter {
+            let (block_height, block_info) = item?;
+            if block_height >= end_height {
+                break;
+            }
+            if let BlockInfo::V1(_) = block_info {
+                continue;
+            }
+
+            let first_version = block_info.first_version();
+            let event = event_db
+                .get_events_by_version(first_version)?
+                .into_iter()
+                .find(|event| event.event_key() == Some(&new_block_event_key()))
+                .ok_or_else(|| {
+                    AptosDbError::NotFound(format!(
+                        "NewBlockEvent for block_height {block_height} at version {first_version}"
+                    ))
+                })?;
+            let new_block_event = NewBlockEvent::try_from_bytes(event.event_data())?;
+            let upgraded = self.block_info_from_event(first_version, &new_block_event)?;
+            if let BlockInfo::V1(_) = upgraded {
+                batch.put::<BlockInfoSchema>(&block_height, &upgraded)?;
+                num_upgraded += 1;
+            }
+        }
+
+        Ok(num_upgraded)
+    }
+
+    /// Builds the BlockInfo of a block. It's V1 if the NewBlockEvent carries votes on the parent
+    /// block, counted against the validator set that cast them, and V0 otherwise (e.g. genesis).
+    /// Falls back to V0 if the validator set can't be looked up yet, which never fails the commit,
+    /// the block info can be upgraded later by `upgrade_block_info`.
+    fn block_info_from_event(
+        &self,
+        version: Version,
+        new_block_event: &NewBlockEvent,
+    ) -> Result<BlockInfo> {
+        let v0 = BlockInfoV0::new(
+            new_block_event.hash()?,
+            new_block_event.epoch(),
+            new_block_event.round(),
+            new_block_event.proposer(),
+            new_block_event.proposed_time(),
+            version,
+        );
+
+        if new_block_event
+            .previous_block_votes_bitvec()
+            .iter()
+            .all(|byte| *byte == 0)
+        {
+            return Ok(BlockInfo::V0(v0));
+        }
+
+        match self.get_voting_epoch_state(version, new_block_event) {
+            Ok(epoch_state) => {
+                let (proposer_vote_count, total_voting_power) =
+                    participation_from_event(new_block_event, &epoch_state.verifier);
+                Ok(BlockInfo::V1(BlockInfoV1::new(
+                    v0,
+                    proposer_vote_count,
+                    total_voting_power,
+                )))
+            },
+            Err(e) => {
+                warn!(
+                    version = version,
+                    error = ?e,
+                    "Failed to get the voting validator set, storing V0 block info.",
+                );
+                Ok(BlockInfo::V0(v0))
+            },
+        }
+    }
+
+    /// Returns the epoch state of the validators whose votes on the parent block are carried by
+    /// the NewBlockEvent at `version`.
+    fn get_voting_epoch_state(
+        &self,
+        version: Version,
+        new_block_event: &NewBlockEvent,
+    ) -> Result<EpochState> {
+        let epoch = new_block_event.epoch();
+        ensure!(
+            epoch > 0,
+            "NewBlockEvent at version {version} carries votes in epoch 0."
+        );
+        let previous_epoch_ending_version = self
+            .db
+            .get::<LedgerInfoSchema>(&(epoch - 1))?
+            .ok_or_else(|| {
+                AptosDbError::NotFound(format!("Last LedgerInfo of epoch {}", epoch - 1))
+            })?
+            .ledger_info()
+            .version();
+        // The first block of an epoch carries the votes on the last block of the previous epoch.
+        let voting_epoch = if version == previous_epoch_ending_version + 1 {
+            epoch - 1
+        } else {
+            epoch
+        };
+        self.get_epoch_state(voting_epoch)
+    }
+}
+
+/// Returns the number of validators whose votes on the parent block are included in the
+/// NewBlockEvent, and the sum of their voting power.
+pub(crate) fn participation_from_event(
+    new_block_event: &NewBlockEvent,
+    verifier: &ValidatorVerifier,
+) -> (u64, u64) {
+    let votes: BitVec = new_block_event.previous_block_votes_bitvec().clone().into();
+    verifier
+        .get_ordered_account_addresses_iter()
+        .enumerate()
+        .filter(|(index, _)| votes.is_set(*index as u16))
+        .fold((0, 0), |(count, voting_power), (_, voter)| {
+            (
+                count + 1,
+                voting_power + verifier.get_voting_power(&voter).unwrap_or(0),
+            )
+        })
 }
 
 /// Usage APIs.
@@ -1,18 +1,27 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ledger_db::ledger_metadata_db::LedgerMetadataDb, AptosDB};
+use crate::{schema::block_info::BlockInfoSchema, AptosDB};
+use aptos_bitvec::BitVec;
+use aptos_crypto::HashValue;
 use aptos_schemadb::SchemaBatch;
-use aptos_storage_interface::AptosDbError;
+use aptos_storage_interface::{
+    block_info::{BlockInfo, BlockInfoV0, BlockParticipationStats},
+    AptosDbError,
+};
 use aptos_temppath::TempPath;
 use aptos_types::{
     account_address::AccountAddress,
     account_config::events::new_block::{new_block_event_key, NewBlockEvent},
+    aggregate_signature::AggregateSignature,
     contract_event::ContractEvent,
-    ledger_info::LedgerInfoWithSignatures,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proptest_types::{AccountInfoUniverse, LedgerInfoWithSignaturesGen},
     state_store::state_storage_usage::StateStorageUsage,
     transaction::Version,
+    validator_signer::ValidatorSigner,
+    validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
 };
 use move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
 use proptest::{
@@ -258,28 +267,30 @@ fn test_block_api() {
             2000,
         ),
     ];
-    LedgerMetadataDb::put_block_info(
-        1,
-        &ContractEvent::new_v1(
-            new_block_event_key(),
-            0,
-            TypeTag::from(NewBlockEvent::struct_tag()),
-            bcs::to_bytes(&events[0]).unwrap(),
-        ),
-        &batch,
-    )
-    .unwrap();
-    LedgerMetadataDb::put_block_info(
-        10,
-        &ContractEvent::new_v1(
-            new_block_event_key(),
+    ledger_metadata_db
+        .put_block_info(
             1,
-            TypeTag::from(NewBlockEvent::struct_tag()),
-            bcs::to_bytes(&events[1]).unwrap(),
-        ),
-        &batch,
-    )
-    .unwrap();
+            &ContractEvent::new_v1(
+                new_block_event_key(),
+                0,
+                TypeTag::from(NewBlockEvent::struct_tag()),
+                bcs::to_bytes(&events[0]).unwrap(),
+            ),
+            &batch,
+        )
+        .unwrap();
+    ledger_metadata_db
+        .put_block_info(
+            10,
+            &ContractEvent::new_v1(
+                new_block_event_key(),
+                1,
+                TypeTag::from(NewBlockEvent::struct_tag()),
+                bcs::to_bytes(&events[1]).unwrap(),
+            ),
+            &batch,
+        )
+        .unwrap();
     ledger_metadata_db.write_schemas(batch).unwrap();
 
     assert_eq!(ledger_metadata_db.get_block_info(0).unwrap(), None);
@@ -319,6 +330,284 @@ fn test_block_api() {
     }
 }
 
+#[test]
+fn test_block_participation_stats() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let ledger_metadata_db = db.ledger_db.metadata_db();
+
+    let signers: Vec<_> = (1..=3).map(ValidatorSigner::from_int).collect();
+    let verifier = ValidatorVerifier::new(
+        signers
+            .iter()
+            .zip([10, 20, 30])
+            .map(|(signer, voting_power)| {
+                ValidatorConsensusInfo::new(signer.author(), signer.public_key(), voting_power)
+            })
+            .collect(),
+    );
+    let epoch_ending_li = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(
+            aptos_types::block_info::BlockInfo::new(
+                0,
+                0,
+                HashValue::zero(),
+                HashValue::zero(),
+                0,
+                0,
+                Some(EpochState {
+                    epoch: 1,
+                    verifier: verifier.clone(),
+                }),
+            ),
+            HashValue::zero(),
+        ),
+        AggregateSignature::empty(),
+    );
+    let batch = SchemaBatch::new();
+    ledger_metadata_db
+        .put_ledger_info(&epoch_ending_li, &batch)
+        .unwrap();
+    ledger_metadata_db.write_schemas(batch).unwrap();
+
+    let mut votes = BitVec::with_num_bits(verifier.len() as u16);
+    votes.set(0);
+    votes.set(2);
+    let new_block_event = |height| {
+        NewBlockEvent::new(
+            AccountAddress::random(),
+            1,
+            height,
+            height,
+            votes.clone().into(),
+            signers[1].author(),
+            vec![],
+            height * 1000,
+        )
+    };
+    let to_contract_event = |event: &NewBlockEvent| {
+        ContractEvent::new_v1(
+            new_block_event_key(),
+            event.height(),
+            TypeTag::from(NewBlockEvent::struct_tag()),
+            bcs::to_bytes(event).unwrap(),
+        )
+    };
+
+    // Newly committed blocks are persisted as V1.
+    let event_1 = new_block_event(1);
+    let batch = SchemaBatch::new();
+    ledger_metadata_db
+        .put_block_info(2, &to_contract_event(&event_1), &batch)
+        .unwrap();
+    ledger_metadata_db.write_schemas(batch).unwrap();
+    let block_info_1 = ledger_metadata_db.get_block_info(1).unwrap().unwrap();
+    assert!(matches!(block_info_1, BlockInfo::V1(_)));
+    let stats_1 = block_info_1.participation_stats(1).unwrap();
+    assert_eq!(stats_1.proposer, signers[1].author());
+    assert_eq!(stats_1.proposer_vote_count, 2);
+    assert_eq!(stats_1.total_voting_power, 40);
+
+    // Blocks persisted as V0 get upgraded by replaying the NewBlockEvent.
+    let event_2 = new_block_event(2);
+    let contract_event_2 = to_contract_event(&event_2);
+    let batch = SchemaBatch::new();
+    db.ledger_db
+        .event_db()
+        .put_events(5, &[contract_event_2], /*skip_index=*/ false, &batch)
+        .unwrap();
+    db.ledger_db.event_db().write_schemas(batch).unwrap();
+    let batch = SchemaBatch::new();
+    batch
+        .put::<BlockInfoSchema>(
+            &2,
+            &BlockInfo::V0(BlockInfoV0::new(
+                event_2.hash().unwrap(),
+                1,
+                2,
+                signers[1].author(),
+                2000,
+                5,
+            )),
+        )
+        .unwrap();
+    ledger_metadata_db.write_schemas(batch).unwrap();
+    assert_eq!(
+        ledger_metadata_db
+            .get_block_info(2)
+            .unwrap()
+            .unwrap()
+            .participation_stats(2),
+        None
+    );
+
+    let batch = SchemaBatch::new();
+    assert_eq!(
+        ledger_metadata_db
+            .upgrade_block_info(db.ledger_db.event_db(), 0, 3, &batch)
+            .unwrap(),
+        1
+    );
+    ledger_metadata_db.write_schemas(batch).unwrap();
+    let block_info_2 = ledger_metadata_db.get_block_info(2).unwrap().unwrap();
+    assert_eq!(block_info_2.first_version(), 5);
+    assert_eq!(
+        block_info_2.participation_stats(2),
+        Some(BlockParticipationStats {
+            block_height: 2,
+            epoch: 1,
+            round: 2,
+            proposer: signers[1].author(),
+            proposer_vote_count: 2,
+            total_voting_power: 40,
+        })
+    );
+}
+
+#[test]
+fn test_block_info_falls_back_to_v0() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let ledger_metadata_db = db.ledger_db.metadata_db();
+
+    // The block carries votes, but the validator set of epoch 1 is not in the DB.
+    let mut votes = BitVec::with_num_bits(3);
+    votes.set(0);
+    let new_block_event = NewBlockEvent::new(
+        AccountAddress::random(),
+        1,
+        1,
+        1,
+        votes.into(),
+        AccountAddress::random(),
+        vec![],
+        1000,
+    );
+    let batch = SchemaBatch::new();
+    ledger_metadata_db
+        .put_block_info(
+            2,
+            &ContractEvent::new_v1(
+                new_block_event_key(),
+                0,
+                TypeTag::from(NewBlockEvent::struct_tag()),
+                bcs::to_bytes(&new_block_event).unwrap(),
+            ),
+            &batch,
+        )
+        .unwrap();
+    ledger_metadata_db.write_schemas(batch).unwrap();
+
+    let block_info = ledger_metadata_db.get_block_info(1).unwrap().unwrap();
+    assert!(matches!(block_info, BlockInfo::V0(_)));
+    assert_eq!(block_info.id(), new_block_event.hash().unwrap());
+    assert_eq!(block_info.first_version(), 2);
+}
+
+#[test]
+fn test_block_participation_stats_across_epochs() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let ledger_metadata_db = db.ledger_db.metadata_db();
+
+    let signers: Vec<_> = (1..=3).map(ValidatorSigner::from_int).collect();
+    let verifier = |voting_powers: [u64; 3]| {
+        ValidatorVerifier::new(
+            signers
+                .iter()
+                .zip(voting_powers)
+                .map(|(signer, voting_power)| {
+                    ValidatorConsensusInfo::new(signer.author(), signer.public_key(), voting_power)
+                })
+                .collect(),
+        )
+    };
+    let epoch_ending_li = |epoch, version, verifier| {
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                aptos_types::block_info::BlockInfo::new(
+                    epoch,
+                    0,
+                    HashValue::zero(),
+                    HashValue::zero(),
+                    version,
+                    0,
+                    Some(EpochState {
+                        epoch: epoch + 1,
+                        verifier,
+                    }),
+                ),
+                HashValue::zero(),
+            ),
+            AggregateSignature::empty(),
+        )
+    };
+    let batch = SchemaBatch::new();
+    ledger_metadata_db
+        .put_ledger_info(&epoch_ending_li(0, 0, verifier([10, 20, 30])), &batch)
+        .unwrap();
+    ledger_metadata_db
+        .put_ledger_info(&epoch_ending_li(1, 10, verifier([1, 2, 3])), &batch)
+        .unwrap();
+    ledger_metadata_db.write_schemas(batch).unwrap();
+
+    let mut votes = BitVec::with_num_bits(signers.len() as u16);
+    votes.set(0);
+    votes.set(2);
+    let put_block_info = |version, epoch, height, votes: Vec<u8>| {
+        let event = NewBlockEvent::new(
+            AccountAddress::random(),
+            epoch,
+            height,
+            height,
+            votes,
+            signers[1].author(),
+            vec![],
+            height * 1000,
+        );
+        let batch = SchemaBatch::new();
+        ledger_metadata_db.put_block_info(
+            version,
+            &ContractEvent::new_v1(
+                new_block_event_key(),
+                height,
+                TypeTag::from(NewBlockEvent::struct_tag()),
+                bcs::to_bytes(&event).unwrap(),
+            ),
+            &batch,
+        )?;
+        ledger_metadata_db.write_schemas(batch)?;
+        Ok::<_, AptosDbError>(
+            ledger_metadata_db
+                .get_block_info(height)?
+                .unwrap()
+                .participation_stats(height),
+        )
+    };
+
+    // Blocks without votes are persisted as V0.
+    assert_eq!(put_block_info(1, 1, 1, vec![]).unwrap(), None);
+
+    // The first block of an epoch carries the votes of the previous epoch's validators.
+    let stats = put_block_info(11, 2, 2, votes.clone().into())
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.proposer_vote_count, 2);
+    assert_eq!(stats.total_voting_power, 40);
+
+    let stats = put_block_info(13, 2, 3, votes.clone().into())
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.proposer_vote_count, 2);
+    assert_eq!(stats.total_voting_power, 4);
+
+    // The validator set of an unknown epoch can't be guessed.
+    assert!(matches!(
+        put_block_info(20, 3, 4, votes.into()).unwrap_err(),
+        AptosDbError::NotFound(_)
+    ));
+}
+
 #[test]
 fn test_usage() {
     let tmp_dir = TempPath::new();
@@ -18,12 +18,13 @@ use crate::{
         transaction_accumulator_db::TransactionAccumulatorDb, transaction_db::TransactionDb,
         transaction_info_db::TransactionInfoDb, write_set_db::WriteSetDb,
     },
+    schema::LEDGER_INFO_CF_NAME,
 };
 use aptos_config::config::{RocksdbConfig, RocksdbConfigs};
 use aptos_logger::prelude::info;
 use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{ColumnFamilyDescriptor, ColumnFamilyName, SchemaBatch, DB};
-use aptos_storage_interface::Result;
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
 use aptos_types::transaction::Version;
 use std::{
     path::{Path, PathBuf},
@@ -110,7 +111,7 @@ impl LedgerDb {
             } else {
                 LEDGER_DB_NAME
             },
-            &rocksdb_configs.ledger_db_config,
+            &rocksdb_configs,
             readonly,
         )?);
 
@@ -122,19 +123,7 @@ impl LedgerDb {
 
         if !sharding {
             info!("Individual ledger dbs are not enabled!");
-            return Ok(Self {
-                ledger_metadata_db: LedgerMetadataDb::new(Arc::clone(&ledger_metadata_db)),
-                event_db: EventDb::new(
-                    Arc::clone(&ledger_metadata_db),
-                    EventStore::new(Arc::clone(&ledger_metadata_db)),
-                ),
-                transaction_accumulator_db: TransactionAccumulatorDb::new(Arc::clone(
-                    &ledger_metadata_db,
-                )),
-                transaction_db: TransactionDb::new(Arc::clone(&ledger_metadata_db)),
-                transaction_info_db: TransactionInfoDb::new(Arc::clone(&ledger_metadata_db)),
-                write_set_db: WriteSetDb::new(Arc::clone(&ledger_metadata_db)),
-            });
+            return Ok(Self::new_unsharded(ledger_metadata_db));
         }
 
         let ledger_db_folder = db_root_path.as_ref().join(LEDGER_DB_FOLDER_NAME);
@@ -142,7 +131,7 @@ impl LedgerDb {
         let event_db_raw = Arc::new(Self::open_rocksdb(
             ledger_db_folder.join(EVENT_DB_NAME),
             EVENT_DB_NAME,
-            &rocksdb_configs.ledger_db_config,
+            &rocksdb_configs,
             readonly,
         )?);
         let event_db = EventDb::new(event_db_raw.clone(), EventStore::new(event_db_raw));
@@ -151,28 +140,28 @@ impl LedgerDb {
             TransactionAccumulatorDb::new(Arc::new(Self::open_rocksdb(
                 ledger_db_folder.join(TRANSACTION_ACCUMULATOR_DB_NAME),
                 TRANSACTION_ACCUMULATOR_DB_NAME,
-                &rocksdb_configs.ledger_db_config,
+                &rocksdb_configs,
                 readonly,
             )?));
 
         let transaction_db = TransactionDb::new(Arc::new(Self::open_rocksdb(
             ledger_db_folder.join(TRANSACTION_DB_NAME),
             TRANSACTION_DB_NAME,
-            &rocksdb_configs.ledger_db_config,
+            &rocksdb_configs,
             readonly,
         )?));
 
         let transaction_info_db = TransactionInfoDb::new(Arc::new(Self::open_rocksdb(
             ledger_db_folder.join(TRANSACTION_INFO_DB_NAME),
             TRANSACTION_INFO_DB_NAME,
-            &rocksdb_configs.ledger_db_config,
+            &rocksdb_configs,
             readonly,
         )?));
 
         let write_set_db = WriteSetDb::new(Arc::new(Self::open_rocksdb(
             ledger_db_folder.join(WRITE_SET_DB_NAME),
             WRITE_SET_DB_NAME,
-            &rocksdb_configs.ledger_db_config,
+            &rocksdb_configs,
             readonly,
         )?));
 
@@ -188,6 +177,86 @@ impl LedgerDb {
         })
     }
 
+    /// Opens the ledger db of the primary at `db_root_path` as a RocksDB secondary instance, which
+    /// sees the primary's writes after each call to `try_catch_up_with_primary`.
+    /// Only supported when storage sharding is disabled.
+    pub(crate) fn new_secondary(
+        db_root_path: impl AsRef<Path>,
+        secondary_db_root_path: impl AsRef<Path>,
+        rocksdb_configs: RocksdbConfigs,
+    ) -> Result<Self> {
+        ensure!(
+            !rocksdb_configs.enable_storage_sharding,
+            "Opening sharded ledger db as secondary is not supported."
+        );
+        let ledger_db_path = Self::metadata_db_path(db_root_path.as_ref(), false);
+        let secondary_path = Self::metadata_db_path(secondary_db_root_path.as_ref(), false);
+        let mut db_opts = gen_rocksdb_options(&rocksdb_configs.ledger_db_config, true);
+        // A secondary instance needs to keep all files open to not lose track of files deleted
+        // by the primary.
+        db_opts.set_max_open_files(-1);
+        let ledger_metadata_db = Arc::new(DB::open_cf_as_secondary(
+            &db_opts,
+            ledger_db_path.clone(),
+            secondary_path,
+            LEDGER_DB_NAME,
+            ledger_db_column_families(),
+        )?);
+
+        info!(
+            ledger_db_path = ledger_db_path,
+            "Opened ledger db as secondary!"
+        );
+
+        Ok(Self::new_unsharded(ledger_metadata_db))
+    }
+
+    fn new_unsharded(ledger_db: Arc<DB>) -> Self {
+        Self {
+            ledger_metadata_db: LedgerMetadataDb::new(Arc::clone(&ledger_db)),
+            event_db: EventDb::new(
+                Arc::clone(&ledger_db),
+                EventStore::new(Arc::clone(&ledger_db)),
+            ),
+            transaction_accumulator_db: TransactionAccumulatorDb::new(Arc::clone(&ledger_db)),
+            transaction_db: TransactionDb::new(Arc::clone(&ledger_db)),
+            transaction_info_db: TransactionInfoDb::new(Arc::clone(&ledger_db)),
+            write_set_db: WriteSetDb::new(ledger_db),
+        }
+    }
+
+    /// Opens only the ledger info of the primary at `db_root_path`, as a separate RocksDB
+    /// secondary instance. Used to read the progress of the primary regardless of how far a
+    /// replica opened via `new_secondary` has caught up.
+    /// Only supported when storage sharding is disabled.
+    pub(crate) fn new_secondary_ledger_info_probe(
+        db_root_path: impl AsRef<Path>,
+        secondary_db_root_path: impl AsRef<Path>,
+        rocksdb_configs: RocksdbConfigs,
+    ) -> Result<LedgerMetadataDb> {
+        ensure!(
+            !rocksdb_configs.enable_storage_sharding,
+            "Opening sharded ledger db as secondary is not supported."
+        );
+        let mut db_opts = gen_rocksdb_options(&rocksdb_configs.ledger_db_config, true);
+        db_opts.set_max_open_files(-1);
+        let db = DB::open_cf_as_secondary(
+            &db_opts,
+            Self::metadata_db_path(db_root_path.as_ref(), false),
+            Self::metadata_db_path(secondary_db_root_path.as_ref(), false),
+            "ledger_info_probe",
+            vec![LEDGER_INFO_CF_NAME],
+        )?;
+        Ok(LedgerMetadataDb::new(Arc::new(db)))
+    }
+
+    /// Catches up with the primary if opened via `new_secondary`. The cached latest ledger info
+    /// is left as is, see `LedgerMetadataDb::refresh_latest_ledger_info`.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<()> {
+        // All the ledger dbs share the same RocksDB instance since sharding is disabled.
+        self.ledger_metadata_db.try_catch_up_with_primary()
+    }
+
     pub(crate) fn create_checkpoint(
         db_root_path: impl AsRef<Path>,
         cp_root_path: impl AsRef<Path>,
@@ -303,9 +372,10 @@ impl LedgerDb {
     fn open_rocksdb(
         path: PathBuf,
         name: &str,
-        db_config: &RocksdbConfig,
+        rocksdb_configs: &RocksdbConfigs,
         readonly: bool,
     ) -> Result<DB> {
+        let db_config = &rocksdb_configs.ledger_db_config;
         let db = if readonly {
             DB::open_cf_readonly(
                 &gen_rocksdb_options(db_config, true),
@@ -318,7 +388,11 @@ impl LedgerDb {
                 &gen_rocksdb_options(db_config, false),
                 path.clone(),
                 name,
-                Self::gen_cfds_by_name(db_config, name),
+                Self::gen_cfds_by_name(
+                    db_config,
+                    rocksdb_configs.transaction_hash_bloom_bits,
+                    name,
+                ),
             )?
         };
 
@@ -340,13 +414,17 @@ impl LedgerDb {
         }
     }
 
-    fn gen_cfds_by_name(db_config: &RocksdbConfig, name: &str) -> Vec<ColumnFamilyDescriptor> {
+    fn gen_cfds_by_name(
+        db_config: &RocksdbConfig,
+        transaction_hash_bloom_bits: u32,
+        name: &str,
+    ) -> Vec<ColumnFamilyDescriptor> {
         match name {
-            LEDGER_DB_NAME => gen_ledger_cfds(db_config),
+            LEDGER_DB_NAME => gen_ledger_cfds(db_config, transaction_hash_bloom_bits),
             LEDGER_METADATA_DB_NAME => gen_ledger_metadata_cfds(db_config),
             EVENT_DB_NAME => gen_event_cfds(db_config),
             TRANSACTION_ACCUMULATOR_DB_NAME => gen_transaction_accumulator_cfds(db_config),
-            TRANSACTION_DB_NAME => gen_transaction_cfds(db_config),
+            TRANSACTION_DB_NAME => gen_transaction_cfds(db_config, transaction_hash_bloom_bits),
             TRANSACTION_INFO_DB_NAME => gen_transaction_info_cfds(db_config),
             WRITE_SET_DB_NAME => gen_write_set_cfds(db_config),
             _ => unreachable!(),
@@ -133,12 +133,7 @@ impl TransactionDb {
         batch: &SchemaBatch,
     ) -> Result<()> {
         if !skip_index {
-            if let Some(txn) = transaction.try_as_signed_user_txn() {
-                batch.put::<TransactionByAccountSchema>(
-                    &(txn.sender(), txn.sequence_number()),
-                    &version,
-                )?;
-            }
+            self.put_transaction_indices(version, transaction, batch)?;
         }
         batch.put::<TransactionByHashSchema>(&transaction.hash(), &version)?;
         batch.put::<TransactionSchema>(&version, transaction)?;
@@ -146,6 +141,23 @@ impl TransactionDb {
         Ok(())
     }
 
+    /// Saves the TransactionByAccount index of the transaction at `version`.
+    pub(crate) fn put_transaction_indices(
+        &self,
+        version: Version,
+        transaction: &Transaction,
+        batch: &SchemaBatch,
+    ) -> Result<()> {
+        if let Some(txn) = transaction.try_as_signed_user_txn() {
+            batch.put::<TransactionByAccountSchema>(
+                &(txn.sender(), txn.sequence_number()),
+                &version,
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Deletes transaction data given version range [begin, end).
     pub(crate) fn prune_transactions(
         &self,
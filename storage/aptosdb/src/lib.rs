@@ -10,7 +10,15 @@
 //! It relays read/write operations on the physical storage via `schemadb` to the underlying
 //! Key-Value storage system, and implements aptos data structures on top of it.
 
-pub use crate::db::AptosDB;
+pub use crate::{
+    db::{
+        integrity_check::{IntegrityCheckOptions, IntegrityReport, IntegrityViolation},
+        AptosDB, CommitValidationIssue,
+    },
+    historical_state_view::HistoricalStateView,
+    pruner::PruningImpactReport,
+    state_proof_cache::CacheStats,
+};
 
 #[cfg(feature = "consensus-only-perf-test")]
 pub mod fake_aptosdb;
@@ -21,6 +29,7 @@ pub mod common;
 pub mod db;
 pub mod get_restore_handler;
 pub mod metrics;
+pub(crate) mod replica_catch_up;
 pub(crate) mod rocksdb_property_reporter;
 pub mod schema;
 pub mod state_restore;
@@ -30,13 +39,18 @@ pub mod utils;
 pub mod db_debugger;
 pub mod fast_sync_storage_wrapper;
 
+mod block_version_cache;
 mod db_options;
 mod event_store;
+mod historical_state_view;
+mod index_update_queue;
 mod ledger_db;
 mod lru_node_cache;
 mod pruner;
 mod state_kv_db;
 mod state_merkle_db;
+mod state_proof_cache;
 mod state_store;
 mod transaction_store;
 mod versioned_node_cache;
+mod wal;
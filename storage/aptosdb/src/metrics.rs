@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram_vec, register_int_counter, register_int_gauge,
-    register_int_gauge_vec, HistogramVec, IntCounter, IntGauge, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -28,6 +29,26 @@ pub static COMMITTED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static WAL_TRUNCATE_FAILURES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_wal_truncate_failures",
+        "Number of times the write-ahead log failed to be truncated after a commit"
+    )
+    .unwrap()
+});
+
+pub static STATE_PROOF_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_storage_state_proof_cache",
+        // metric description
+        "Aptos storage state proof cache hits, misses and evictions",
+        // metric labels (dimensions)
+        &["event"]
+    )
+    .unwrap()
+});
+
 pub static LATEST_TXN_VERSION: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
         "aptos_storage_latest_transaction_version",
@@ -179,6 +200,24 @@ pub(crate) static STATE_MERKLE_DB_PROPERTIES: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static STATE_KV_SHARD_KEY_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_state_kv_shard_key_count",
+        "Estimated number of keys in each shard of the state K/V DB.",
+        &["shard"]
+    )
+    .unwrap()
+});
+
+pub(crate) static STATE_KV_SHARD_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_state_kv_shard_bytes",
+        "Estimated size of the live data in each shard of the state K/V DB.",
+        &["shard"]
+    )
+    .unwrap()
+});
+
 // Async committer gauges:
 pub(crate) static LATEST_SNAPSHOT_VERSION: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
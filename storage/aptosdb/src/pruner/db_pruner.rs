@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::pruner::pruner_manager::PruningImpactReport;
 use aptos_storage_interface::Result;
 use aptos_types::transaction::Version;
 use std::cmp::min;
@@ -13,6 +14,10 @@ pub trait DBPruner: Send + Sync {
     /// tries to prune.
     fn prune(&self, batch_size: usize) -> Result<Version>;
 
+    /// Estimates what pruning from the current progress to `target_version` would delete,
+    /// without deleting anything.
+    fn dry_run(&self, target_version: Version, max_versions: usize) -> Result<PruningImpactReport>;
+
     /// Returns the progress of the pruner.
     fn progress(&self) -> Version;
 
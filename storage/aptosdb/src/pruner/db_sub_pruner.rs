@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::pruner::pruner_manager::PruningImpactReport;
 use aptos_storage_interface::Result;
 use aptos_types::transaction::Version;
 /// Defines the trait for sub-pruner of a parent DB pruner
@@ -11,4 +12,11 @@ pub trait DBSubPruner {
     /// Performs the actual pruning, a target version is passed, which is the target the pruner
     /// tries to prune.
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()>;
+
+    /// Estimates what `prune` would delete, without deleting anything.
+    fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport>;
 }
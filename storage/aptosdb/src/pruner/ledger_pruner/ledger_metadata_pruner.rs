@@ -1,9 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::schema::{
-    db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
-    version_data::VersionDataSchema,
+use crate::{
+    pruner::pruner_manager::PruningImpactReport,
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        version_data::VersionDataSchema,
+    },
 };
 use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::{AptosDbError, Result};
@@ -44,6 +47,24 @@ impl LedgerMetadataPruner {
         current_progress: Version,
         target_version: Version,
     ) -> Result<()> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        self.ledger_metadata_db.write_schemas(batch)
+    }
+
+    pub(in crate::pruner) fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        PruningImpactReport::estimate(&self.ledger_metadata_db, &batch)
+    }
+
+    fn prune_batch(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<SchemaBatch> {
         let batch = SchemaBatch::new();
         for version in current_progress..target_version {
             batch.delete::<VersionDataSchema>(&version)?;
@@ -52,7 +73,8 @@ impl LedgerMetadataPruner {
             &DbMetadataKey::LedgerPrunerProgress,
             &DbMetadataValue::Version(target_version),
         )?;
-        self.ledger_metadata_db.write_schemas(batch)
+
+        Ok(batch)
     }
 
     pub(in crate::pruner) fn progress(&self) -> Result<Version> {
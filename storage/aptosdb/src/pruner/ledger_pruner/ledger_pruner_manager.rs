@@ -5,7 +5,10 @@ use crate::{
     ledger_db::LedgerDb,
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
-        ledger_pruner::LedgerPruner, pruner_manager::PrunerManager, pruner_utils,
+        db_pruner::DBPruner,
+        ledger_pruner::LedgerPruner,
+        pruner_manager::{PrunerManager, PruningImpactReport},
+        pruner_utils,
         pruner_worker::PrunerWorker,
     },
 };
@@ -76,6 +79,12 @@ impl PrunerManager for LedgerPrunerManager {
         }
     }
 
+    fn set_pruner_target_version(&self, target_version: Version) {
+        if target_version > self.get_min_readable_version() {
+            self.set_min_readable_version_target(target_version);
+        }
+    }
+
     fn save_min_readable_version(&self, min_readable_version: Version) -> Result<()> {
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
@@ -93,6 +102,14 @@ impl PrunerManager for LedgerPrunerManager {
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn dry_run_prune(&self, target_version: Version) -> Result<PruningImpactReport> {
+        match &self.pruner_worker {
+            Some(pruner_worker) => pruner_worker.dry_run(target_version),
+            None => LedgerPruner::new(Arc::clone(&self.ledger_db))?
+                .dry_run(target_version, self.pruning_batch_size),
+        }
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -151,8 +168,11 @@ impl LedgerPrunerManager {
     }
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
+        self.set_min_readable_version_target(latest_version.saturating_sub(self.prune_window));
+    }
+
+    fn set_min_readable_version_target(&self, min_readable_version: Version) {
         assert!(self.pruner_worker.is_some());
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
 
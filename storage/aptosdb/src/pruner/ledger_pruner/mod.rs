@@ -21,6 +21,7 @@ use crate::{
             transaction_info_pruner::TransactionInfoPruner, transaction_pruner::TransactionPruner,
             write_set_pruner::WriteSetPruner,
         },
+        pruner_manager::PruningImpactReport,
     },
     transaction_store::TransactionStore,
 };
@@ -31,7 +32,7 @@ use aptos_storage_interface::Result;
 use aptos_types::transaction::{AtomicVersion, Version};
 use rayon::prelude::*;
 use std::{
-    cmp::min,
+    cmp::{max, min},
     sync::{atomic::Ordering, Arc},
 };
 
@@ -86,6 +87,32 @@ impl DBPruner for LedgerPruner {
         Ok(target_version)
     }
 
+    fn dry_run(&self, target_version: Version, max_versions: usize) -> Result<PruningImpactReport> {
+        let mut report = PruningImpactReport::default();
+        let mut progress = self.progress();
+
+        while progress < target_version {
+            let current_batch_target_version =
+                min(progress + max(max_versions, 1) as Version, target_version);
+
+            report.merge(
+                self.ledger_metadata_pruner
+                    .dry_run(progress, current_batch_target_version)?,
+            );
+            for sub_pruner in &self.sub_pruners {
+                report.merge(
+                    sub_pruner
+                        .dry_run(progress, current_batch_target_version)
+                        .map_err(|err| anyhow!("{} failed to dry run: {err}", sub_pruner.name()))?,
+                );
+            }
+
+            progress = current_batch_target_version;
+        }
+
+        Ok(report)
+    }
+
     fn progress(&self) -> Version {
         self.progress.load(Ordering::SeqCst)
     }
@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::{transaction_accumulator_db::TransactionAccumulatorDb, LedgerDb},
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner, pruner_manager::PruningImpactReport,
+        pruner_utils::get_or_initialize_subpruner_progress,
+    },
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
 };
 use aptos_logger::info;
@@ -23,16 +26,20 @@ impl DBSubPruner for TransactionAccumulatorPruner {
     }
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
-        let batch = SchemaBatch::new();
-        TransactionAccumulatorDb::prune(current_progress, target_version, &batch)?;
-        batch.put::<DbMetadataSchema>(
-            &DbMetadataKey::TransactionAccumulatorPrunerProgress,
-            &DbMetadataValue::Version(target_version),
-        )?;
+        let batch = self.prune_batch(current_progress, target_version)?;
         self.ledger_db
             .transaction_accumulator_db()
             .write_schemas(batch)
     }
+
+    fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        PruningImpactReport::estimate(self.ledger_db.transaction_accumulator_db_raw(), &batch)
+    }
 }
 
 impl TransactionAccumulatorPruner {
@@ -57,4 +64,19 @@ impl TransactionAccumulatorPruner {
 
         Ok(myself)
     }
+
+    fn prune_batch(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<SchemaBatch> {
+        let batch = SchemaBatch::new();
+        TransactionAccumulatorDb::prune(current_progress, target_version, &batch)?;
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::TransactionAccumulatorPrunerProgress,
+            &DbMetadataValue::Version(target_version),
+        )?;
+
+        Ok(batch)
+    }
 }
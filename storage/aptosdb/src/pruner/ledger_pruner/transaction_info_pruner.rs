@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::{transaction_info_db::TransactionInfoDb, LedgerDb},
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner, pruner_manager::PruningImpactReport,
+        pruner_utils::get_or_initialize_subpruner_progress,
+    },
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
 };
 use aptos_logger::info;
@@ -23,14 +26,18 @@ impl DBSubPruner for TransactionInfoPruner {
     }
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
-        let batch = SchemaBatch::new();
-        TransactionInfoDb::prune(current_progress, target_version, &batch)?;
-        batch.put::<DbMetadataSchema>(
-            &DbMetadataKey::TransactionInfoPrunerProgress,
-            &DbMetadataValue::Version(target_version),
-        )?;
+        let batch = self.prune_batch(current_progress, target_version)?;
         self.ledger_db.transaction_info_db().write_schemas(batch)
     }
+
+    fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        PruningImpactReport::estimate(self.ledger_db.transaction_info_db_raw(), &batch)
+    }
 }
 
 impl TransactionInfoPruner {
@@ -55,4 +62,19 @@ impl TransactionInfoPruner {
 
         Ok(myself)
     }
+
+    fn prune_batch(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<SchemaBatch> {
+        let batch = SchemaBatch::new();
+        TransactionInfoDb::prune(current_progress, target_version, &batch)?;
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::TransactionInfoPrunerProgress,
+            &DbMetadataValue::Version(target_version),
+        )?;
+
+        Ok(batch)
+    }
 }
@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::LedgerDb,
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner, pruner_manager::PruningImpactReport,
+        pruner_utils::get_or_initialize_subpruner_progress,
+    },
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
         transaction::TransactionSchema,
@@ -28,25 +31,18 @@ impl DBSubPruner for TransactionPruner {
     }
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
-        let batch = SchemaBatch::new();
-        let candidate_transactions =
-            self.get_pruning_candidate_transactions(current_progress, target_version)?;
-        self.ledger_db
-            .transaction_db()
-            .prune_transaction_by_hash_indices(&candidate_transactions, &batch)?;
-        self.transaction_store
-            .prune_transaction_by_account(&candidate_transactions, &batch)?;
-        self.ledger_db.transaction_db().prune_transactions(
-            current_progress,
-            target_version,
-            &batch,
-        )?;
-        batch.put::<DbMetadataSchema>(
-            &DbMetadataKey::TransactionPrunerProgress,
-            &DbMetadataValue::Version(target_version),
-        )?;
+        let batch = self.prune_batch(current_progress, target_version)?;
         self.ledger_db.transaction_db().write_schemas(batch)
     }
+
+    fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        PruningImpactReport::estimate(self.ledger_db.transaction_db_raw(), &batch)
+    }
 }
 
 impl TransactionPruner {
@@ -102,4 +98,30 @@ impl TransactionPruner {
 
         Ok(txns)
     }
+
+    fn prune_batch(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<SchemaBatch> {
+        let batch = SchemaBatch::new();
+        let candidate_transactions =
+            self.get_pruning_candidate_transactions(current_progress, target_version)?;
+        self.ledger_db
+            .transaction_db()
+            .prune_transaction_by_hash_indices(&candidate_transactions, &batch)?;
+        self.transaction_store
+            .prune_transaction_by_account(&candidate_transactions, &batch)?;
+        self.ledger_db.transaction_db().prune_transactions(
+            current_progress,
+            target_version,
+            &batch,
+        )?;
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::TransactionPrunerProgress,
+            &DbMetadataValue::Version(target_version),
+        )?;
+
+        Ok(batch)
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::pruner::db_pruner::DBPruner;
+use aptos_schemadb::{SchemaBatch, DB};
 use aptos_storage_interface::Result;
 use aptos_types::transaction::Version;
 
@@ -30,12 +31,21 @@ pub trait PrunerManager: Sync {
     /// Sets pruner target version when necessary.
     fn maybe_set_pruner_target_db_version(&self, latest_version: Version);
 
+    /// Sets the pruner target to `target_version` regardless of the prune window, so that
+    /// everything before it gets pruned. No-op if the target is not ahead of the min readable
+    /// version. Must only be called when the pruner is enabled.
+    fn set_pruner_target_version(&self, target_version: Version);
+
     // Only used at the end of fast sync to store the min_readable_version to db and update the
     // in memory progress.
     fn save_min_readable_version(&self, min_readable_version: Version) -> Result<()>;
 
     fn is_pruning_pending(&self) -> bool;
 
+    /// Estimates what pruning everything before `target_version` would delete, without deleting
+    /// anything. Works whether the pruner is enabled or not.
+    fn dry_run_prune(&self, target_version: Version) -> Result<PruningImpactReport>;
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
     /// an internal counter.
     #[cfg(test)]
@@ -72,3 +82,37 @@ pub trait PrunerManager: Sync {
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version);
 }
+
+/// Estimated impact of pruning, see `PrunerManager::dry_run_prune`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PruningImpactReport {
+    pub estimated_keys_deleted: u64,
+    pub estimated_bytes_freed: u64,
+    /// Sorted and deduplicated.
+    pub affected_column_families: Vec<String>,
+}
+
+impl PruningImpactReport {
+    /// Estimates the impact of writing the deletions in `batch` to `db`.
+    pub(crate) fn estimate(db: &DB, batch: &SchemaBatch) -> Result<Self> {
+        let estimate = db.estimate_deletions(batch)?;
+        Ok(Self {
+            estimated_keys_deleted: estimate.num_keys,
+            estimated_bytes_freed: estimate.num_bytes,
+            affected_column_families: estimate
+                .column_families
+                .into_iter()
+                .map(ToString::to_string)
+                .collect(),
+        })
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.estimated_keys_deleted += other.estimated_keys_deleted;
+        self.estimated_bytes_freed += other.estimated_bytes_freed;
+        self.affected_column_families
+            .extend(other.affected_column_families);
+        self.affected_column_families.sort_unstable();
+        self.affected_column_families.dedup();
+    }
+}
@@ -1,11 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::pruner::db_pruner::DBPruner;
+use crate::pruner::{db_pruner::DBPruner, pruner_manager::PruningImpactReport};
 use aptos_logger::{
     error,
     prelude::{sample, SampleRate},
 };
+use aptos_storage_interface::Result;
 use aptos_types::transaction::Version;
 use std::{
     sync::{
@@ -99,6 +100,12 @@ impl PrunerWorker {
     pub fn is_pruning_pending(&self) -> bool {
         self.inner.pruner.is_pruning_pending()
     }
+
+    pub fn dry_run(&self, target_version: Version) -> Result<PruningImpactReport> {
+        self.inner
+            .pruner
+            .dry_run(target_version, self.inner.batch_size)
+    }
 }
 
 impl Drop for PrunerWorker {
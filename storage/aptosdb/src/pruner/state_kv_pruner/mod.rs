@@ -9,6 +9,7 @@ use crate::{
     metrics::{OTHER_TIMERS_SECONDS, PRUNER_VERSIONS},
     pruner::{
         db_pruner::DBPruner,
+        pruner_manager::PruningImpactReport,
         state_kv_pruner::{
             state_kv_metadata_pruner::StateKvMetadataPruner,
             state_kv_shard_pruner::StateKvShardPruner,
@@ -23,7 +24,7 @@ use aptos_storage_interface::Result;
 use aptos_types::transaction::{AtomicVersion, Version};
 use rayon::prelude::*;
 use std::{
-    cmp::min,
+    cmp::{max, min},
     sync::{atomic::Ordering, Arc},
 };
 
@@ -86,6 +87,37 @@ impl DBPruner for StateKvPruner {
         Ok(target_version)
     }
 
+    fn dry_run(&self, target_version: Version, max_versions: usize) -> Result<PruningImpactReport> {
+        let mut report = PruningImpactReport::default();
+        let mut progress = self.progress();
+
+        while progress < target_version {
+            let current_batch_target_version =
+                min(progress + max(max_versions, 1) as Version, target_version);
+
+            report.merge(
+                self.metadata_pruner
+                    .dry_run(progress, current_batch_target_version)?,
+            );
+            for shard_pruner in &self.shard_pruners {
+                report.merge(
+                    shard_pruner
+                        .dry_run(progress, current_batch_target_version)
+                        .map_err(|err| {
+                            anyhow!(
+                                "Failed to dry run state kv shard {}: {err}",
+                                shard_pruner.shard_id(),
+                            )
+                        })?,
+                );
+            }
+
+            progress = current_batch_target_version;
+        }
+
+        Ok(report)
+    }
+
     fn progress(&self) -> Version {
         self.progress.load(Ordering::SeqCst)
     }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    pruner::pruner_manager::PruningImpactReport,
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
         stale_state_value_index::StaleStateValueIndexSchema,
@@ -30,6 +31,24 @@ impl StateKvMetadataPruner {
         current_progress: Version,
         target_version: Version,
     ) -> Result<()> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        self.state_kv_db.metadata_db().write_schemas(batch)
+    }
+
+    pub(in crate::pruner) fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        PruningImpactReport::estimate(self.state_kv_db.metadata_db(), &batch)
+    }
+
+    fn prune_batch(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<SchemaBatch> {
         let batch = SchemaBatch::new();
 
         if self.state_kv_db.enabled_sharding() {
@@ -70,7 +89,7 @@ impl StateKvMetadataPruner {
             &DbMetadataValue::Version(target_version),
         )?;
 
-        self.state_kv_db.metadata_db().write_schemas(batch)
+        Ok(batch)
     }
 
     pub(in crate::pruner) fn progress(&self) -> Result<Version> {
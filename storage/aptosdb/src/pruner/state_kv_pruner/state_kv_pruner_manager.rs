@@ -4,7 +4,10 @@
 use crate::{
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
-        pruner_manager::PrunerManager, pruner_utils, pruner_worker::PrunerWorker,
+        db_pruner::DBPruner,
+        pruner_manager::{PrunerManager, PruningImpactReport},
+        pruner_utils,
+        pruner_worker::PrunerWorker,
         state_kv_pruner::StateKvPruner,
     },
     state_kv_db::StateKvDb,
@@ -54,6 +57,12 @@ impl PrunerManager for StateKvPrunerManager {
         }
     }
 
+    fn set_pruner_target_version(&self, target_version: Version) {
+        if target_version > self.get_min_readable_version() {
+            self.set_min_readable_version_target(target_version);
+        }
+    }
+
     fn save_min_readable_version(&self, min_readable_version: Version) -> Result<()> {
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
@@ -71,6 +80,14 @@ impl PrunerManager for StateKvPrunerManager {
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn dry_run_prune(&self, target_version: Version) -> Result<PruningImpactReport> {
+        match &self.pruner_worker {
+            Some(pruner_worker) => pruner_worker.dry_run(target_version),
+            None => StateKvPruner::new(Arc::clone(&self.state_kv_db))?
+                .dry_run(target_version, self.pruning_batch_size),
+        }
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -126,8 +143,11 @@ impl StateKvPrunerManager {
     }
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
+        self.set_min_readable_version_target(latest_version.saturating_sub(self.prune_window));
+    }
+
+    fn set_min_readable_version_target(&self, min_readable_version: Version) {
         assert!(self.pruner_worker.is_some());
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
 
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    pruner::pruner_utils::get_or_initialize_subpruner_progress,
+    pruner::{
+        pruner_manager::PruningImpactReport, pruner_utils::get_or_initialize_subpruner_progress,
+    },
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
         stale_state_value_index::StaleStateValueIndexSchema,
@@ -48,6 +50,28 @@ impl StateKvShardPruner {
         current_progress: Version,
         target_version: Version,
     ) -> Result<()> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        self.db_shard.write_schemas(batch)
+    }
+
+    pub(in crate::pruner) fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        let batch = self.prune_batch(current_progress, target_version)?;
+        PruningImpactReport::estimate(&self.db_shard, &batch)
+    }
+
+    pub(in crate::pruner) fn shard_id(&self) -> u8 {
+        self.shard_id
+    }
+
+    fn prune_batch(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<SchemaBatch> {
         let batch = SchemaBatch::new();
 
         let mut iter = self
@@ -67,10 +91,6 @@ impl StateKvShardPruner {
             &DbMetadataValue::Version(target_version),
         )?;
 
-        self.db_shard.write_schemas(batch)
-    }
-
-    pub(in crate::pruner) fn shard_id(&self) -> u8 {
-        self.shard_id
+        Ok(batch)
     }
 }
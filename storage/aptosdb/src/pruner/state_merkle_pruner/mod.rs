@@ -12,19 +12,21 @@ use crate::{
     metrics::{OTHER_TIMERS_SECONDS, PRUNER_VERSIONS},
     pruner::{
         db_pruner::DBPruner,
+        pruner_manager::PruningImpactReport,
         state_merkle_pruner::{
             generics::StaleNodeIndexSchemaTrait,
             state_merkle_metadata_pruner::StateMerkleMetadataPruner,
             state_merkle_shard_pruner::StateMerkleShardPruner,
         },
     },
+    schema::jellyfish_merkle_node::JellyfishMerkleNodeSchema,
     state_merkle_db::StateMerkleDb,
 };
 use anyhow::anyhow;
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_jellyfish_merkle::{node_type::NodeKey, StaleNodeIndex};
 use aptos_logger::info;
-use aptos_schemadb::{schema::KeyCodec, ReadOptions, DB};
+use aptos_schemadb::{schema::KeyCodec, ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::Result;
 use aptos_types::transaction::{AtomicVersion, Version};
 use rayon::prelude::*;
@@ -94,6 +96,34 @@ where
         Ok(target_version)
     }
 
+    fn dry_run(
+        &self,
+        target_version: Version,
+        _max_versions: usize,
+    ) -> Result<PruningImpactReport> {
+        let progress = self.progress();
+        let mut report = PruningImpactReport::default();
+        if progress >= target_version {
+            return Ok(report);
+        }
+
+        report.merge(self.metadata_pruner.dry_run(progress, target_version)?);
+        for shard_pruner in &self.shard_pruners {
+            report.merge(
+                shard_pruner
+                    .dry_run(progress, target_version)
+                    .map_err(|err| {
+                        anyhow!(
+                            "Failed to dry run state merkle shard {}: {err}",
+                            shard_pruner.shard_id(),
+                        )
+                    })?,
+            );
+        }
+
+        Ok(report)
+    }
+
     fn progress(&self) -> Version {
         self.progress.load(Ordering::SeqCst)
     }
@@ -183,6 +213,21 @@ where
             .map_err(Into::into)
     }
 
+    pub(in crate::pruner::state_merkle_pruner) fn dry_run_single_db(
+        state_merkle_db_shard: &DB,
+        start_version: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        let (indices, _) =
+            Self::get_stale_node_indices(state_merkle_db_shard, start_version, target_version)?;
+        let batch = SchemaBatch::new();
+        indices.into_iter().try_for_each(|index| {
+            batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key)?;
+            batch.delete::<S>(&index)
+        })?;
+        PruningImpactReport::estimate(state_merkle_db_shard, &batch)
+    }
+
     pub(in crate::pruner::state_merkle_pruner) fn get_stale_node_indices(
         state_merkle_db_shard: &DB,
         start_version: Version,
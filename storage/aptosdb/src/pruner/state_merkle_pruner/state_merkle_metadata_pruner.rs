@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    pruner::state_merkle_pruner::{generics::StaleNodeIndexSchemaTrait, StateMerklePruner},
+    pruner::{
+        pruner_manager::PruningImpactReport,
+        state_merkle_pruner::{generics::StaleNodeIndexSchemaTrait, StateMerklePruner},
+    },
     schema::{
         db_metadata::{DbMetadataSchema, DbMetadataValue},
         jellyfish_merkle_node::JellyfishMerkleNodeSchema,
@@ -77,6 +80,18 @@ where
         Ok(Some(target_version_for_this_round))
     }
 
+    pub(in crate::pruner) fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        Ok(StateMerklePruner::<S>::dry_run_single_db(
+            &self.metadata_db,
+            current_progress,
+            target_version,
+        )?)
+    }
+
     pub(in crate::pruner) fn progress(&self) -> Result<Version> {
         Ok(get_progress(&self.metadata_db, &S::progress_metadata_key(None))?.unwrap_or(0))
     }
@@ -7,7 +7,8 @@
 use crate::{
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
-        pruner_manager::PrunerManager,
+        db_pruner::DBPruner,
+        pruner_manager::{PrunerManager, PruningImpactReport},
         pruner_utils,
         pruner_worker::PrunerWorker,
         state_merkle_pruner::{generics::StaleNodeIndexSchemaTrait, StateMerklePruner},
@@ -71,6 +72,12 @@ where
         }
     }
 
+    fn set_pruner_target_version(&self, target_version: Version) {
+        if target_version > self.get_min_readable_version() {
+            self.set_min_readable_version_target(target_version);
+        }
+    }
+
     fn save_min_readable_version(&self, min_readable_version: Version) -> Result<()> {
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
@@ -89,6 +96,14 @@ where
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn dry_run_prune(&self, target_version: Version) -> Result<PruningImpactReport> {
+        match &self.pruner_worker {
+            Some(pruner_worker) => pruner_worker.dry_run(target_version),
+            None => StateMerklePruner::<S>::new(Arc::clone(&self.state_merkle_db))?
+                .dry_run(target_version, /*max_versions=*/ 0),
+        }
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -157,9 +172,11 @@ where
     }
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
-        assert!(self.pruner_worker.is_some());
+        self.set_min_readable_version_target(latest_version.saturating_sub(self.prune_window));
+    }
 
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
+    fn set_min_readable_version_target(&self, min_readable_version: Version) {
+        assert!(self.pruner_worker.is_some());
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
 
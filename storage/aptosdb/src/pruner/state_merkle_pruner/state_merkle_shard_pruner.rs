@@ -3,6 +3,7 @@
 
 use crate::{
     pruner::{
+        pruner_manager::PruningImpactReport,
         pruner_utils::get_or_initialize_subpruner_progress,
         state_merkle_pruner::{generics::StaleNodeIndexSchemaTrait, StateMerklePruner},
     },
@@ -97,6 +98,18 @@ where
         Ok(())
     }
 
+    pub(in crate::pruner) fn dry_run(
+        &self,
+        current_progress: Version,
+        target_version: Version,
+    ) -> Result<PruningImpactReport> {
+        Ok(StateMerklePruner::<S>::dry_run_single_db(
+            &self.db_shard,
+            current_progress,
+            target_version,
+        )?)
+    }
+
     pub(in crate::pruner) fn shard_id(&self) -> u8 {
         self.shard_id
     }
@@ -0,0 +1,87 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ledger_db::{ledger_metadata_db::LedgerMetadataDb, LedgerDb},
+    state_store::StateStore,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::Result;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+    thread::JoinHandle,
+    time::Duration,
+};
+
+fn catch_up_with_primary(ledger_db: &LedgerDb, state_store: &StateStore) -> Result<()> {
+    let version_before = ledger_db.metadata_db().get_latest_version().ok();
+    // Catch up state merkle db first, so that state is never behind the ledger.
+    state_store.state_merkle_db.try_catch_up_with_primary()?;
+    ledger_db.try_catch_up_with_primary()?;
+    if ledger_db.metadata_db().get_latest_version().ok() != version_before {
+        // The latest ledger info is only made visible once the state is rebuilt up to it.
+        state_store.try_reset()?;
+        ledger_db.metadata_db().refresh_latest_ledger_info()?;
+    }
+    Ok(())
+}
+
+/// Periodically catches up a DB opened in secondary mode with its primary, in a background thread.
+pub(crate) struct ReplicaCatchUp {
+    /// Ledger info of the primary, opened as its own secondary instance so that it can be caught
+    /// up independently of the replica.
+    primary_ledger_info_probe: LedgerMetadataDb,
+    sender: Mutex<mpsc::Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl ReplicaCatchUp {
+    pub(crate) fn new(
+        ledger_db: Arc<LedgerDb>,
+        state_store: Arc<StateStore>,
+        primary_ledger_info_probe: LedgerMetadataDb,
+        interval: Duration,
+    ) -> Self {
+        let (send, recv) = mpsc::channel();
+        let join_handle = Some(thread::spawn(move || loop {
+            match recv.recv_timeout(interval) {
+                Ok(_) => break,
+                Err(mpsc::RecvTimeoutError::Timeout) => (),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+            if let Err(e) = catch_up_with_primary(&ledger_db, &state_store) {
+                warn!(
+                    error = ?e,
+                    "Catching up with primary failed."
+                );
+            }
+        }));
+        Self {
+            primary_ledger_info_probe,
+            sender: Mutex::new(send),
+            join_handle,
+        }
+    }
+
+    /// Returns the latest ledger info committed by the primary.
+    pub(crate) fn primary_latest_ledger_info(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        self.primary_ledger_info_probe.try_catch_up_with_primary()?;
+        self.primary_ledger_info_probe.refresh_latest_ledger_info()?;
+        Ok(self.primary_ledger_info_probe.get_latest_ledger_info_option())
+    }
+}
+
+impl Drop for ReplicaCatchUp {
+    fn drop(&mut self) {
+        // Notify the catch up thread to exit
+        self.sender.lock().send(()).unwrap();
+        self.join_handle
+            .take()
+            .expect("Replica catch up thread must exist.")
+            .join()
+            .expect("Replica catch up thread should join peacefully.");
+    }
+}
@@ -167,6 +167,7 @@ fn update_rocksdb_properties(
             }
         }
     }
+    state_kv_db.shard_distribution_stats()?;
     Ok(())
 }
 
@@ -0,0 +1,83 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which a ContractEvent (
+//! represented by a <txn_version, event_idx> tuple so that it can be fetched from `EventSchema`)
+//! can be found by the account that created its event stream and the struct type of the event,
+//! regardless of which event key it was emitted under.
+//!
+//! The struct type is stored as the sha3-256 hash of its BCS encoding, see
+//! [`event_type_hash`], so that keys are of fixed length.
+//!
+//! ```text
+//! |<--------------------key------------------->|
+//! | address | type_hash | txn_ver | idx |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::{account_address::AccountAddress, transaction::Version};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::language_storage::StructTag;
+use std::{convert::TryFrom, mem::size_of};
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (AccountAddress, HashValue, Version, Index);
+
+const KEY_LEN: usize =
+    AccountAddress::LENGTH + HashValue::LENGTH + size_of::<Version>() + size_of::<Index>();
+
+/// Hash identifying an event struct type in `EventByTypeSchema`.
+pub(crate) fn event_type_hash(struct_tag: &StructTag) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(struct_tag)?))
+}
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref address, ref type_hash, version, index) = *self;
+
+        let mut encoded = Vec::with_capacity(KEY_LEN);
+        encoded.extend_from_slice(address.as_ref());
+        encoded.extend_from_slice(type_hash.as_ref());
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, KEY_LEN)?;
+
+        const TYPE_HASH_OFFSET: usize = AccountAddress::LENGTH;
+        const VERSION_OFFSET: usize = TYPE_HASH_OFFSET + HashValue::LENGTH;
+        const INDEX_OFFSET: usize = VERSION_OFFSET + size_of::<Version>();
+
+        let address = AccountAddress::try_from(&data[..TYPE_HASH_OFFSET])?;
+        let type_hash = HashValue::from_slice(&data[TYPE_HASH_OFFSET..VERSION_OFFSET])?;
+        let version = (&data[VERSION_OFFSET..INDEX_OFFSET]).read_u64::<BigEndian>()?;
+        let index = (&data[INDEX_OFFSET..]).read_u64::<BigEndian>()?;
+
+        Ok((address, type_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -6,8 +6,11 @@
 use crate::{
     common::NUM_STATE_SHARDS,
     db_options::{gen_state_kv_cfds, state_kv_db_column_families},
-    metrics::OTHER_TIMERS_SECONDS,
-    schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+    metrics::{OTHER_TIMERS_SECONDS, STATE_KV_SHARD_BYTES, STATE_KV_SHARD_KEY_COUNT},
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        STATE_VALUE_CF_NAME,
+    },
     utils::truncation_helper::{get_state_kv_commit_progress, truncate_state_kv_db_shards},
 };
 use aptos_config::config::{RocksdbConfig, RocksdbConfigs, StorageDirPaths};
@@ -20,7 +23,11 @@ use aptos_types::transaction::Version;
 use arr_macro::arr;
 use std::{
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
 };
 
 pub const STATE_KV_DB_FOLDER_NAME: &str = "state_kv_db";
@@ -30,6 +37,22 @@ pub struct StateKvDb {
     state_kv_metadata_db: Arc<DB>,
     state_kv_db_shards: [Arc<DB>; NUM_STATE_SHARDS],
     enabled_sharding: bool,
+    /// Number of state values written to each shard since the DB was opened.
+    shard_writes: [AtomicU64; NUM_STATE_SHARDS],
+    opened_at: Instant,
+}
+
+/// How much of the state lives in a single shard of the state K/V DB, to detect skew in the
+/// distribution of keys across shards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShardStats {
+    pub shard_id: usize,
+    /// Estimated number of keys in the shard, as reported by RocksDB.
+    pub key_count: u64,
+    /// Estimated size of the live data in the shard, as reported by RocksDB.
+    pub estimated_size_bytes: u64,
+    /// Average number of state values written to the shard per second since the DB was opened.
+    pub write_rate_per_sec: f64,
 }
 
 impl StateKvDb {
@@ -46,6 +69,8 @@ impl StateKvDb {
                 state_kv_metadata_db: Arc::clone(&ledger_db),
                 state_kv_db_shards: arr![Arc::clone(&ledger_db); 16],
                 enabled_sharding: false,
+                shard_writes: arr![AtomicU64::new(0); 16],
+                opened_at: Instant::now(),
             });
         }
 
@@ -86,6 +111,8 @@ impl StateKvDb {
             state_kv_metadata_db,
             state_kv_db_shards,
             enabled_sharding: true,
+            shard_writes: arr![AtomicU64::new(0); 16],
+            opened_at: Instant::now(),
         };
 
         if let Some(overall_kv_commit_progress) = get_state_kv_commit_progress(&state_kv_db)? {
@@ -196,6 +223,52 @@ impl StateKvDb {
         NUM_STATE_SHARDS as u8
     }
 
+    pub(crate) fn record_shard_writes(&self, shard_id: usize, num_writes: u64) {
+        self.shard_writes[shard_id].fetch_add(num_writes, Ordering::Relaxed);
+    }
+
+    /// Returns the key count, size and write rate of each shard, and updates the corresponding
+    /// gauges. If sharding is disabled, all the state lives in a single DB, reported as shard 0.
+    pub fn shard_distribution_stats(&self) -> Result<Vec<ShardStats>> {
+        let elapsed_secs = self.opened_at.elapsed().as_secs_f64().max(f64::EPSILON);
+        let num_shards = if self.enabled_sharding {
+            NUM_STATE_SHARDS
+        } else {
+            1
+        };
+        let stats = (0..num_shards)
+            .map(|shard_id| {
+                let db = self.db_shard(shard_id as u8);
+                let num_writes = if self.enabled_sharding {
+                    self.shard_writes[shard_id].load(Ordering::Relaxed)
+                } else {
+                    self.shard_writes
+                        .iter()
+                        .map(|writes| writes.load(Ordering::Relaxed))
+                        .sum()
+                };
+                Ok(ShardStats {
+                    shard_id,
+                    key_count: db.get_property(STATE_VALUE_CF_NAME, "rocksdb.estimate-num-keys")?,
+                    estimated_size_bytes: db
+                        .get_property(STATE_VALUE_CF_NAME, "rocksdb.estimate-live-data-size")?,
+                    write_rate_per_sec: num_writes as f64 / elapsed_secs,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for shard_stats in &stats {
+            let shard = shard_stats.shard_id.to_string();
+            STATE_KV_SHARD_KEY_COUNT
+                .with_label_values(&[&shard])
+                .set(shard_stats.key_count as i64);
+            STATE_KV_SHARD_BYTES
+                .with_label_values(&[&shard])
+                .set(shard_stats.estimated_size_bytes as i64);
+        }
+        Ok(stats)
+    }
+
     pub(crate) fn commit_single_shard(
         &self,
         version: Version,
@@ -73,15 +73,8 @@ impl StateMerkleDb {
     ) -> Result<Self> {
         let sharding = rocksdb_configs.enable_storage_sharding;
         let state_merkle_db_config = rocksdb_configs.state_merkle_db_config;
-        // TODO(grao): Currently when this value is set to 0 we disable both caches. This is
-        // hacky, need to revisit.
-        let enable_cache = max_nodes_per_lru_cache_shard > 0;
-        let mut version_caches = HashMap::with_capacity(NUM_STATE_SHARDS + 1);
-        version_caches.insert(None, VersionedNodeCache::new());
-        for i in 0..NUM_STATE_SHARDS {
-            version_caches.insert(Some(i as u8), VersionedNodeCache::new());
-        }
-        let lru_cache = LruNodeCache::new(max_nodes_per_lru_cache_shard);
+        let (enable_cache, version_caches, lru_cache) =
+            Self::new_caches(max_nodes_per_lru_cache_shard);
         if !sharding {
             info!("Sharded state merkle DB is not enabled!");
             let state_merkle_db_path = db_paths.default_root_path().join(STATE_MERKLE_DB_NAME);
@@ -111,6 +104,70 @@ impl StateMerkleDb {
         )
     }
 
+    /// Opens the state merkle db of the primary at `db_paths` as a RocksDB secondary instance,
+    /// which sees the primary's writes after each call to `try_catch_up_with_primary`.
+    /// Only supported when storage sharding is disabled.
+    pub(crate) fn new_secondary(
+        db_paths: &StorageDirPaths,
+        secondary_db_root_path: &Path,
+        rocksdb_configs: RocksdbConfigs,
+        max_nodes_per_lru_cache_shard: usize,
+    ) -> Result<Self> {
+        ensure!(
+            !rocksdb_configs.enable_storage_sharding,
+            "Opening sharded state merkle db as secondary is not supported."
+        );
+        let (enable_cache, version_caches, lru_cache) =
+            Self::new_caches(max_nodes_per_lru_cache_shard);
+        let state_merkle_db_path = db_paths.default_root_path().join(STATE_MERKLE_DB_NAME);
+        let mut db_opts = gen_rocksdb_options(&rocksdb_configs.state_merkle_db_config, true);
+        // A secondary instance needs to keep all files open to not lose track of files deleted
+        // by the primary.
+        db_opts.set_max_open_files(-1);
+        let db = Arc::new(DB::open_cf_as_secondary(
+            &db_opts,
+            state_merkle_db_path.clone(),
+            secondary_db_root_path.join(STATE_MERKLE_DB_NAME),
+            STATE_MERKLE_DB_NAME,
+            state_merkle_db_column_families(),
+        )?);
+
+        info!(
+            state_merkle_db_path = state_merkle_db_path,
+            "Opened state merkle db as secondary!"
+        );
+
+        Ok(Self {
+            state_merkle_metadata_db: Arc::clone(&db),
+            state_merkle_db_shards: arr![Arc::clone(&db); 16],
+            enable_sharding: false,
+            enable_cache,
+            version_caches,
+            lru_cache,
+        })
+    }
+
+    /// Catches up with the primary if opened via `new_secondary`.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<()> {
+        // All the shards share the same RocksDB instance since sharding is disabled.
+        self.state_merkle_metadata_db.try_catch_up_with_primary()
+    }
+
+    fn new_caches(
+        max_nodes_per_lru_cache_shard: usize,
+    ) -> (bool, HashMap<Option<u8>, VersionedNodeCache>, LruNodeCache) {
+        // TODO(grao): Currently when this value is set to 0 we disable both caches. This is
+        // hacky, need to revisit.
+        let enable_cache = max_nodes_per_lru_cache_shard > 0;
+        let mut version_caches = HashMap::with_capacity(NUM_STATE_SHARDS + 1);
+        version_caches.insert(None, VersionedNodeCache::new());
+        for i in 0..NUM_STATE_SHARDS {
+            version_caches.insert(Some(i as u8), VersionedNodeCache::new());
+        }
+        let lru_cache = LruNodeCache::new(max_nodes_per_lru_cache_shard);
+        (enable_cache, version_caches, lru_cache)
+    }
+
     pub(crate) fn commit(
         &self,
         version: Version,
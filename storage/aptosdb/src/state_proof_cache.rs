@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::STATE_PROOF_CACHE;
+use aptos_types::{state_proof::StateProof, transaction::Version};
+use dashmap::DashMap;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+pub(crate) const DEFAULT_STATE_PROOF_CACHE_TTL: Duration = Duration::from_millis(500);
+
+/// Hits, misses and evictions of the state proof cache since the DB was opened.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// Caches the state proofs returned by `get_state_proof`, by known version and version of the
+/// ledger info they are relative to, for `ttl`. A proof is never served against another ledger
+/// info, and all the entries are invalidated when the latest ledger info changes.
+pub(crate) struct StateProofCache {
+    entries: DashMap<(Version, Version), (StateProof, Instant)>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl StateProofCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, known_version: Version, ledger_version: Version) -> Option<StateProof> {
+        let key = (known_version, ledger_version);
+        let expired = match self.entries.get(&key) {
+            Some(entry) if entry.1.elapsed() < self.ttl => {
+                self.record(&self.hits, "hit", 1);
+                return Some(entry.0.clone());
+            },
+            Some(_) => true,
+            None => false,
+        };
+        if expired
+            && self
+                .entries
+                .remove_if(&key, |_, (_, inserted)| {
+                    inserted.elapsed() >= self.ttl
+                })
+                .is_some()
+        {
+            self.record(&self.evictions, "eviction", 1);
+        }
+        self.record(&self.misses, "miss", 1);
+        None
+    }
+
+    pub fn put(&self, known_version: Version, ledger_version: Version, state_proof: StateProof) {
+        self.entries
+            .insert((known_version, ledger_version), (state_proof, Instant::now()));
+    }
+
+    pub fn invalidate(&self) {
+        let num_entries = self.entries.len();
+        self.entries.clear();
+        self.record(&self.evictions, "eviction", num_entries as u64);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record(&self, counter: &AtomicU64, event: &str, count: u64) {
+        counter.fetch_add(count, Ordering::Relaxed);
+        STATE_PROOF_CACHE.with_label_values(&[event]).inc_by(count);
+    }
+}
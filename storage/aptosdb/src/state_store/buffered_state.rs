@@ -40,6 +40,9 @@ pub struct BufferedState {
     state_after_checkpoint: StateDelta,
     state_commit_sender: SyncSender<CommitMessage<Arc<StateDelta>>>,
     target_items: usize,
+    /// Set on a replica, which must not write to the DB. The state is then only kept in memory
+    /// and never committed as a snapshot.
+    read_only: bool,
     join_handle: Option<JoinHandle<()>>,
 }
 
@@ -54,6 +57,7 @@ impl BufferedState {
         state_db: &Arc<StateDb>,
         state_after_checkpoint: StateDelta,
         target_items: usize,
+        read_only: bool,
     ) -> (Self, SmtAncestors<StateValue>) {
         let (state_commit_sender, state_commit_receiver) =
             mpsc::sync_channel(ASYNC_COMMIT_CHANNEL_BUFFER_SIZE as usize);
@@ -77,6 +81,7 @@ impl BufferedState {
             state_after_checkpoint,
             state_commit_sender,
             target_items,
+            read_only,
             // The join handle of the async state commit thread for graceful drop.
             join_handle: Some(join_handle),
         };
@@ -96,6 +101,9 @@ impl BufferedState {
     /// If a commit is needed, it sends a CommitMessage::Data message to the StateSnapshotCommitter thread to commit the data.
     /// If sync_commit is true, it also sends a CommitMessage::Sync message to ensure that the commit is completed before returning.
     fn maybe_commit(&mut self, sync_commit: bool) {
+        if self.read_only {
+            return;
+        }
         if sync_commit {
             let (commit_sync_sender, commit_sync_receiver) = mpsc::channel();
             if let Some(to_commit) = self.state_until_checkpoint.take().map(Arc::from) {
@@ -60,7 +60,7 @@ use aptos_types::{
         state_value::{StaleStateValueIndex, StateValue, StateValueChunkWithProof},
         ShardedStateUpdates, StateViewId,
     },
-    transaction::Version,
+    transaction::{TransactionToCommit, Version},
     write_set::{TransactionWrite, WriteSet},
 };
 use claims::{assert_ge, assert_le};
@@ -101,6 +101,8 @@ pub(crate) struct StateStore {
     buffered_state: Mutex<BufferedState>,
     buffered_state_target_items: usize,
     smt_ancestors: Mutex<SmtAncestors<StateValue>>,
+    /// Set on a replica, whose buffered state is only kept in memory.
+    read_only: bool,
 }
 
 impl Deref for StateStore {
@@ -191,6 +193,20 @@ impl DbReader for StateDb {
         ))
     }
 
+    /// Get the state values with proofs given the state keys and version, in the order of the keys
+    fn get_state_values_with_proofs_by_version_ext(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<(Option<StateValue>, SparseMerkleProofExt)>> {
+        THREAD_MANAGER.get_io_pool().install(|| {
+            state_keys
+                .par_iter()
+                .map(|state_key| self.get_state_value_with_proof_by_version_ext(state_key, version))
+                .collect()
+        })
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         version.map_or(Ok(StateStorageUsage::zero()), |version| {
             Ok(match self.ledger_db.metadata_db().get_usage(version) {
@@ -258,6 +274,16 @@ impl DbReader for StateStore {
         self.deref()
             .get_state_value_with_proof_by_version_ext(state_key, version)
     }
+
+    /// Get the state values with proof extensions given the state keys and version
+    fn get_state_values_with_proofs_by_version_ext(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<(Option<StateValue>, SparseMerkleProofExt)>> {
+        self.deref()
+            .get_state_values_with_proofs_by_version_ext(state_keys, version)
+    }
 }
 
 impl StateDb {
@@ -290,8 +316,12 @@ impl StateStore {
         hack_for_tests: bool,
         empty_buffered_state_for_restore: bool,
         skip_usage: bool,
+        read_only: bool,
     ) -> Self {
-        if !hack_for_tests && !empty_buffered_state_for_restore {
+        // A replica can't truncate the partial writes of the primary. It checks the commit
+        // progress with `check_commit_progress` instead, and never reads past the latest
+        // ledger info.
+        if !hack_for_tests && !empty_buffered_state_for_restore && !read_only {
             Self::sync_commit_progress(
                 Arc::clone(&ledger_db),
                 Arc::clone(&state_kv_db),
@@ -312,6 +342,7 @@ impl StateStore {
                 &state_db,
                 StateDelta::new_empty(),
                 buffered_state_target_items,
+                read_only,
             )
         } else {
             Self::create_buffered_state_from_latest_snapshot(
@@ -319,6 +350,7 @@ impl StateStore {
                 buffered_state_target_items,
                 hack_for_tests,
                 /*check_max_versions_after_snapshot=*/ true,
+                read_only,
             )
             .expect("buffered state creation failed.")
         };
@@ -328,6 +360,7 @@ impl StateStore {
             buffered_state: Mutex::new(buffered_state),
             buffered_state_target_items,
             smt_ancestors: Mutex::new(smt_ancestors),
+            read_only,
         }
     }
 
@@ -392,6 +425,40 @@ impl StateStore {
         }
     }
 
+    /// Checks that the ledger db and the state K/V db have both committed up to the overall
+    /// commit progress, without truncating what they wrote past it. Used when opening a replica,
+    /// which can't write to the DB.
+    pub(crate) fn check_commit_progress(
+        ledger_db: &LedgerDb,
+        state_kv_db: &StateKvDb,
+    ) -> Result<()> {
+        let ledger_metadata_db = ledger_db.metadata_db();
+        if let Ok(overall_commit_progress) = ledger_metadata_db.get_latest_version() {
+            let ledger_commit_progress = ledger_metadata_db.get_ledger_commit_progress()?;
+            ensure!(
+                ledger_commit_progress >= overall_commit_progress,
+                "Ledger commit progress {} is behind overall commit progress {}.",
+                ledger_commit_progress,
+                overall_commit_progress,
+            );
+
+            let state_kv_commit_progress = state_kv_db
+                .metadata_db()
+                .get::<DbMetadataSchema>(&DbMetadataKey::StateKvCommitProgress)?
+                .ok_or_else(|| {
+                    AptosDbError::NotFound("State K/V commit progress cannot be None.".to_string())
+                })?
+                .expect_version();
+            ensure!(
+                state_kv_commit_progress >= overall_commit_progress,
+                "State K/V commit progress {} is behind overall commit progress {}.",
+                state_kv_commit_progress,
+                overall_commit_progress,
+            );
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "db-debugger")]
     pub fn catch_up_state_merkle_db(
         ledger_db: Arc<LedgerDb>,
@@ -423,7 +490,7 @@ impl StateStore {
         });
         let (buffered_state, _) = Self::create_buffered_state_from_latest_snapshot(
             &state_db, 0, /*hack_for_tests=*/ false,
-            /*check_max_versions_after_snapshot=*/ false,
+            /*check_max_versions_after_snapshot=*/ false, /*read_only=*/ false,
         )?;
         Ok(buffered_state.current_state().base_version)
     }
@@ -433,6 +500,7 @@ impl StateStore {
         buffered_state_target_items: usize,
         hack_for_tests: bool,
         check_max_versions_after_snapshot: bool,
+        read_only: bool,
     ) -> Result<(BufferedState, SmtAncestors<StateValue>)> {
         let num_transactions = state_db
             .ledger_db
@@ -467,6 +535,7 @@ impl StateStore {
                 latest_snapshot_version,
             ),
             buffered_state_target_items,
+            read_only,
         );
 
         // In some backup-restore tests we hope to open the db without consistency check.
@@ -552,15 +621,54 @@ impl StateStore {
     }
 
     pub fn reset(&self) {
+        self.try_reset().expect("buffered state creation failed.");
+    }
+
+    /// Recreates the buffered state from the latest snapshot and the write sets after it, e.g.
+    /// after a replica caught up with its primary.
+    pub(crate) fn try_reset(&self) -> Result<()> {
         let (buffered_state, smt_ancestors) = Self::create_buffered_state_from_latest_snapshot(
             &self.state_db,
             self.buffered_state_target_items,
             false,
             true,
-        )
-        .expect("buffered state creation failed.");
+            self.read_only,
+        )?;
         *self.buffered_state.lock() = buffered_state;
         *self.smt_ancestors.lock() = smt_ancestors;
+        Ok(())
+    }
+
+    /// Calculates the in-memory state after `txns_to_commit` are applied on top of the current
+    /// state, together with the state updates until the last checkpoint among them, as expected
+    /// by `save_transactions`. Used to re-apply transactions recovered from the WAL.
+    pub(crate) fn calculate_state_for_txns(
+        &self,
+        txns_to_commit: &[TransactionToCommit],
+    ) -> Result<(Option<ShardedStateUpdates>, StateDelta)> {
+        let current_state = self.buffered_state.lock().current_state().clone();
+        let next_version = current_state.current_version.map_or(0, |v| v + 1);
+        let state_view = CachedStateView::new_impl(
+            StateViewId::Miscellaneous,
+            self.get_state_snapshot_before(next_version)?,
+            current_state.current.freeze(&current_state.base),
+            Arc::new(AsyncProofFetcher::new(self.state_db.clone())),
+        );
+        let write_sets = txns_to_commit
+            .iter()
+            .map(|txn_to_commit| txn_to_commit.write_set().clone())
+            .collect::<Vec<_>>();
+        let last_checkpoint_index = txns_to_commit
+            .iter()
+            .rposition(|txn_to_commit| txn_to_commit.is_state_checkpoint());
+        state_view.prime_cache_by_write_set(&write_sets)?;
+
+        InMemoryStateCalculatorV2::calculate_for_write_sets_after_snapshot(
+            &current_state,
+            state_view.into_state_cache(),
+            last_checkpoint_index,
+            &write_sets,
+        )
     }
 
     pub fn buffered_state(&self) -> &Mutex<BufferedState> {
@@ -585,6 +693,35 @@ impl StateStore {
         )
     }
 
+    /// Returns the earliest version no greater than `ledger_version` at which `state_key` has a
+    /// value.
+    ///
+    /// State values overwritten before the state kv pruner's min readable version are deleted, so
+    /// with pruning enabled this is the oldest version still retained for the key, which is only
+    /// an upper bound of the version the key was first written at.
+    pub fn get_earliest_state_value_version(
+        &self,
+        state_key: &StateKey,
+        ledger_version: Version,
+    ) -> Result<Option<Version>> {
+        let mut iter = self
+            .state_kv_db
+            .db_shard(state_key.get_shard_id())
+            .rev_iter::<StateValueSchema>(ReadOptions::default())?;
+        // Versions are encoded in descending order, so the oldest entry of a key sorts last.
+        iter.seek_for_prev(&(state_key.clone(), 0))?;
+        for res in iter {
+            let ((key, version), value_opt) = res?;
+            if &key != state_key || version > ledger_version {
+                break;
+            }
+            if value_opt.is_some() {
+                return Ok(Some(version));
+            }
+        }
+        Ok(None)
+    }
+
     /// Gets the proof that proves a range of accounts.
     pub fn get_value_range_proof(
         &self,
@@ -709,7 +846,15 @@ impl StateStore {
                             batch.put::<StateValueSchema>(&(k.clone(), version), v)
                         })
                     })
-                    .collect::<Result<_>>()
+                    .collect::<Result<()>>()?;
+                self.state_kv_db.record_shard_writes(
+                    shard_id,
+                    value_state_sets
+                        .iter()
+                        .map(|shards| shards[shard_id].len() as u64)
+                        .sum(),
+                );
+                Ok(())
             })?;
 
         // Eventually this index will move to indexer side. For now we temporarily write this into
@@ -23,6 +23,7 @@ use aptos_types::{
     state_store::state_key::StateKeyTag,
 };
 use arr_macro::arr;
+use futures::{executor::block_on, StreamExt};
 use proptest::{collection::hash_map, prelude::*};
 use std::collections::HashMap;
 
@@ -120,6 +121,46 @@ fn test_empty_store() {
         .is_err());
 }
 
+#[test]
+fn test_state_kv_shard_distribution() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_sharding(&tmp_dir, 0);
+    let store = &db.state_store;
+
+    let num_versions = 10;
+    let keys_per_version = 1_000;
+    let mut base_version = None;
+    for version in 0..num_versions {
+        let value_set = (0..keys_per_version)
+            .map(|i| {
+                let key = format!("key_{}", version * keys_per_version + i);
+                (
+                    StateKey::raw(key.into_bytes()),
+                    StateValue::from(vec![version as u8]),
+                )
+            })
+            .collect();
+        put_value_set(store, value_set, version, base_version);
+        base_version = Some(version);
+    }
+
+    let stats = db.state_kv_db.shard_distribution_stats().unwrap();
+    assert_eq!(stats.len(), NUM_STATE_SHARDS);
+    let total_keys: u64 = stats.iter().map(|s| s.key_count).sum();
+    let average = total_keys as f64 / NUM_STATE_SHARDS as f64;
+    assert!(average > 0.0);
+    for shard_stats in &stats {
+        assert!(
+            shard_stats.key_count as f64 <= 3.0 * average,
+            "Shard {} holds {} keys, average is {}.",
+            shard_stats.shard_id,
+            shard_stats.key_count,
+            average,
+        );
+        assert!(shard_stats.write_rate_per_sec > 0.0);
+    }
+}
+
 #[test]
 fn test_state_store_reader_writer() {
     let tmp_dir = TempPath::new();
@@ -165,6 +206,130 @@ fn test_state_store_reader_writer() {
     verify_value_and_proof(store, key3, Some(&value3), 1, root);
 }
 
+#[test]
+fn test_get_state_values_with_proofs() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let key1 = StateKey::raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::raw(String::from("test_key2").into_bytes());
+    let key3 = StateKey::raw(String::from("test_key3").into_bytes());
+
+    let value1 = StateValue::from(String::from("test_val1").into_bytes());
+    let value1_update = StateValue::from(String::from("test_val1_update").into_bytes());
+    let value2 = StateValue::from(String::from("test_val2").into_bytes());
+
+    let root0 = put_value_set(store, vec![(key1.clone(), value1.clone())], 0, None);
+    let root1 = put_value_set(
+        store,
+        vec![
+            (key1.clone(), value1_update.clone()),
+            (key2.clone(), value2.clone()),
+        ],
+        1,
+        Some(0),
+    );
+
+    // Duplicated and missing keys keep their positions in the result.
+    let keys = vec![key3.clone(), key1.clone(), key2.clone(), key1.clone()];
+    for (version, root, expected_values) in [
+        (0, root0, vec![None, Some(&value1), None, Some(&value1)]),
+        (1, root1, vec![
+            None,
+            Some(&value1_update),
+            Some(&value2),
+            Some(&value1_update),
+        ]),
+    ] {
+        let values_with_proofs = db
+            .get_state_values_with_proofs_by_version_ext(&keys, version)
+            .unwrap();
+        assert_eq!(values_with_proofs.len(), keys.len());
+        for ((key, expected_value), (value, proof)) in keys
+            .iter()
+            .zip(expected_values)
+            .zip(values_with_proofs.iter())
+        {
+            assert_eq!(value.as_ref(), expected_value);
+            proof.verify(root, key.hash(), value.as_ref()).unwrap();
+        }
+    }
+
+    assert!(db
+        .get_state_values_with_proofs_by_version_ext(&[], 1)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_get_earliest_state_value_version() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let key1 = StateKey::raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::raw(String::from("test_key2").into_bytes());
+    let key3 = StateKey::raw(String::from("test_key3").into_bytes());
+
+    let value1 = StateValue::from(String::from("test_val1").into_bytes());
+    let value1_update = StateValue::from(String::from("test_val1_update").into_bytes());
+    let value2 = StateValue::from(String::from("test_val2").into_bytes());
+
+    put_value_set(store, vec![(key1.clone(), value1)], 0, None);
+    put_value_set(
+        store,
+        vec![(key1.clone(), value1_update), (key2.clone(), value2)],
+        1,
+        Some(0),
+    );
+
+    // Updates don't move the earliest version.
+    assert_eq!(
+        store.get_earliest_state_value_version(&key1, 1).unwrap(),
+        Some(0)
+    );
+    assert_eq!(
+        store.get_earliest_state_value_version(&key2, 1).unwrap(),
+        Some(1)
+    );
+    // Values beyond the ledger version are not visible.
+    assert_eq!(
+        store.get_earliest_state_value_version(&key2, 0).unwrap(),
+        None
+    );
+    assert_eq!(
+        store.get_earliest_state_value_version(&key3, 1).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_get_last_write_version() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let key1 = StateKey::raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::raw(String::from("test_key2").into_bytes());
+    let key3 = StateKey::raw(String::from("test_key3").into_bytes());
+
+    let value1 = StateValue::from(String::from("test_val1").into_bytes());
+    let value1_update = StateValue::from(String::from("test_val1_update").into_bytes());
+    let value2 = StateValue::from(String::from("test_val2").into_bytes());
+
+    put_value_set(store, vec![(key1.clone(), value1)], 0, None);
+    put_value_set(store, vec![(key2.clone(), value2)], 1, Some(0));
+    put_value_set(store, vec![(key1.clone(), value1_update)], 2, Some(1));
+
+    // Written once.
+    assert_eq!(db.get_last_write_version(&key2, 2).unwrap(), Some(1));
+    assert_eq!(db.get_last_write_version(&key2, 0).unwrap(), None);
+    // Overwritten, the latest write as of the ledger version wins.
+    assert_eq!(db.get_last_write_version(&key1, 0).unwrap(), Some(0));
+    assert_eq!(db.get_last_write_version(&key1, 1).unwrap(), Some(0));
+    assert_eq!(db.get_last_write_version(&key1, 2).unwrap(), Some(2));
+    // Never written.
+    assert_eq!(db.get_last_write_version(&key3, 2).unwrap(), None);
+}
+
 fn traverse_values(
     store: &StateStore,
     prefix: &StateKeyPrefix,
@@ -186,6 +351,59 @@ fn traverse_values(
     }
 }
 
+#[test]
+fn test_get_state_snapshot_chunk_iter() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let address = AccountAddress::new([12u8; AccountAddress::LENGTH]);
+    let key = |i: usize| {
+        StateKey::access_path(AccessPath::new(
+            address,
+            format!("state_key{}", i).into_bytes(),
+        ))
+    };
+    let value = |i: usize, version: Version| {
+        StateValue::from(format!("value{}_v{}", i, version).into_bytes())
+    };
+
+    put_value_set(
+        store,
+        (0..60).map(|i| (key(i), value(i, 0))).collect(),
+        0,
+        None,
+    );
+    put_value_set(
+        store,
+        (40..100).map(|i| (key(i), value(i, 1))).collect(),
+        1,
+        Some(0),
+    );
+
+    let account_key_prefix = StateKeyPrefix::new(StateKeyTag::AccessPath, address.to_vec());
+    for (version, num_values) in [(0, 60), (1, 100)] {
+        let chunks = db
+            .get_state_snapshot_chunk_iter(version, 7)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks.len(), (num_values + 6) / 7);
+        assert!(chunks.last().unwrap().is_last_chunk());
+
+        let key_value_map: HashMap<_, _> = chunks
+            .into_iter()
+            .flat_map(|chunk| chunk.raw_values)
+            .collect();
+        assert_eq!(key_value_map.len(), num_values);
+        assert_eq!(
+            key_value_map,
+            traverse_values(store, &account_key_prefix, version)
+        );
+    }
+
+    assert!(db.get_state_snapshot_chunk_iter(1, 0).is_err());
+}
+
 #[test]
 fn test_get_values_by_key_prefix() {
     let tmp_dir = TempPath::new();
@@ -466,6 +684,47 @@ proptest! {
         );
     }
 
+    #[test]
+    fn test_state_stream(
+        (input, chunk_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
+            .prop_flat_map(|input| {
+                let len = input.len();
+                (Just(input), 1..len*2)
+            })
+    ) {
+        let tmp_dir1 = TempPath::new();
+        let db1 = AptosDB::new_for_test(&tmp_dir1);
+        init_store(&db1.state_store, input.clone().into_iter());
+
+        let version = (input.len() - 1) as Version;
+        let expected_root_hash = db1.state_store.get_root_hash(version).unwrap();
+        let chunks: Vec<_> = block_on(
+            db1.get_state_stream(version, chunk_size)
+                .unwrap()
+                .collect::<Vec<_>>(),
+        )
+        .into_iter()
+        .collect::<Result<_>>()
+        .unwrap();
+
+        let tmp_dir2 = TempPath::new();
+        let db2 = AptosDB::new_for_test(&tmp_dir2);
+        let store2 = &db2.state_store;
+        let mut restore = store2.get_snapshot_receiver(version, expected_root_hash).unwrap();
+        let mut expected_first_index = 0;
+        for (i, chunk) in chunks.iter().enumerate() {
+            prop_assert_eq!(chunk.first_index, expected_first_index);
+            prop_assert_eq!(chunk.root_hash, expected_root_hash);
+            prop_assert_eq!(chunk.is_last_chunk(), i == chunks.len() - 1);
+            expected_first_index = chunk.last_index + 1;
+            restore.add_chunk(chunk.raw_values.clone(), chunk.proof.clone()).unwrap();
+        }
+        prop_assert_eq!(expected_first_index, input.len() as u64);
+
+        restore.finish_box().unwrap();
+        prop_assert_eq!(store2.get_root_hash(version).unwrap(), expected_root_hash);
+    }
+
     #[test]
     fn test_get_rightmost_leaf(
         (input, batch1_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
@@ -5,9 +5,14 @@
 //! This file defines transaction store APIs that are related to committed signed transactions.
 
 use crate::{
-    ledger_db::LedgerDb, schema::transaction_by_account::TransactionByAccountSchema,
-    utils::iterators::AccountTransactionVersionIter,
+    ledger_db::LedgerDb,
+    schema::{
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+    },
+    utils::iterators::{AccountTransactionVersionDescIter, AccountTransactionVersionIter},
 };
+use aptos_crypto::HashValue;
 use aptos_schemadb::{ReadOptions, SchemaBatch};
 use aptos_storage_interface::{AptosDbError, Result};
 use aptos_types::{
@@ -29,6 +34,17 @@ impl TransactionStore {
         Self { ledger_db }
     }
 
+    /// Returns false if no transaction with `hash` is in the DB, without reading from disk when
+    /// the transaction by hash index has bloom filters, see
+    /// `RocksdbConfigs::transaction_hash_bloom_bits`. A true result can be a false positive.
+    pub fn might_contain_hash(&self, hash: HashValue) -> bool {
+        self.ledger_db
+            .transaction_db_raw()
+            .key_may_exist::<TransactionByHashSchema>(&hash)
+            // Fall back to the actual lookup.
+            .unwrap_or(true)
+    }
+
     /// Gets the version of a transaction by the sender `address` and `sequence_number`.
     pub fn get_account_transaction_version(
         &self,
@@ -77,6 +93,35 @@ impl TransactionStore {
         ))
     }
 
+    /// Same as `get_account_transaction_version_iter`, but walks the transactions sent by an
+    /// account backwards, from sequence number `end_seq_num` down, returning at most `limit`
+    /// results. Yields the same results as reversing the ascending iterator over the `limit`
+    /// sequence numbers ending at `end_seq_num`.
+    pub fn get_account_transaction_version_iter_desc(
+        &self,
+        address: AccountAddress,
+        end_seq_num: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionVersionDescIter> {
+        let mut iter = self
+            .ledger_db
+            .transaction_db_raw()
+            .rev_iter::<TransactionByAccountSchema>(ReadOptions::default())?;
+        iter.seek_for_prev(&(address, end_seq_num))?;
+        // With a limit of 0, the minimum sequence number is beyond the end and nothing is returned.
+        let min_seq_num = match limit {
+            0 => end_seq_num.saturating_add(1),
+            limit => end_seq_num.saturating_sub(limit - 1),
+        };
+        Ok(AccountTransactionVersionDescIter::new(
+            iter,
+            address,
+            min_seq_num,
+            ledger_version,
+        ))
+    }
+
     /// Prune the transaction by account store given a list of transaction
     pub fn prune_transaction_by_account(
         &self,
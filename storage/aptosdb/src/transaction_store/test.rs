@@ -6,6 +6,7 @@
 
 use super::*;
 use crate::{ledger_db::transaction_db_test::init_db, AptosDB};
+use aptos_crypto::hash::CryptoHash;
 use aptos_proptest_helpers::Index;
 use aptos_temppath::TempPath;
 use aptos_types::proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen};
@@ -43,6 +44,7 @@ proptest! {
                     .unwrap(),
                 Some(ver as Version)
             );
+            prop_assert!(store.might_contain_hash(txn.hash()));
         }
     }
 
@@ -128,4 +130,56 @@ proptest! {
 
         prop_assert_eq!(&actual_scan, &expected_scan);
     }
+
+    #[test]
+    fn test_get_account_transaction_version_iter_desc(
+        universe in any_with::<AccountInfoUniverse>(5),
+        gens in vec(
+            (any::<Index>(), any::<SignatureCheckedTransactionGen>()),
+            1..=50,
+        ),
+        end_seq_num in 0_u64..=15,
+        ledger_version in 0_u64..50,
+        limit in 0_u64..=20,
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+        let txns = init_db(universe, gens, db.ledger_db.transaction_db());
+
+        let mut addresses = txns
+            .iter()
+            .map(|txn| txn.try_as_signed_user_txn().unwrap().sender())
+            .collect::<Vec<_>>();
+        addresses.push(AccountAddress::from_hex_literal("0x1234").unwrap());
+
+        for address in addresses {
+            let descending = store
+                .get_account_transaction_version_iter_desc(
+                    address,
+                    end_seq_num,
+                    limit,
+                    ledger_version,
+                )
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+
+            // The same sequence numbers, scanned in ascending order.
+            let min_seq_num = (end_seq_num + 1).saturating_sub(limit);
+            let mut ascending = store
+                .get_account_transaction_version_iter(
+                    address,
+                    min_seq_num,
+                    end_seq_num + 1 - min_seq_num,
+                    ledger_version,
+                )
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            ascending.reverse();
+
+            prop_assert_eq!(descending, ascending);
+        }
+    }
 }
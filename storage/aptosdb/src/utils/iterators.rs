@@ -329,6 +329,85 @@ impl<'a> Iterator for AccountTransactionVersionIter<'a> {
     }
 }
 
+/// Iterates the account transaction index backwards, from the highest sequence number down.
+pub struct AccountTransactionVersionDescIter<'a> {
+    inner: SchemaIterator<'a, TransactionByAccountSchema>,
+    address: AccountAddress,
+    expected_next_seq_num: Option<u64>,
+    min_seq_num: u64,
+    prev_version: Option<Version>,
+    ledger_version: Version,
+}
+
+impl<'a> AccountTransactionVersionDescIter<'a> {
+    pub(crate) fn new(
+        inner: SchemaIterator<'a, TransactionByAccountSchema>,
+        address: AccountAddress,
+        min_seq_num: u64,
+        ledger_version: Version,
+    ) -> Self {
+        Self {
+            inner,
+            address,
+            min_seq_num,
+            ledger_version,
+            expected_next_seq_num: None,
+            prev_version: None,
+        }
+    }
+
+    fn next_impl(&mut self) -> Result<Option<(u64, Version)>> {
+        loop {
+            let ((address, seq_num), version) = match self.inner.next().transpose()? {
+                Some(item) => item,
+                None => return Ok(None),
+            };
+            // No more transactions sent by this account.
+            if address != self.address || seq_num < self.min_seq_num {
+                return Ok(None);
+            }
+
+            // Ensure seq_num_{i+1} == seq_num_{i} - 1
+            if let Some(expected_seq_num) = self.expected_next_seq_num {
+                ensure!(
+                    seq_num == expected_seq_num,
+                    "DB corruption: account transactions sequence numbers are not contiguous: \
+                     actual: {}, expected: {}",
+                    seq_num,
+                    expected_seq_num,
+                );
+            };
+
+            // Ensure version_{i+1} < version_{i}
+            if let Some(prev_version) = self.prev_version {
+                ensure!(
+                    version < prev_version,
+                    "DB corruption: account transaction versions are not strictly increasing: \
+                     previous version: {}, current version: {}",
+                    prev_version,
+                    version,
+                );
+            }
+            self.expected_next_seq_num = seq_num.checked_sub(1);
+            self.prev_version = Some(version);
+
+            // Transactions after this view of the ledger come first, skip them.
+            if version > self.ledger_version {
+                continue;
+            }
+            return Ok(Some((seq_num, version)));
+        }
+    }
+}
+
+impl<'a> Iterator for AccountTransactionVersionDescIter<'a> {
+    type Item = Result<(u64, Version)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_impl().transpose()
+    }
+}
+
 pub struct EpochEndingLedgerInfoIter<'a> {
     inner: SchemaIterator<'a, LedgerInfoSchema>,
     next_epoch: u64,
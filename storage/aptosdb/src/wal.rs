@@ -0,0 +1,124 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An append-only write-ahead log of the transactions handed to `save_transactions`.
+//!
+//! Each entry is the BCS encoding of `(first_version, ledger_info_with_sigs, txns_to_commit)`,
+//! prefixed with its length as a little-endian u64. The log is truncated once the commit it
+//! describes is fully persisted, so a non-empty log on startup means the process died in the
+//! middle of a commit.
+
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::Result;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionToCommit, Version},
+};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+const LEN_PREFIX_BYTES: usize = std::mem::size_of::<u64>();
+
+pub(crate) type WalEntry = (
+    Version,
+    Option<LedgerInfoWithSignatures>,
+    Vec<TransactionToCommit>,
+);
+
+#[derive(Debug)]
+pub(crate) struct Wal {
+    path: PathBuf,
+    sync_on_write: bool,
+    file: Mutex<File>,
+}
+
+impl Wal {
+    pub(crate) fn open(path: impl AsRef<Path>, sync_on_write: bool) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            sync_on_write,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn is_empty(&self) -> Result<bool> {
+        Ok(self.file.lock().metadata()?.len() == 0)
+    }
+
+    pub(crate) fn append(
+        &self,
+        first_version: Version,
+        ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+        txns_to_commit: &[TransactionToCommit],
+    ) -> Result<()> {
+        let bytes = bcs::to_bytes(&(first_version, ledger_info_with_sigs, txns_to_commit))?;
+        let mut buf = Vec::with_capacity(LEN_PREFIX_BYTES + bytes.len());
+        buf.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&bytes);
+
+        let mut file = self.file.lock();
+        file.write_all(&buf)?;
+        if self.sync_on_write {
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Reads all the complete entries in the log. A partially written trailing entry, left by a
+    /// crash while appending, is ignored since the commit it describes never started.
+    pub(crate) fn read_all(&self) -> Result<Vec<WalEntry>> {
+        let mut bytes = Vec::new();
+        File::open(&self.path)?.read_to_end(&mut bytes)?;
+
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            if bytes.len() - offset < LEN_PREFIX_BYTES {
+                break;
+            }
+            let mut len_bytes = [0u8; LEN_PREFIX_BYTES];
+            len_bytes.copy_from_slice(&bytes[offset..offset + LEN_PREFIX_BYTES]);
+            let len = u64::from_le_bytes(len_bytes) as usize;
+            let start = offset + LEN_PREFIX_BYTES;
+            if bytes.len() - start < len {
+                break;
+            }
+            entries.push(bcs::from_bytes(&bytes[start..start + len])?);
+            offset = start + len;
+        }
+        if offset < bytes.len() {
+            warn!(
+                path = ?self.path,
+                torn_bytes = bytes.len() - offset,
+                "Ignoring partially written entry at the end of the WAL.",
+            );
+        }
+        Ok(entries)
+    }
+
+    pub(crate) fn truncate(&self) -> Result<()> {
+        let file = self.file.lock();
+        file.set_len(0)?;
+        if self.sync_on_write {
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+}
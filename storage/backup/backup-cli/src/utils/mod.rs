@@ -109,6 +109,7 @@ impl From<RocksdbOpt> for RocksdbConfigs {
                 max_background_jobs: opt.max_background_jobs,
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
 }
@@ -0,0 +1,48 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_config::config::{
+    NodeConfig, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::AptosDB;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(
+    name = "aptos-db-dry-run-prune",
+    about = "Estimate what the pruners would delete with the given node config, without deleting anything."
+)]
+pub struct Command {
+    /// Path to the node config, whose storage section points to the DB and has the pruner config.
+    #[clap(long, value_parser)]
+    config: PathBuf,
+}
+
+impl Command {
+    pub fn run(self) -> Result<()> {
+        let config = NodeConfig::load_from_path(&self.config)?;
+
+        // Opened readonly with the pruners disabled, so that nothing is written or deleted.
+        let db = AptosDB::open(
+            config.storage.get_dir_paths(),
+            true,                        /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            config.storage.rocksdb_configs,
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        )?;
+        let report = db.dry_run_pruning(config.storage.storage_pruner_config)?;
+
+        println!("Estimated keys deleted: {}", report.estimated_keys_deleted);
+        println!("Estimated bytes freed: {}", report.estimated_bytes_freed);
+        println!(
+            "Affected column families: {:?}",
+            report.affected_column_families
+        );
+        Ok(())
+    }
+}
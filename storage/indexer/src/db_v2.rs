@@ -7,9 +7,10 @@
 /// and this file will be moved to /ecosystem/indexer-grpc/indexer-grpc-table-info.
 use crate::{
     metadata::{MetadataKey, MetadataValue},
+    metrics::{INDEXER_ASYNC_V2_LAG_ALERTS, INDEXER_ASYNC_V2_LAG_VERSIONS},
     schema::{indexer_metadata::IndexerMetadataSchema, table_info::TableInfoSchema},
 };
-use aptos_logger::info;
+use aptos_logger::{error, info, warn};
 use aptos_schemadb::{SchemaBatch, DB};
 use aptos_storage_interface::{
     db_other_bail as bail, state_view::DbStateViewAtVersion, AptosDbError, DbReader, Result,
@@ -43,8 +44,10 @@ use std::{
     },
     time::Duration,
 };
+use tokio::task::JoinHandle;
 
 const TABLE_INFO_RETRY_TIME_MILLIS: u64 = 10;
+const LAG_CHECK_INTERVAL_SECS: u64 = 10;
 
 #[derive(Debug)]
 pub struct IndexerAsyncV2 {
@@ -59,6 +62,9 @@ pub struct IndexerAsyncV2 {
     // is obscure and will be stored as bytes with parent table's handle, once parent table's parsed with instructions,
     // child table handle will be parsed accordingly.
     pending_on: DashMap<TableHandle, DashSet<Bytes>>,
+    // Number of versions behind the latest committed version above which an alert is raised.
+    // u64::MAX, the default, means alerting is disabled.
+    lag_alert_threshold: AtomicU64,
 }
 
 impl IndexerAsyncV2 {
@@ -71,6 +77,7 @@ impl IndexerAsyncV2 {
             db,
             next_version: AtomicU64::new(next_version),
             pending_on: DashMap::new(),
+            lag_alert_threshold: AtomicU64::new(u64::MAX),
         })
     }
 
@@ -185,6 +192,52 @@ impl IndexerAsyncV2 {
             .map_or(0, |v| v.expect_version())
     }
 
+    pub fn set_lag_alert_threshold(&self, versions_behind: u64) {
+        self.lag_alert_threshold
+            .store(versions_behind, Ordering::Relaxed);
+    }
+
+    /// Updates the lag gauge with how many committed versions the indexer has yet to process,
+    /// same as `DbReader::get_indexer_lag`, and alerts if that exceeds the lag alert threshold.
+    /// Returns the lag.
+    pub fn check_lag(&self, db_reader: &dyn DbReader) -> Result<u64> {
+        let latest_version = db_reader.get_latest_version()?;
+        let next_version = self.next_version();
+        let lag = (latest_version + 1).saturating_sub(next_version);
+        INDEXER_ASYNC_V2_LAG_VERSIONS.set(lag as i64);
+
+        let threshold = self.lag_alert_threshold.load(Ordering::Relaxed);
+        if lag > threshold {
+            warn!(
+                latest_version = latest_version,
+                next_version = next_version,
+                lag = lag,
+                threshold = threshold,
+                "[DB] Indexer async v2 is lagging behind the ledger"
+            );
+            INDEXER_ASYNC_V2_LAG_ALERTS.inc();
+        }
+        Ok(lag)
+    }
+
+    /// Spawns a task on the current tokio runtime checking the indexer lag every
+    /// `LAG_CHECK_INTERVAL_SECS` seconds.
+    pub fn spawn_lag_monitor(self: &Arc<Self>, db_reader: Arc<dyn DbReader>) -> JoinHandle<()> {
+        let indexer_async_v2 = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(LAG_CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if let Err(err) = indexer_async_v2.check_lag(db_reader.as_ref()) {
+                    error!(
+                        error = ?err,
+                        "[DB] Failed to check indexer async v2 lag"
+                    );
+                }
+            }
+        })
+    }
+
     pub fn get_table_info(&self, handle: TableHandle) -> Result<Option<TableInfo>> {
         self.db.get::<TableInfoSchema>(&handle).map_err(Into::into)
     }
@@ -371,3 +424,53 @@ impl<'a, R: ModuleResolver> TableInfoParser<'a, R> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db_ops::open_db;
+    use aptos_config::config::RocksdbConfig;
+    use aptos_temppath::TempPath;
+
+    struct MockLedger {
+        latest_version: AtomicU64,
+    }
+
+    impl DbReader for MockLedger {
+        fn get_latest_version(&self) -> Result<Version> {
+            Ok(self.latest_version.load(Ordering::Relaxed))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lag_alert() {
+        let tmp_dir = TempPath::new();
+        let db = open_db(&tmp_dir, &RocksdbConfig::default()).unwrap();
+        let indexer_async_v2 = Arc::new(IndexerAsyncV2::new(db).unwrap());
+        let ledger = Arc::new(MockLedger {
+            latest_version: AtomicU64::new(0),
+        });
+        indexer_async_v2.set_lag_alert_threshold(500);
+
+        let alerts_before = INDEXER_ASYNC_V2_LAG_ALERTS.get();
+        // Nothing is indexed yet, while the ledger has its genesis version committed.
+        assert_eq!(indexer_async_v2.check_lag(ledger.as_ref()).unwrap(), 1);
+        assert_eq!(INDEXER_ASYNC_V2_LAG_ALERTS.get(), alerts_before);
+
+        // The ledger advances without the indexer running.
+        ledger.latest_version.store(1000, Ordering::Relaxed);
+        assert_eq!(indexer_async_v2.check_lag(ledger.as_ref()).unwrap(), 1001);
+        assert_eq!(INDEXER_ASYNC_V2_LAG_ALERTS.get(), alerts_before + 1);
+        assert_eq!(INDEXER_ASYNC_V2_LAG_VERSIONS.get(), 1001);
+
+        // The first check of the monitor happens right away.
+        let monitor = indexer_async_v2.spawn_lag_monitor(ledger);
+        let mut retries = 0;
+        while INDEXER_ASYNC_V2_LAG_ALERTS.get() < alerts_before + 2 {
+            assert!(retries < 100, "Lag alert didn't fire.");
+            retries += 1;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        monitor.abort();
+    }
+}
@@ -6,6 +6,7 @@ mod db;
 pub mod db_ops;
 pub mod db_v2;
 mod metadata;
+mod metrics;
 mod schema;
 pub mod table_info_reader;
 
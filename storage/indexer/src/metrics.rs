@@ -0,0 +1,23 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{register_int_counter, register_int_gauge, IntCounter, IntGauge};
+use once_cell::sync::Lazy;
+
+/// Number of versions the indexer async v2 is behind the latest committed version.
+pub static INDEXER_ASYNC_V2_LAG_VERSIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "indexer_async_v2_lag_versions",
+        "Number of versions the indexer async v2 is behind the latest committed version"
+    )
+    .unwrap()
+});
+
+/// Number of times the indexer async v2 lag exceeded the alert threshold.
+pub static INDEXER_ASYNC_V2_LAG_ALERTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "indexer_async_v2_lag_alerts_total",
+        "Number of times the indexer async v2 lag exceeded the alert threshold"
+    )
+    .unwrap()
+});
@@ -0,0 +1,128 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the latency of looking up transaction hashes which are not in the DB, with and
+//! without bloom filters on an index shaped like the transaction by hash index of AptosDB, the
+//! way `AptosDB::get_transaction_by_hash` does it.
+
+use anyhow::Result;
+use aptos_schemadb::{
+    define_schema,
+    schema::{KeyCodec, Schema, ValueCodec},
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Options, SchemaBatch, DB,
+    DEFAULT_COLUMN_FAMILY_NAME,
+};
+use aptos_temppath::TempPath;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const NUM_TRANSACTIONS: u64 = 1_000_000;
+const WRITE_BATCH_SIZE: u64 = 10_000;
+const BLOOM_BITS_PER_KEY: u32 = 10;
+
+define_schema!(TransactionByHashSchema, TxnHash, u64, "transaction_by_hash");
+
+#[derive(Debug, Eq, PartialEq)]
+struct TxnHash([u8; 32]);
+
+impl TxnHash {
+    fn random(rng: &mut StdRng) -> Self {
+        Self(rng.gen())
+    }
+}
+
+impl KeyCodec<TransactionByHashSchema> for TxnHash {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.0.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(Self(data.try_into()?))
+    }
+}
+
+impl ValueCodec<TransactionByHashSchema> for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(u64::from_be_bytes(data.try_into()?))
+    }
+}
+
+/// Opens a DB like the ledger DB of AptosDB, filled with `NUM_TRANSACTIONS` random hashes.
+fn open_db(dir: &TempPath, bloom_bits_per_key: Option<u32>) -> DB {
+    let mut table_options = BlockBasedOptions::default();
+    table_options.set_block_size(4 << 10);
+    table_options.set_block_cache(&Cache::new_lru_cache(8 << 20));
+    if let Some(bits_per_key) = bloom_bits_per_key {
+        table_options.set_bloom_filter(bits_per_key as f64, false);
+    }
+    let mut cf_opts = Options::default();
+    cf_opts.set_compression_type(DBCompressionType::Lz4);
+    cf_opts.set_block_based_table_factory(&table_options);
+
+    let mut db_opts = Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.create_missing_column_families(true);
+    let db = DB::open_cf(&db_opts, dir.path(), "bloom_filter_bench", vec![
+        ColumnFamilyDescriptor::new(DEFAULT_COLUMN_FAMILY_NAME, Options::default()),
+        ColumnFamilyDescriptor::new(TransactionByHashSchema::COLUMN_FAMILY_NAME, cf_opts),
+    ])
+    .unwrap();
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for first_version in (0..NUM_TRANSACTIONS).step_by(WRITE_BATCH_SIZE as usize) {
+        let batch = SchemaBatch::new();
+        for version in first_version..first_version + WRITE_BATCH_SIZE {
+            batch
+                .put::<TransactionByHashSchema>(&TxnHash::random(&mut rng), &version)
+                .unwrap();
+        }
+        db.write_schemas(batch).unwrap();
+    }
+    db.flush_cf(TransactionByHashSchema::COLUMN_FAMILY_NAME)
+        .unwrap();
+    db
+}
+
+fn get_version_by_hash(db: &DB, hash: &TxnHash, check_filter: bool) -> Option<u64> {
+    if check_filter && !db.key_may_exist::<TransactionByHashSchema>(hash).unwrap() {
+        return None;
+    }
+    db.get::<TransactionByHashSchema>(hash).unwrap()
+}
+
+fn bench_missing_hash_lookups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transaction_by_hash_miss");
+    for (name, bloom_bits_per_key) in [
+        ("without_bloom_filter", None),
+        ("with_bloom_filter", Some(BLOOM_BITS_PER_KEY)),
+    ] {
+        let dir = TempPath::new();
+        let db = open_db(&dir, bloom_bits_per_key);
+        // A different seed than the one used to fill the DB, so the hashes are all misses.
+        let mut rng = StdRng::seed_from_u64(1);
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || TxnHash::random(&mut rng),
+                |hash| {
+                    assert_eq!(
+                        get_version_by_hash(&db, &hash, bloom_bits_per_key.is_some()),
+                        None
+                    )
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    name = bloom_filter_benches;
+    config = Criterion::default().sample_size(50);
+    targets = bench_missing_hash_lookups
+);
+criterion_main!(bloom_filter_benches);
@@ -40,7 +40,11 @@ pub use rocksdb::{
     BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Options, ReadOptions,
     SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
 };
-use std::{collections::HashMap, iter::Iterator, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    iter::Iterator,
+    path::Path,
+};
 
 pub type ColumnFamilyName = &'static str;
 
@@ -101,6 +105,18 @@ impl SchemaBatch {
     }
 }
 
+/// What applying the deletions in a [`SchemaBatch`] would remove from a DB, see
+/// [`DB::estimate_deletions`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeletionEstimate {
+    /// Number of existing keys that would be deleted.
+    pub num_keys: u64,
+    /// Total size of the keys and values that would be deleted.
+    pub num_bytes: u64,
+    /// Column families with at least one key to be deleted, sorted by name.
+    pub column_families: Vec<ColumnFamilyName>,
+}
+
 /// This DB is a schematized RocksDB wrapper where all data passed in and out are typed according to
 /// [`Schema`]s.
 #[derive(Debug)]
@@ -202,6 +218,17 @@ impl DB {
             .map_err(Into::into)
     }
 
+    /// Returns false if the key definitely doesn't exist. This is answered from the memtables
+    /// and the block cache, including the bloom filters of the SST files if the column family is
+    /// configured with them, without reading data blocks from disk. A true result can be a false
+    /// positive.
+    pub fn key_may_exist<S: Schema>(&self, schema_key: &S::Key) -> DbResult<bool> {
+        let k = <S::Key as KeyCodec<S>>::encode_key(schema_key)?;
+        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
+
+        Ok(self.inner.key_may_exist_cf(cf_handle, k))
+    }
+
     /// Writes single record.
     pub fn put<S: Schema>(&self, key: &S::Key, value: &S::Value) -> DbResult<()> {
         // Not necessary to use a batch, but we'd like a central place to bump counters.
@@ -291,6 +318,37 @@ impl DB {
         Ok(())
     }
 
+    /// Estimates what writing `batch` would delete, without writing anything. Deletions of keys
+    /// that don't exist, or that appear more than once in the batch, are only counted once if at
+    /// all. Puts in the batch are ignored.
+    pub fn estimate_deletions(&self, batch: &SchemaBatch) -> DbResult<DeletionEstimate> {
+        let rows_locked = batch.rows.lock();
+        let mut estimate = DeletionEstimate::default();
+        for (cf_name, rows) in rows_locked.iter() {
+            let cf_handle = self.get_cf_handle(cf_name)?;
+            let mut seen_keys = HashSet::new();
+            let mut num_keys = 0;
+            for write_op in rows {
+                if let WriteOp::Deletion { key } = write_op {
+                    if !seen_keys.insert(key) {
+                        continue;
+                    }
+                    if let Some(value) = self.inner.get_cf(cf_handle, key)? {
+                        num_keys += 1;
+                        estimate.num_bytes += (key.len() + value.len()) as u64;
+                    }
+                }
+            }
+            if num_keys > 0 {
+                estimate.num_keys += num_keys;
+                estimate.column_families.push(cf_name);
+            }
+        }
+        estimate.column_families.sort_unstable();
+
+        Ok(estimate)
+    }
+
     fn get_cf_handle(&self, cf_name: &str) -> DbResult<&rocksdb::ColumnFamily> {
         self.inner
             .cf_handle(cf_name)
@@ -328,6 +386,11 @@ impl DB {
         rocksdb::checkpoint::Checkpoint::new(&self.inner)?.create_checkpoint(path)?;
         Ok(())
     }
+
+    /// Makes a DB opened via `open_cf_as_secondary` see the latest writes made by the primary.
+    pub fn try_catch_up_with_primary(&self) -> DbResult<()> {
+        Ok(self.inner.try_catch_up_with_primary()?)
+    }
 }
 
 impl Drop for DB {
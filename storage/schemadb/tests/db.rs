@@ -6,7 +6,7 @@ use anyhow::Result;
 use aptos_schemadb::{
     define_schema,
     schema::{KeyCodec, Schema, ValueCodec},
-    ColumnFamilyName, SchemaBatch, DB,
+    ColumnFamilyName, DeletionEstimate, SchemaBatch, DB,
 };
 use aptos_storage_interface::AptosDbError;
 use byteorder::{LittleEndian, ReadBytesExt};
@@ -134,6 +134,25 @@ impl std::ops::Deref for TestDB {
     }
 }
 
+#[test]
+fn test_key_may_exist() {
+    let db = TestDB::new();
+
+    // Nothing in the memtables nor in any SST file.
+    assert!(!db.key_may_exist::<TestSchema1>(&TestField(0)).unwrap());
+
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.put::<TestSchema2>(&TestField(1), &TestField(1)).unwrap();
+    assert!(db.key_may_exist::<TestSchema1>(&TestField(0)).unwrap());
+    assert!(db.key_may_exist::<TestSchema2>(&TestField(1)).unwrap());
+
+    // No false negatives once the keys are flushed to SST files.
+    db.flush_cf(TestSchema1::COLUMN_FAMILY_NAME).unwrap();
+    db.flush_cf(TestSchema2::COLUMN_FAMILY_NAME).unwrap();
+    assert!(db.key_may_exist::<TestSchema1>(&TestField(0)).unwrap());
+    assert!(db.key_may_exist::<TestSchema2>(&TestField(1)).unwrap());
+}
+
 #[test]
 fn test_schema_put_get() {
     let db = TestDB::new();
@@ -273,6 +292,42 @@ fn test_two_schema_batches() {
     );
 }
 
+#[test]
+fn test_estimate_deletions() {
+    let db = TestDB::new();
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+    db.put::<TestSchema2>(&TestField(2), &TestField(2)).unwrap();
+
+    let db_batch = SchemaBatch::new();
+    db_batch.delete::<TestSchema1>(&TestField(0)).unwrap();
+    db_batch.delete::<TestSchema1>(&TestField(0)).unwrap();
+    db_batch.delete::<TestSchema1>(&TestField(1)).unwrap();
+    // doesn't exist
+    db_batch.delete::<TestSchema2>(&TestField(3)).unwrap();
+    db_batch
+        .put::<TestSchema2>(&TestField(4), &TestField(4))
+        .unwrap();
+
+    assert_eq!(
+        db.estimate_deletions(&db_batch).unwrap(),
+        DeletionEstimate {
+            num_keys: 2,
+            num_bytes: 16,
+            column_families: vec!["TestCF1"],
+        }
+    );
+    // nothing is written
+    assert_eq!(
+        collect_values::<TestSchema1>(&db),
+        gen_expected_values(&[(0, 0), (1, 1)]),
+    );
+    assert_eq!(
+        collect_values::<TestSchema2>(&db),
+        gen_expected_values(&[(2, 2)]),
+    );
+}
+
 #[test]
 fn test_reopen() {
     let tmpdir = aptos_temppath::TempPath::new();
@@ -10,6 +10,25 @@ use std::ops::Deref;
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 pub enum BlockInfo {
     V0(BlockInfoV0),
+    V1(BlockInfoV1),
+}
+
+impl BlockInfo {
+    /// Returns the participation stats of the block, or None if it was persisted as V0, i.e. it
+    /// carries no votes or has not been upgraded yet.
+    pub fn participation_stats(&self, block_height: u64) -> Option<BlockParticipationStats> {
+        match self {
+            BlockInfo::V0(_) => None,
+            BlockInfo::V1(v1) => Some(BlockParticipationStats {
+                block_height,
+                epoch: v1.epoch(),
+                round: v1.round(),
+                proposer: v1.proposer(),
+                proposer_vote_count: v1.proposer_vote_count(),
+                total_voting_power: v1.total_voting_power(),
+            }),
+        }
+    }
 }
 
 impl Deref for BlockInfo {
@@ -18,6 +37,7 @@ impl Deref for BlockInfo {
     fn deref(&self) -> &Self::Target {
         match self {
             BlockInfo::V0(v0) => v0,
+            BlockInfo::V1(v1) => &v1.v0,
         }
     }
 }
@@ -77,3 +97,52 @@ impl BlockInfoV0 {
         self.first_version
     }
 }
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+pub struct BlockInfoV1 {
+    v0: BlockInfoV0,
+    /// Number of validators whose votes on the parent block were aggregated by the proposer.
+    proposer_vote_count: u64,
+    /// Sum of the voting power of those validators.
+    total_voting_power: u64,
+}
+
+impl BlockInfoV1 {
+    pub fn new(v0: BlockInfoV0, proposer_vote_count: u64, total_voting_power: u64) -> Self {
+        Self {
+            v0,
+            proposer_vote_count,
+            total_voting_power,
+        }
+    }
+
+    pub fn proposer_vote_count(&self) -> u64 {
+        self.proposer_vote_count
+    }
+
+    pub fn total_voting_power(&self) -> u64 {
+        self.total_voting_power
+    }
+}
+
+impl Deref for BlockInfoV1 {
+    type Target = BlockInfoV0;
+
+    fn deref(&self) -> &Self::Target {
+        &self.v0
+    }
+}
+
+/// Participation of the validator set in a block, derived from the `NewBlockEvent`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockParticipationStats {
+    pub block_height: u64,
+    pub epoch: u64,
+    pub round: u64,
+    pub proposer: AccountAddress,
+    /// Number of validators whose votes on the parent block were aggregated by the proposer.
+    pub proposer_vote_count: u64,
+    /// Sum of the voting power of those validators.
+    pub total_voting_power: u64,
+}
@@ -18,6 +18,9 @@ pub enum AptosDbError {
     TooManyRequested(u64, u64),
     #[error("Missing state root node at version {0}, probably pruned.")]
     MissingRootError(u64),
+    /// A requested item is pruned.
+    #[error("{0} at version {1} is pruned, min available version is {2}.")]
+    Pruned(String, u64, u64),
     /// Other non-classified error.
     #[error("AptosDB Other Error: {0}")]
     Other(String),
@@ -69,6 +72,12 @@ impl From<std::num::ParseIntError> for AptosDbError {
     }
 }
 
+impl From<std::num::TryFromIntError> for AptosDbError {
+    fn from(error: std::num::TryFromIntError) -> Self {
+        Self::Other(format!("{}", error))
+    }
+}
+
 impl From<AptosDbError> for StateviewError {
     fn from(error: AptosDbError) -> Self {
         match error {
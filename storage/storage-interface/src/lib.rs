@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::cached_state_view::ShardedStateCache;
+use crate::{block_info::BlockParticipationStats, cached_state_view::ShardedStateCache};
 use anyhow::anyhow;
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_types::{
@@ -17,14 +17,14 @@ use aptos_types::{
     move_resource::MoveStorage,
     on_chain_config::{access_path_for_config, ConfigID},
     proof::{
-        AccumulatorConsistencyProof, SparseMerkleProof, SparseMerkleProofExt,
+        AccumulatorConsistencyProof, EventProof, SparseMerkleProof, SparseMerkleProofExt,
         SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
     },
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
         state_key_prefix::StateKeyPrefix,
-        state_storage_usage::StateStorageUsage,
+        state_storage_usage::{StateStorageUsage, StateStorageUsageDelta},
         state_value::{StateValue, StateValueChunkWithProof},
         table::{TableHandle, TableInfo},
         ShardedStateUpdates,
@@ -35,6 +35,7 @@ use aptos_types::{
     },
     write_set::WriteSet,
 };
+use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -138,6 +139,15 @@ pub trait DbReader: Send + Sync {
             end_epoch: u64,
         ) -> Result<EpochChangeProof>;
 
+        /// Returns an iterator over the epoch ending ledger infos of the epochs in
+        /// [start_epoch, end_epoch), without the cap `get_epoch_ending_ledger_infos` puts on the
+        /// number of results. The ledger infos are fetched lazily, page by page.
+        fn get_epoch_ending_ledger_info_iter(
+            &self,
+            start_epoch: u64,
+            end_epoch: u64,
+        ) -> Result<Box<dyn Iterator<Item = Result<LedgerInfoWithSignatures>> + '_>>;
+
         /// See [AptosDB::get_transactions].
         ///
         /// [AptosDB::get_transactions]: ../aptosdb/struct.AptosDB.html#method.get_transactions
@@ -204,6 +214,29 @@ pub trait DbReader: Send + Sync {
             ledger_version: Version,
         ) -> Result<Vec<EventWithVersion>>;
 
+        /// Returns events of type `event_type_tag` emitted under any event key created by
+        /// `address`. Unlike `get_events`, `start` is a transaction version cursor rather than a
+        /// sequence number: ascending results start at or after it, descending results at or
+        /// before it.
+        fn get_events_by_account_and_type(
+            &self,
+            address: AccountAddress,
+            event_type_tag: &StructTag,
+            start: u64,
+            order: Order,
+            limit: u64,
+            ledger_version: Version,
+        ) -> Result<Vec<EventWithVersion>>;
+
+        /// Returns the `event_index`-th event emitted by the transaction at `version`, with the
+        /// proof of its inclusion in the ledger at `ledger_version`.
+        fn get_event_proof(
+            &self,
+            version: Version,
+            event_index: u64,
+            ledger_version: Version,
+        ) -> Result<(ContractEvent, EventProof)>;
+
         fn get_transaction_iterator(
             &self,
             start_version: Version,
@@ -246,6 +279,24 @@ pub trait DbReader: Send + Sync {
         /// See [AptosDB::get_latest_block_events].
         fn get_latest_block_events(&self, num_events: usize) -> Result<Vec<EventWithVersion>>;
 
+        /// Returns the latest `num_events` NewBlockEvents as of `ledger_version`, in descending
+        /// order.
+        fn get_latest_block_events_before(
+            &self,
+            num_events: usize,
+            ledger_version: Version,
+        ) -> Result<Vec<EventWithVersion>>;
+
+        /// Returns up to `limit` NewBlockEvents in `order`, starting from the block at height
+        /// `cursor`, or from the first (ascending) or latest (descending) block if `cursor` is
+        /// `None`. Also returns the cursor of the next page, `None` if there are no more blocks.
+        fn get_block_events_page(
+            &self,
+            cursor: Option<u64>,
+            limit: usize,
+            order: Order,
+        ) -> Result<(Vec<EventWithVersion>, Option<u64>)>;
+
         /// Returns the start_version, end_version and NewBlockEvent of the block containing the input
         /// transaction version.
         fn get_block_info_by_version(
@@ -253,6 +304,9 @@ pub trait DbReader: Send + Sync {
             version: Version,
         ) -> Result<(Version, Version, NewBlockEvent)>;
 
+        /// Returns the height of the block containing the input transaction version.
+        fn get_block_height_containing_version(&self, version: Version) -> Result<u64>;
+
         /// Returns the start_version, end_version and NewBlockEvent of the block containing the input
         /// transaction version.
         fn get_block_info_by_height(
@@ -260,6 +314,14 @@ pub trait DbReader: Send + Sync {
             height: u64,
         ) -> Result<(Version, Version, NewBlockEvent)>;
 
+        /// Returns how much of the validator set participated in each block with height in
+        /// [start_block, end_block).
+        fn get_participation_stats(
+            &self,
+            start_block: u64,
+            end_block: u64,
+        ) -> Result<Vec<BlockParticipationStats>>;
+
         /// Gets the version of the last transaction committed before timestamp,
         /// a committed block at or after the required timestamp must exist (otherwise it's possible
         /// the next block committed as a timestamp smaller than the one in the request).
@@ -291,6 +353,9 @@ pub trait DbReader: Send + Sync {
         /// Returns the latest state checkpoint version if any.
         fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>>;
 
+        /// Returns whether the transaction at `version` ends with a state checkpoint.
+        fn is_state_checkpoint_version(&self, version: Version) -> Result<bool>;
+
         /// Returns the latest state snapshot strictly before `next_version` if any.
         fn get_state_snapshot_before(
             &self,
@@ -307,14 +372,23 @@ pub trait DbReader: Send + Sync {
             ledger_version: Version,
         ) -> Result<Option<TransactionWithProof>>;
 
+        /// Returns the earliest version at which the `0x1::account::Account` resource of
+        /// `address` exists, or `None` if the account doesn't exist.
+        ///
+        /// With the state kv pruner enabled, history older than the prune window is gone and the
+        /// result is only an upper bound of the actual creation version.
+        fn get_account_creation_version(&self, address: AccountAddress) -> Result<Option<Version>>;
+
         /// Returns the list of transactions sent by an account with `address` starting
-        /// at sequence number `seq_num`. Will return no more than `limit` transactions.
+        /// at sequence number `seq_num`, going up if `order` is ascending and down if it is
+        /// descending. Will return no more than `limit` transactions.
         /// Will ignore transactions with `txn.version > ledger_version`. Optionally
         /// fetch events for each transaction when `fetch_events` is `true`.
         fn get_account_transactions(
             &self,
             address: AccountAddress,
             seq_num: u64,
+            order: Order,
             limit: u64,
             include_events: bool,
             ledger_version: Version,
@@ -354,6 +428,14 @@ pub trait DbReader: Send + Sync {
             version: Version,
         ) -> Result<Option<(Version, StateValue)>>;
 
+        /// Returns the latest version no greater than `ledger_version` at which `state_key` was
+        /// written, or `None` if the key has no value as of `ledger_version`.
+        fn get_last_write_version(
+            &self,
+            state_key: &StateKey,
+            ledger_version: Version,
+        ) -> Result<Option<Version>>;
+
         /// Returns the proof of the given state key and version.
         fn get_state_proof_by_version_ext(
             &self,
@@ -375,6 +457,14 @@ pub trait DbReader: Send + Sync {
             version: Version,
         ) -> Result<(Option<StateValue>, SparseMerkleProofExt)>;
 
+        /// Batch version of [`DbReader::get_state_value_with_proof_by_version_ext`]. The result
+        /// at each position corresponds to the state key at the same position in `state_keys`.
+        fn get_state_values_with_proofs_by_version_ext(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> Result<Vec<(Option<StateValue>, SparseMerkleProofExt)>>;
+
         /// Gets the latest ExecutedTrees no matter if db has been bootstrapped.
         /// Used by the Db-bootstrapper.
         fn get_latest_executed_trees(&self) -> Result<ExecutedTrees>;
@@ -392,6 +482,10 @@ pub trait DbReader: Send + Sync {
         /// Caller must guarantee the version is not greater than the latest version.
         fn get_accumulator_root_hash(&self, _version: Version) -> Result<HashValue>;
 
+        /// Gets the transaction accumulator root hashes at the specified versions, in the same
+        /// order. Caller must guarantee no version is greater than the latest version.
+        fn get_accumulator_root_hashes(&self, versions: &[Version]) -> Result<Vec<HashValue>>;
+
         /// Gets an [`AccumulatorConsistencyProof`] starting from `client_known_version`
         /// (or pre-genesis if `None`) until `ledger_version`.
         ///
@@ -450,8 +544,20 @@ pub trait DbReader: Send + Sync {
         /// Returns whether the internal indexer DB has been enabled or not
         fn indexer_enabled(&self) -> bool;
 
+        /// Returns how many versions the internal indexer is behind the latest committed version,
+        /// `None` if the indexer is not enabled.
+        fn get_indexer_lag(&self) -> Result<Option<u64>>;
+
         /// Returns state storage usage at the end of an epoch.
         fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage>;
+
+        /// Returns the difference in state storage usage between versions `from` and `to`, in
+        /// items and bytes. Errors if `from > to` or the usage is not tracked at either version.
+        fn get_state_storage_usage_delta(
+            &self,
+            from: Version,
+            to: Version,
+        ) -> Result<StateStorageUsageDelta>;
     ); // end delegated
 
     /// Returns the latest ledger info.
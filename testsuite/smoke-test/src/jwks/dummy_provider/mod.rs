@@ -103,7 +103,9 @@ impl DummyProvider {
         handler_holder: Arc<RwLock<Option<Arc<dyn RequestHandler>>>>,
     ) -> Result<Response<Body>, Infallible> {
         let handler = handler_holder.write();
-        let raw_response = handler.as_ref().unwrap().handle(request);
-        Ok(Response::new(Body::from(raw_response)))
+        let (status, raw_response) = handler.as_ref().unwrap().handle_with_status(request);
+        let mut response = Response::new(Body::from(raw_response));
+        *response.status_mut() = status;
+        Ok(response)
     }
 }
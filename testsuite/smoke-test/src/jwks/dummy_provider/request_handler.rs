@@ -1,14 +1,31 @@
 // Copyright © Aptos Foundation
 
 use aptos_infallible::Mutex;
-use hyper::{Body, Request};
+use hyper::{Body, Request, StatusCode};
 use move_core_types::account_address::AccountAddress;
-use std::{collections::HashSet, str::FromStr};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
 
 /// A handler that handles JWK requests from a validator,
 /// assuming the validator account address is written as the COOKIE.
 pub trait RequestHandler: Send + Sync {
     fn handle(&self, request: Request<Body>) -> Vec<u8>;
+
+    /// Like `handle`, but also decides the HTTP status of the reply, which is `200 OK` by default.
+    fn handle_with_status(&self, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+        (StatusCode::OK, self.handle(request))
+    }
+}
+
+/// A `RequestHandler` whose replies are taken from a fixed set of JWK contents,
+/// so that a test can tell which results are acceptable.
+pub trait JWKContentServer: RequestHandler {
+    /// All the contents this server may reply with.
+    fn contents(&self) -> Vec<Vec<u8>>;
 }
 
 pub struct StaticContentServer {
@@ -31,6 +48,12 @@ impl RequestHandler for StaticContentServer {
     }
 }
 
+impl JWKContentServer for StaticContentServer {
+    fn contents(&self) -> Vec<Vec<u8>> {
+        vec![self.content.clone()]
+    }
+}
+
 fn origin_from_cookie(request: &Request<Body>) -> AccountAddress {
     let cookie = request
         .headers()
@@ -41,41 +64,139 @@ fn origin_from_cookie(request: &Request<Body>) -> AccountAddress {
     AccountAddress::from_str(cookie).unwrap()
 }
 
-/// The first `k` requesters will get content A forever, the rest will get content B forever.
+/// How an `EquivocatingServer` picks the content of a reply.
+#[derive(Clone, Copy, Debug)]
+pub enum EquivocationPattern {
+    /// The first `k` requesters will get the first content forever, the rest will get the second content forever.
+    FirstK(usize),
+    /// Every request gets the content after the one of the previous request.
+    Alternating,
+    /// Every request gets a content picked uniformly at random, using an RNG with the given seed.
+    RandomUniform(u64),
+    /// Every content is served for `step` consecutive requests before moving on to the next one.
+    StepN(usize),
+    /// Every requester always gets the same content, picked by its identity.
+    /// All requests come from localhost, so the requester is identified by the address in the COOKIE.
+    BySourceIp,
+}
+
+/// A server that replies with different contents, following an `EquivocationPattern`.
 pub struct EquivocatingServer {
-    content_a: Vec<u8>,
-    content_b: Vec<u8>,
-    k: usize,
-    requesters_observed: Mutex<HashSet<AccountAddress>>,
+    contents: Vec<Vec<u8>>,
+    pattern: EquivocationPattern,
+    state: Mutex<EquivocationState>,
+}
+
+struct EquivocationState {
+    num_requests: usize,
+    requesters_observed: HashSet<AccountAddress>,
+    rng: StdRng,
 }
 
 impl EquivocatingServer {
+    /// The first `k` requesters will get content A forever, the rest will get content B forever.
     pub fn new(content_a: Vec<u8>, content_b: Vec<u8>, k: usize) -> Self {
+        Self::new_with_pattern(vec![content_a, content_b], EquivocationPattern::FirstK(k))
+    }
+
+    pub fn new_with_pattern(contents: Vec<Vec<u8>>, pattern: EquivocationPattern) -> Self {
+        assert!(!contents.is_empty(), "at least one content is required");
+        let seed = match pattern {
+            EquivocationPattern::RandomUniform(seed) => seed,
+            _ => 0,
+        };
         Self {
-            content_a,
-            content_b,
-            k,
-            requesters_observed: Mutex::new(HashSet::new()),
+            contents,
+            pattern,
+            state: Mutex::new(EquivocationState {
+                num_requests: 0,
+                requesters_observed: HashSet::new(),
+                rng: StdRng::seed_from_u64(seed),
+            }),
         }
     }
 }
 
 impl RequestHandler for EquivocatingServer {
     fn handle(&self, request: Request<Body>) -> Vec<u8> {
-        let mut requesters_observed = self.requesters_observed.lock();
-        let origin = origin_from_cookie(&request);
-        if requesters_observed.len() < self.k {
-            requesters_observed.insert(origin);
+        let mut state = self.state.lock();
+        let num_contents = self.contents.len();
+        let idx = match self.pattern {
+            EquivocationPattern::FirstK(k) => {
+                let origin = origin_from_cookie(&request);
+                if state.requesters_observed.len() < k {
+                    state.requesters_observed.insert(origin);
+                }
+                if state.requesters_observed.contains(&origin) {
+                    0
+                } else {
+                    1usize.min(num_contents - 1)
+                }
+            },
+            EquivocationPattern::Alternating => state.num_requests % num_contents,
+            EquivocationPattern::RandomUniform(_) => state.rng.gen_range(0, num_contents),
+            EquivocationPattern::StepN(step) => (state.num_requests / step.max(1)) % num_contents,
+            EquivocationPattern::BySourceIp => {
+                let mut hasher = DefaultHasher::new();
+                origin_from_cookie(&request).hash(&mut hasher);
+                hasher.finish() as usize % num_contents
+            },
+        };
+        state.num_requests += 1;
+        self.contents[idx].clone()
+    }
+}
+
+impl JWKContentServer for EquivocatingServer {
+    fn contents(&self) -> Vec<Vec<u8>> {
+        self.contents.clone()
+    }
+}
+
+/// This server replies with an HTTP error to the first `num_failures` requests, then with `content` forever.
+///
+/// This behavior simulates a provider that is temporarily unavailable.
+pub struct FlakyServer {
+    content: Vec<u8>,
+    num_failures: usize,
+    num_requests: Mutex<usize>,
+}
+
+impl FlakyServer {
+    pub fn new(content: Vec<u8>, num_failures: usize) -> Self {
+        Self {
+            content,
+            num_failures,
+            num_requests: Mutex::new(0),
         }
+    }
+}
 
-        if requesters_observed.contains(&origin) {
-            self.content_a.clone()
+impl RequestHandler for FlakyServer {
+    fn handle(&self, request: Request<Body>) -> Vec<u8> {
+        self.handle_with_status(request).1
+    }
+
+    fn handle_with_status(&self, _request: Request<Body>) -> (StatusCode, Vec<u8>) {
+        let mut num_requests = self.num_requests.lock();
+        *num_requests += 1;
+        if *num_requests <= self.num_failures {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                b"Service Unavailable".to_vec(),
+            )
         } else {
-            self.content_b.clone()
+            (StatusCode::OK, self.content.clone())
         }
     }
 }
 
+impl JWKContentServer for FlakyServer {
+    fn contents(&self) -> Vec<Vec<u8>> {
+        vec![self.content.clone()]
+    }
+}
+
 /// This server first replies with `initial_thoughts`.
 /// After enough audience receives it for at least once, it switches its reply to `second_thoughts`.
 ///
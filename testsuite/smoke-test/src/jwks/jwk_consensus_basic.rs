@@ -6,7 +6,7 @@ use crate::{
             request_handler::{EquivocatingServer, StaticContentServer},
             DummyProvider,
         },
-        get_patched_jwks, put_provider_on_chain,
+        put_provider_on_chain, wait_for_jwk,
     },
     smoke_test_environment::SwarmBuilder,
 };
@@ -17,7 +17,6 @@ use aptos_types::jwks::{
     ProviderJWKs,
 };
 use std::{sync::Arc, time::Duration};
-use tokio::time::sleep;
 
 /// The validators should agree on the JWK after provider set is changed/JWK is rotated.
 #[tokio::test]
@@ -44,10 +43,13 @@ async fn jwk_consensus_basic() {
 
     info!("Initially the provider set is empty. So should be the JWK map.");
 
-    sleep(Duration::from_secs(10)).await;
-    let patched_jwks = get_patched_jwks(&client).await;
-    debug!("patched_jwks={:?}", patched_jwks);
-    assert!(patched_jwks.jwks.entries.is_empty());
+    wait_for_jwk(
+        &client,
+        &AllProvidersJWKs { entries: vec![] },
+        Duration::from_secs(10),
+    )
+    .await
+    .unwrap();
 
     info!("Adding some providers.");
     let (provider_alice, provider_bob) =
@@ -76,15 +78,13 @@ async fn jwk_consensus_basic() {
             config_url: provider_bob.open_id_config_url().into_bytes(),
         },
     ];
-    let txn_summary = put_provider_on_chain(cli, root_idx, providers).await;
+    let txn_summary = put_provider_on_chain(&cli, root_idx, providers).await;
     debug!("txn_summary={:?}", txn_summary);
 
     info!("Waiting for an on-chain update. 10 sec should be enough.");
-    sleep(Duration::from_secs(10)).await;
-    let patched_jwks = get_patched_jwks(&client).await;
-    debug!("patched_jwks={:?}", patched_jwks);
-    assert_eq!(
-        AllProvidersJWKs {
+    let elapsed = wait_for_jwk(
+        &client,
+        &AllProvidersJWKs {
             entries: vec![
                 ProviderJWKs {
                     issuer: b"https://alice.io".to_vec(),
@@ -99,14 +99,18 @@ async fn jwk_consensus_basic() {
                     issuer: b"https://bob.dev".to_vec(),
                     version: 1,
                     jwks: vec![JWK::Unsupported(UnsupportedJWK::new_with_payload(
-                        "\"BOB_JWK_V0\""
+                        "\"BOB_JWK_V0\"",
                     ))
                     .into()],
                 },
-            ]
+            ],
         },
-        patched_jwks.jwks
-    );
+        Duration::from_secs(60),
+    )
+    .await
+    .unwrap();
+    info!("Converged in {:?}.", elapsed);
+    assert!(elapsed < Duration::from_secs(10));
 
     info!("Rotating Alice keys. Also making https://alice.io gently equivocate.");
     provider_alice.update_request_handler(Some(Arc::new(EquivocatingServer::new(
@@ -116,17 +120,15 @@ async fn jwk_consensus_basic() {
     ))));
 
     info!("Waiting for an on-chain update. 30 sec should be enough.");
-    sleep(Duration::from_secs(30)).await;
-    let patched_jwks = get_patched_jwks(&client).await;
-    debug!("patched_jwks={:?}", patched_jwks);
-    assert_eq!(
-        AllProvidersJWKs {
+    let elapsed = wait_for_jwk(
+        &client,
+        &AllProvidersJWKs {
             entries: vec![
                 ProviderJWKs {
                     issuer: b"https://alice.io".to_vec(),
                     version: 2,
                     jwks: vec![JWK::Unsupported(UnsupportedJWK::new_with_payload(
-                        "\"ALICE_JWK_V1B\""
+                        "\"ALICE_JWK_V1B\"",
                     ))
                     .into()],
                 },
@@ -134,14 +136,18 @@ async fn jwk_consensus_basic() {
                     issuer: b"https://bob.dev".to_vec(),
                     version: 1,
                     jwks: vec![JWK::Unsupported(UnsupportedJWK::new_with_payload(
-                        "\"BOB_JWK_V0\""
+                        "\"BOB_JWK_V0\"",
                     ))
                     .into()],
                 },
-            ]
+            ],
         },
-        patched_jwks.jwks
-    );
+        Duration::from_secs(120),
+    )
+    .await
+    .unwrap();
+    info!("Converged in {:?}.", elapsed);
+    assert!(elapsed < Duration::from_secs(30));
 
     info!("Tear down.");
     provider_alice.shutdown().await;
@@ -0,0 +1,73 @@
+// Copyright © Aptos Foundation
+
+use crate::{
+    jwks::{
+        dummy_provider::{request_handler::FlakyServer, DummyProvider},
+        get_patched_jwks, put_provider_on_chain,
+    },
+    smoke_test_environment::SwarmBuilder,
+};
+use aptos_forge::{NodeExt, Swarm, SwarmExt};
+use aptos_logger::{debug, info};
+use aptos_types::jwks::{
+    jwk::JWK, unsupported::UnsupportedJWK, AllProvidersJWKs, OIDCProvider, ProviderJWKs,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+
+/// The validators should still agree on the JWKs of a provider that fails some requests at first.
+#[tokio::test]
+async fn jwk_consensus_flaky_provider() {
+    let epoch_duration_secs = 30;
+
+    let (mut swarm, mut cli, _faucet) = SwarmBuilder::new_local(4)
+        .with_num_fullnodes(1)
+        .with_aptos()
+        .with_init_genesis_config(Arc::new(move |conf| {
+            conf.epoch_duration_secs = epoch_duration_secs;
+        }))
+        .build_with_cli(0)
+        .await;
+    let client = swarm.validators().next().unwrap().rest_client();
+    let root_idx = cli.add_account_with_address_to_cli(
+        swarm.root_key(),
+        swarm.chain_info().root_account().address(),
+    );
+    swarm
+        .wait_for_all_nodes_to_catchup_to_epoch(2, Duration::from_secs(epoch_duration_secs * 2))
+        .await
+        .expect("Epoch 2 taking too long to arrive!");
+
+    info!("Adding a provider that is unavailable for its first requests.");
+    let provider_alice = DummyProvider::spawn().await;
+    provider_alice.update_request_handler(Some(Arc::new(FlakyServer::new(
+        r#"{"keys": ["ALICE_JWK_V0"]}"#.as_bytes().to_vec(),
+        8,
+    ))));
+    let providers = vec![OIDCProvider {
+        name: b"https://alice.io".to_vec(),
+        config_url: provider_alice.open_id_config_url().into_bytes(),
+    }];
+    let txn_summary = put_provider_on_chain(&cli, root_idx, providers).await;
+    debug!("txn_summary={:?}", txn_summary);
+
+    info!("Waiting for an on-chain update after the provider recovers. 40 secs should be enough.");
+    sleep(Duration::from_secs(40)).await;
+    let patched_jwks = get_patched_jwks(&client).await;
+    debug!("patched_jwks={:?}", patched_jwks);
+    assert_eq!(
+        AllProvidersJWKs {
+            entries: vec![ProviderJWKs {
+                issuer: b"https://alice.io".to_vec(),
+                version: 1,
+                jwks: vec![
+                    JWK::Unsupported(UnsupportedJWK::new_with_payload("\"ALICE_JWK_V0\"")).into()
+                ],
+            }]
+        },
+        patched_jwks.jwks
+    );
+
+    info!("Tear down.");
+    provider_alice.shutdown().await;
+}
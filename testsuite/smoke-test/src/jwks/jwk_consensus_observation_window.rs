@@ -0,0 +1,99 @@
+// Copyright © Aptos Foundation
+
+use crate::{
+    jwks::{
+        dummy_provider::{request_handler::StaticContentServer, DummyProvider},
+        get_patched_jwks,
+    },
+    smoke_test_environment::SwarmBuilder,
+};
+use aptos_forge::{NodeExt, Swarm, SwarmExt};
+use aptos_logger::{debug, info};
+use aptos_types::jwks::{jwk::JWK, unsupported::UnsupportedJWK, AllProvidersJWKs, ProviderJWKs};
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+
+/// With a minimum observation window, a JWK that changes within the window should not be committed.
+/// Only the JWKs that stay unchanged for the whole window should be.
+#[tokio::test]
+async fn jwk_consensus_observation_window() {
+    // Big epoch duration to ensure the config is only picked up by the reconfiguration below.
+    let epoch_duration_secs = 1800;
+    let min_observation_secs = 60;
+
+    let (mut swarm, mut cli, _faucet) = SwarmBuilder::new_local(4)
+        .with_num_fullnodes(1)
+        .with_aptos()
+        .with_init_genesis_config(Arc::new(move |conf| {
+            conf.epoch_duration_secs = epoch_duration_secs;
+        }))
+        .build_with_cli(0)
+        .await;
+    let client = swarm.validators().next().unwrap().rest_client();
+    let root_idx = cli.add_account_with_address_to_cli(
+        swarm.root_key(),
+        swarm.chain_info().root_account().address(),
+    );
+    swarm
+        .wait_for_all_nodes_to_catchup_to_epoch(2, Duration::from_secs(epoch_duration_secs * 2))
+        .await
+        .expect("Epoch 2 taking too long to arrive!");
+
+    info!("Adding a provider and setting the observation window.");
+    let provider_alice = DummyProvider::spawn().await;
+    provider_alice.update_request_handler(Some(Arc::new(StaticContentServer::new(
+        r#"{"keys": ["ALICE_JWK_V0"]}"#.as_bytes().to_vec(),
+    ))));
+    let script = format!(
+        r#"
+script {{
+    use aptos_framework::aptos_governance;
+    use aptos_framework::jwks;
+    fun main(core_resources: &signer) {{
+        let framework_signer = aptos_governance::get_signer_testnet_only(core_resources, @0000000000000000000000000000000000000000000000000000000000000001);
+        jwks::set_jwk_consensus_config(&framework_signer, {min_observation_secs}, 67);
+        jwks::upsert_oidc_provider(&framework_signer, b"https://alice.io", b"{}");
+        aptos_governance::reconfigure(&framework_signer);
+    }}
+}}
+"#,
+        provider_alice.open_id_config_url(),
+    );
+    let txn_summary = cli.run_script(root_idx, &script).await.unwrap();
+    debug!("txn_summary={:?}", txn_summary);
+
+    info!("Alice rotates her key before the observation window passes.");
+    sleep(Duration::from_secs(30)).await;
+    let patched_jwks = get_patched_jwks(&client).await;
+    debug!("patched_jwks={:?}", patched_jwks);
+    assert!(patched_jwks.jwks.entries.is_empty());
+    provider_alice.update_request_handler(Some(Arc::new(StaticContentServer::new(
+        r#"{"keys": ["ALICE_JWK_V1"]}"#.as_bytes().to_vec(),
+    ))));
+
+    info!("Neither key should be committed while the new key is still being observed.");
+    sleep(Duration::from_secs(40)).await;
+    let patched_jwks = get_patched_jwks(&client).await;
+    debug!("patched_jwks={:?}", patched_jwks);
+    assert!(patched_jwks.jwks.entries.is_empty());
+
+    info!("Waiting for the new key to stabilize. 60 secs more should be enough.");
+    sleep(Duration::from_secs(60)).await;
+    let patched_jwks = get_patched_jwks(&client).await;
+    debug!("patched_jwks={:?}", patched_jwks);
+    assert_eq!(
+        AllProvidersJWKs {
+            entries: vec![ProviderJWKs {
+                issuer: b"https://alice.io".to_vec(),
+                version: 1,
+                jwks: vec![
+                    JWK::Unsupported(UnsupportedJWK::new_with_payload("\"ALICE_JWK_V1\"")).into()
+                ],
+            }]
+        },
+        patched_jwks.jwks
+    );
+
+    info!("Tear down.");
+    provider_alice.shutdown().await;
+}
@@ -70,7 +70,7 @@ async fn jwk_consensus_per_issuer() {
             config_url: provider_bob.open_id_config_url().into_bytes(),
         },
     ];
-    let txn_summary = put_provider_on_chain(cli, root_idx, providers).await;
+    let txn_summary = put_provider_on_chain(&cli, root_idx, providers).await;
     debug!("txn_summary={:?}", txn_summary);
 
     info!("Wait for 60 secs and there should only update for Bob, not Alice.");
@@ -72,7 +72,7 @@ async fn jwk_consensus_provider_change_mind() {
             config_url: provider_bob.open_id_config_url().into_bytes(),
         },
     ];
-    let txn_summary = put_provider_on_chain(cli, root_idx, providers).await;
+    let txn_summary = put_provider_on_chain(&cli, root_idx, providers).await;
     debug!("txn_summary={:?}", txn_summary);
 
     info!("Waiting for an on-chain update. 30 secs should be enough.");
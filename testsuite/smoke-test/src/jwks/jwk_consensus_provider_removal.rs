@@ -0,0 +1,111 @@
+// Copyright © Aptos Foundation
+
+use crate::{
+    jwks::{
+        dummy_provider::{request_handler::StaticContentServer, DummyProvider},
+        get_patched_jwks, put_provider_on_chain, remove_provider_from_chain,
+    },
+    smoke_test_environment::SwarmBuilder,
+};
+use aptos_forge::{NodeExt, Swarm, SwarmExt};
+use aptos_logger::{debug, info};
+use aptos_types::jwks::{
+    jwk::JWK, unsupported::UnsupportedJWK, AllProvidersJWKs, OIDCProvider, ProviderJWKs,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+
+/// Removing a provider should drop its JWKs from the JWK map, and leave the other providers intact.
+#[tokio::test]
+async fn jwk_consensus_provider_removal() {
+    let epoch_duration_secs = 30;
+
+    let (mut swarm, mut cli, _faucet) = SwarmBuilder::new_local(4)
+        .with_num_fullnodes(1)
+        .with_aptos()
+        .with_init_genesis_config(Arc::new(move |conf| {
+            conf.epoch_duration_secs = epoch_duration_secs;
+        }))
+        .build_with_cli(0)
+        .await;
+    let client = swarm.validators().next().unwrap().rest_client();
+    let root_idx = cli.add_account_with_address_to_cli(
+        swarm.root_key(),
+        swarm.chain_info().root_account().address(),
+    );
+    swarm
+        .wait_for_all_nodes_to_catchup_to_epoch(2, Duration::from_secs(epoch_duration_secs * 2))
+        .await
+        .expect("Epoch 2 taking too long to arrive!");
+
+    info!("Adding some providers.");
+    let (provider_alice, provider_bob) =
+        tokio::join!(DummyProvider::spawn(), DummyProvider::spawn());
+    provider_alice.update_request_handler(Some(Arc::new(StaticContentServer::new(
+        r#"{"keys": ["ALICE_JWK_V0"]}"#.as_bytes().to_vec(),
+    ))));
+    provider_bob.update_request_handler(Some(Arc::new(StaticContentServer::new(
+        r#"{"keys": ["BOB_JWK_V0"]}"#.as_bytes().to_vec(),
+    ))));
+    let provider_bob_on_chain = || OIDCProvider {
+        name: b"https://bob.dev".to_vec(),
+        config_url: provider_bob.open_id_config_url().into_bytes(),
+    };
+    let providers = vec![
+        OIDCProvider {
+            name: b"https://alice.io".to_vec(),
+            config_url: provider_alice.open_id_config_url().into_bytes(),
+        },
+        provider_bob_on_chain(),
+    ];
+    let txn_summary = put_provider_on_chain(&cli, root_idx, providers).await;
+    debug!("txn_summary={:?}", txn_summary);
+
+    info!("Waiting for an on-chain update. 10 sec should be enough.");
+    sleep(Duration::from_secs(10)).await;
+    let patched_jwks = get_patched_jwks(&client).await;
+    debug!("patched_jwks={:?}", patched_jwks);
+    let bob_jwks = ProviderJWKs {
+        issuer: b"https://bob.dev".to_vec(),
+        version: 1,
+        jwks: vec![JWK::Unsupported(UnsupportedJWK::new_with_payload("\"BOB_JWK_V0\"")).into()],
+    };
+    assert_eq!(
+        AllProvidersJWKs {
+            entries: vec![
+                ProviderJWKs {
+                    issuer: b"https://alice.io".to_vec(),
+                    version: 1,
+                    jwks: vec![JWK::Unsupported(UnsupportedJWK::new_with_payload(
+                        "\"ALICE_JWK_V0\""
+                    ))
+                    .into()],
+                },
+                bob_jwks.clone(),
+            ]
+        },
+        patched_jwks.jwks
+    );
+
+    info!("Removing https://alice.io, keeping only https://bob.dev.");
+    let txn_summary =
+        remove_provider_from_chain(&cli, root_idx, vec![b"https://alice.io".to_vec()]).await;
+    debug!("txn_summary={:?}", txn_summary);
+    let txn_summary = put_provider_on_chain(&cli, root_idx, vec![provider_bob_on_chain()]).await;
+    debug!("txn_summary={:?}", txn_summary);
+
+    info!("Waiting for an on-chain update. 10 sec should be enough.");
+    sleep(Duration::from_secs(10)).await;
+    let patched_jwks = get_patched_jwks(&client).await;
+    debug!("patched_jwks={:?}", patched_jwks);
+    assert_eq!(
+        AllProvidersJWKs {
+            entries: vec![bob_jwks]
+        },
+        patched_jwks.jwks
+    );
+
+    info!("Tear down.");
+    provider_alice.shutdown().await;
+    provider_bob.shutdown().await;
+}
@@ -0,0 +1,109 @@
+// Copyright © Aptos Foundation
+
+use crate::{
+    jwks::{
+        dummy_provider::{
+            request_handler::{
+                EquivocatingServer, EquivocationPattern, JWKContentServer, StaticContentServer,
+            },
+            DummyProvider,
+        },
+        get_patched_jwks, put_provider_on_chain,
+    },
+    smoke_test_environment::SwarmBuilder,
+};
+use aptos_forge::{NodeExt, Swarm, SwarmExt};
+use aptos_logger::{debug, info};
+use aptos_types::jwks::{
+    jwk::{JWKMoveStruct, JWK},
+    OIDCProvider,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+
+/// Parse a JWK response the same way validators do.
+fn jwks_from_content(content: &[u8]) -> Vec<JWKMoveStruct> {
+    let response: serde_json::Value = serde_json::from_slice(content).unwrap();
+    response["keys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|key| JWKMoveStruct::from(JWK::from(key.clone())))
+        .collect()
+}
+
+/// The validators should eventually agree on the JWKs of a provider,
+/// even if the provider replies with random content to every request.
+#[tokio::test]
+async fn jwk_consensus_random_equivocation() {
+    // Big epoch duration to ensure epoch change does not help reset validators if they are stuck.
+    let epoch_duration_secs = 1800;
+
+    let (mut swarm, mut cli, _faucet) = SwarmBuilder::new_local(4)
+        .with_num_fullnodes(1)
+        .with_aptos()
+        .with_init_genesis_config(Arc::new(move |conf| {
+            conf.epoch_duration_secs = epoch_duration_secs;
+        }))
+        .build_with_cli(0)
+        .await;
+    let client = swarm.validators().next().unwrap().rest_client();
+    let root_idx = cli.add_account_with_address_to_cli(
+        swarm.root_key(),
+        swarm.chain_info().root_account().address(),
+    );
+    swarm
+        .wait_for_all_nodes_to_catchup_to_epoch(2, Duration::from_secs(epoch_duration_secs * 2))
+        .await
+        .expect("Epoch 2 taking too long to arrive!");
+
+    info!("Adding a provider which replies randomly.");
+    let provider_alice = DummyProvider::spawn().await;
+    let equivocating_server = Arc::new(EquivocatingServer::new_with_pattern(
+        vec![
+            r#"{"keys": ["ALICE_JWK_V0A"]}"#.as_bytes().to_vec(),
+            r#"{"keys": ["ALICE_JWK_V0B"]}"#.as_bytes().to_vec(),
+        ],
+        EquivocationPattern::RandomUniform(7),
+    ));
+    let acceptable_jwks: Vec<_> = equivocating_server
+        .contents()
+        .iter()
+        .map(|content| jwks_from_content(content))
+        .collect();
+    provider_alice.update_request_handler(Some(equivocating_server));
+    let providers = vec![OIDCProvider {
+        name: b"https://alice.io".to_vec(),
+        config_url: provider_alice.open_id_config_url().into_bytes(),
+    }];
+    let txn_summary = put_provider_on_chain(&cli, root_idx, providers).await;
+    debug!("txn_summary={:?}", txn_summary);
+
+    info!("Waiting for an on-chain update. 120 secs should be enough.");
+    let mut committed = None;
+    for _ in 0..12 {
+        sleep(Duration::from_secs(10)).await;
+        let patched_jwks = get_patched_jwks(&client).await;
+        debug!("patched_jwks={:?}", patched_jwks);
+        if let Some(entry) = patched_jwks.jwks.entries.into_iter().next() {
+            committed = Some(entry);
+            break;
+        }
+    }
+    let committed = committed.expect("no JWK update for https://alice.io was committed");
+    assert_eq!(b"https://alice.io".to_vec(), committed.issuer);
+    assert!(acceptable_jwks.contains(&committed.jwks));
+
+    info!("Once Alice stops equivocating, the validators should converge to her JWKs.");
+    let static_server = StaticContentServer::new_str(r#"{"keys": ["ALICE_JWK_V1"]}"#);
+    let expected_jwks = jwks_from_content(&static_server.contents()[0]);
+    provider_alice.update_request_handler(Some(Arc::new(static_server)));
+    sleep(Duration::from_secs(30)).await;
+    let patched_jwks = get_patched_jwks(&client).await;
+    debug!("patched_jwks={:?}", patched_jwks);
+    assert_eq!(1, patched_jwks.jwks.entries.len());
+    assert_eq!(expected_jwks, patched_jwks.jwks.entries[0].jwks);
+
+    info!("Tear down.");
+    provider_alice.shutdown().await;
+}
@@ -2,10 +2,15 @@
 
 mod dummy_provider;
 mod jwk_consensus_basic;
+mod jwk_consensus_flaky_provider;
+mod jwk_consensus_observation_window;
 mod jwk_consensus_per_issuer;
 mod jwk_consensus_provider_change_mind;
+mod jwk_consensus_provider_removal;
+mod jwk_consensus_random_equivocation;
 
 use crate::smoke_test_environment::SwarmBuilder;
+use anyhow::{bail, Result};
 use aptos::{common::types::TransactionSummary, test::CliTestFramework};
 use aptos_forge::{NodeExt, Swarm, SwarmExt};
 use aptos_logger::{debug, info};
@@ -16,10 +21,11 @@ use aptos_types::jwks::{
     AllProvidersJWKs, OIDCProvider, PatchedJWKs, ProviderJWKs,
 };
 use move_core_types::account_address::AccountAddress;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
 
 pub async fn put_provider_on_chain(
-    cli: CliTestFramework,
+    cli: &CliTestFramework,
     account_idx: usize,
     providers: Vec<OIDCProvider>,
 ) -> TransactionSummary {
@@ -58,6 +64,45 @@ script {{
         .unwrap()
 }
 
+/// Stop watching the given issuers and drop their JWKs from `ObservedJWKs`.
+pub async fn remove_provider_from_chain(
+    cli: &CliTestFramework,
+    account_idx: usize,
+    issuers: Vec<Vec<u8>>,
+) -> TransactionSummary {
+    let implementation = issuers
+        .into_iter()
+        .map(|issuer| {
+            format!(
+                r#"
+        let issuer = b"{}";
+        jwks::remove_oidc_provider(&framework_signer, issuer);
+        jwks::remove_issuer_from_observed_jwks(&framework_signer, issuer);
+"#,
+                String::from_utf8(issuer).unwrap(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("");
+
+    let remove_provider_script = format!(
+        r#"
+script {{
+    use aptos_framework::aptos_governance;
+    use aptos_framework::jwks;
+    fun main(core_resources: &signer) {{
+        let framework_signer = aptos_governance::get_signer_testnet_only(core_resources, @0000000000000000000000000000000000000000000000000000000000000001);
+        {implementation}
+        aptos_governance::reconfigure(&framework_signer);
+    }}
+}}
+"#,
+    );
+    cli.run_script(account_idx, &remove_provider_script)
+        .await
+        .unwrap()
+}
+
 async fn get_patched_jwks(rest_client: &Client) -> PatchedJWKs {
     let maybe_response = rest_client
         .get_account_resource_bcs::<PatchedJWKs>(AccountAddress::ONE, "0x1::jwks::PatchedJWKs")
@@ -66,6 +111,26 @@ async fn get_patched_jwks(rest_client: &Client) -> PatchedJWKs {
     response.into_inner()
 }
 
+/// Poll the patched JWKs until they become `expected`, and return how long it took.
+pub async fn wait_for_jwk(
+    client: &Client,
+    expected: &AllProvidersJWKs,
+    timeout: Duration,
+) -> Result<Duration> {
+    let start = Instant::now();
+    loop {
+        let patched_jwks = get_patched_jwks(client).await;
+        if &patched_jwks.jwks == expected {
+            return Ok(start.elapsed());
+        }
+        if start.elapsed() >= timeout {
+            debug!("patched_jwks={:?}", patched_jwks);
+            bail!("JWKs did not converge within {:?}", timeout);
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+}
+
 /// Patch the JWK with governance proposal and see it is effective.
 #[tokio::test]
 async fn jwk_patching() {
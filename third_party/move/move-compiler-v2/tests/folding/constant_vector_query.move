@@ -0,0 +1,19 @@
+module 0x42::M {
+    use std::vector;
+
+    const LEN: u64 = vector::length(&vector[1, 2, 3]);
+    const EMPTY: bool = vector::is_empty(&vector<u64>[]);
+    const NOT_EMPTY: bool = vector::is_empty(&b"abc");
+
+    fun len(): u64 {
+        LEN
+    }
+
+    fun empty(): bool {
+        EMPTY
+    }
+
+    fun not_empty(): bool {
+        NOT_EMPTY
+    }
+}
@@ -5,6 +5,7 @@
 //! Contains definitions for the abstract syntax tree (AST) of the Move language.
 
 use crate::{
+    constant_folder::foldable_vector_query,
     exp_rewriter::ExpRewriterFunctions,
     model::{
         EnvDisplay, FieldId, FunId, FunctionEnv, GlobalEnv, GlobalId, Loc, ModuleId, NodeId,
@@ -842,12 +843,26 @@ impl ExpData {
     /// but is not documented as such in the book
     pub fn is_valid_for_constant(&self, env: &GlobalEnv, reasons: &mut Vec<(Loc, String)>) -> bool {
         let mut valid = true;
+        // Borrows of the vector passed to a foldable vector query, which are visited after the
+        // query itself.
+        let mut vector_query_operands = BTreeSet::new();
         let mut visitor = |e: &ExpData| {
             match e {
                 ExpData::Value(..) | ExpData::Invalid(_) | ExpData::Sequence(_, _) => {},
-                ExpData::Call(id, oper, _args) => {
-                    // Note that _args are visited separately.  No need to check them here.
-                    if !oper.is_builtin_op() {
+                ExpData::Call(id, oper, args) => {
+                    // Note that args are visited separately.  No need to check them here.
+                    if foldable_vector_query(env, oper).is_some() {
+                        if let [arg] = args.as_slice() {
+                            if let ExpData::Call(
+                                arg_id,
+                                Operation::Borrow(ReferenceKind::Immutable),
+                                _,
+                            ) = arg.as_ref()
+                            {
+                                vector_query_operands.insert(*arg_id);
+                            }
+                        }
+                    } else if !oper.is_builtin_op() && !vector_query_operands.contains(id) {
                         reasons.push((
                             env.get_node_loc(*id),
                             "Invalid call or operation in constant".to_owned(),
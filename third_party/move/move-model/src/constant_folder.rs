@@ -19,6 +19,9 @@
 //        let rewritten: Exp = constant_folder.rewrite_exp(exp);
 //    }
 
+// Besides the builtin operators, calls of `std::vector::length` and `std::vector::is_empty` on
+// a borrowed constant vector are folded, e.g. `vector::length(&vector[1, 2, 3])` to `3`.
+//
 // The current implementation handles
 // Operators not yet handled that match `ast::Operation::is_builtin_op`:
 // TODO:
@@ -34,12 +37,29 @@ use crate::{
     ast::{Exp, ExpData, Operation, Value},
     exp_rewriter::ExpRewriterFunctions,
     model::{GlobalEnv, Loc, NodeId},
-    ty::{PrimitiveType, Type, TypeDisplay, TypeDisplayContext},
+    ty::{PrimitiveType, ReferenceKind, Type, TypeDisplay, TypeDisplayContext},
+    well_known::{VECTOR_IS_EMPTY, VECTOR_LENGTH},
 };
 use codespan_reporting::diagnostic::Severity;
 use core::ops::{BitAnd, BitOr, BitXor, Rem, Shl, Shr};
 use num::{BigInt, ToPrimitive, Zero};
 
+/// Returns the name of the `std::vector` function called by `oper`, if it is a query which
+/// can be folded when called on a constant vector.
+pub fn foldable_vector_query(env: &GlobalEnv, oper: &Operation) -> Option<&'static str> {
+    if let Operation::MoveFunction(mid, fid) = oper {
+        // The module currently being built is not in the env yet, and is not `std::vector`
+        // unless the stdlib itself is compiled, where there's nothing to fold.
+        if mid.to_usize() < env.get_module_count() && env.get_module(*mid).is_std_vector() {
+            let name = format!("vector::{}", fid.symbol().display(env.symbol_pool()));
+            return [VECTOR_LENGTH, VECTOR_IS_EMPTY]
+                .into_iter()
+                .find(|query| *query == name);
+        }
+    }
+    None
+}
+
 pub struct ConstantFolder<'env> {
     env: &'env GlobalEnv,
     type_display_ctxt: TypeDisplayContext<'env>,
@@ -352,6 +372,40 @@ impl<'env> ConstantFolder<'env> {
             None
         }
     }
+
+    /// Folds a call of the vector `query` (see `foldable_vector_query`) on a borrowed constant
+    /// vector.
+    ///
+    /// Returns `None` and emitting diagnostic messages (referencing code corresponding to`id`)
+    /// if the vector is not a constant.
+    pub fn fold_vector_query(&mut self, id: NodeId, query: &str, args: &[Exp]) -> Option<Exp> {
+        use ExpData::{Call, Value as V};
+
+        let len = match args {
+            [arg0] => match arg0.as_ref() {
+                Call(_, Operation::Borrow(ReferenceKind::Immutable), borrowed) => {
+                    match borrowed.first().map(|exp| exp.as_ref()) {
+                        Some(V(_, Value::Vector(elems))) => Some(elems.len()),
+                        Some(V(_, Value::ByteArray(bytes))) => Some(bytes.len()),
+                        Some(V(_, Value::AddressArray(addrs))) => Some(addrs.len()),
+                        _ => None,
+                    }
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+        match len {
+            Some(len) if query == VECTOR_LENGTH => {
+                Some(V(id, Value::Number(BigInt::from(len))).into_exp())
+            },
+            Some(len) => Some(V(id, Value::Bool(len == 0)).into_exp()),
+            None => self.constant_folding_error(
+                id,
+                &format!("Argument to `{}` not foldable to a constant vector", query),
+            ),
+        }
+    }
 }
 
 impl<'env> ExpRewriterFunctions for ConstantFolder<'env> {
@@ -360,6 +414,11 @@ impl<'env> ExpRewriterFunctions for ConstantFolder<'env> {
             self.fold_vector_exp(id, "tuple", args)
         } else if matches!(oper, Operation::Vector) {
             self.fold_vector_exp(id, "vector", args)
+        } else if let Some(query) = foldable_vector_query(self.env, oper) {
+            self.fold_vector_query(id, query, args)
+        } else if matches!(oper, Operation::Borrow(ReferenceKind::Immutable)) {
+            // Only valid as the argument of a vector query, which folds it along with the query.
+            None
         } else if args.len() == 1 {
             // unary op
             self.fold_unary_exp(id, oper, &args[0])
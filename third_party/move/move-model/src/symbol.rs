@@ -11,6 +11,7 @@ use std::{
     fmt,
     fmt::{Error, Formatter},
     rc::Rc,
+    sync::{Arc, RwLock},
 };
 
 /// Representation of a symbol.
@@ -38,6 +39,7 @@ impl<'a> fmt::Display for SymbolDisplay<'a> {
 /// A pool of symbols. Allows to lookup a symbol by a string representation, and discover
 /// the string representation of an existing symbol. This struct does not need be mutable
 /// for operations on it, which is important so references to it can be freely passed around.
+/// See `SyncSymbolPool` for a pool which can be shared across threads.
 #[derive(Debug)]
 pub struct SymbolPool {
     inner: RefCell<InnerPool>,
@@ -65,15 +67,24 @@ impl SymbolPool {
     /// pool. The implementation uses internally a RefCell for storing symbols, so the pool
     /// does not need to be mutable.
     pub fn make(&self, s: &str) -> Symbol {
+        self.inner.borrow_mut().make(s)
+    }
+
+    /// Like `make`, but interns all the given strings at once, returning their symbols in the
+    /// same order. Cheaper than calling `make` for each of them when loading many names.
+    pub fn make_all(&self, strings: &[&str]) -> Vec<Symbol> {
         let mut pool = self.inner.borrow_mut();
-        let key = Rc::new(s.to_string());
-        if let Some(n) = pool.lookup.get(&key) {
-            return Symbol(*n);
-        }
-        let new_sym = pool.strings.len();
-        pool.strings.push(key.clone());
-        pool.lookup.insert(key, new_sym);
-        Symbol(new_sym)
+        strings.iter().map(|s| pool.make(s)).collect()
+    }
+
+    /// Looks up a symbol by its string representation, without creating it if it does not
+    /// exist yet.
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.inner
+            .borrow()
+            .lookup
+            .get(&s.to_string())
+            .map(|n| Symbol(*n))
     }
 
     /// Returns the string representation of this symbol, as an rc'ed string to avoid copies.
@@ -86,6 +97,36 @@ impl SymbolPool {
     pub fn symbol_starts_with_underscore(&self, sym: Symbol) -> bool {
         self.string(sym).starts_with('_')
     }
+
+    /// Returns the number of unique symbols interned in this pool.
+    pub fn len(&self) -> usize {
+        self.inner.borrow().strings.len()
+    }
+
+    /// Returns true if no symbol has been interned in this pool yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an estimate of the bytes held by this pool, computed as the sum of the lengths
+    /// of all interned strings. Each string is shared between the symbol table and the lookup
+    /// map, so it is only counted once; allocator and map overhead is not included.
+    pub fn approx_bytes(&self) -> usize {
+        self.inner.borrow().strings.iter().map(|s| s.len()).sum()
+    }
+}
+
+impl InnerPool {
+    fn make(&mut self, s: &str) -> Symbol {
+        let key = Rc::new(s.to_string());
+        if let Some(n) = self.lookup.get(&key) {
+            return Symbol(*n);
+        }
+        let new_sym = self.strings.len();
+        self.strings.push(key.clone());
+        self.lookup.insert(key, new_sym);
+        Symbol(new_sym)
+    }
 }
 
 impl Default for SymbolPool {
@@ -93,3 +134,173 @@ impl Default for SymbolPool {
         Self::new()
     }
 }
+
+impl From<SyncSymbolPool> for SymbolPool {
+    /// Converts a thread-safe pool into a `SymbolPool`, preserving all symbols created so far.
+    fn from(pool: SyncSymbolPool) -> Self {
+        let inner = pool.inner.into_inner().expect("lock not poisoned");
+        let strings: Vec<_> = inner
+            .strings
+            .into_iter()
+            .map(|s| Rc::new(Arc::try_unwrap(s).unwrap_or_else(|s| s.as_ref().clone())))
+            .collect();
+        let lookup = strings
+            .iter()
+            .enumerate()
+            .map(|(n, s)| (s.clone(), n))
+            .collect();
+        SymbolPool {
+            inner: RefCell::new(InnerPool { strings, lookup }),
+        }
+    }
+}
+
+/// A thread-safe variant of `SymbolPool`, which allows to create and look up symbols
+/// concurrently, e.g. from parallel compilation passes. As with `SymbolPool`, identical
+/// strings are always mapped to the same symbol.
+#[derive(Debug, Default)]
+pub struct SyncSymbolPool {
+    inner: RwLock<SyncInnerPool>,
+}
+
+#[derive(Debug, Default)]
+struct SyncInnerPool {
+    strings: Vec<Arc<String>>,
+    lookup: HashMap<Arc<String>, usize>,
+}
+
+impl SyncSymbolPool {
+    /// Creates a new SyncSymbolPool.
+    pub fn new() -> SyncSymbolPool {
+        Self::default()
+    }
+
+    /// Looks up a symbol by its string representation, creating a new one if it does not
+    /// exist yet. Existing symbols are looked up under a shared lock only.
+    pub fn make(&self, s: &str) -> Symbol {
+        let key = s.to_string();
+        if let Some(n) = self
+            .inner
+            .read()
+            .expect("lock not poisoned")
+            .lookup
+            .get(&key)
+        {
+            return Symbol(*n);
+        }
+        let mut pool = self.inner.write().expect("lock not poisoned");
+        // Another thread may have created the symbol since we released the shared lock.
+        if let Some(n) = pool.lookup.get(&key) {
+            return Symbol(*n);
+        }
+        let key = Arc::new(key);
+        let new_sym = pool.strings.len();
+        pool.strings.push(key.clone());
+        pool.lookup.insert(key, new_sym);
+        Symbol(new_sym)
+    }
+
+    /// Returns the string representation of this symbol. If the passed symbol was not created
+    /// from this pool, a runtime error may happen (or a wrong string will be returned).
+    pub fn string(&self, sym: Symbol) -> Arc<String> {
+        self.inner.read().expect("lock not poisoned").strings[sym.0].clone()
+    }
+
+    pub fn symbol_starts_with_underscore(&self, sym: Symbol) -> bool {
+        self.string(sym).starts_with('_')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_symbol_pool_get() {
+        let pool = SymbolPool::new();
+        let sym = pool.make("foo");
+        assert_eq!(pool.get("foo"), Some(sym));
+        assert_eq!(pool.get("bar"), None);
+        // looking up does not intern
+        assert_eq!(pool.get("bar"), None);
+        assert_eq!(pool.make("bar"), Symbol(1));
+    }
+
+    #[test]
+    fn test_symbol_pool_make_all() {
+        let pool = SymbolPool::new();
+        let existing = pool.make("b");
+        let syms = pool.make_all(&["a", "b", "c", "a", "b"]);
+        assert_eq!(syms[1], existing);
+        assert_eq!(syms[0], syms[3]);
+        assert_eq!(syms[1], syms[4]);
+
+        let individual = SymbolPool::new();
+        individual.make("b");
+        let expected: Vec<_> = ["a", "b", "c", "a", "b"]
+            .iter()
+            .map(|s| individual.make(s))
+            .collect();
+        assert_eq!(syms, expected);
+        for (sym, s) in syms.iter().zip(["a", "b", "c", "a", "b"]) {
+            assert_eq!(pool.string(*sym).as_str(), s);
+        }
+    }
+
+    #[test]
+    fn test_symbol_pool_stats() {
+        let pool = SymbolPool::new();
+        assert!(pool.is_empty());
+        assert_eq!(pool.approx_bytes(), 0);
+
+        let strings = ["alpha", "beta", "gamma", "alpha", "beta"];
+        pool.make_all(&strings);
+        pool.make("delta");
+        pool.make("gamma");
+        assert_eq!(pool.len(), 4);
+        assert!(!pool.is_empty());
+        // duplicates are only counted once
+        assert!(pool.approx_bytes() >= "alphabetagammadelta".len());
+
+        // looking up does not change the stats
+        pool.get("epsilon");
+        assert_eq!(pool.len(), 4);
+    }
+
+    #[test]
+    fn test_sync_symbol_pool_concurrent_make() {
+        let pool = Arc::new(SyncSymbolPool::new());
+        let handles: Vec<_> = (0..16)
+            .map(|t| {
+                let pool = pool.clone();
+                thread::spawn(move || {
+                    // threads intern overlapping ranges of strings, in different orders
+                    (0..1000)
+                        .map(|i| {
+                            let name = format!("sym_{}", (i * 7 + t * 31) % 500);
+                            (name.clone(), pool.make(&name))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let results: Vec<_> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+
+        let mut symbols = HashMap::new();
+        for (name, sym) in &results {
+            assert_eq!(*symbols.entry(name.clone()).or_insert(*sym), *sym);
+            assert_eq!(pool.string(*sym).as_str(), name);
+        }
+        assert_eq!(symbols.len(), 500);
+
+        let pool = SymbolPool::from(Arc::try_unwrap(pool).unwrap());
+        for (name, sym) in symbols {
+            assert_eq!(pool.make(&name), sym);
+            assert_eq!(pool.string(sym).as_str(), name);
+        }
+    }
+}
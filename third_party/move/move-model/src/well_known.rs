@@ -21,6 +21,8 @@ pub fn is_verify_only_attribute_name(s: &str) -> bool {
 }
 
 pub const VECTOR_BORROW_MUT: &str = "vector::borrow_mut";
+pub const VECTOR_LENGTH: &str = "vector::length";
+pub const VECTOR_IS_EMPTY: &str = "vector::is_empty";
 pub const EVENT_EMIT_EVENT: &str = "event::emit_event";
 
 pub const TYPE_NAME_MOVE: &str = "type_info::type_name";
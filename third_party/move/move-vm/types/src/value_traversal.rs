@@ -2,12 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    delayed_values::error::code_invariant_error,
-    values::{Container, Value, ValueImpl},
+    delayed_values::{
+        delayed_field_id::{DelayedFieldID, TryFromMoveValue},
+        error::code_invariant_error,
+    },
+    value_serde::RelaxedCustomSerDe,
+    values::{Container, DeserializationSeed, Value, ValueImpl},
 };
 use move_binary_format::errors::{PartialVMError, PartialVMResult};
-use move_core_types::vm_status::StatusCode;
-use std::collections::HashSet;
+use move_core_types::{
+    account_address::AccountAddress, u256, value::MoveTypeLayout, vm_status::StatusCode,
+};
+use serde::{
+    de::{DeserializeSeed, Error as DeError, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{collections::HashSet, fmt};
 
 // TODO[agg_v2](cleanup): This is a temporary traversal which collects
 //   identifiers stored in values. We do not use ValueVisitor because
@@ -73,12 +83,149 @@ fn find_identifiers_in_value_impl(
     Ok(())
 }
 
+/// Collects identifiers stored in serialized bytes of a value where delayed values are
+/// serialized as is. Returns the same identifiers as deserializing the bytes with
+/// `deserialize_and_allow_delayed_values` and calling `find_identifiers_in_value`, but
+/// only delayed values are constructed while traversing the bytes.
+pub fn find_identifiers_in_serialized_value(
+    bytes: &[u8],
+    layout: &MoveTypeLayout,
+    identifiers: &mut HashSet<u64>,
+) -> PartialVMResult<()> {
+    let seed = IdentifierCollectingSeed {
+        layout,
+        identifiers,
+    };
+    bcs::from_bytes_seed(seed, bytes).map_err(|e| {
+        PartialVMError::new(StatusCode::VALUE_DESERIALIZATION_ERROR).with_message(format!(
+            "Failed to find identifiers in serialized value: {}",
+            e
+        ))
+    })
+}
+
+struct IdentifierCollectingSeed<'l, 'i> {
+    layout: &'l MoveTypeLayout,
+    identifiers: &'i mut HashSet<u64>,
+}
+
+impl<'d, 'l, 'i> DeserializeSeed<'d> for IdentifierCollectingSeed<'l, 'i> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'d>>(self, deserializer: D) -> Result<(), D::Error> {
+        use MoveTypeLayout as L;
+
+        match self.layout {
+            // Primitive types are deserialized only to advance past them.
+            L::Bool => bool::deserialize(deserializer).map(|_| ()),
+            L::U8 => u8::deserialize(deserializer).map(|_| ()),
+            L::U16 => u16::deserialize(deserializer).map(|_| ()),
+            L::U32 => u32::deserialize(deserializer).map(|_| ()),
+            L::U64 => u64::deserialize(deserializer).map(|_| ()),
+            L::U128 => u128::deserialize(deserializer).map(|_| ()),
+            L::U256 => u256::U256::deserialize(deserializer).map(|_| ()),
+            L::Address | L::Signer => AccountAddress::deserialize(deserializer).map(|_| ()),
+
+            L::Struct(struct_layout) => {
+                let field_layouts = struct_layout.fields();
+                deserializer.deserialize_tuple(field_layouts.len(), StructFieldsCollectingVisitor {
+                    field_layouts,
+                    identifiers: self.identifiers,
+                })
+            },
+
+            L::Vector(elem_layout) => deserializer.deserialize_seq(VectorCollectingVisitor {
+                elem_layout,
+                identifiers: self.identifiers,
+            }),
+
+            // Delayed values are serialized as is, so the identifier is read back the same
+            // way as RelaxedCustomSerDe does.
+            L::Native(kind, layout) => {
+                let value = DeserializationSeed {
+                    custom_deserializer: None::<&RelaxedCustomSerDe>,
+                    layout: layout.as_ref(),
+                }
+                .deserialize(deserializer)?;
+                let (id, _width) = DelayedFieldID::try_from_move_value(layout, value, &())
+                    .map_err(|_| {
+                        D::Error::custom(format!(
+                            "Custom deserialization failed for {:?} with layout {}",
+                            kind, layout
+                        ))
+                    })?;
+                if !self.identifiers.insert(id.as_u64()) {
+                    return Err(D::Error::custom(code_invariant_error(
+                        "Duplicated identifiers for Move value".to_string(),
+                    )));
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+struct VectorCollectingVisitor<'l, 'i> {
+    elem_layout: &'l MoveTypeLayout,
+    identifiers: &'i mut HashSet<u64>,
+}
+
+impl<'d, 'l, 'i> Visitor<'d> for VectorCollectingVisitor<'l, 'i> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Vector")
+    }
+
+    fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq
+            .next_element_seed(IdentifierCollectingSeed {
+                layout: self.elem_layout,
+                identifiers: &mut *self.identifiers,
+            })?
+            .is_some()
+        {}
+        Ok(())
+    }
+}
+
+struct StructFieldsCollectingVisitor<'l, 'i> {
+    field_layouts: &'l [MoveTypeLayout],
+    identifiers: &'i mut HashSet<u64>,
+}
+
+impl<'d, 'l, 'i> Visitor<'d> for StructFieldsCollectingVisitor<'l, 'i> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Struct")
+    }
+
+    fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<(), A::Error> {
+        for (i, field_layout) in self.field_layouts.iter().enumerate() {
+            if seq
+                .next_element_seed(IdentifierCollectingSeed {
+                    layout: field_layout,
+                    identifiers: &mut *self.identifiers,
+                })?
+                .is_none()
+            {
+                return Err(A::Error::invalid_length(i, &self));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{delayed_values::delayed_field_id::DelayedFieldID, values::Struct};
+    use crate::{
+        value_serde::{deserialize_and_allow_delayed_values, serialize_and_allow_delayed_values},
+        values::Struct,
+    };
     use claims::{assert_err, assert_ok, assert_some};
-    use move_core_types::account_address::AccountAddress;
+    use move_core_types::value::{IdentifierMappingKind, MoveStructLayout};
 
     #[test]
     fn test_traversal_in_invalid_value() {
@@ -122,6 +269,88 @@ mod test {
         assert_some!(ids.get(&3));
     }
 
+    fn assert_same_identifiers_in_serialized_value(value: Value, layout: &MoveTypeLayout) {
+        let bytes = assert_some!(serialize_and_allow_delayed_values(&value, layout));
+
+        let mut expected = HashSet::new();
+        let deserialized = assert_some!(deserialize_and_allow_delayed_values(&bytes, layout));
+        assert_ok!(find_identifiers_in_value(&deserialized, &mut expected));
+
+        let mut ids = HashSet::new();
+        assert_ok!(find_identifiers_in_serialized_value(
+            &bytes, layout, &mut ids
+        ));
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn test_traversal_in_serialized_value() {
+        let aggregator_layout = || {
+            MoveTypeLayout::Native(
+                IdentifierMappingKind::Aggregator,
+                Box::new(MoveTypeLayout::U64),
+            )
+        };
+        let snapshot_layout = || {
+            MoveTypeLayout::Native(
+                IdentifierMappingKind::Snapshot,
+                Box::new(MoveTypeLayout::U128),
+            )
+        };
+        let struct_layout =
+            |fields: Vec<MoveTypeLayout>| MoveTypeLayout::Struct(MoveStructLayout::new(fields));
+        let vector_layout = |elem: MoveTypeLayout| MoveTypeLayout::Vector(Box::new(elem));
+
+        // No delayed fields.
+        let layout = struct_layout(vec![
+            MoveTypeLayout::U64,
+            vector_layout(MoveTypeLayout::U32),
+        ]);
+        let value = Value::struct_(Struct::pack(vec![
+            Value::u64(10),
+            Value::vector_u32(vec![1, 2, 3, 4]),
+        ]));
+        assert_same_identifiers_in_serialized_value(value, &layout);
+
+        // Delayed fields in nested structs and vectors.
+        let layout = struct_layout(vec![
+            struct_layout(vec![aggregator_layout(), vector_layout(MoveTypeLayout::U8)]),
+            vector_layout(struct_layout(vec![
+                snapshot_layout(),
+                vector_layout(aggregator_layout()),
+            ])),
+            MoveTypeLayout::Address,
+        ]);
+        let delayed_value = |id: u64| Value::delayed_value(DelayedFieldID::from(id));
+        let inner = |snapshot_id: u64, aggregator_ids: Vec<u64>| {
+            Value::struct_(Struct::pack(vec![
+                delayed_value(snapshot_id),
+                Value::vector_for_testing_only(
+                    aggregator_ids.into_iter().map(delayed_value).collect(),
+                ),
+            ]))
+        };
+        let value = Value::struct_(Struct::pack(vec![
+            Value::struct_(Struct::pack(vec![
+                delayed_value(0),
+                Value::vector_u8(vec![5, 6]),
+            ])),
+            Value::vector_for_testing_only(vec![inner(1, vec![2, 3]), inner(4, vec![])]),
+            Value::address(AccountAddress::ONE),
+        ]));
+        assert_same_identifiers_in_serialized_value(value, &layout);
+
+        // Duplicated identifiers are rejected.
+        let layout = struct_layout(vec![aggregator_layout(), aggregator_layout()]);
+        let value = Value::struct_(Struct::pack(vec![delayed_value(7), delayed_value(7)]));
+        let bytes = assert_some!(serialize_and_allow_delayed_values(&value, &layout));
+        assert_err!(find_identifiers_in_serialized_value(
+            &bytes,
+            &layout,
+            &mut HashSet::new()
+        ));
+    }
+
     #[test]
     fn test_duplicated_ids() {
         let a = Value::delayed_value(DelayedFieldID::from(0));
@@ -13,6 +13,12 @@ pub struct BlockExecutorLocalConfig {
     // If true, we will discard the failed blocks and continue with the next block.
     // (allow_fallback needs to be set)
     pub discard_failed_blocks: bool,
+    // If specified, the starting values of delayed field ids are generated from this seed,
+    // making the ids reproducible across runs. Otherwise, they are random.
+    pub id_start_value_seed: Option<u64>,
+    // If true, sequential execution drops the resource group writes that leave the group
+    // byte-identical (with the same metadata) to its value before the transaction.
+    pub skip_unchanged_group_writes: bool,
 }
 
 /// Configuration from on-chain configuration, that is
@@ -72,6 +78,8 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                id_start_value_seed: None,
+                skip_unchanged_group_writes: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         }
@@ -86,6 +94,8 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                id_start_value_seed: None,
+                skip_unchanged_group_writes: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_maybe_block_limit(maybe_block_gas_limit),
         }
@@ -63,6 +63,36 @@ impl OnChainConfig for SupportedOIDCProviders {
     const TYPE_IDENTIFIER: &'static str = "SupportedOIDCProviders";
 }
 
+/// Move type `0x1::jwks::JWKConsensusConfig` in rust.
+/// See its doc in Move for more details.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct JWKConsensusConfig {
+    pub min_observation_secs: u64,
+    pub quorum_threshold_pct: u64,
+}
+
+impl JWKConsensusConfig {
+    /// The fraction of the total voting power required to certify an update.
+    pub fn quorum_threshold(&self) -> f64 {
+        self.quorum_threshold_pct as f64 / 100.0
+    }
+}
+
+impl Default for JWKConsensusConfig {
+    /// Used when the config is not published on chain.
+    fn default() -> Self {
+        Self {
+            min_observation_secs: 0,
+            quorum_threshold_pct: 67,
+        }
+    }
+}
+
+impl OnChainConfig for JWKConsensusConfig {
+    const MODULE_IDENTIFIER: &'static str = "jwks";
+    const TYPE_IDENTIFIER: &'static str = "JWKConsensusConfig";
+}
+
 /// Move type `0x1::jwks::ProviderJWKs` in rust.
 /// See its doc in Move for more details.
 #[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
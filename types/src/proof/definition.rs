@@ -818,6 +818,63 @@ impl TransactionInfoWithProof {
     }
 }
 
+/// The proof used to authenticate an event: a `TransactionInfoWithProof` connecting the
+/// transaction info to the ledger root, and an `EventAccumulatorProof` connecting the event to the
+/// event root hash in that transaction info.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct EventProof {
+    transaction_info_with_proof: TransactionInfoWithProof,
+    transaction_info_to_event_proof: EventAccumulatorProof,
+}
+
+impl EventProof {
+    /// Constructs a new `EventProof` using given `transaction_info_with_proof` and
+    /// `transaction_info_to_event_proof`.
+    pub fn new(
+        transaction_info_with_proof: TransactionInfoWithProof,
+        transaction_info_to_event_proof: EventAccumulatorProof,
+    ) -> Self {
+        Self {
+            transaction_info_with_proof,
+            transaction_info_to_event_proof,
+        }
+    }
+
+    /// Returns the `transaction_info_with_proof` object in this proof.
+    pub fn transaction_info_with_proof(&self) -> &TransactionInfoWithProof {
+        &self.transaction_info_with_proof
+    }
+
+    /// Returns the `transaction_info_to_event_proof` object in this proof.
+    pub fn transaction_info_to_event_proof(&self) -> &EventAccumulatorProof {
+        &self.transaction_info_to_event_proof
+    }
+
+    /// Verifies that the event with `event_hash`, emitted as the `event_index_in_txn`-th event of
+    /// the transaction at `transaction_version`, exists in the ledger represented by `ledger_info`.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        event_hash: HashValue,
+        transaction_version: Version,
+        event_index_in_txn: u64,
+    ) -> Result<()> {
+        self.transaction_info_to_event_proof.verify(
+            self.transaction_info_with_proof
+                .transaction_info()
+                .event_root_hash(),
+            event_hash,
+            event_index_in_txn,
+        )?;
+
+        self.transaction_info_with_proof
+            .verify(ledger_info, transaction_version)?;
+
+        Ok(())
+    }
+}
+
 /// The proof used to authenticate a list of consecutive transaction infos.
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
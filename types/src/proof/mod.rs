@@ -13,9 +13,10 @@ mod unit_tests;
 
 pub use self::definition::{
     AccumulatorConsistencyProof, AccumulatorExtensionProof, AccumulatorProof,
-    AccumulatorRangeProof, SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof,
-    TransactionAccumulatorProof, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
-    TransactionInfoListWithProof, TransactionInfoWithProof,
+    AccumulatorRangeProof, EventAccumulatorProof, EventProof, SparseMerkleProof,
+    SparseMerkleProofExt, SparseMerkleRangeProof, TransactionAccumulatorProof,
+    TransactionAccumulatorRangeProof, TransactionAccumulatorSummary, TransactionInfoListWithProof,
+    TransactionInfoWithProof,
 };
 #[cfg(any(test, feature = "fuzzing"))]
 pub use self::definition::{TestAccumulatorProof, TestAccumulatorRangeProof};
@@ -67,3 +67,24 @@ impl StateStorageUsage {
         }
     }
 }
+
+/// Difference in state storage usage between two versions, see
+/// `StateStorageUsageDelta::between`.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateStorageUsageDelta {
+    pub items: i64,
+    pub bytes: i64,
+}
+
+impl StateStorageUsageDelta {
+    /// Returns the usage at `to` minus the usage at `from`, or None if either is untracked.
+    pub fn between(from: &StateStorageUsage, to: &StateStorageUsage) -> Option<Self> {
+        if from.is_untracked() || to.is_untracked() {
+            return None;
+        }
+        Some(Self {
+            items: to.items() as i64 - from.items() as i64,
+            bytes: to.bytes() as i64 - from.bytes() as i64,
+        })
+    }
+}